@@ -322,6 +322,7 @@ impl Amm {
                     authenticated: true,
                     is_tracked: false,
                     resources: Resources::default(),
+                    deadline: None,
                     message,
                 });
             }
@@ -366,6 +367,7 @@ impl Amm {
                     authenticated: true,
                     is_tracked: false,
                     resources: Resources::default(),
+                    deadline: None,
                     message,
                 });
             }
@@ -0,0 +1,195 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod state;
+
+use self::state::AtomicCounter;
+use async_trait::async_trait;
+use atomic_counter::{Message, Operation};
+use linera_sdk::{
+    base::{SessionId, WithContractAbi},
+    contract::system_api,
+    ensure,
+    two_phase_commit::{TransactionError, TransactionMessage},
+    views::ViewError,
+    ApplicationCallOutcome, CalleeContext, Contract, ExecutionOutcome, MessageContext,
+    OperationContext, SessionCallOutcome, ViewStateStorage,
+};
+use thiserror::Error;
+
+linera_sdk::contract!(AtomicCounter);
+
+impl WithContractAbi for AtomicCounter {
+    type Abi = atomic_counter::AtomicCounterAbi;
+}
+
+#[async_trait]
+impl Contract for AtomicCounter {
+    type Error = Error;
+    type Storage = ViewStateStorage<Self>;
+
+    async fn initialize(
+        &mut self,
+        _context: &OperationContext,
+        value: u64,
+    ) -> Result<ExecutionOutcome<Self::Message>, Self::Error> {
+        // Validate that the application parameters were configured correctly.
+        assert!(Self::parameters().is_ok());
+
+        self.value.set(value);
+
+        Ok(ExecutionOutcome::default())
+    }
+
+    async fn execute_operation(
+        &mut self,
+        context: &OperationContext,
+        operation: Operation,
+    ) -> Result<ExecutionOutcome<Self::Message>, Self::Error> {
+        let mut outcome = ExecutionOutcome::default();
+        // The counters are only changed by the participants, so there is nothing to
+        // compensate on the coordinator chain when a transaction is aborted.
+        match operation {
+            Operation::Update { deltas, deadline } => {
+                self.coordinator
+                    .begin(context.chain_id, deltas, deadline, &mut outcome)?;
+            }
+            Operation::AbortExpired => {
+                let now = system_api::current_system_time();
+                self.coordinator
+                    .abort_expired(context.chain_id, now, &mut outcome)
+                    .await?;
+            }
+        }
+        Ok(outcome)
+    }
+
+    async fn execute_message(
+        &mut self,
+        context: &MessageContext,
+        message: Message,
+    ) -> Result<ExecutionOutcome<Self::Message>, Self::Error> {
+        let mut outcome = ExecutionOutcome::default();
+        let sender = context.message_id.chain_id;
+        match message {
+            TransactionMessage::Prepare { transaction_id, .. } if context.is_bouncing => {
+                self.coordinator
+                    .handle_bounced_prepare(context.chain_id, transaction_id, sender, &mut outcome)
+                    .await?;
+            }
+            TransactionMessage::Prepare {
+                transaction_id,
+                payload: delta,
+            } => {
+                self.reserve(delta)?;
+                self.participant
+                    .prepare(transaction_id, sender, delta, &mut outcome)?;
+            }
+            TransactionMessage::Prepared { transaction_id } => {
+                self.coordinator
+                    .handle_prepared(context.chain_id, transaction_id, sender, &mut outcome)
+                    .await?;
+            }
+            TransactionMessage::Commit { transaction_id } => {
+                if let Some(delta) = self.participant.commit(transaction_id, sender).await? {
+                    self.release(delta);
+                    self.apply(delta);
+                }
+            }
+            TransactionMessage::Abort { transaction_id } => {
+                if let Some(delta) = self.participant.abort(transaction_id, sender).await? {
+                    self.release(delta);
+                }
+            }
+        }
+        Ok(outcome)
+    }
+
+    async fn handle_application_call(
+        &mut self,
+        _context: &CalleeContext,
+        _call: (),
+        _forwarded_sessions: Vec<SessionId>,
+    ) -> Result<
+        ApplicationCallOutcome<Self::Message, Self::Response, Self::SessionState>,
+        Self::Error,
+    > {
+        Err(Error::ApplicationCallsNotSupported)
+    }
+
+    async fn handle_session_call(
+        &mut self,
+        _context: &CalleeContext,
+        _state: Self::SessionState,
+        _call: (),
+        _forwarded_sessions: Vec<SessionId>,
+    ) -> Result<SessionCallOutcome<Self::Message, Self::Response, Self::SessionState>, Self::Error>
+    {
+        Err(Error::SessionsNotSupported)
+    }
+}
+
+impl AtomicCounter {
+    /// Reserves the part of the value that a prepared transaction may remove from the
+    /// counter, failing if it is not available.
+    fn reserve(&mut self, delta: i64) -> Result<(), Error> {
+        if delta < 0 {
+            let amount = delta.unsigned_abs();
+            let available = *self.value.get() - *self.reserved.get();
+            ensure!(available >= amount, Error::InsufficientValue);
+            *self.reserved.get_mut() += amount;
+        }
+        Ok(())
+    }
+
+    /// Releases the part of the value reserved by a transaction.
+    fn release(&mut self, delta: i64) {
+        if delta < 0 {
+            *self.reserved.get_mut() -= delta.unsigned_abs();
+        }
+    }
+
+    /// Applies the change of a committed transaction to the counter.
+    fn apply(&mut self, delta: i64) {
+        let value = self.value.get_mut();
+        if delta < 0 {
+            *value -= delta.unsigned_abs();
+        } else {
+            *value += delta.unsigned_abs();
+        }
+    }
+}
+
+/// An error that can occur during the contract execution.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Atomic counter application doesn't support any cross-application calls.
+    #[error("Atomic counter application doesn't support any cross-application calls")]
+    ApplicationCallsNotSupported,
+
+    /// Atomic counter application doesn't support any cross-application sessions.
+    #[error("Atomic counter application doesn't support any cross-application sessions")]
+    SessionsNotSupported,
+
+    /// The counter is too low to apply the transaction.
+    #[error("The counter is too low to apply the transaction")]
+    InsufficientValue,
+
+    /// View error.
+    #[error(transparent)]
+    View(#[from] ViewError),
+
+    /// Invalid transaction message.
+    #[error(transparent)]
+    Transaction(#[from] TransactionError),
+
+    /// Failed to deserialize BCS bytes
+    #[error("Failed to deserialize BCS bytes")]
+    BcsError(#[from] bcs::Error),
+
+    /// Failed to deserialize JSON string
+    #[error("Failed to deserialize JSON string")]
+    JsonError(#[from] serde_json::Error),
+}
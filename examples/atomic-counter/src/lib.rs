@@ -0,0 +1,61 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*!
+# Atomic Counter Example Application
+
+This application keeps a counter on every chain where it is used, and updates the counters
+of several chains atomically, using the two-phase commit helpers of `linera-sdk`.
+
+# How It Works
+
+The `Update` operation starts a transaction on the chain where it is executed. It sends a
+`Prepare` message with the change to apply to the counter of each participant chain. A
+participant that can apply its change reserves it and answers with `Prepared`. Once all
+participants are prepared, the changes are committed on every chain. If one of the `Prepare`
+messages is rejected, or is not received before the deadline of the transaction, it bounces
+back and the transaction is aborted on every chain.
+*/
+
+use async_graphql::{Request, Response};
+use linera_sdk::{
+    base::{ChainId, ContractAbi, ServiceAbi, Timestamp},
+    two_phase_commit::TransactionMessage,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct AtomicCounterAbi;
+
+impl ContractAbi for AtomicCounterAbi {
+    type InitializationArgument = u64;
+    type Parameters = ();
+    type Operation = Operation;
+    type ApplicationCall = ();
+    type Message = Message;
+    type SessionCall = ();
+    type Response = ();
+    type SessionState = ();
+}
+
+impl ServiceAbi for AtomicCounterAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+    type Parameters = ();
+}
+
+/// Operations that can be executed by the application.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum Operation {
+    /// Atomically adds the given (possibly negative) amounts to the counters of the given
+    /// chains.
+    Update {
+        deltas: BTreeMap<ChainId, i64>,
+        deadline: Timestamp,
+    },
+    /// Aborts the transactions started on this chain whose deadline has passed.
+    AbortExpired,
+}
+
+/// Messages exchanged by the coordinator and the participants of a transaction.
+pub type Message = TransactionMessage<i64>;
@@ -0,0 +1,64 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod state;
+
+use self::state::AtomicCounter;
+use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Response, Schema};
+use async_trait::async_trait;
+use linera_sdk::{base::WithServiceAbi, QueryContext, Service, ViewStateStorage};
+use std::sync::Arc;
+use thiserror::Error;
+
+linera_sdk::service!(AtomicCounter);
+
+impl WithServiceAbi for AtomicCounter {
+    type Abi = atomic_counter::AtomicCounterAbi;
+}
+
+#[async_trait]
+impl Service for AtomicCounter {
+    type Error = Error;
+    type Storage = ViewStateStorage<Self>;
+
+    async fn handle_query(
+        self: Arc<Self>,
+        _context: &QueryContext,
+        request: Request,
+    ) -> Result<Response, Self::Error> {
+        let query_root = QueryRoot {
+            value: *self.value.get(),
+            reserved: *self.reserved.get(),
+        };
+        let schema = Schema::build(query_root, EmptyMutation, EmptySubscription).finish();
+        Ok(schema.execute(request).await)
+    }
+}
+
+struct QueryRoot {
+    value: u64,
+    reserved: u64,
+}
+
+#[Object]
+impl QueryRoot {
+    /// The value of the counter on this chain.
+    async fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The part of the value reserved by prepared transactions.
+    async fn reserved(&self) -> u64 {
+        self.reserved
+    }
+}
+
+/// An error that can occur during the service execution.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Invalid query argument; could not deserialize GraphQL request.
+    #[error("Invalid query argument; could not deserialize GraphQL request")]
+    InvalidQuery(#[from] serde_json::Error),
+}
@@ -0,0 +1,21 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use linera_sdk::{
+    two_phase_commit::{Coordinator, Participant},
+    views::{linera_views, RegisterView, RootView, ViewStorageContext},
+};
+
+/// The application state.
+#[derive(RootView)]
+#[view(context = "ViewStorageContext")]
+pub struct AtomicCounter {
+    /// The value of the counter on this chain.
+    pub value: RegisterView<u64>,
+    /// The part of the value reserved by prepared transactions that decrease it.
+    pub reserved: RegisterView<u64>,
+    /// The transactions started on this chain.
+    pub coordinator: Coordinator<i64>,
+    /// The transactions this chain takes part in.
+    pub participant: Participant<i64>,
+}
@@ -0,0 +1,200 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integration tests for the Atomic Counter application.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use atomic_counter::{AtomicCounterAbi, Operation};
+use linera_sdk::{
+    base::{ApplicationId, Timestamp},
+    test::{ActiveChain, TestValidator},
+};
+
+/// Test updating the counters of three microchains atomically.
+///
+/// Creates the application on a `coordinator_chain` with a value of 10, and moves 7 of it to two
+/// other chains in a single transaction. The coordinator chain takes part in the transaction too.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_committed_transaction() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode().await;
+    let mut coordinator_chain = validator.new_chain().await;
+    let application_id = coordinator_chain
+        .create_application::<AtomicCounterAbi>(bytecode_id, (), 10, vec![])
+        .await;
+
+    let chain_a = validator.new_chain().await;
+    let chain_b = validator.new_chain().await;
+    chain_a.register_application(application_id).await;
+    chain_b.register_application(application_id).await;
+
+    coordinator_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::Update {
+                    deltas: [
+                        (coordinator_chain.id(), -7),
+                        (chain_a.id(), 3),
+                        (chain_b.id(), 4),
+                    ]
+                    .into(),
+                    deadline: Timestamp::from(1_000_000),
+                },
+            );
+        })
+        .await;
+
+    coordinator_chain.handle_received_messages().await;
+    chain_a.handle_received_messages().await;
+    chain_b.handle_received_messages().await;
+
+    assert_eq!(
+        query_counter(application_id, &coordinator_chain).await,
+        (10, 7)
+    );
+    assert_eq!(query_counter(application_id, &chain_a).await, (0, 0));
+
+    // Receive the `Prepared` answers, and then the `Commit` messages.
+    coordinator_chain.handle_received_messages().await;
+    coordinator_chain.handle_received_messages().await;
+    chain_a.handle_received_messages().await;
+    chain_b.handle_received_messages().await;
+
+    assert_eq!(
+        query_counter(application_id, &coordinator_chain).await,
+        (3, 0)
+    );
+    assert_eq!(query_counter(application_id, &chain_a).await, (3, 0));
+    assert_eq!(query_counter(application_id, &chain_b).await, (4, 0));
+}
+
+/// Test that a transaction refused by one of its participants is aborted on all of them.
+///
+/// Creates the application on a `coordinator_chain` and starts a transaction that updates the
+/// counters of two other chains. The first one prepares the transaction, but the second one
+/// rejects it, so the `Prepare` message bounces back and the counters are left unchanged.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_aborted_transaction() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode().await;
+    let mut coordinator_chain = validator.new_chain().await;
+    let application_id = coordinator_chain
+        .create_application::<AtomicCounterAbi>(bytecode_id, (), 0, vec![])
+        .await;
+
+    let chain_a = validator.new_chain().await;
+    let chain_b = validator.new_chain().await;
+    chain_a.register_application(application_id).await;
+    chain_b.register_application(application_id).await;
+
+    let messages = coordinator_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::Update {
+                    deltas: [(chain_a.id(), 5), (chain_b.id(), 5)].into(),
+                    deadline: Timestamp::from(1_000_000),
+                },
+            );
+        })
+        .await;
+    // The `Prepare` messages are sent in the order of the participants' chain IDs.
+    let prepare_b = if chain_a.id() < chain_b.id() {
+        messages[1]
+    } else {
+        messages[0]
+    };
+
+    chain_a.handle_received_messages().await;
+    chain_b
+        .add_block(|block| {
+            block.with_rejected_incoming_message(prepare_b);
+        })
+        .await;
+
+    // Receive the `Prepared` answer and the bounced `Prepare` message, then the `Abort` message.
+    coordinator_chain.handle_received_messages().await;
+    chain_a.handle_received_messages().await;
+
+    assert_eq!(
+        query_counter(application_id, &coordinator_chain).await,
+        (0, 0)
+    );
+    assert_eq!(query_counter(application_id, &chain_a).await, (0, 0));
+    assert_eq!(query_counter(application_id, &chain_b).await, (0, 0));
+}
+
+/// Test that a transaction whose deadline has passed is aborted by the coordinator.
+///
+/// Creates the application on a `coordinator_chain` and starts a transaction that updates the
+/// counters of two other chains. Before the participants receive their `Prepare` messages, the
+/// deadline passes and the coordinator aborts the transaction, so the participants reject the
+/// expired `Prepare` messages, receive the `Abort` messages and leave their counters unchanged.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_expired_transaction() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode().await;
+    let mut coordinator_chain = validator.new_chain().await;
+    let application_id = coordinator_chain
+        .create_application::<AtomicCounterAbi>(bytecode_id, (), 0, vec![])
+        .await;
+
+    let chain_a = validator.new_chain().await;
+    let chain_b = validator.new_chain().await;
+    chain_a.register_application(application_id).await;
+    chain_b.register_application(application_id).await;
+
+    let prepare_messages = coordinator_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::Update {
+                    deltas: [(chain_a.id(), 5), (chain_b.id(), 5)].into(),
+                    deadline: Timestamp::from(1_000),
+                },
+            );
+        })
+        .await;
+    let abort_messages = coordinator_chain
+        .add_block(|block| {
+            block
+                .with_timestamp(Timestamp::from(2_000))
+                .with_operation(application_id, Operation::AbortExpired);
+        })
+        .await;
+    assert_eq!(abort_messages.len(), 2);
+
+    // The messages are sent in the order of the participants' chain IDs.
+    let (index_a, index_b) = if chain_a.id() < chain_b.id() {
+        (0, 1)
+    } else {
+        (1, 0)
+    };
+    for (chain, index) in [(&chain_a, index_a), (&chain_b, index_b)] {
+        chain
+            .add_block(|block| {
+                block
+                    .with_rejected_incoming_message(prepare_messages[index])
+                    .with_incoming_message(abort_messages[index]);
+            })
+            .await;
+    }
+
+    assert_eq!(query_counter(application_id, &chain_a).await, (0, 0));
+    assert_eq!(query_counter(application_id, &chain_b).await, (0, 0));
+}
+
+/// Queries the value of the counter on the `chain`, and the part of it that is reserved by
+/// prepared transactions.
+async fn query_counter(
+    application_id: ApplicationId<AtomicCounterAbi>,
+    chain: &ActiveChain,
+) -> (u64, u64) {
+    let response = chain
+        .graphql_query(application_id, "query { value reserved }")
+        .await;
+    let value = response["value"].as_u64().expect("Failed to get the value");
+    let reserved = response["reserved"]
+        .as_u64()
+        .expect("Failed to get the reserved value");
+    (value, reserved)
+}
@@ -222,6 +222,7 @@ mod tests {
                 height: BlockHeight(1),
                 index: 1,
             },
+            response_to: None,
         }
     }
 
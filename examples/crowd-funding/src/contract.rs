@@ -169,6 +169,7 @@ impl CrowdFunding {
             authenticated: true,
             is_tracked: false,
             resources: Resources::default(),
+            deadline: None,
             message,
         });
         Ok(())
@@ -318,6 +318,7 @@ impl MatchingEngine {
             authenticated: true,
             is_tracked: false,
             resources: Resources::default(),
+            deadline: None,
             message,
         });
         Ok(())
@@ -57,6 +57,7 @@ impl Contract for MetaCounter {
             authenticated,
             is_tracked,
             fuel_grant,
+            deadline,
             message,
         } = operation;
         let message = OutgoingMessage {
@@ -67,6 +68,7 @@ impl Contract for MetaCounter {
                 fuel: fuel_grant,
                 ..Default::default()
             },
+            deadline,
             message,
         };
         let mut outcome = ExecutionOutcome::default();
@@ -8,7 +8,7 @@ This application is only used for testing cross-application calls.
 */
 
 use async_graphql::{Request, Response};
-use linera_sdk::base::{ApplicationId, ChainId, ContractAbi, ServiceAbi};
+use linera_sdk::base::{ApplicationId, ChainId, ContractAbi, ServiceAbi, Timestamp};
 use serde::{Deserialize, Serialize};
 
 pub struct MetaCounterAbi;
@@ -30,6 +30,7 @@ pub struct Operation {
     pub authenticated: bool,
     pub is_tracked: bool,
     pub fuel_grant: u64,
+    pub deadline: Option<Timestamp>,
     pub message: Message,
 }
 
@@ -40,6 +41,7 @@ impl Operation {
             authenticated: false,
             is_tracked: false,
             fuel_grant: 0,
+            deadline: None,
             message: Message::Increment(value),
         }
     }
@@ -50,6 +52,7 @@ impl Operation {
             authenticated: false,
             is_tracked: false,
             fuel_grant: 0,
+            deadline: None,
             message: Message::Fail,
         }
     }
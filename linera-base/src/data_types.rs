@@ -168,6 +168,16 @@ pub struct Resources {
     // TODO(#1533): Allow declaring calls to other applications instead of having to count them here.
 }
 
+/// How an application pays the fees of the operations that target it, using the funds in
+/// its own account on the chain. Any fees beyond these limits are paid as usual.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct FeeSponsorship {
+    /// The maximum amount of fees paid for a single operation.
+    pub max_fees_per_operation: Amount,
+    /// The maximum amount of fees paid for all the operations in a block.
+    pub max_fees_per_block: Amount,
+}
+
 /// An error type for arithmetic errors.
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
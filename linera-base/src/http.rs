@@ -0,0 +1,86 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types for the HTTP requests that application services can make.
+
+use serde::{Deserialize, Serialize};
+
+/// The method of an HTTP request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HttpMethod {
+    /// A `GET` request.
+    Get,
+    /// A `POST` request.
+    Post,
+}
+
+/// An HTTP request made by an application service.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HttpRequest {
+    /// The method of the request.
+    pub method: HttpMethod,
+    /// The URL to send the request to, including the `http` or `https` scheme.
+    pub url: String,
+    /// The headers of the request, as names and values.
+    pub headers: Vec<(String, String)>,
+    /// The body of the request.
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Creates a `GET` request for the `url`.
+    pub fn get(url: impl Into<String>) -> Self {
+        HttpRequest {
+            method: HttpMethod::Get,
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Creates a `POST` request for the `url`, with the given `body`.
+    pub fn post(url: impl Into<String>, body: impl Into<Vec<u8>>) -> Self {
+        HttpRequest {
+            method: HttpMethod::Post,
+            url: url.into(),
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Adds a header to the request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// The response to an [`HttpRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HttpResponse {
+    /// The status code of the response.
+    pub status: u16,
+    /// The headers of the response, as names and values.
+    pub headers: Vec<(String, String)>,
+    /// The body of the response.
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Creates a response with the given `status` and `body`, and no headers.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Returns the value of the first header with the given name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
@@ -11,6 +11,7 @@ pub mod abi;
 pub mod crypto;
 pub mod data_types;
 mod graphql;
+pub mod http;
 pub mod identifiers;
 pub mod ownership;
 #[cfg(with_metrics)]
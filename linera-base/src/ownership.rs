@@ -6,7 +6,11 @@
 
 use crate::{crypto::PublicKey, data_types::Round, doc_scalar, identifiers::Owner};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, iter, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    time::Duration,
+};
 
 /// The timeout configuration: how long fast, multi-leader and single-leader rounds last.
 #[derive(PartialEq, Eq, Clone, Hash, Debug, Serialize, Deserialize)]
@@ -40,6 +44,14 @@ pub struct ChainOwnership {
     pub multi_leader_rounds: u32,
     /// The timeout configuration: how long fast, multi-leader and single-leader rounds last.
     pub timeout_config: TimeoutConfig,
+    /// If set, every block proposal must be approved by regular owners whose weights add
+    /// up to at least this threshold. The proposer counts as one of the approvers. Super
+    /// owners have no weight, so that a single super owner key cannot bypass the threshold.
+    pub approval_threshold: Option<u64>,
+    /// Whether the chain is public, i.e. anyone can propose blocks in the multi-leader
+    /// rounds, not only the owners. Proposers who are not owners must sign their blocks and
+    /// pay the fees from their own account on the chain.
+    pub open_multi_leader_rounds: bool,
 }
 
 impl ChainOwnership {
@@ -50,6 +62,8 @@ impl ChainOwnership {
             owners: BTreeMap::new(),
             multi_leader_rounds: 2,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
+            open_multi_leader_rounds: false,
         }
     }
 
@@ -67,9 +81,23 @@ impl ChainOwnership {
                 .collect(),
             multi_leader_rounds,
             timeout_config,
+            approval_threshold: None,
+            open_multi_leader_rounds: false,
         }
     }
 
+    /// Requires block proposals to be approved by owners with the given total weight.
+    pub fn with_approval_threshold(mut self, threshold: u64) -> Self {
+        self.approval_threshold = Some(threshold);
+        self
+    }
+
+    /// Allows anyone to propose blocks in the multi-leader rounds, paying their own fees.
+    pub fn with_open_multi_leader_rounds(mut self) -> Self {
+        self.open_multi_leader_rounds = true;
+        self
+    }
+
     /// Adds a regular owner.
     pub fn with_regular_owner(mut self, public_key: PublicKey, weight: u64) -> Self {
         self.owners
@@ -82,6 +110,25 @@ impl ChainOwnership {
         !self.super_owners.is_empty() || !self.owners.is_empty()
     }
 
+    /// Returns the total weight of the given regular owners. Other approvers, including super
+    /// owners who are not also regular owners, are ignored, and each owner is only counted
+    /// once.
+    pub fn approval_weight<'a>(&self, approvers: impl IntoIterator<Item = &'a Owner>) -> u64 {
+        approvers
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter_map(|owner| self.owners.get(owner))
+            .fold(0, |total, (_, weight)| total.saturating_add(*weight))
+    }
+
+    /// Returns whether the approval threshold, if any, can be reached by the regular owners.
+    pub fn is_approval_threshold_reachable(&self) -> bool {
+        self.approval_threshold.map_or(true, |threshold| {
+            threshold > 0 && threshold <= self.approval_weight(self.owners.keys())
+        })
+    }
+
     /// Returns the given owner's public key, if they are an owner or super owner.
     pub fn verify_owner(&self, owner: &Owner) -> Option<PublicKey> {
         if let Some(public_key) = self.super_owners.get(owner) {
@@ -91,6 +138,12 @@ impl ChainOwnership {
         }
     }
 
+    /// Returns whether the signer is allowed to propose blocks only because the chain is
+    /// public, i.e. they are neither an owner nor a super owner.
+    pub fn is_open_proposer(&self, signer: &Owner) -> bool {
+        self.open_multi_leader_rounds && self.verify_owner(signer).is_none()
+    }
+
     /// Returns the duration of the given round.
     pub fn round_timeout(&self, round: Round) -> Option<Duration> {
         let tc = &self.timeout_config;
@@ -188,6 +241,8 @@ mod tests {
                 base_timeout: Duration::from_secs(10),
                 timeout_increment: Duration::from_secs(1),
             },
+            approval_threshold: None,
+            open_multi_leader_rounds: false,
         };
 
         assert_eq!(
@@ -212,6 +267,46 @@ mod tests {
             Some(Duration::from_secs(18))
         );
     }
+
+    #[test]
+    fn test_ownership_approval_weight() {
+        use crate::crypto::KeyPair;
+
+        let super_pub_key = KeyPair::generate().public();
+        let pub_keys = (0..3)
+            .map(|_| KeyPair::generate().public())
+            .collect::<Vec<_>>();
+        let owners = pub_keys
+            .iter()
+            .copied()
+            .map(Owner::from)
+            .collect::<Vec<_>>();
+        let mut ownership = ChainOwnership::multiple(
+            pub_keys.iter().copied().zip([1, 1, 2]),
+            2,
+            TimeoutConfig::default(),
+        )
+        .with_approval_threshold(3);
+        let super_owner = Owner::from(super_pub_key);
+        ownership.super_owners.insert(super_owner, super_pub_key);
+        assert!(ownership.is_approval_threshold_reachable());
+
+        assert_eq!(ownership.approval_weight(&owners[..1]), 1);
+        assert_eq!(ownership.approval_weight(&owners[1..]), 3);
+        // Duplicates and super owners don't count.
+        assert_eq!(
+            ownership.approval_weight([&owners[0], &owners[0], &super_owner]),
+            1
+        );
+
+        assert!(!ownership
+            .clone()
+            .with_approval_threshold(5)
+            .is_approval_threshold_reachable());
+        assert!(!ownership
+            .with_approval_threshold(0)
+            .is_approval_threshold_reachable());
+    }
 }
 
 doc_scalar!(ChainOwnership, "Represents the owner(s) of a chain");
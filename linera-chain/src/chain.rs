@@ -16,17 +16,19 @@ use linera_base::{
     crypto::CryptoHash,
     data_types::{Amount, ArithmeticError, BlockHeight, Timestamp},
     ensure,
-    identifiers::{ChainId, Destination, MessageId},
+    identifiers::{ChainId, Destination, MessageId, Owner},
 };
 use linera_execution::{
-    system::SystemMessage, ExecutionOutcome, ExecutionRuntimeContext, ExecutionStateView,
-    GenericApplicationId, Message, MessageContext, OperationContext, Query, QueryContext,
-    RawExecutionOutcome, RawOutgoingMessage, ResourceController, ResourceTracker, Response,
+    committee::Epoch, system::SystemMessage, ApplicationLog, BlockRandomness, ExecutionOutcome,
+    ExecutionRuntimeContext, ExecutionStateView, GenericApplicationId, Message, MessageContext,
+    Operation, OperationContext, Query, QueryContext, RawExecutionOutcome, RawOutgoingMessage,
+    ResourceControlPolicy, ResourceController, ResourceTracker, Response,
     UserApplicationDescription, UserApplicationId,
 };
 use linera_views::{
     common::Context,
     log_view::LogView,
+    map_view::MapView,
     reentrant_collection_view::ReentrantCollectionView,
     register_view::RegisterView,
     set_view::SetView,
@@ -35,6 +37,7 @@ use linera_views::{
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashSet},
+    mem,
     sync::Arc,
 };
 
@@ -228,6 +231,11 @@ where
     pub outbox_counters: RegisterView<C, BTreeMap<BlockHeight, u32>>,
     /// Channels able to multicast messages to subscribers.
     pub channels: ReentrantCollectionView<C, ChannelFullName, ChannelStateView<C>>,
+    /// The messages logged by contracts in the latest blocks, if this node keeps them.
+    #[graphql(skip)]
+    pub application_logs: MapView<C, BlockHeight, Vec<ApplicationLog>>,
+    /// Epoch of the latest certified block in this chain, if any.
+    pub tip_epoch: RegisterView<C, Option<Epoch>>,
 }
 
 /// Block-chaining state.
@@ -446,6 +454,64 @@ where
         Ok(())
     }
 
+    /// Verifies the deadlines of the incoming messages against `local_time`, the validator's
+    /// own clock at the time of validation, rather than the proposer-chosen block timestamp,
+    /// which could be in the past:
+    /// * The block must not accept any message that is expired at `local_time`.
+    /// * Once the incoming messages of `block` have been removed from the inboxes, no inbox
+    ///   may start with a tracked message that is expired at `local_time`. The block should
+    ///   have rejected such a message, so that it bounces back to its sender. Blocks that
+    ///   already contain the maximum number of incoming messages are exempt.
+    ///
+    /// Confirmed blocks are only checked against their own timestamp during execution, so
+    /// the deadline of an accepted message is enforced by the local clocks of the quorum that
+    /// voted for the proposal.
+    pub async fn validate_message_deadlines(
+        &self,
+        block: &Block,
+        local_time: Timestamp,
+    ) -> Result<(), ChainError> {
+        let chain_id = self.chain_id();
+        for message in &block.incoming_messages {
+            ensure!(
+                message.action != MessageAction::Accept || !message.event.is_expired(local_time),
+                ChainError::ExpiredMessage {
+                    chain_id,
+                    origin: Box::new(message.origin.clone()),
+                    event: message.event.clone(),
+                }
+            );
+        }
+        let Some((_, committee)) = self.execution_state.system.current_committee() else {
+            return Err(ChainError::InactiveChain(chain_id));
+        };
+        let maximum = committee.policy().maximum_incoming_messages_per_block;
+        if block.incoming_messages.len() as u64 >= maximum {
+            return Ok(());
+        }
+        let origins = self.inboxes.indices().await?;
+        let inboxes = self.inboxes.try_load_entries(&origins).await?;
+        let stream = origins.into_iter().zip(inboxes);
+        let max_stream_queries = self.context().max_stream_queries();
+        let stream = stream::iter(stream)
+            .map(|(origin, inbox)| async move {
+                if let Some(event) = inbox.added_events.front().await? {
+                    ensure!(
+                        !event.is_tracked() || !event.is_expired(local_time),
+                        ChainError::UnrejectedExpiredMessage {
+                            chain_id,
+                            origin: origin.into(),
+                            event,
+                        }
+                    );
+                }
+                Ok::<(), ChainError>(())
+            })
+            .buffer_unordered(max_stream_queries);
+        stream.try_collect::<Vec<_>>().await?;
+        Ok(())
+    }
+
     pub async fn next_block_height_to_receive(
         &mut self,
         origin: &Origin,
@@ -502,6 +568,8 @@ where
                 grant,
                 refund_grant_to,
                 kind,
+                deadline,
+                response_to,
                 message,
             } = outgoing_message;
             // See if the chain needs initialization.
@@ -525,6 +593,8 @@ where
                 refund_grant_to,
                 kind,
                 timestamp: bundle.timestamp,
+                deadline,
+                response_to,
                 message,
             });
         }
@@ -613,15 +683,116 @@ where
         Ok(())
     }
 
+    /// Returns an error if the block is larger than the current policy allows.
+    fn check_block_limits(block: &Block, policy: &ResourceControlPolicy) -> Result<(), ChainError> {
+        let count = block.operations.len() as u64;
+        ensure!(
+            count <= policy.maximum_operations_per_block,
+            ChainError::TooManyOperations {
+                count,
+                maximum: policy.maximum_operations_per_block,
+            }
+        );
+        let count = block.incoming_messages.len() as u64;
+        ensure!(
+            count <= policy.maximum_incoming_messages_per_block,
+            ChainError::TooManyIncomingMessages {
+                count,
+                maximum: policy.maximum_incoming_messages_per_block,
+            }
+        );
+        let size = block
+            .operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::System(_) => 0,
+                Operation::User { bytes, .. } => bytes.len() as u64,
+            })
+            .sum::<u64>();
+        ensure!(
+            size <= policy.maximum_operation_bytes_per_block,
+            ChainError::ExcessiveOperationBytes {
+                size,
+                maximum: policy.maximum_operation_bytes_per_block,
+            }
+        );
+        let size = bcs::serialized_size(block).expect("Serializing blocks should not fail") as u64;
+        ensure!(
+            size <= policy.maximum_block_size,
+            ChainError::BlockTooLarge {
+                size,
+                maximum: policy.maximum_block_size,
+            }
+        );
+        Ok(())
+    }
+
+    /// Returns an error if the transactions executed so far created too many messages or
+    /// consumed too much fuel.
+    fn check_execution_limits(
+        resource_controller: &ResourceController<Option<Owner>>,
+    ) -> Result<(), ChainError> {
+        let policy = &resource_controller.policy;
+        let tracker = &resource_controller.tracker;
+        ensure!(
+            u64::from(tracker.messages) <= policy.maximum_outgoing_messages_per_block,
+            ChainError::TooManyOutgoingMessages {
+                count: tracker.messages.into(),
+                maximum: policy.maximum_outgoing_messages_per_block,
+            }
+        );
+        ensure!(
+            tracker.fuel <= policy.maximum_fuel_per_block,
+            ChainError::ExcessiveFuel {
+                fuel: tracker.fuel,
+                maximum: policy.maximum_fuel_per_block,
+            }
+        );
+        Ok(())
+    }
+
     /// Executes a new block: first the incoming messages, then the main operation.
     /// * Modifies the state of inboxes, outboxes, and channels, if needed.
     /// * As usual, in case of errors, `self` may not be consistent any more and should be thrown
     ///   away.
     /// * Returns the list of messages caused by the block being executed.
+    /// * If the execution fails and the messages logged by contracts are recorded, they are
+    ///   returned in a [`ChainError::ExecutionFailedWithLogs`].
     pub async fn execute_block(
         &mut self,
         block: &Block,
         local_time: Timestamp,
+    ) -> Result<BlockExecutionOutcome, ChainError> {
+        let mut application_logs = Vec::new();
+        // Discard the logs of any earlier execution that did not complete.
+        self.take_application_logs(ChainExecutionContext::Block);
+        let result = self
+            .execute_block_with_logs(block, local_time, &mut application_logs)
+            .await;
+        result.map_err(|error| {
+            let context = match &error {
+                ChainError::ExecutionError(_, context) => *context,
+                _ => ChainExecutionContext::Block,
+            };
+            application_logs.extend(self.take_application_logs(context));
+            if application_logs.is_empty() {
+                error
+            } else {
+                ChainError::ExecutionFailedWithLogs {
+                    error: Box::new(error),
+                    logs: application_logs,
+                }
+            }
+        })
+    }
+
+    /// Executes a new block, adding the messages logged by contracts to `application_logs`
+    /// after each transaction.
+    async fn execute_block_with_logs(
+        &mut self,
+        block: &Block,
+        local_time: Timestamp,
+        application_logs: &mut Vec<ApplicationLog>,
     ) -> Result<BlockExecutionOutcome, ChainError> {
         #[cfg(with_metrics)]
         let _execution_latency = BLOCK_EXECUTION_LATENCY.measure_latency();
@@ -636,8 +807,29 @@ where
         let Some((_, committee)) = self.execution_state.system.current_committee() else {
             return Err(ChainError::InactiveChain(chain_id));
         };
+        let seed = match (&block.previous_block_signatures, block.previous_block_hash) {
+            (None, _) => {
+                // Proposers must not be able to omit the signatures when the seed doesn't suit
+                // them.
+                ensure!(
+                    *self.tip_epoch.get() != Some(block.epoch),
+                    ChainError::MissingPreviousBlockSignatures
+                );
+                None
+            }
+            (Some(signatures), Some(previous_block_hash)) => {
+                Some(signatures.check(chain_id, previous_block_hash, committee)?)
+            }
+            (Some(_), None) => return Err(ChainError::UnexpectedPreviousBlockSignatures),
+        };
+        let policy = Arc::new(committee.policy().clone());
+        self.execution_state
+            .system
+            .randomness
+            .set(seed.map(BlockRandomness::new));
+        Self::check_block_limits(block, &policy)?;
         let mut resource_controller = ResourceController {
-            policy: Arc::new(committee.policy().clone()),
+            policy,
             tracker: ResourceTracker::default(),
             account: block.authenticated_signer,
         };
@@ -651,6 +843,24 @@ where
             );
         }
 
+        // Proposers who don't own a public chain can't use its funds or change its
+        // configuration, and pay an additional fee against spam.
+        let ownership = self.execution_state.system.ownership.get();
+        if block
+            .authenticated_signer
+            .is_some_and(|signer| ownership.is_open_proposer(&signer))
+        {
+            ensure!(
+                block.has_only_open_proposer_actions(),
+                ChainError::RestrictedOpenProposal
+            );
+            resource_controller
+                .with_state(&mut self.execution_state)
+                .await?
+                .track_open_proposal()
+                .map_err(|err| ChainError::ExecutionError(err, ChainExecutionContext::Block))?;
+        }
+
         // The first incoming message of any child chain must be `OpenChain`. A root chain must
         // already be initialized
         if block.height == BlockHeight::ZERO
@@ -696,10 +906,19 @@ where
                 },
                 authenticated_signer: message.event.authenticated_signer,
                 refund_grant_to: message.event.refund_grant_to,
+                response_to: message.event.response_to,
                 next_message_index,
             };
             let outcomes = match message.action {
                 MessageAction::Accept => {
+                    ensure!(
+                        !message.event.is_expired(block.timestamp),
+                        ChainError::ExpiredMessage {
+                            chain_id,
+                            origin: Box::new(message.origin.clone()),
+                            event: message.event.clone(),
+                        }
+                    );
                     let mut grant = message.event.grant;
                     let mut outcomes = self
                         .execution_state
@@ -789,8 +1008,12 @@ where
             messages.append(&mut messages_out);
             message_counts
                 .push(u32::try_from(messages.len()).map_err(|_| ArithmeticError::Overflow)?);
+            application_logs.extend(self.take_application_logs(chain_execution_context));
+            Self::check_execution_limits(&resource_controller)?;
         }
         // Second, execute the operations in the block and remember the recipients to notify.
+        // Keep track of the fees paid by each sponsoring application in this block.
+        let mut sponsored_fees = BTreeMap::<UserApplicationId, Amount>::new();
         for (index, operation) in block.operations.iter().enumerate() {
             let app_permissions = self.execution_state.system.application_permissions.get();
             ensure!(
@@ -812,29 +1035,73 @@ where
                 authenticated_signer: block.authenticated_signer,
                 next_message_index,
             };
+            // The application targeted by the operation may pay (part of) its fees.
+            let sponsoring_application_id = match operation {
+                Operation::User { application_id, .. } => Some(*application_id),
+                Operation::System(_) => None,
+            };
+            let mut sponsor = match sponsoring_application_id {
+                Some(application_id) => {
+                    let paid_in_block = sponsored_fees
+                        .get(&application_id)
+                        .copied()
+                        .unwrap_or_default();
+                    self.execution_state
+                        .system
+                        .take_sponsored_fees(application_id, paid_in_block)
+                        .await
+                        .map_err(|err| {
+                            ChainError::ExecutionError(err.into(), chain_execution_context)
+                        })?
+                }
+                None => None,
+            };
+            let initial_sponsor = sponsor;
             let outcomes = self
                 .execution_state
-                .execute_operation(context, operation.clone(), &mut resource_controller)
+                .execute_operation_with_sponsor(
+                    context,
+                    operation.clone(),
+                    sponsor.as_mut(),
+                    &mut resource_controller,
+                )
                 .await
                 .map_err(|err| ChainError::ExecutionError(err, chain_execution_context))?;
             let mut messages_out = self
                 .process_execution_outcomes(context.height, outcomes)
                 .await?;
             resource_controller
-                .with_state(&mut self.execution_state)
+                .with_state_and_sponsor(&mut self.execution_state, sponsor.as_mut())
                 .await?
                 .track_operation(operation)
                 .map_err(|err| ChainError::ExecutionError(err, chain_execution_context))?;
             for message_out in &messages_out {
                 resource_controller
-                    .with_state(&mut self.execution_state)
+                    .with_state_and_sponsor(&mut self.execution_state, sponsor.as_mut())
                     .await?
                     .track_message(&message_out.message)
                     .map_err(|err| ChainError::ExecutionError(err, chain_execution_context))?;
             }
+            if let (Some(application_id), Some(initial), Some(remaining)) =
+                (sponsoring_application_id, initial_sponsor, sponsor)
+            {
+                sponsored_fees
+                    .entry(application_id)
+                    .or_default()
+                    .try_add_assign(initial.saturating_sub(remaining))?;
+                self.execution_state
+                    .system
+                    .return_sponsored_fees(application_id, remaining)
+                    .await
+                    .map_err(|err| {
+                        ChainError::ExecutionError(err.into(), chain_execution_context)
+                    })?;
+            }
             messages.append(&mut messages_out);
             message_counts
                 .push(u32::try_from(messages.len()).map_err(|_| ArithmeticError::Overflow)?);
+            application_logs.extend(self.take_application_logs(chain_execution_context));
+            Self::check_execution_limits(&resource_controller)?;
         }
 
         // Finally, charge for the block fee, except if the chain is closed. Closed chains should
@@ -847,6 +1114,23 @@ where
                 .map_err(|err| ChainError::ExecutionError(err, ChainExecutionContext::Block))?;
         }
 
+        // Set aside the validators' share of the fees, to be paid out at the end of the epoch.
+        let validator_fees = resource_controller
+            .policy
+            .validator_fees(resource_controller.tracker.fees);
+        if validator_fees > Amount::ZERO {
+            self.execution_state
+                .system
+                .collected_fees
+                .get_mut()
+                .try_add_assign(validator_fees)?;
+        }
+
+        self.store_application_logs(block.height, mem::take(application_logs))
+            .await?;
+        // The randomness only applies to this block, so it must not affect the state hash.
+        self.execution_state.system.randomness.set(None);
+
         // Recompute the state hash.
         let state_hash = {
             #[cfg(with_metrics)]
@@ -890,6 +1174,52 @@ where
         })
     }
 
+    /// Returns the messages logged by contracts since the last call, as logs of the
+    /// transaction executed in the given `context`.
+    fn take_application_logs(&self, context: ChainExecutionContext) -> Vec<ApplicationLog> {
+        let Some(logger) = self.context().extra().application_logger() else {
+            return Vec::new();
+        };
+        let mut logs = logger.take();
+        for log in &mut logs {
+            match context {
+                ChainExecutionContext::IncomingMessage(index) => {
+                    log.incoming_message_index = Some(index)
+                }
+                ChainExecutionContext::Operation(index) => log.operation_index = Some(index),
+                _ => {}
+            }
+        }
+        logs
+    }
+
+    /// Keeps the `logs` of the block at the given `height`, and removes the logs of the blocks
+    /// that are no longer retained.
+    async fn store_application_logs(
+        &mut self,
+        height: BlockHeight,
+        logs: Vec<ApplicationLog>,
+    ) -> Result<(), ChainError> {
+        let Some(logger) = self.context().extra().application_logger() else {
+            return Ok(());
+        };
+        let first_retained_height = BlockHeight(
+            height
+                .0
+                .saturating_add(1)
+                .saturating_sub(logger.retained_blocks()),
+        );
+        for index in self.application_logs.indices().await? {
+            if index < first_retained_height {
+                self.application_logs.remove(&index)?;
+            }
+        }
+        if !logs.is_empty() && height >= first_retained_height {
+            self.application_logs.insert(&height, logs)?;
+        }
+        Ok(())
+    }
+
     async fn process_execution_outcomes(
         &mut self,
         height: BlockHeight,
@@ -947,6 +1277,8 @@ where
             authenticated,
             grant,
             kind,
+            deadline,
+            response_to,
             message,
         } in raw_outcome.messages
         {
@@ -966,6 +1298,8 @@ where
                 grant,
                 refund_grant_to,
                 kind,
+                deadline,
+                response_to,
                 message: lift(message),
             });
         }
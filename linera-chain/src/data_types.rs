@@ -5,13 +5,14 @@
 use crate::ChainError;
 use async_graphql::{Object, SimpleObject};
 use linera_base::{
-    crypto::{BcsHashable, BcsSignable, CryptoHash, KeyPair, Signature},
+    crypto::{BcsHashable, BcsSignable, CryptoHash, KeyPair, PublicKey, Signature},
     data_types::{Amount, BlockHeight, Round, Timestamp},
     doc_scalar, ensure,
     identifiers::{Account, ChainId, ChannelName, Destination, MessageId, Owner},
 };
 use linera_execution::{
     committee::{Committee, Epoch, ValidatorName},
+    system::SystemOperation,
     BytecodeLocation, GenericApplicationId, Message, MessageKind, Operation,
 };
 use serde::{de::Deserializer, Deserialize, Serialize};
@@ -55,6 +56,10 @@ pub struct Block {
     /// Certified hash (see `Certificate` below) of the previous block in the
     /// chain, if any.
     pub previous_block_hash: Option<CryptoHash>,
+    /// The validators' signatures on the previous block, which are required if it was
+    /// certified in the same epoch. These are used to seed the randomness available to
+    /// applications.
+    pub previous_block_signatures: Option<PreviousBlockSignatures>,
 }
 
 impl Block {
@@ -83,6 +88,22 @@ impl Block {
                 .iter()
                 .all(|message| message.action == MessageAction::Reject)
     }
+
+    /// Returns whether the block only accepts incoming messages and executes user operations
+    /// or system operations spending the signer's own funds. This is all that proposers who
+    /// don't own a public chain are allowed to do. During execution, applications are not
+    /// allowed to spend the chain's balance or to close the chain on their behalf either.
+    pub fn has_only_open_proposer_actions(&self) -> bool {
+        self.incoming_messages
+            .iter()
+            .all(|message| message.action == MessageAction::Accept)
+            && self.operations.iter().all(|operation| match operation {
+                Operation::User { .. } => true,
+                Operation::System(SystemOperation::Transfer { owner, .. }) => owner.is_some(),
+                Operation::System(SystemOperation::Claim { .. }) => true,
+                Operation::System(_) => false,
+            })
+    }
 }
 
 /// A chain ID with a block height.
@@ -151,6 +172,10 @@ pub struct Event {
     pub kind: MessageKind,
     /// The timestamp of the block that caused the message.
     pub timestamp: Timestamp,
+    /// The time after which the message can no longer be accepted, if any.
+    pub deadline: Option<Timestamp>,
+    /// The request that this message answers, or the original message if it is bouncing.
+    pub response_to: Option<MessageId>,
     /// The message of the event (i.e. the actual payload of a message).
     pub message: Message,
 }
@@ -215,9 +240,26 @@ pub enum Medium {
 pub struct BlockProposal {
     pub content: BlockAndRound,
     pub owner: Owner,
+    /// The public key of the owner, needed to verify proposals by signers who are not
+    /// among the chain's owners.
+    pub public_key: PublicKey,
     pub signature: Signature,
     pub blobs: Vec<HashedValue>,
     pub validated: Option<Certificate>,
+    /// Approvals of the block by other owners, if the chain requires several of them.
+    pub approvals: Vec<BlockApproval>,
+}
+
+/// The approval of a block by one of the owners of a chain, for chains that require block
+/// proposals to be signed by several owners.
+///
+/// The approval doesn't depend on the round, so it can be collected out of band before
+/// the block is proposed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(any(test, feature = "test"), derive(Eq, PartialEq))]
+pub struct BlockApproval {
+    pub owner: Owner,
+    pub signature: Signature,
 }
 
 /// A message together with routing information.
@@ -233,6 +275,10 @@ pub struct OutgoingMessage {
     pub refund_grant_to: Option<Account>,
     /// The kind of event being sent.
     pub kind: MessageKind,
+    /// The time after which the receiver can no longer accept the message, if any.
+    pub deadline: Option<Timestamp>,
+    /// The request that this message answers, or the original message if it is bouncing.
+    pub response_to: Option<MessageId>,
     /// The message itself.
     pub message: Message,
 }
@@ -499,6 +545,16 @@ pub struct Certificate {
     signatures: Vec<(ValidatorName, Signature)>,
 }
 
+/// The signatures of a certificate for the previous block of a chain, included in the next
+/// block so that its execution can derive randomness from them.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct PreviousBlockSignatures {
+    /// The round in which the previous block was confirmed.
+    pub round: Round,
+    /// The first quorum of validators' signatures, ordered by validator name.
+    pub signatures: Vec<(ValidatorName, Signature)>,
+}
+
 impl Origin {
     pub fn chain(sender: ChainId) -> Self {
         Self {
@@ -668,6 +724,11 @@ impl Event {
     pub fn is_bouncing(&self) -> bool {
         matches!(self.kind, MessageKind::Bouncing)
     }
+
+    /// Returns whether the deadline of the message has passed at the given time.
+    pub fn is_expired(&self, timestamp: Timestamp) -> bool {
+        self.deadline.is_some_and(|deadline| deadline < timestamp)
+    }
 }
 
 impl ExecutedBlock {
@@ -796,11 +857,34 @@ impl BlockProposal {
         Self {
             content,
             owner: secret.public().into(),
+            public_key: secret.public(),
             signature,
             blobs,
             validated,
+            approvals: Vec::new(),
+        }
+    }
+
+    /// Adds approvals of the block by other owners.
+    pub fn with_approvals(mut self, approvals: impl IntoIterator<Item = BlockApproval>) -> Self {
+        self.approvals.extend(approvals);
+        self
+    }
+}
+
+impl BlockApproval {
+    /// Approves the block with the given owner key.
+    pub fn new(block: &Block, key_pair: &KeyPair) -> Self {
+        Self {
+            owner: key_pair.public().into(),
+            signature: Signature::new(block, key_pair),
         }
     }
+
+    /// Verifies that the approval was signed by the given key, for the given block.
+    pub fn check(&self, block: &Block, public_key: PublicKey) -> Result<(), ChainError> {
+        Ok(self.signature.check(block, public_key)?)
+    }
 }
 
 impl LiteVote {
@@ -1006,6 +1090,63 @@ impl Certificate {
     }
 }
 
+impl PreviousBlockSignatures {
+    /// Takes the signatures from the certificate of the previous block, keeping only the
+    /// first validators by name whose weight in the `committee` reaches a quorum.
+    pub fn new(certificate: &Certificate, committee: &Committee) -> Self {
+        let mut weight = 0;
+        let signatures = certificate
+            .signatures()
+            .iter()
+            .take_while(|(validator, _)| {
+                let is_needed = weight < committee.quorum_threshold();
+                weight += committee.weight(validator);
+                is_needed
+            })
+            .cloned()
+            .collect();
+        Self {
+            round: certificate.round,
+            signatures,
+        }
+    }
+
+    /// Verifies that these are a quorum of signatures by the `committee` on the block with
+    /// the given hash, and returns the randomness seed derived from them.
+    ///
+    /// The signatures must be ordered and the last one must be needed for the quorum, so that
+    /// a proposer can't choose among different encodings of the same signatures, or add
+    /// signatures to change the seed.
+    pub fn check(
+        &self,
+        chain_id: ChainId,
+        previous_block_hash: CryptoHash,
+        committee: &Committee,
+    ) -> Result<CryptoHash, ChainError> {
+        ensure!(
+            is_strictly_ordered(&self.signatures),
+            ChainError::UnorderedPreviousBlockSignatures
+        );
+        let weight_without_last = self
+            .signatures
+            .iter()
+            .rev()
+            .skip(1)
+            .map(|(validator, _)| committee.weight(validator))
+            .sum::<u64>();
+        ensure!(
+            weight_without_last < committee.quorum_threshold(),
+            ChainError::ExcessivePreviousBlockSignatures
+        );
+        let value = LiteValue {
+            value_hash: previous_block_hash,
+            chain_id,
+        };
+        check_signatures(&value, self.round, &self.signatures, committee)?;
+        Ok(CryptoHash::new(self))
+    }
+}
+
 /// Verifies certificate signatures.
 fn check_signatures(
     value: &LiteValue,
@@ -1038,12 +1179,20 @@ fn check_signatures(
     Ok(())
 }
 
+impl BcsSignable for Block {}
+
 impl BcsSignable for BlockAndRound {}
 
 impl BcsSignable for ValueHashAndRound {}
 
 impl BcsHashable for CertificateValue {}
 
+impl BcsHashable for PreviousBlockSignatures {}
+
+doc_scalar!(
+    PreviousBlockSignatures,
+    "The validators' signatures on the previous block of a chain"
+);
 doc_scalar!(
     MessageAction,
     "Whether an incoming message is accepted or rejected"
@@ -1068,3 +1217,7 @@ doc_scalar!(
     Target,
     "The target of a message, relative to a particular application. Used to identify each outbox."
 );
+doc_scalar!(
+    BlockApproval,
+    "The approval of a block by one of the regular owners of a chain"
+);
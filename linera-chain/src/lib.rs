@@ -20,7 +20,7 @@ use linera_base::{
     data_types::{ArithmeticError, BlockHeight, Round, Timestamp},
     identifiers::{ApplicationId, ChainId},
 };
-use linera_execution::ExecutionError;
+use linera_execution::{ApplicationLog, ExecutionError};
 use linera_views::views::ViewError;
 use rand_distr::WeightedError;
 use thiserror::Error;
@@ -75,6 +75,18 @@ pub enum ChainError {
         origin: Box<Origin>,
         event: Event,
     },
+    #[error("Block proposed to {chain_id:?} is attempting to accept expired message {event:?}")]
+    ExpiredMessage {
+        chain_id: ChainId,
+        origin: Box<Origin>,
+        event: Event,
+    },
+    #[error("Block proposed to {chain_id:?} must reject expired message {event:?}")]
+    UnrejectedExpiredMessage {
+        chain_id: ChainId,
+        origin: Box<Origin>,
+        event: Event,
+    },
     #[error(
         "Block proposed to {chain_id:?} is attempting to skip a message \
          that cannot be skipped: {event:?}"
@@ -114,6 +126,11 @@ pub enum ChainError {
     InvalidBlockProposal,
     #[error("Round number should be at least {0:?}")]
     InsufficientRound(Round),
+    #[error(
+        "The block proposal is approved by owners with a total weight of {weight}, \
+         but the chain requires {threshold}"
+    )]
+    InsufficientApprovals { weight: u64, threshold: u64 },
     #[error("Round number should greater than {0:?}")]
     InsufficientRoundStrict(Round),
     #[error("Round number should be {0:?}")]
@@ -126,6 +143,14 @@ pub enum ChainError {
     CertificateValidatorReuse,
     #[error("Signatures in a certificate must form a quorum")]
     CertificateRequiresQuorum,
+    #[error("The signatures on the previous block must be ordered by validator")]
+    UnorderedPreviousBlockSignatures,
+    #[error("The block includes signatures on a previous block, but the chain has none")]
+    UnexpectedPreviousBlockSignatures,
+    #[error("The block must include the signatures on the previous block of the same epoch")]
+    MissingPreviousBlockSignatures,
+    #[error("The signatures on the previous block must be the first quorum by validator")]
+    ExcessivePreviousBlockSignatures,
     #[error("Certificate signature verification failed: {error}")]
     CertificateSignatureVerificationFailed { error: String },
     #[error("Internal error {0}")]
@@ -138,6 +163,40 @@ pub enum ChainError {
     ClosedChain,
     #[error("All operations on this chain must be from one of the following applications: {0:?}")]
     AuthorizedApplications(Vec<ApplicationId>),
+    #[error(
+        "Proposers who don't own the chain can only accept messages and spend their own funds"
+    )]
+    RestrictedOpenProposal,
+    #[error("The block has {count} operations, but at most {maximum} are allowed")]
+    TooManyOperations { count: u64, maximum: u64 },
+    #[error("The block has {count} incoming messages, but at most {maximum} are allowed")]
+    TooManyIncomingMessages { count: u64, maximum: u64 },
+    #[error("The block creates {count} outgoing messages, but at most {maximum} are allowed")]
+    TooManyOutgoingMessages { count: u64, maximum: u64 },
+    #[error(
+        "The operations of the block have {size} bytes of arguments, \
+         but at most {maximum} are allowed"
+    )]
+    ExcessiveOperationBytes { size: u64, maximum: u64 },
+    #[error("The block has {size} bytes, but at most {maximum} are allowed")]
+    BlockTooLarge { size: u64, maximum: u64 },
+    #[error("The block consumes {fuel} units of fuel, but at most {maximum} are allowed")]
+    ExcessiveFuel { fuel: u64, maximum: u64 },
+    #[error("{error}\nMessages logged by the applications before the failure: {logs:?}")]
+    ExecutionFailedWithLogs {
+        error: Box<ChainError>,
+        logs: Vec<ApplicationLog>,
+    },
+}
+
+impl ChainError {
+    /// Returns the error that caused the failure, without the logs of the failed execution.
+    pub fn without_logs(&self) -> &ChainError {
+        match self {
+            ChainError::ExecutionFailedWithLogs { error, .. } => error.without_logs(),
+            error => error,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
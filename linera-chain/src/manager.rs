@@ -269,9 +269,37 @@ impl ChainManager {
                 ChainError::HasLockedBlock(block.height, locked.round)
             );
         }
+        // A validated block was already approved when it was first proposed.
+        if validated.is_none() {
+            self.check_approvals(proposal)?;
+        }
         Ok(Outcome::Accept)
     }
 
+    /// Verifies that the proposal has enough approvals from the chain owners, if the chain
+    /// requires it. The proposer's signature must already have been checked.
+    fn check_approvals(&self, proposal: &BlockProposal) -> Result<(), ChainError> {
+        let Some(threshold) = self.ownership.approval_threshold else {
+            return Ok(());
+        };
+        let block = &proposal.content.block;
+        let mut approvers = vec![&proposal.owner];
+        for approval in &proposal.approvals {
+            let public_key = self
+                .ownership
+                .verify_owner(&approval.owner)
+                .ok_or(ChainError::InvalidSigner)?;
+            approval.check(block, public_key)?;
+            approvers.push(&approval.owner);
+        }
+        let weight = self.ownership.approval_weight(approvers);
+        ensure!(
+            weight >= threshold,
+            ChainError::InsufficientApprovals { weight, threshold }
+        );
+        Ok(())
+    }
+
     /// Checks if the current round has timed out, and signs a `LeaderTimeout`.
     pub fn vote_leader_timeout(
         &mut self,
@@ -443,10 +471,13 @@ impl ChainManager {
             }
             Round::MultiLeader(_) => {
                 // Not in leader rotation mode; any owner is allowed to propose.
-                self.ownership
-                    .owners
-                    .get(&proposal.owner)
-                    .map(|(public_key, _)| *public_key)
+                if let Some((public_key, _)) = self.ownership.owners.get(&proposal.owner) {
+                    return Some(*public_key);
+                }
+                // On public chains, anyone else is allowed to propose, too.
+                (self.ownership.open_multi_leader_rounds
+                    && Owner::from(proposal.public_key) == proposal.owner)
+                    .then_some(proposal.public_key)
             }
             Round::SingleLeader(r) => {
                 let index = self.round_leader_index(r)?;
@@ -16,7 +16,7 @@ use linera_execution::{
 
 use crate::data_types::{
     Block, BlockAndRound, BlockProposal, Certificate, Event, HashedValue, IncomingMessage,
-    MessageAction, Origin, SignatureAggregator, Vote,
+    MessageAction, Origin, PreviousBlockSignatures, SignatureAggregator, Vote,
 };
 
 /// Creates a new child of the given block, with the same timestamp.
@@ -29,6 +29,7 @@ pub fn make_child_block(parent: &HashedValue) -> Block {
         incoming_messages: vec![],
         operations: vec![],
         previous_block_hash: Some(parent.hash()),
+        previous_block_signatures: None,
         height: parent_value.height().try_add_one().unwrap(),
         authenticated_signer: None,
         timestamp: parent_block.timestamp,
@@ -43,6 +44,7 @@ pub fn make_first_block(chain_id: ChainId) -> Block {
         incoming_messages: vec![],
         operations: vec![],
         previous_block_hash: None,
+        previous_block_signatures: None,
         height: BlockHeight::ZERO,
         authenticated_signer: None,
         timestamp: Timestamp::default(),
@@ -69,6 +71,14 @@ pub trait BlockTestExt: Sized {
     /// Returns the block with the specified epoch.
     fn with_epoch(self, epoch: impl Into<Epoch>) -> Self;
 
+    /// Returns the block with the `committee`'s signatures from the certificate of the
+    /// previous block.
+    fn with_previous_block_signatures(
+        self,
+        certificate: &Certificate,
+        committee: &Committee,
+    ) -> Self;
+
     /// Returns a block proposal in `Round::Fast` without any blobs or validated block.
     fn into_fast_proposal(self, key_pair: &KeyPair) -> BlockProposal {
         self.into_proposal_with_round(key_pair, Round::Fast)
@@ -112,6 +122,15 @@ impl BlockTestExt for Block {
         self
     }
 
+    fn with_previous_block_signatures(
+        mut self,
+        certificate: &Certificate,
+        committee: &Committee,
+    ) -> Self {
+        self.previous_block_signatures = Some(PreviousBlockSignatures::new(certificate, committee));
+        self
+    }
+
     fn into_proposal_with_round(self, key_pair: &KeyPair, round: Round) -> BlockProposal {
         let content = BlockAndRound { block: self, round };
         BlockProposal::new(content, key_pair, vec![], None)
@@ -128,6 +147,7 @@ impl VoteTestExt for Vote {
         let state = ValidatorState {
             network_address: "".to_string(),
             votes: 100,
+            fee_recipient: None,
         };
         let committee = Committee::new(
             vec![(self.validator, state)].into_iter().collect(),
@@ -158,6 +178,8 @@ impl<T: Into<Message>> MessageTestExt for T {
                 refund_grant_to: None,
                 kind: MessageKind::Protected,
                 timestamp: Timestamp::from(0),
+                deadline: None,
+                response_to: None,
                 message: self.into(),
             },
             action: MessageAction::Accept,
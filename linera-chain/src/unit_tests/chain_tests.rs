@@ -2,23 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    data_types::HashedValue,
+    data_types::{HashedValue, MessageAction, OutgoingMessage},
     test::{make_child_block, make_first_block, BlockTestExt, MessageTestExt},
-    ChainError, ChainStateView,
+    ChainError, ChainExecutionContext, ChainStateView,
 };
 use assert_matches::assert_matches;
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
     data_types::{Amount, BlockHeight, Timestamp},
-    identifiers::{ApplicationId, BytecodeId, ChainId, MessageId},
+    identifiers::{ApplicationId, BytecodeId, ChainId, Destination, MessageId},
     ownership::ChainOwnership,
 };
 use linera_execution::{
     committee::{Committee, Epoch},
     system::{ApplicationPermissions, OpenChainConfig},
     test_utils::{ExpectedCall, MockApplication},
-    BytecodeLocation, ExecutionRuntimeConfig, ExecutionRuntimeContext, Operation,
-    RawExecutionOutcome, SystemMessage, TestExecutionRuntimeContext, UserApplicationDescription,
+    ApplicationLog, ApplicationLogger, BytecodeLocation, ContractRuntime, ExecutionError,
+    ExecutionRuntimeConfig, ExecutionRuntimeContext, LogLevel, Message, MessageKind, Operation,
+    RawExecutionOutcome, ResourceControlPolicy, SystemMessage, TestExecutionRuntimeContext,
+    UserApplicationDescription,
 };
 use linera_views::{
     memory::{MemoryContext, TEST_MEMORY_MAX_STREAM_QUERIES},
@@ -53,6 +55,8 @@ fn make_app_description() -> UserApplicationDescription {
         creation: make_admin_message_id(BlockHeight(2)),
         required_application_ids: vec![],
         parameters: vec![],
+        governance: None,
+        upgrades: Vec::new(),
     }
 }
 
@@ -94,7 +98,7 @@ async fn test_application_permissions() {
     let extra = &chain.context().extra;
     extra
         .user_contracts()
-        .insert(application_id, application.clone());
+        .insert(application_id.bytecode_id, application.clone());
 
     // Initialize the chain, with a chain application.
     let config = OpenChainConfig {
@@ -153,3 +157,322 @@ async fn test_application_permissions() {
     let valid_block = make_child_block(&value).with_operation(app_operation);
     chain.execute_block(&valid_block, time).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_application_logs() {
+    let time = Timestamp::from(0);
+    let message_id = make_admin_message_id(BlockHeight(3));
+    let chain_id = ChainId::child(message_id);
+    let exec_runtime_context =
+        TestExecutionRuntimeContext::new(chain_id, ExecutionRuntimeConfig::Synchronous)
+            .with_application_logger(ApplicationLogger::new(2));
+    let context = MemoryContext::new(TEST_MEMORY_MAX_STREAM_QUERIES, exec_runtime_context);
+    let mut chain = ChainStateView::load(context).await.unwrap();
+
+    // Create a mock application.
+    let app_description = make_app_description();
+    let application_id = ApplicationId::from(&app_description);
+    let application = Arc::new(MockApplication::default());
+    let extra = &chain.context().extra;
+    extra
+        .user_contracts()
+        .insert(application_id.bytecode_id, application.clone());
+
+    let message = SystemMessage::OpenChain(make_open_chain_config()).into();
+    chain
+        .execute_init_message(message_id, &message, time, time)
+        .await
+        .unwrap();
+    let open_chain_message = message.to_simple_incoming(admin_id(), BlockHeight(1));
+    let register_app_message = SystemMessage::RegisterApplications {
+        applications: vec![app_description],
+    }
+    .to_simple_incoming(admin_id(), BlockHeight(2));
+    let app_operation = Operation::User {
+        application_id,
+        bytes: b"foo".to_vec(),
+    };
+    let expect_logging_operation = |message: &'static str| {
+        application.expect_call(ExpectedCall::execute_operation(move |runtime, _, _| {
+            runtime.log(LogLevel::Info, message)?;
+            Ok(RawExecutionOutcome::default())
+        }));
+    };
+
+    // The logs of the operations are kept with their index.
+    expect_logging_operation("first");
+    expect_logging_operation("second");
+    let block = make_first_block(chain_id)
+        .with_incoming_message(open_chain_message)
+        .with_incoming_message(register_app_message)
+        .with_operation(app_operation.clone())
+        .with_operation(app_operation.clone());
+    let outcome = chain.execute_block(&block, time).await.unwrap();
+    let mut value = HashedValue::new_confirmed(outcome.with(block));
+    let make_log = |operation_index, message: &str| ApplicationLog {
+        incoming_message_index: None,
+        operation_index: Some(operation_index),
+        application_id,
+        level: LogLevel::Info,
+        message: message.to_owned(),
+    };
+    assert_eq!(
+        chain.application_logs.get(&BlockHeight(0)).await.unwrap(),
+        Some(vec![make_log(0, "first"), make_log(1, "second")])
+    );
+
+    // Only the logs of the latest two blocks are kept.
+    for height in 1..3 {
+        expect_logging_operation("next");
+        let block = make_child_block(&value).with_operation(app_operation.clone());
+        let outcome = chain.execute_block(&block, time).await.unwrap();
+        value = HashedValue::new_confirmed(outcome.with(block));
+        assert_eq!(
+            chain
+                .application_logs
+                .get(&BlockHeight(height))
+                .await
+                .unwrap(),
+            Some(vec![make_log(0, "next")])
+        );
+    }
+    assert_eq!(
+        chain.application_logs.indices().await.unwrap(),
+        vec![BlockHeight(1), BlockHeight(2)]
+    );
+
+    // The logs of a failed execution are returned with the error.
+    expect_logging_operation("kept");
+    application.expect_call(ExpectedCall::execute_operation(|runtime, _, _| {
+        runtime.log(LogLevel::Info, "failed")?;
+        Err(ExecutionError::UserError("failure".to_owned()))
+    }));
+    let block = make_child_block(&value)
+        .with_operation(app_operation.clone())
+        .with_operation(app_operation);
+    let result = chain.execute_block(&block, time).await;
+    assert_matches!(
+        result,
+        Err(ChainError::ExecutionFailedWithLogs { error, logs })
+            if matches!(*error, ChainError::ExecutionError(
+                ExecutionError::UserError(_),
+                ChainExecutionContext::Operation(1)
+            )) && logs == vec![make_log(0, "kept"), make_log(1, "failed")]
+    );
+}
+
+#[tokio::test]
+async fn test_block_limits() {
+    let time = Timestamp::from(0);
+    let message_id = make_admin_message_id(BlockHeight(3));
+    let chain_id = ChainId::child(message_id);
+    let mut chain = ChainStateView::new(chain_id).await;
+
+    // Create a mock application.
+    let app_description = make_app_description();
+    let application_id = ApplicationId::from(&app_description);
+    let application = Arc::new(MockApplication::default());
+    let extra = &chain.context().extra;
+    extra
+        .user_contracts()
+        .insert(application_id.bytecode_id, application.clone());
+
+    // Initialize the chain, with a policy that only allows small blocks.
+    let policy = ResourceControlPolicy {
+        maximum_operations_per_block: 1,
+        maximum_incoming_messages_per_block: 2,
+        maximum_operation_bytes_per_block: 3,
+        maximum_fuel_per_block: 10,
+        ..ResourceControlPolicy::default()
+    };
+    let mut config = make_open_chain_config();
+    for committee in config.committees.values_mut() {
+        *committee = Committee::new(committee.validators().clone(), policy.clone());
+    }
+    let message = SystemMessage::OpenChain(config).into();
+    chain
+        .execute_init_message(message_id, &message, time, time)
+        .await
+        .unwrap();
+    let open_chain_message = message.to_simple_incoming(admin_id(), BlockHeight(1));
+    let register_app_message = SystemMessage::RegisterApplications {
+        applications: vec![app_description],
+    }
+    .to_simple_incoming(admin_id(), BlockHeight(2));
+    let first_block = make_first_block(chain_id)
+        .with_incoming_message(open_chain_message)
+        .with_incoming_message(register_app_message.clone());
+
+    let block = first_block
+        .clone()
+        .with_incoming_message(register_app_message);
+    let result = chain.execute_block(&block, time).await;
+    assert_matches!(
+        result,
+        Err(ChainError::TooManyIncomingMessages {
+            count: 3,
+            maximum: 2
+        })
+    );
+
+    let block = first_block
+        .clone()
+        .with_simple_transfer(chain_id, Amount::ONE)
+        .with_simple_transfer(chain_id, Amount::ONE);
+    let result = chain.execute_block(&block, time).await;
+    assert_matches!(
+        result,
+        Err(ChainError::TooManyOperations {
+            count: 2,
+            maximum: 1
+        })
+    );
+
+    let block = first_block.clone().with_operation(Operation::User {
+        application_id,
+        bytes: b"foobar".to_vec(),
+    });
+    let result = chain.execute_block(&block, time).await;
+    assert_matches!(
+        result,
+        Err(ChainError::ExcessiveOperationBytes {
+            size: 6,
+            maximum: 3
+        })
+    );
+
+    application.expect_call(ExpectedCall::execute_operation(|runtime, _, _| {
+        runtime.consume_fuel(11)?;
+        Ok(RawExecutionOutcome::default())
+    }));
+    let block = first_block.with_operation(Operation::User {
+        application_id,
+        bytes: b"foo".to_vec(),
+    });
+    let result = chain.execute_block(&block, time).await;
+    assert_matches!(
+        result,
+        Err(ChainError::ExcessiveFuel {
+            fuel: 11,
+            maximum: 10
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_expired_messages() {
+    let time = Timestamp::from(0);
+    let message_id = make_admin_message_id(BlockHeight(3));
+    let chain_id = ChainId::child(message_id);
+    let open_chain_message = SystemMessage::OpenChain(make_open_chain_config())
+        .to_simple_incoming(admin_id(), BlockHeight(1));
+    let init_message = &open_chain_message.event.message;
+    let new_chain = move || async move {
+        let mut chain = ChainStateView::new(chain_id).await;
+        chain
+            .execute_init_message(message_id, init_message, time, time)
+            .await
+            .unwrap();
+        chain
+    };
+
+    // A tracked transfer that must be accepted no later than at time 5.
+    let sender = ChainId::root(1);
+    let mut credit_message = SystemMessage::Credit {
+        target: None,
+        source: None,
+        amount: Amount::ONE,
+    }
+    .to_simple_incoming(sender, BlockHeight(1));
+    credit_message.event.kind = MessageKind::Tracked;
+    credit_message.event.deadline = Some(Timestamp::from(5));
+
+    let block = make_first_block(chain_id)
+        .with_incoming_message(open_chain_message.clone())
+        .with_incoming_message(credit_message.clone());
+    new_chain()
+        .await
+        .execute_block(&block.clone().with_timestamp(5), time)
+        .await
+        .unwrap();
+
+    // After the deadline, the message can't be accepted anymore.
+    let expired_block = block.with_timestamp(6);
+    let result = new_chain().await.execute_block(&expired_block, time).await;
+    assert_matches!(result, Err(ChainError::ExpiredMessage { event, .. })
+        if event == credit_message.event
+    );
+
+    // Rejecting it bounces it back to the sender.
+    let mut rejecting_block = expired_block;
+    rejecting_block.incoming_messages[1].action = MessageAction::Reject;
+    let outcome = new_chain()
+        .await
+        .execute_block(&rejecting_block, time)
+        .await
+        .unwrap();
+    assert_matches!(
+        &outcome.messages[..],
+        [OutgoingMessage {
+            destination: Destination::Recipient(recipient),
+            kind: MessageKind::Bouncing,
+            message: Message::System(SystemMessage::Credit { .. }),
+            ..
+        }] if *recipient == sender
+    );
+
+    // Validators check the deadlines against their own clock, so a proposal can't accept
+    // the message by choosing an earlier block timestamp.
+    let local_time = Timestamp::from(6);
+    let backdated_block = make_first_block(chain_id)
+        .with_incoming_message(open_chain_message.clone())
+        .with_incoming_message(credit_message.clone())
+        .with_timestamp(5);
+    let mut chain = new_chain().await;
+    chain.execute_block(&backdated_block, time).await.unwrap();
+    let result = chain
+        .validate_message_deadlines(&backdated_block, local_time)
+        .await;
+    assert_matches!(result, Err(ChainError::ExpiredMessage { event, .. })
+        if event == credit_message.event
+    );
+
+    // A proposal must reject an expired tracked message at the head of an inbox, even if
+    // the block timestamp is before the deadline.
+    let mut chain = new_chain().await;
+    chain
+        .inboxes
+        .try_load_entry_mut(&credit_message.origin)
+        .await
+        .unwrap()
+        .add_event(credit_message.event.clone())
+        .await
+        .unwrap();
+    let block = make_first_block(chain_id)
+        .with_incoming_message(open_chain_message)
+        .with_timestamp(5);
+    chain.remove_events_from_inboxes(&block).await.unwrap();
+    chain.execute_block(&block, time).await.unwrap();
+    let result = chain.validate_message_deadlines(&block, local_time).await;
+    assert_matches!(result, Err(ChainError::UnrejectedExpiredMessage { event, .. })
+        if event == credit_message.event
+    );
+    let mut chain = new_chain().await;
+    chain
+        .inboxes
+        .try_load_entry_mut(&credit_message.origin)
+        .await
+        .unwrap()
+        .add_event(credit_message.event)
+        .await
+        .unwrap();
+    chain
+        .remove_events_from_inboxes(&rejecting_block)
+        .await
+        .unwrap();
+    chain.execute_block(&rejecting_block, time).await.unwrap();
+    chain
+        .validate_message_deadlines(&rejecting_block, local_time)
+        .await
+        .unwrap();
+}
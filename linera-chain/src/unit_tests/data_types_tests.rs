@@ -71,3 +71,63 @@ fn test_certificates() {
         .is_none());
     assert!(builder.append(v3.validator, v3.signature).is_err());
 }
+
+#[test]
+fn test_previous_block_signatures() {
+    let keys = (0..4).map(|_| KeyPair::generate()).collect::<Vec<_>>();
+    let names = keys.iter().map(|key| ValidatorName(key.public())).collect();
+    let committee = Committee::make_simple(names);
+
+    let chain_id = ChainId::root(1);
+    let block = make_first_block(chain_id).with_simple_transfer(ChainId::root(2), Amount::ONE);
+    let executed_block = ExecutedBlock {
+        block,
+        messages: Vec::new(),
+        message_counts: vec![1],
+        state_hash: CryptoHash::test_hash("state"),
+    };
+    let value = HashedValue::new_confirmed(executed_block);
+    let signatures = keys
+        .iter()
+        .map(|key| {
+            let vote = LiteVote::new(value.lite(), Round::Fast, key);
+            (vote.validator, vote.signature)
+        })
+        .collect();
+    let certificate = Certificate::new(value.clone(), Round::Fast, signatures);
+
+    // Only the first quorum of signatures by validator name is kept.
+    let previous = PreviousBlockSignatures::new(&certificate, &committee);
+    assert_eq!(previous.signatures, certificate.signatures()[..3].to_vec());
+    let seed = previous.check(chain_id, value.hash(), &committee).unwrap();
+    assert_eq!(
+        previous.check(chain_id, value.hash(), &committee).unwrap(),
+        seed
+    );
+    assert!(previous
+        .check(chain_id, CryptoHash::test_hash("other block"), &committee)
+        .is_err());
+
+    let mut unordered = previous.clone();
+    unordered.signatures.swap(0, 1);
+    assert!(matches!(
+        unordered.check(chain_id, value.hash(), &committee),
+        Err(ChainError::UnorderedPreviousBlockSignatures)
+    ));
+
+    let all = PreviousBlockSignatures {
+        round: Round::Fast,
+        signatures: certificate.signatures().clone(),
+    };
+    assert!(matches!(
+        all.check(chain_id, value.hash(), &committee),
+        Err(ChainError::ExcessivePreviousBlockSignatures)
+    ));
+
+    let mut incomplete = previous;
+    incomplete.signatures.pop();
+    assert!(matches!(
+        incomplete.check(chain_id, value.hash(), &committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    ));
+}
@@ -25,6 +25,8 @@ fn make_event(
         refund_grant_to: None,
         kind: MessageKind::Simple,
         timestamp: Timestamp::default(),
+        deadline: None,
+        response_to: None,
         message: Message::User {
             application_id: UserApplicationId::default(),
             bytes: message.into(),
@@ -6,13 +6,15 @@ use crate::{
     data_types::{
         BlockHeightRange, ChainInfo, ChainInfoQuery, ChainInfoResponse, ClientOutcome, RoundTimeout,
     },
-    local_node::{LocalNodeClient, LocalNodeError},
+    light_client::{self, LightClientError, VerifiedChainState},
+    local_node::{LocalNodeClient, LocalNodeError, PendingDownloads},
     node::{
         CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
         ValidatorNodeProvider,
     },
     notifier::Notifier,
     updater::{communicate_with_quorum, CommunicateAction, CommunicationError, ValidatorUpdater},
+    validator_health::{TrackedNode, ValidatorHealth},
     worker::{
         DeliveryNotifiers, Notification, Reason, WorkerError, WorkerState, DEFAULT_VALUE_CACHE_SIZE,
     },
@@ -32,8 +34,9 @@ use linera_base::{
 };
 use linera_chain::{
     data_types::{
-        Block, BlockAndRound, BlockProposal, Certificate, CertificateValue, ExecutedBlock,
-        HashedValue, IncomingMessage, LiteCertificate, LiteVote, MessageAction,
+        Block, BlockAndRound, BlockApproval, BlockProposal, Certificate, CertificateValue,
+        ExecutedBlock, HashedValue, IncomingMessage, LiteCertificate, LiteVote, MessageAction,
+        PreviousBlockSignatures,
     },
     ChainError, ChainExecutionContext, ChainStateView,
 };
@@ -44,8 +47,8 @@ use linera_execution::{
         SystemOperation, UserData, CREATE_APPLICATION_MESSAGE_INDEX, OPEN_CHAIN_MESSAGE_INDEX,
         PUBLISH_BYTECODE_MESSAGE_INDEX,
     },
-    Bytecode, ExecutionError, Message, Operation, Query, Response, SystemExecutionError,
-    SystemMessage, SystemQuery, SystemResponse, UserApplicationId,
+    Bytecode, CompressedBytecode, ExecutionError, Message, Operation, Query, Response,
+    SystemExecutionError, SystemMessage, SystemQuery, SystemResponse, UserApplicationId,
 };
 use linera_storage::Storage;
 use linera_views::views::ViewError;
@@ -83,6 +86,10 @@ pub struct ChainClientBuilder<ValidatorNodeProvider> {
     delivery_notifiers: Arc<tokio::sync::Mutex<DeliveryNotifiers>>,
     /// References to clients waiting for chain notifications.
     notifier: Arc<Notifier<Notification>>,
+    /// Statistics about the health of the validators, shared by all clients.
+    validator_health: ValidatorHealth,
+    /// The certificates and blobs currently being downloaded by any of the clients.
+    pending_downloads: PendingDownloads,
 }
 
 impl<ValidatorNodeProvider: Clone> ChainClientBuilder<ValidatorNodeProvider> {
@@ -102,6 +109,8 @@ impl<ValidatorNodeProvider: Clone> ChainClientBuilder<ValidatorNodeProvider> {
             recent_values,
             delivery_notifiers: Arc::new(tokio::sync::Mutex::new(DeliveryNotifiers::default())),
             notifier: Arc::new(Notifier::default()),
+            validator_health: ValidatorHealth::default(),
+            pending_downloads: PendingDownloads::default(),
         }
     }
 
@@ -117,6 +126,7 @@ impl<ValidatorNodeProvider: Clone> ChainClientBuilder<ValidatorNodeProvider> {
         timestamp: Timestamp,
         next_block_height: BlockHeight,
         pending_block: Option<Block>,
+        block_approvals: Vec<BlockApproval>,
     ) -> ChainClient<ValidatorNodeProvider, Storage> {
         let known_key_pairs = known_key_pairs
             .into_iter()
@@ -130,7 +140,8 @@ impl<ValidatorNodeProvider: Clone> ChainClientBuilder<ValidatorNodeProvider> {
         )
         .with_allow_inactive_chains(true)
         .with_allow_messages_from_deprecated_epochs(true);
-        let node_client = LocalNodeClient::new(state, self.notifier.clone());
+        let node_client = LocalNodeClient::new(state, self.notifier.clone())
+            .with_pending_downloads(self.pending_downloads.clone());
         ChainClient {
             chain_id,
             known_key_pairs,
@@ -143,7 +154,9 @@ impl<ValidatorNodeProvider: Clone> ChainClientBuilder<ValidatorNodeProvider> {
             timestamp,
             next_block_height,
             pending_block,
+            block_approvals,
             node_client,
+            validator_health: self.validator_health.clone(),
         }
     }
 }
@@ -167,6 +180,8 @@ pub struct ChainClient<ValidatorNodeProvider, Storage> {
     next_block_height: BlockHeight,
     /// Pending block.
     pending_block: Option<Block>,
+    /// Approvals by other owners, to be included in our next block proposal.
+    block_approvals: Vec<BlockApproval>,
     /// Known key pairs from present and past identities.
     known_key_pairs: BTreeMap<Owner, KeyPair>,
     /// The id of the admin chain.
@@ -181,6 +196,8 @@ pub struct ChainClient<ValidatorNodeProvider, Storage> {
     /// Local node to manage the execution state and the local storage of the chains that we are
     /// tracking.
     node_client: LocalNodeClient<Storage>,
+    /// Statistics about the health of the validators.
+    validator_health: ValidatorHealth,
 }
 
 /// Error type for [`ChainClient`].
@@ -204,6 +221,9 @@ pub enum ChainClientError {
     #[error(transparent)]
     CommunicationError(#[from] CommunicationError<NodeError>),
 
+    #[error("Failed to verify the chain state: {0}")]
+    LightClientError(#[from] LightClientError),
+
     #[error("Internal error within chain client: {0}")]
     InternalError(&'static str),
 
@@ -235,6 +255,37 @@ pub enum ChainClientError {
     FoundMultipleKeysForChain(ChainId),
 }
 
+impl ChainClientError {
+    /// Returns the latest deadline of the expired messages that a block proposal was
+    /// required to reject, if that is why it failed.
+    fn expired_message_deadline(&self) -> Option<Timestamp> {
+        fn node_error_deadline(error: &NodeError) -> Option<Timestamp> {
+            match error {
+                NodeError::ExpiredMessage { event, .. } => event.deadline,
+                _ => None,
+            }
+        }
+        match self {
+            ChainClientError::LocalNodeError(LocalNodeError::WorkerError(
+                WorkerError::ChainError(error),
+            )) => match &**error {
+                ChainError::ExpiredMessage { event, .. }
+                | ChainError::UnrejectedExpiredMessage { event, .. } => event.deadline,
+                _ => None,
+            },
+            ChainClientError::RemoteNodeError(error)
+            | ChainClientError::CommunicationError(CommunicationError::Trusted(error)) => {
+                node_error_deadline(error)
+            }
+            ChainClientError::CommunicationError(CommunicationError::Sample(errors)) => errors
+                .iter()
+                .filter_map(|(error, _)| node_error_deadline(error))
+                .max(),
+            _ => None,
+        }
+    }
+}
+
 impl From<Infallible> for ChainClientError {
     fn from(infallible: Infallible) -> Self {
         infallible.into()
@@ -246,6 +297,11 @@ impl<P, S> ChainClient<P, S> {
         self.chain_id
     }
 
+    /// Returns the ID of the admin chain.
+    pub fn admin_id(&self) -> ChainId {
+        self.admin_id
+    }
+
     /// Returns the hash of the latest known block.
     pub fn block_hash(&self) -> Option<CryptoHash> {
         self.block_hash
@@ -263,6 +319,22 @@ impl<P, S> ChainClient<P, S> {
     pub fn pending_block(&self) -> &Option<Block> {
         &self.pending_block
     }
+
+    /// Returns the approvals by other owners to be included in our next block proposal.
+    pub fn block_approvals(&self) -> &[BlockApproval] {
+        &self.block_approvals
+    }
+
+    /// Adds approvals by other owners to be included in our next block proposal, if the
+    /// chain requires it. Approvals that don't match the proposed block are ignored.
+    pub fn add_block_approvals(&mut self, approvals: impl IntoIterator<Item = BlockApproval>) {
+        self.block_approvals.extend(approvals);
+    }
+
+    /// Returns the statistics about the health of the validators.
+    pub fn validator_health(&self) -> &ValidatorHealth {
+        &self.validator_health
+    }
 }
 
 enum ReceiveCertificateMode {
@@ -305,6 +377,19 @@ where
         Ok(response.info)
     }
 
+    /// Obtains the state of the local chain as signed by a quorum of validators, without
+    /// trusting the local node. States older than the blocks this client knows about are
+    /// rejected.
+    pub async fn verified_chain_state(&mut self) -> Result<VerifiedChainState, ChainClientError> {
+        let committee = self.local_committee().await?;
+        let nodes = self.make_nodes(&committee)?;
+        let query = ChainInfoQuery::new(self.chain_id);
+        Ok(
+            light_client::query_verified_state(&nodes, &committee, query, self.next_block_height)
+                .await?,
+        )
+    }
+
     /// Obtains the basic `ChainInfo` data for the local chain, with chain manager values.
     pub async fn chain_info_with_manager_values(
         &mut self,
@@ -358,17 +443,23 @@ where
                 &message.event.message
             {
                 let chain_id = self.chain_id;
+                let node_client = &self.node_client;
                 if applications
                     .iter()
-                    .map(|application| {
-                        self.node_client
+                    .map(|application| async move {
+                        let version = application.version();
+                        node_client
                             .describe_application(chain_id, application.into())
+                            .await
+                            .map(|known| known.version() >= version)
                     })
                     .collect::<FuturesUnordered<_>>()
-                    .all(|result| async move { result.is_ok() })
+                    .all(|result| async move { result.unwrap_or(false) })
                     .await
                 {
-                    continue; // These applications are already registered; skip register message.
+                    // These applications are already registered and up to date; skip register
+                    // message.
+                    continue;
                 }
             }
             pending_messages.push(message);
@@ -426,10 +517,22 @@ where
         Ok((committees, epoch))
     }
 
-    /// Obtains the validators trusted by the local chain.
-    async fn validator_nodes(&mut self) -> Result<Vec<(ValidatorName, P::Node)>, ChainClientError> {
+    /// Creates nodes for the validators of the given committee, tracking their health.
+    fn make_nodes(
+        &self,
+        committee: &Committee,
+    ) -> Result<Vec<(ValidatorName, TrackedNode<P::Node>)>, NodeError> {
+        let nodes = self.validator_node_provider.make_nodes(committee)?;
+        Ok(self.validator_health.track(nodes))
+    }
+
+    /// Obtains the validators trusted by the local chain, from the healthiest to the least
+    /// healthy.
+    async fn validator_nodes(
+        &mut self,
+    ) -> Result<Vec<(ValidatorName, TrackedNode<P::Node>)>, ChainClientError> {
         match self.local_committee().await {
-            Ok(committee) => Ok(self.validator_node_provider.make_nodes(&committee)?),
+            Ok(committee) => Ok(self.validator_health.rank(self.make_nodes(&committee)?)),
             Err(LocalNodeError::InactiveChain(_)) => Ok(Vec::new()),
             Err(LocalNodeError::WorkerError(WorkerError::ChainError(error)))
                 if matches!(*error, ChainError::InactiveChain(_)) =>
@@ -448,8 +551,29 @@ where
             .ok_or(LocalNodeError::InactiveChain(self.chain_id))
     }
 
+    /// Obtains the first quorum of signatures on the previous block of the chain, if it was
+    /// certified in the given epoch. These seed the randomness available to applications in
+    /// the next block, and validators require them in that case.
+    async fn previous_block_signatures(
+        &mut self,
+        epoch: Epoch,
+    ) -> Result<Option<PreviousBlockSignatures>, LocalNodeError> {
+        let Some(hash) = self.block_hash else {
+            return Ok(None);
+        };
+        let certificate = self.storage_client().await.read_certificate(hash).await?;
+        if certificate.value().epoch() != epoch {
+            return Ok(None);
+        }
+        let committee = self.local_committee().await?;
+        Ok(Some(PreviousBlockSignatures::new(&certificate, &committee)))
+    }
+
     /// Obtains the identity of the current owner of the chain. HACK: In the case of a
     /// multi-owner chain, we pick one identity for which we know the private key.
+    ///
+    /// On public chains, if we don't own the chain but know a single key, we propose blocks
+    /// with that key.
     pub async fn identity(&mut self) -> Result<Owner, ChainClientError> {
         let manager = self.chain_info().await?.manager;
         ensure!(
@@ -461,6 +585,11 @@ where
             .all_owners()
             .filter(|owner| self.known_key_pairs.contains_key(owner));
         let Some(identity) = identities.next() else {
+            if manager.ownership.open_multi_leader_rounds && self.known_key_pairs.len() == 1 {
+                if let Some(owner) = self.known_key_pairs.keys().next() {
+                    return Ok(*owner);
+                }
+            }
             return Err(ChainClientError::CannotFindKeyForChain(self.chain_id));
         };
         ensure!(
@@ -624,7 +753,7 @@ where
         let (chain_id, nodes, local_node) = {
             let mut guard = this.lock().await;
             let committee = guard.local_committee().await?;
-            let nodes: HashMap<_, _> = guard.validator_node_provider.make_nodes(&committee)?;
+            let nodes: HashMap<_, _> = guard.make_nodes(&committee)?.into_iter().collect();
             (guard.chain_id, nodes, guard.node_client.clone())
         };
         // Drop removed validators.
@@ -690,7 +819,9 @@ where
             // For chains with any owner other than ourselves, we could be missing recent
             // certificates created by other owners. Further synchronize blocks from the network.
             // This is a best-effort that depends on network conditions.
-            let nodes = self.validator_nodes().await?;
+            let nodes = self
+                .validator_health
+                .preferred(self.validator_nodes().await?);
             info = self
                 .node_client
                 .synchronize_chain_state(nodes, self.chain_id)
@@ -756,10 +887,14 @@ where
         delivery: CrossChainMessageDelivery,
     ) -> Result<(), ChainClientError> {
         let storage_client = self.storage_client().await;
-        let nodes: Vec<_> = self.validator_node_provider.make_nodes(committee)?;
+        let nodes = self
+            .validator_health
+            .select_for_quorum(committee, self.make_nodes(committee)?);
+        let grace_period = self.validator_health.grace_period(committee);
         communicate_with_quorum(
             &nodes,
             committee,
+            grace_period,
             |_: &()| (),
             |name, node| {
                 let mut updater = ValidatorUpdater {
@@ -790,10 +925,14 @@ where
         value: HashedValue,
     ) -> Result<Certificate, ChainClientError> {
         let storage_client = self.storage_client().await;
-        let nodes: Vec<_> = self.validator_node_provider.make_nodes(committee)?;
+        let nodes = self
+            .validator_health
+            .select_for_quorum(committee, self.make_nodes(committee)?);
+        let grace_period = self.validator_health.grace_period(committee);
         let ((votes_hash, votes_round), votes) = communicate_with_quorum(
             &nodes,
             committee,
+            grace_period,
             |vote: &LiteVote| (vote.value.value_hash, vote.round),
             |name, node| {
                 let mut updater = ValidatorUpdater {
@@ -855,7 +994,9 @@ where
         }
         // Recover history from the network. We assume that the committee that signed the
         // certificate is still active.
-        let nodes: Vec<_> = self.validator_node_provider.make_nodes(remote_committee)?;
+        let nodes = self
+            .validator_health
+            .rank(self.make_nodes(remote_committee)?);
         self.node_client
             .download_certificates(nodes.clone(), block.chain_id, block.height)
             .await?;
@@ -1012,14 +1153,35 @@ where
     /// However, this should be the case whenever a sender's chain is still in use and
     /// is regularly upgraded to new committees.
     async fn find_received_certificates(&mut self) -> Result<(), ChainClientError> {
-        // Use network information from the local chain.
-        let chain_id = self.chain_id;
+        self.synchronize_admin_chain().await?;
+        self.find_received_certificates_after_admin_sync().await
+    }
+
+    /// Synchronizes the state of the admin chain from the network, using the validators
+    /// of the local chain.
+    pub(crate) async fn synchronize_admin_chain(&mut self) -> Result<(), ChainClientError> {
         let local_committee = self.local_committee().await?;
-        let nodes: Vec<_> = self.validator_node_provider.make_nodes(&local_committee)?;
-        // Synchronize the state of the admin chain from the network.
+        let nodes = self
+            .validator_health
+            .preferred(self.make_nodes(&local_committee)?);
         self.node_client
-            .synchronize_chain_state(nodes.clone(), self.admin_id)
+            .synchronize_chain_state(nodes, self.admin_id)
             .await?;
+        Ok(())
+    }
+
+    /// Same as `find_received_certificates` but assumes that the admin chain was already
+    /// synchronized.
+    async fn find_received_certificates_after_admin_sync(
+        &mut self,
+    ) -> Result<(), ChainClientError> {
+        // Use network information from the local chain.
+        let chain_id = self.chain_id;
+        let local_committee = self.local_committee().await?;
+        let nodes = self
+            .validator_health
+            .select_for_quorum(&local_committee, self.make_nodes(&local_committee)?);
+        let grace_period = self.validator_health.grace_period(&local_committee);
         let node_client = self.node_client.clone();
         // Now we should have a complete view of all committees in the system.
         let (committees, max_epoch) = self.known_committees().await?;
@@ -1028,6 +1190,7 @@ where
         let result = communicate_with_quorum(
             &nodes,
             &local_committee,
+            grace_period,
             |_| (),
             |name, node| {
                 let tracker = *trackers.get(&name).unwrap_or(&0);
@@ -1212,7 +1375,7 @@ where
                 if let ChainError::ExecutionError(
                     error,
                     ChainExecutionContext::IncomingMessage(index),
-                ) = &**chain_error
+                ) = chain_error.without_logs()
                 {
                     let message = block
                         .incoming_messages
@@ -1297,11 +1460,23 @@ where
         };
         // Collect the blobs required for execution.
         let committee = self.local_committee().await?;
-        let nodes = self.validator_node_provider.make_nodes(&committee)?;
+        let nodes = self.validator_health.rank(self.make_nodes(&committee)?);
         let blobs = self
             .node_client
             .read_or_download_blobs(nodes, block.bytecode_locations())
             .await?;
+        // Only keep the approvals of this exact block by the current owners.
+        let approvals = self
+            .block_approvals
+            .iter()
+            .filter(|approval| {
+                manager
+                    .ownership
+                    .verify_owner(&approval.owner)
+                    .is_some_and(|public_key| approval.check(&block, public_key).is_ok())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
         // Create the final block proposal.
         let key_pair = self.key_pair().await?;
         let proposal = BlockProposal::new(
@@ -1312,7 +1487,8 @@ where
             key_pair,
             blobs,
             validated,
-        );
+        )
+        .with_approvals(approvals);
         // Check the final block proposal. This will be cheaper after #1401.
         self.node_client
             .handle_block_proposal(proposal.clone())
@@ -1324,6 +1500,7 @@ where
             .submit_block_proposal(&committee, proposal, hashed_value)
             .await?;
         self.pending_block = None;
+        self.block_approvals.clear();
         // Communicate the new certificate now.
         self.communicate_chain_updates(
             &committee,
@@ -1394,7 +1571,7 @@ where
     /// This must be preceded by a call to `prepare_chain()`.
     async fn execute_block(
         &mut self,
-        incoming_messages: Vec<IncomingMessage>,
+        mut incoming_messages: Vec<IncomingMessage>,
         operations: Vec<Operation>,
     ) -> Result<ExecuteBlockOutcome, ChainClientError> {
         match self.process_pending_block_without_prepare().await? {
@@ -1406,10 +1583,35 @@ where
                 return Ok(ExecuteBlockOutcome::WaitForTimeout(timeout))
             }
         }
-        let confirmed_value = self
-            .set_pending_block(incoming_messages, operations)
-            .await?;
-        match self.process_pending_block_without_prepare().await? {
+        let mut rejected_deadline = None;
+        let (confirmed_value, outcome) = loop {
+            let confirmed_value = self
+                .set_pending_block(incoming_messages, operations.clone())
+                .await?;
+            let error = match self.process_pending_block_without_prepare().await {
+                Ok(outcome) => break (confirmed_value, outcome),
+                Err(error) => error,
+            };
+            // The validators' clocks may be ahead of ours, or they may have received messages
+            // we don't know about yet. If they require us to reject an expired message, we
+            // synchronize our inbox and retry, rejecting every message that expired by then.
+            let Some(deadline) = error.expired_message_deadline() else {
+                return Err(error);
+            };
+            if rejected_deadline >= Some(deadline) {
+                return Err(error);
+            }
+            info!(%deadline, "Expired messages must be rejected; retrying.");
+            rejected_deadline = Some(deadline);
+            self.clear_pending_block();
+            self.find_received_certificates().await?;
+            incoming_messages = self.pending_messages().await?;
+            Self::reject_expired_messages(
+                &mut incoming_messages,
+                deadline.saturating_add_micros(1),
+            );
+        };
+        match outcome {
             ClientOutcome::Committed(Some(certificate))
                 if certificate.hash() == confirmed_value.hash() =>
             {
@@ -1430,16 +1632,19 @@ where
 
     async fn set_pending_block(
         &mut self,
-        incoming_messages: Vec<IncomingMessage>,
+        mut incoming_messages: Vec<IncomingMessage>,
         operations: Vec<Operation>,
     ) -> Result<HashedValue, ChainClientError> {
         let timestamp = self.next_timestamp(&incoming_messages).await;
+        Self::reject_expired_messages(&mut incoming_messages, timestamp);
+        let epoch = self.epoch().await?;
         let block = Block {
-            epoch: self.epoch().await?,
+            epoch,
             chain_id: self.chain_id,
             incoming_messages,
             operations,
             previous_block_hash: self.block_hash,
+            previous_block_signatures: self.previous_block_signatures(epoch).await?,
             height: self.next_block_height,
             authenticated_signer: Some(self.identity().await?),
             timestamp,
@@ -1467,6 +1672,16 @@ where
             .max(self.timestamp)
     }
 
+    /// Rejects the incoming messages that can no longer be accepted in a block with the given
+    /// timestamp. Tracked messages will bounce back to their sender.
+    fn reject_expired_messages(incoming_messages: &mut [IncomingMessage], timestamp: Timestamp) {
+        for message in incoming_messages {
+            if message.event.is_expired(timestamp) {
+                message.action = MessageAction::Reject;
+            }
+        }
+    }
+
     /// Queries an application.
     pub async fn query_application(&self, query: Query) -> Result<Response, ChainClientError> {
         let response = self
@@ -1547,14 +1762,17 @@ where
         &mut self,
         owner: Option<Owner>,
     ) -> Result<(Amount, Option<Amount>), ChainClientError> {
-        let incoming_messages = self.pending_messages().await?;
+        let mut incoming_messages = self.pending_messages().await?;
         let timestamp = self.next_timestamp(&incoming_messages).await;
+        Self::reject_expired_messages(&mut incoming_messages, timestamp);
+        let epoch = self.epoch().await?;
         let block = Block {
-            epoch: self.epoch().await?,
+            epoch,
             chain_id: self.chain_id,
             incoming_messages,
             operations: Vec::new(),
             previous_block_hash: self.block_hash,
+            previous_block_signatures: self.previous_block_signatures(epoch).await?,
             height: self.next_block_height,
             authenticated_signer: owner,
             timestamp,
@@ -1570,7 +1788,7 @@ where
             Err(ChainClientError::LocalNodeError(LocalNodeError::WorkerError(
                 WorkerError::ChainError(error),
             ))) if matches!(
-                *error,
+                error.without_logs(),
                 ChainError::ExecutionError(
                     ExecutionError::SystemError(
                         SystemExecutionError::InsufficientFundingForFees { .. }
@@ -1653,6 +1871,22 @@ where
         .await
     }
 
+    /// Upgrades an application created by this chain to a new bytecode, and runs its `migrate`
+    /// entrypoint with the given argument.
+    pub async fn upgrade_application(
+        &mut self,
+        application_id: UserApplicationId,
+        bytecode_id: BytecodeId,
+        migration_argument: Vec<u8>,
+    ) -> Result<ClientOutcome<Certificate>, ChainClientError> {
+        self.execute_operation(Operation::System(SystemOperation::UpgradeApplication {
+            application_id,
+            bytecode_id,
+            migration_argument,
+        }))
+        .await
+    }
+
     /// Sends tokens to a chain.
     pub async fn transfer_to_account(
         &mut self,
@@ -1688,6 +1922,16 @@ where
         self.prepare_chain().await
     }
 
+    /// Same as [`Self::synchronize_from_validators`] but assumes that the admin chain was
+    /// already synchronized, e.g. by a [`ChainSynchronizer`](crate::sync::ChainSynchronizer)
+    /// working on several chains at once.
+    pub(crate) async fn synchronize_from_validators_after_admin_sync(
+        &mut self,
+    ) -> Result<Box<ChainInfo>, ChainClientError> {
+        self.find_received_certificates_after_admin_sync().await?;
+        self.prepare_chain().await
+    }
+
     /// Processes the last pending block
     pub async fn process_pending_block(
         &mut self,
@@ -1786,6 +2030,27 @@ where
     /// Clears the information on any operation that previously failed.
     pub fn clear_pending_block(&mut self) {
         self.pending_block = None;
+        self.block_approvals.clear();
+    }
+
+    /// Approves a block proposed by another owner of this chain, for chains that require
+    /// block proposals to be approved by several owners. Only regular owners can approve
+    /// blocks: see [`ChainOwnership::approval_weight`].
+    pub async fn approve_block(
+        &mut self,
+        block: &Block,
+    ) -> Result<BlockApproval, ChainClientError> {
+        ensure!(
+            block.chain_id == self.chain_id,
+            ChainClientError::BlockProposalError("The block belongs to a different chain")
+        );
+        let owner = self.identity().await?;
+        let manager = self.chain_info().await?.manager;
+        ensure!(
+            manager.ownership.owners.contains_key(&owner),
+            ChainClientError::BlockProposalError("Only regular owners can approve blocks")
+        );
+        Ok(BlockApproval::new(block, self.key_pair().await?))
     }
 
     /// Processes confirmed operation for which this chain is a recipient.
@@ -1817,6 +2082,8 @@ where
             owners: Vec::new(),
             multi_leader_rounds: 2,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
+            open_multi_leader_rounds: false,
         }))
         .await
     }
@@ -1848,6 +2115,8 @@ where
                 owners,
                 multi_leader_rounds: ownership.multi_leader_rounds,
                 timeout_config: ownership.timeout_config,
+                approval_threshold: ownership.approval_threshold,
+                open_multi_leader_rounds: ownership.open_multi_leader_rounds,
             })];
             match self.execute_block(messages, operations).await? {
                 ExecuteBlockOutcome::Executed(certificate) => {
@@ -1926,22 +2195,41 @@ where
         contract: Bytecode,
         service: Bytecode,
     ) -> Result<ClientOutcome<(BytecodeId, Certificate)>, ChainClientError> {
-        self.execute_operation(Operation::System(SystemOperation::PublishBytecode {
-            contract: contract.clone(),
-            service: service.clone(),
-        }))
-        .await?
-        .try_map(|certificate| {
-            // The first message of the only operation published the bytecode.
-            let message_id = certificate
-                .value()
-                .executed_block()
-                .and_then(|executed_block| {
-                    executed_block.message_id_for_operation(0, PUBLISH_BYTECODE_MESSAGE_INDEX)
-                })
-                .ok_or_else(|| ChainClientError::InternalError("Failed to publish bytecode"))?;
-            Ok((BytecodeId::new(message_id), certificate))
+        self.execute_publish_operation(SystemOperation::PublishBytecode { contract, service })
+            .await
+    }
+
+    /// Publishes some bytecode in compressed form, which makes the block smaller and cheaper.
+    pub async fn publish_compressed_bytecode(
+        &mut self,
+        contract: CompressedBytecode,
+        service: CompressedBytecode,
+    ) -> Result<ClientOutcome<(BytecodeId, Certificate)>, ChainClientError> {
+        self.execute_publish_operation(SystemOperation::PublishCompressedBytecode {
+            contract,
+            service,
         })
+        .await
+    }
+
+    /// Executes an operation publishing some bytecode, and returns the new bytecode ID.
+    async fn execute_publish_operation(
+        &mut self,
+        operation: SystemOperation,
+    ) -> Result<ClientOutcome<(BytecodeId, Certificate)>, ChainClientError> {
+        self.execute_operation(Operation::System(operation))
+            .await?
+            .try_map(|certificate| {
+                // The first message of the only operation published the bytecode.
+                let message_id = certificate
+                    .value()
+                    .executed_block()
+                    .and_then(|executed_block| {
+                        executed_block.message_id_for_operation(0, PUBLISH_BYTECODE_MESSAGE_INDEX)
+                    })
+                    .ok_or_else(|| ChainClientError::InternalError("Failed to publish bytecode"))?;
+                Ok((BytecodeId::new(message_id), certificate))
+            })
     }
 
     /// Creates an application by instantiating some bytecode.
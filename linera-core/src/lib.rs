@@ -6,9 +6,12 @@
 
 pub mod client;
 pub mod data_types;
+pub mod light_client;
 pub mod local_node;
 pub mod node;
 pub mod notifier;
+pub mod sync;
+pub mod validator_health;
 pub mod worker;
 
 pub(crate) mod updater;
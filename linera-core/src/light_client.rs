@@ -0,0 +1,272 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of the state of a chain by clients that only know the current committee.
+//!
+//! Instead of downloading and re-executing the blocks of a chain, a light client asks every
+//! validator for a signed [`ChainInfoResponse`] and only accepts the chain state that a
+//! quorum of validators signed. Since any two quorums share at least one honest validator,
+//! no other state can be accepted for the same chain and height by another light client.
+//!
+//! Signed responses are not bound to the query they answer, so an old quorum of responses
+//! remains valid forever. To reject such replays, a light client never accepts a state older
+//! than the latest one it verified, and relayed responses are checked against a minimum
+//! height chosen by the caller.
+
+use crate::{
+    data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse},
+    node::{NodeError, ValidatorNode, ValidatorNodeProvider},
+    updater::{communicate_with_quorum, CommunicationError},
+};
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Amount, BlockHeight, Timestamp},
+    identifiers::{ChainId, Owner},
+};
+use linera_execution::{
+    application_state_positions,
+    committee::{Committee, Epoch, ValidatorName},
+    UserApplicationId,
+};
+use linera_views::proof::{ProofError, ViewProof};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+use thiserror::Error;
+
+#[cfg(test)]
+#[path = "unit_tests/light_client_tests.rs"]
+mod light_client_tests;
+
+/// The state of a chain, as signed by a quorum of validators.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct VerifiedChainState {
+    /// The chain id.
+    pub chain_id: ChainId,
+    /// The number identifying the current configuration.
+    pub epoch: Option<Epoch>,
+    /// The height after the latest block in the chain.
+    pub next_block_height: BlockHeight,
+    /// The last block hash, if any.
+    pub block_hash: Option<CryptoHash>,
+    /// The hash of the current execution state. Individual entries of the execution
+    /// state can be checked against it.
+    pub state_hash: Option<CryptoHash>,
+    /// The earliest possible timestamp for the next block.
+    pub timestamp: Timestamp,
+    /// The balance of the chain.
+    pub chain_balance: Amount,
+    /// The balance of the requested owner, if any.
+    pub owner_balance: Option<Amount>,
+}
+
+impl From<&ChainInfo> for VerifiedChainState {
+    fn from(info: &ChainInfo) -> Self {
+        VerifiedChainState {
+            chain_id: info.chain_id,
+            epoch: info.epoch,
+            next_block_height: info.next_block_height,
+            block_hash: info.block_hash,
+            state_hash: info.state_hash,
+            timestamp: info.timestamp,
+            chain_balance: info.chain_balance,
+            owner_balance: info.requested_owner_balance,
+        }
+    }
+}
+
+impl VerifiedChainState {
+    /// Verifies a proof of the storage of the given application, obtained with
+    /// `ExecutionStateView::prove_application_state`, against the signed state hash.
+    pub fn verify_state_proof(
+        &self,
+        application_id: &UserApplicationId,
+        proof: &ViewProof,
+    ) -> Result<(), ProofError> {
+        let state_hash = self.state_hash.ok_or(ProofError::HashMismatch)?;
+        let positions = application_state_positions(application_id)?;
+        proof.verify_crypto_hash("ExecutionStateView", &positions, &state_hash)
+    }
+}
+
+/// Error type for [`LightClient`].
+#[derive(Debug, Error)]
+pub enum LightClientError {
+    #[error("Failed to create validator nodes: {0}")]
+    NodeError(#[from] NodeError),
+
+    #[error("No quorum of validators signed the same state: {0}")]
+    CommunicationError(#[from] CommunicationError<NodeError>),
+
+    #[error(
+        "The validators signed a state of chain {chain_id} with next block height \
+         {next_block_height}, older than the known height {min_height}"
+    )]
+    StaleState {
+        chain_id: ChainId,
+        next_block_height: BlockHeight,
+        min_height: BlockHeight,
+    },
+}
+
+/// A client that verifies chain states using nothing but the committee.
+pub struct LightClient<P> {
+    /// The committee whose signatures are trusted.
+    committee: Committee,
+    /// How to talk to the validators.
+    validator_node_provider: P,
+    /// The next block height of the latest state verified for each chain.
+    verified_heights: Mutex<HashMap<ChainId, BlockHeight>>,
+}
+
+impl<P> LightClient<P>
+where
+    P: ValidatorNodeProvider,
+{
+    /// Creates a new light client trusting the given committee.
+    pub fn new(committee: Committee, validator_node_provider: P) -> Self {
+        Self {
+            committee,
+            validator_node_provider,
+            verified_heights: Mutex::default(),
+        }
+    }
+
+    /// Returns the committee trusted by this client.
+    pub fn committee(&self) -> &Committee {
+        &self.committee
+    }
+
+    /// Returns the state of the given chain, as signed by a quorum of validators.
+    ///
+    /// Fails if the state is older than the latest one this client verified for the chain.
+    pub async fn chain_state(
+        &self,
+        chain_id: ChainId,
+    ) -> Result<VerifiedChainState, LightClientError> {
+        self.query_verified_state(ChainInfoQuery::new(chain_id))
+            .await
+    }
+
+    /// Returns the state of the given chain, including the balance of `owner`, as signed
+    /// by a quorum of validators.
+    pub async fn chain_state_with_owner_balance(
+        &self,
+        chain_id: ChainId,
+        owner: Owner,
+    ) -> Result<VerifiedChainState, LightClientError> {
+        self.query_verified_state(ChainInfoQuery::new(chain_id).with_owner_balance(owner))
+            .await
+    }
+
+    /// Returns the next block height of the latest state verified for the given chain.
+    pub fn verified_height(&self, chain_id: ChainId) -> BlockHeight {
+        self.verified_heights
+            .lock()
+            .unwrap()
+            .get(&chain_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    async fn query_verified_state(
+        &self,
+        query: ChainInfoQuery,
+    ) -> Result<VerifiedChainState, LightClientError> {
+        let chain_id = query.chain_id;
+        let nodes: Vec<_> = self.validator_node_provider.make_nodes(&self.committee)?;
+        let min_height = self.verified_height(chain_id);
+        let state = query_verified_state(&nodes, &self.committee, query, min_height).await?;
+        let mut verified_heights = self.verified_heights.lock().unwrap();
+        let height = verified_heights.entry(chain_id).or_default();
+        *height = (*height).max(state.next_block_height);
+        Ok(state)
+    }
+}
+
+/// Sends `query` to all the given validators and returns the chain state signed by a
+/// quorum of `committee`, if its next block height is at least `min_height`.
+pub(crate) async fn query_verified_state<A>(
+    nodes: &[(ValidatorName, A)],
+    committee: &Committee,
+    query: ChainInfoQuery,
+    min_height: BlockHeight,
+) -> Result<VerifiedChainState, LightClientError>
+where
+    A: ValidatorNode + Clone + Send + Sync + 'static,
+{
+    let (state, _) = communicate_with_quorum(
+        nodes,
+        committee,
+        None,
+        |state: &VerifiedChainState| state.clone(),
+        |name, node| {
+            let query = query.clone();
+            Box::pin(query_signed_state(name, node, query))
+        },
+    )
+    .await?;
+    if state.next_block_height < min_height {
+        return Err(LightClientError::StaleState {
+            chain_id: state.chain_id,
+            next_block_height: state.next_block_height,
+            min_height,
+        });
+    }
+    Ok(state)
+}
+
+/// Queries a single validator and checks that its response is signed by it.
+async fn query_signed_state<A>(
+    name: ValidatorName,
+    mut node: A,
+    query: ChainInfoQuery,
+) -> Result<VerifiedChainState, NodeError>
+where
+    A: ValidatorNode + Send,
+{
+    let chain_id = query.chain_id;
+    let response = node.handle_chain_info_query(query).await?;
+    response.check(name)?;
+    if response.info.chain_id != chain_id {
+        return Err(NodeError::InvalidChainInfoResponse);
+    }
+    Ok(VerifiedChainState::from(&*response.info))
+}
+
+/// Verifies a set of signed responses that were collected by other means, e.g. relayed by
+/// an untrusted server, and returns the state signed by a quorum of the committee.
+///
+/// Responses with invalid or missing signatures, from validators outside the committee, or
+/// with a next block height below `min_height` are ignored. Each validator is counted at most
+/// once. Since signed responses never expire, `min_height` should be the height of the latest
+/// state the caller knows, so that old responses cannot be replayed.
+pub fn verify_signed_responses<'a>(
+    committee: &Committee,
+    chain_id: ChainId,
+    min_height: BlockHeight,
+    responses: impl IntoIterator<Item = (ValidatorName, &'a ChainInfoResponse)>,
+) -> Option<VerifiedChainState> {
+    let mut seen = HashSet::new();
+    let mut scores = HashMap::<VerifiedChainState, u64>::new();
+    for (name, response) in responses {
+        let weight = committee.weight(&name);
+        if weight == 0
+            || response.info.chain_id != chain_id
+            || response.info.next_block_height < min_height
+            || response.check(name).is_err()
+            || !seen.insert(name)
+        {
+            continue;
+        }
+        let score = scores
+            .entry(VerifiedChainState::from(&*response.info))
+            .or_default();
+        *score += weight;
+        if *score >= committee.quorum_threshold() {
+            return Some(VerifiedChainState::from(&*response.info));
+        }
+    }
+    None
+}
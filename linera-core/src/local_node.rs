@@ -4,12 +4,14 @@
 
 use crate::{
     data_types::{BlockHeightRange, ChainInfo, ChainInfoQuery, ChainInfoResponse},
-    node::{NotificationStream, ValidatorNode},
+    node::{CertificateStream, NotificationStream, ValidatorNode},
     notifier::Notifier,
     worker::{Notification, ValidatorWorker, WorkerError, WorkerState},
 };
-use futures::{future, lock::Mutex};
+use dashmap::DashMap;
+use futures::{future, lock::Mutex, Future, StreamExt};
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{ArithmeticError, BlockHeight},
     identifiers::{ChainId, MessageId},
 };
@@ -22,8 +24,7 @@ use linera_execution::{
 };
 use linera_storage::Storage;
 use linera_views::views::ViewError;
-use rand::prelude::SliceRandom;
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, hash::Hash, sync::Arc};
 use thiserror::Error;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -37,6 +38,69 @@ pub struct LocalNode<S> {
 #[derive(Clone)]
 pub struct LocalNodeClient<S> {
     node: Arc<Mutex<LocalNode<S>>>,
+    downloads: PendingDownloads,
+}
+
+/// The downloads currently in progress. Sharing this between the local nodes of several
+/// chain clients makes sure that chains synchronized concurrently don't fetch the same
+/// certificates or blobs more than once.
+#[derive(Clone, Default)]
+pub struct PendingDownloads {
+    chains: Arc<DashMap<ChainId, Arc<Mutex<()>>>>,
+    synchronizations: Arc<DashMap<ChainId, Arc<Mutex<()>>>>,
+    blobs: Arc<DashMap<CryptoHash, Arc<Mutex<()>>>>,
+}
+
+impl PendingDownloads {
+    /// Runs `future` while no other download of the certificates of `chain_id` is in
+    /// progress.
+    async fn for_chain<F: Future>(&self, chain_id: ChainId, future: F) -> F::Output {
+        Self::exclusively(&self.chains, chain_id, future).await
+    }
+
+    /// Runs `future` to synchronize `chain_id` with the validators, while no other download
+    /// of its certificates is in progress. If another synchronization of the same chain is
+    /// already in progress, waits for it to finish instead, and returns `None`.
+    pub(crate) async fn synchronize_chain<F: Future>(
+        &self,
+        chain_id: ChainId,
+        future: F,
+    ) -> Option<F::Output> {
+        let lock = self.synchronizations.entry(chain_id).or_default().clone();
+        let output = match lock.try_lock() {
+            Some(_guard) => Some(self.for_chain(chain_id, future).await),
+            None => {
+                drop(lock.lock().await);
+                None
+            }
+        };
+        drop(lock);
+        // Forget the lock unless someone else is waiting for it.
+        self.synchronizations
+            .remove_if(&chain_id, |_, lock| Arc::strong_count(lock) == 1);
+        output
+    }
+
+    /// Runs `future` while no other download of the blob `hash` is in progress.
+    async fn for_blob<F: Future>(&self, hash: CryptoHash, future: F) -> F::Output {
+        Self::exclusively(&self.blobs, hash, future).await
+    }
+
+    async fn exclusively<K, F>(locks: &DashMap<K, Arc<Mutex<()>>>, key: K, future: F) -> F::Output
+    where
+        K: Eq + Hash + Clone,
+        F: Future,
+    {
+        let lock = locks.entry(key.clone()).or_default().clone();
+        let output = {
+            let _guard = lock.lock().await;
+            future.await
+        };
+        drop(lock);
+        // Forget the lock unless someone else is waiting for it.
+        locks.remove_if(&key, |_, lock| Arc::strong_count(lock) == 1);
+        output
+    }
 }
 
 /// Error type for the operations on a local node.
@@ -146,8 +210,15 @@ impl<S> LocalNodeClient<S> {
 
         Self {
             node: Arc::new(Mutex::new(node)),
+            downloads: PendingDownloads::default(),
         }
     }
+
+    /// Shares the given record of pending downloads with other local nodes.
+    pub fn with_pending_downloads(mut self, downloads: PendingDownloads) -> Self {
+        self.downloads = downloads;
+        self
+    }
 }
 
 impl<S> LocalNodeClient<S>
@@ -174,18 +245,27 @@ where
         Ok((executed_block, info))
     }
 
+    /// Processes the certificates downloaded from a validator as they arrive, and stops at
+    /// the first one that fails.
     async fn try_process_certificates<A>(
         &mut self,
         name: ValidatorName,
         node: &mut A,
         chain_id: ChainId,
-        certificates: Vec<Certificate>,
+        mut certificates: CertificateStream,
     ) -> Option<Box<ChainInfo>>
     where
         A: ValidatorNode + Send + Sync + 'static + Clone,
     {
         let mut info = None;
-        for certificate in certificates {
+        while let Some(result) = certificates.next().await {
+            let certificate = match result {
+                Ok(certificate) => certificate,
+                Err(error) => {
+                    tracing::warn!("Failed to download network certificate: {}", error);
+                    return info;
+                }
+            };
             let hash = certificate.hash();
             if !certificate.value().is_confirmed() || certificate.value().chain_id() != chain_id {
                 // The certificate is not as expected. Give up.
@@ -243,8 +323,9 @@ where
         chain_id: ChainId,
         query: Query,
     ) -> Result<Response, LocalNodeError> {
-        let mut node = self.node.lock().await;
-        let response = node.state.query_application(chain_id, query).await?;
+        // Services may wait for HTTP requests, so the node isn't locked during the query.
+        let mut state = self.node.lock().await.state.clone();
+        let response = state.query_application(chain_id, query).await?;
         Ok(response)
     }
 
@@ -261,40 +342,46 @@ where
         Ok(response)
     }
 
+    /// Downloads certificates until the local chain reaches `target_next_block_height`.
+    ///
+    /// Validators are tried sequentially, in the given order.
     pub async fn download_certificates<A>(
         &mut self,
-        mut validators: Vec<(ValidatorName, A)>,
+        validators: Vec<(ValidatorName, A)>,
         chain_id: ChainId,
         target_next_block_height: BlockHeight,
     ) -> Result<Box<ChainInfo>, LocalNodeError>
     where
         A: ValidatorNode + Send + Sync + 'static + Clone,
     {
-        // Sequentially try each validator in random order.
-        validators.shuffle(&mut rand::thread_rng());
-        for (name, node) in validators {
-            let info = self.local_chain_info(chain_id).await?;
-            if target_next_block_height <= info.next_block_height {
-                return Ok(info);
-            }
-            self.try_download_certificates_from(
-                name,
-                node,
-                chain_id,
-                info.next_block_height,
-                target_next_block_height,
-            )
-            .await?;
-        }
-        let info = self.local_chain_info(chain_id).await?;
-        if target_next_block_height <= info.next_block_height {
-            Ok(info)
-        } else {
-            Err(LocalNodeError::CannotDownloadCertificates {
-                chain_id,
-                target_next_block_height,
+        let downloads = self.downloads.clone();
+        downloads
+            .for_chain(chain_id, async {
+                for (name, node) in validators {
+                    let info = self.local_chain_info(chain_id).await?;
+                    if target_next_block_height <= info.next_block_height {
+                        return Ok(info);
+                    }
+                    self.try_download_certificates_from(
+                        name,
+                        node,
+                        chain_id,
+                        info.next_block_height,
+                        target_next_block_height,
+                    )
+                    .await?;
+                }
+                let info = self.local_chain_info(chain_id).await?;
+                if target_next_block_height <= info.next_block_height {
+                    Ok(info)
+                } else {
+                    Err(LocalNodeError::CannotDownloadCertificates {
+                        chain_id,
+                        target_next_block_height,
+                    })
+                }
             })
-        }
+            .await
     }
 
     /// Downloads and stores the specified blobs, unless they are already in the cache or storage.
@@ -317,8 +404,9 @@ where
             } else {
                 let validators = validators.clone();
                 let storage = node.state.storage_client().clone();
-                tasks.push(Self::read_or_download_blob(
-                    storage, validators, chain_id, location,
+                tasks.push(self.downloads.for_blob(
+                    location.certificate_hash,
+                    Self::read_or_download_blob(storage, validators, chain_id, location),
                 ));
             }
         }
@@ -396,25 +484,21 @@ where
             start,
             limit: Some(limit),
         };
-        let query = ChainInfoQuery::new(chain_id).with_sent_certificates_in_range(range);
-        if let Ok(response) = node.handle_chain_info_query(query).await {
-            if response.check(name).is_ok() {
-                let ChainInfo {
-                    requested_sent_certificates,
-                    ..
-                } = *response.info;
-                self.try_process_certificates(
-                    name,
-                    &mut node,
-                    chain_id,
-                    requested_sent_certificates,
-                )
-                .await;
+        match node.download_certificates(chain_id, range).await {
+            Ok(certificates) => {
+                self.try_process_certificates(name, &mut node, chain_id, certificates)
+                    .await;
             }
+            Err(error) => tracing::warn!("Failed to download certificates: {}", error),
         }
         Ok(())
     }
 
+    /// Synchronizes the chain with all the given validators in parallel.
+    ///
+    /// If the chain is already being synchronized, e.g. by another chain client sharing the
+    /// same [`PendingDownloads`], waits for that synchronization instead of contacting the
+    /// validators again.
     pub async fn synchronize_chain_state<A>(
         &mut self,
         validators: Vec<(ValidatorName, A)>,
@@ -434,7 +518,9 @@ where
                 }
             })
             .collect::<Vec<_>>();
-        futures::future::join_all(futures).await;
+        self.downloads
+            .synchronize_chain(chain_id, futures::future::join_all(futures))
+            .await;
         let info = self.local_chain_info(chain_id).await?;
         Ok(info)
     }
@@ -453,9 +539,17 @@ where
             start: local_info.next_block_height,
             limit: None,
         };
-        let query = ChainInfoQuery::new(chain_id)
-            .with_sent_certificates_in_range(range)
-            .with_manager_values();
+        match node.download_certificates(chain_id, range).await {
+            Ok(certificates) => {
+                self.try_process_certificates(name, &mut node, chain_id, certificates)
+                    .await;
+            }
+            Err(err) => {
+                tracing::warn!("Ignoring error from validator: {}", err);
+                return Ok(());
+            }
+        }
+        let query = ChainInfoQuery::new(chain_id).with_manager_values();
         let info = match node.handle_chain_info_query(query).await {
             Ok(response) if response.check(name).is_ok() => response.info,
             Ok(_) => {
@@ -468,19 +562,6 @@ where
                 return Ok(());
             }
         };
-        if !info.requested_sent_certificates.is_empty()
-            && self
-                .try_process_certificates(
-                    name,
-                    &mut node,
-                    chain_id,
-                    info.requested_sent_certificates,
-                )
-                .await
-                .is_none()
-        {
-            return Ok(());
-        };
         if let Some(proposal) = info.manager.requested_proposed {
             if proposal.content.block.chain_id == chain_id {
                 let owner = proposal.owner;
@@ -500,16 +581,15 @@ where
         Ok(())
     }
 
+    /// Downloads a blob, trying each validator sequentially in the given order.
     pub async fn download_blob<A>(
-        mut validators: Vec<(ValidatorName, A)>,
+        validators: Vec<(ValidatorName, A)>,
         chain_id: ChainId,
         location: BytecodeLocation,
     ) -> Option<HashedValue>
     where
         A: ValidatorNode + Send + Sync + 'static + Clone,
     {
-        // Sequentially try each validator in random order.
-        validators.shuffle(&mut rand::thread_rng());
         for (name, mut node) in validators {
             if let Some(blob) =
                 Self::try_download_blob_from(name, &mut node, chain_id, location).await
@@ -3,10 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
+    data_types::{BlockHeightRange, ChainInfoQuery, ChainInfoResponse},
     worker::{Notification, WorkerError},
 };
 use async_trait::async_trait;
+use dashmap::DashMap;
 use futures::Stream;
 use linera_base::{
     crypto::CryptoError,
@@ -14,7 +15,7 @@ use linera_base::{
     identifiers::ChainId,
 };
 use linera_chain::{
-    data_types::{BlockProposal, Certificate, HashedValue, LiteCertificate, Origin},
+    data_types::{BlockProposal, Certificate, Event, HashedValue, LiteCertificate, Origin},
     ChainError,
 };
 use linera_execution::{
@@ -24,12 +25,15 @@ use linera_execution::{
 use linera_version::VersionInfo;
 use linera_views::views::ViewError;
 use serde::{Deserialize, Serialize};
-use std::pin::Pin;
+use std::{pin::Pin, sync::Arc};
 use thiserror::Error;
 
 /// A pinned [`Stream`] of Notifications.
 pub type NotificationStream = Pin<Box<dyn Stream<Item = Notification> + Send>>;
 
+/// A pinned [`Stream`] of downloaded certificates.
+pub type CertificateStream = Pin<Box<dyn Stream<Item = Result<Certificate, NodeError>> + Send>>;
+
 /// Whether to wait for the delivery of outgoing cross-chain messages.
 #[derive(Debug, Default, Clone, Copy)]
 pub enum CrossChainMessageDelivery {
@@ -73,6 +77,14 @@ pub trait ValidatorNode {
 
     /// Subscribes to receiving notifications for a collection of chains.
     async fn subscribe(&mut self, chains: Vec<ChainId>) -> Result<NotificationStream, NodeError>;
+
+    /// Downloads the confirmed certificates of a chain in the given range of heights, in
+    /// order.
+    async fn download_certificates(
+        &mut self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError>;
 }
 
 /// Turn an address into a validator node.
@@ -104,6 +116,47 @@ pub trait ValidatorNodeProvider {
     }
 }
 
+/// A [`ValidatorNodeProvider`] that remembers the nodes it has created, so that all the
+/// clients using it share a single connection to each validator.
+pub struct CachingNodeProvider<P: ValidatorNodeProvider> {
+    provider: P,
+    nodes: Arc<DashMap<String, P::Node>>,
+}
+
+impl<P: ValidatorNodeProvider> CachingNodeProvider<P> {
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            nodes: Arc::default(),
+        }
+    }
+}
+
+impl<P: ValidatorNodeProvider + Clone> Clone for CachingNodeProvider<P> {
+    fn clone(&self) -> Self {
+        Self {
+            provider: self.provider.clone(),
+            nodes: self.nodes.clone(),
+        }
+    }
+}
+
+impl<P: ValidatorNodeProvider> ValidatorNodeProvider for CachingNodeProvider<P> {
+    type Node = P::Node;
+
+    fn make_node(&self, address: &str) -> Result<Self::Node, NodeError> {
+        if let Some(node) = self.nodes.get(address) {
+            return Ok(node.clone());
+        }
+        let node = self.provider.make_node(address)?;
+        Ok(self
+            .nodes
+            .entry(address.to_string())
+            .or_insert(node)
+            .clone())
+    }
+}
+
 /// Error type for node queries.
 ///
 /// This error is meant to be serialized over the network and aggregated by clients (i.e.
@@ -178,6 +231,14 @@ pub enum NodeError {
 
     #[error("Failed to make a chain info query on the local node: {error}")]
     LocalNodeQuery { error: String },
+
+    // This error must be normalized during conversions.
+    #[error("Block proposed to {chain_id:?} must reject expired message {event:?}")]
+    ExpiredMessage {
+        chain_id: ChainId,
+        origin: Origin,
+        event: Event,
+    },
 }
 
 impl From<tonic::Status> for NodeError {
@@ -243,6 +304,20 @@ impl From<ChainError> for NodeError {
                 height,
             },
             ChainError::InactiveChain(chain_id) => Self::InactiveChain(chain_id),
+            ChainError::ExpiredMessage {
+                chain_id,
+                origin,
+                event,
+            }
+            | ChainError::UnrejectedExpiredMessage {
+                chain_id,
+                origin,
+                event,
+            } => Self::ExpiredMessage {
+                chain_id,
+                origin: *origin,
+                event,
+            },
             error => Self::ChainError {
                 error: error.to_string(),
             },
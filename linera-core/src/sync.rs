@@ -0,0 +1,172 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronization of many chains at once.
+//!
+//! Synchronizing the chains of a wallet one after the other repeats a lot of work: every
+//! chain synchronizes the admin chain again, and chains that received messages from the
+//! same senders download the same certificates and blobs. A [`ChainSynchronizer`] instead
+//! synchronizes the admin chain once, then works on several chains concurrently. The
+//! chain clients should be created by the same
+//! [`ChainClientBuilder`](crate::client::ChainClientBuilder), so that they share their
+//! pending downloads, and, ideally, with a
+//! [`CachingNodeProvider`](crate::node::CachingNodeProvider), so that they share their
+//! connections to the validators.
+//!
+//! Everything that is downloaded is written to storage as soon as it is verified, and
+//! chains pick up from their local state. An interrupted synchronization can thus be
+//! resumed by running it again: only the missing data is fetched.
+
+use crate::{
+    client::{ChainClient, ChainClientError},
+    data_types::ChainInfo,
+    node::ValidatorNodeProvider,
+};
+use futures::{
+    future,
+    stream::{self, Stream, StreamExt},
+};
+use linera_storage::Storage;
+use linera_views::views::ViewError;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    sync::Arc,
+};
+use tracing::warn;
+
+#[cfg(test)]
+#[path = "unit_tests/sync_tests.rs"]
+mod sync_tests;
+
+/// The default number of chains synchronized concurrently.
+pub const DEFAULT_MAX_CONCURRENT_CHAINS: usize = 16;
+
+/// Synchronizes a set of chains from the validators.
+pub struct ChainSynchronizer<P, S> {
+    clients: Vec<ChainClient<P, S>>,
+    max_concurrent_chains: usize,
+}
+
+/// How many of the chains have been synchronized so far.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SyncProgress {
+    /// The number of chains to synchronize.
+    pub total: usize,
+    /// The number of chains that were synchronized successfully.
+    pub synchronized: usize,
+    /// The number of chains that could not be synchronized.
+    pub failed: usize,
+}
+
+/// The result of synchronizing one of the chains.
+pub struct ChainSyncOutcome<P, S> {
+    /// The client of the chain, so that the caller can save its new state.
+    pub client: ChainClient<P, S>,
+    /// The synchronized chain information, or why the synchronization failed.
+    pub result: Result<Box<ChainInfo>, ChainClientError>,
+    /// The progress of the synchronization, including this chain.
+    pub progress: SyncProgress,
+}
+
+impl SyncProgress {
+    /// Returns whether all the chains have been handled.
+    pub fn is_done(&self) -> bool {
+        self.synchronized + self.failed == self.total
+    }
+}
+
+impl fmt::Display for SyncProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} chains synchronized",
+            self.synchronized, self.total
+        )?;
+        if self.failed > 0 {
+            write!(f, ", {} failed", self.failed)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P, S> ChainSynchronizer<P, S>
+where
+    P: ValidatorNodeProvider + Sync,
+    S: Storage + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    /// Creates a synchronizer for the chains of the given clients.
+    pub fn new(clients: impl IntoIterator<Item = ChainClient<P, S>>) -> Self {
+        Self {
+            clients: clients.into_iter().collect(),
+            max_concurrent_chains: DEFAULT_MAX_CONCURRENT_CHAINS,
+        }
+    }
+
+    /// Sets how many chains are synchronized at the same time.
+    pub fn with_max_concurrent_chains(mut self, max_concurrent_chains: usize) -> Self {
+        self.max_concurrent_chains = max_concurrent_chains.max(1);
+        self
+    }
+
+    /// Synchronizes the chains, returning the outcome for each of them as soon as it is
+    /// known.
+    pub fn run(self) -> impl Stream<Item = ChainSyncOutcome<P, S>> {
+        let Self {
+            mut clients,
+            max_concurrent_chains,
+        } = self;
+        let progress = SyncProgress {
+            total: clients.len(),
+            ..SyncProgress::default()
+        };
+        let synchronize_admin_chains = async move {
+            let mut admin_chains = BTreeMap::new();
+            for client in &mut clients {
+                let admin_id = client.admin_id();
+                if admin_chains.contains_key(&admin_id) {
+                    continue;
+                }
+                let synchronized = match client.synchronize_admin_chain().await {
+                    Ok(()) => true,
+                    Err(error) => {
+                        warn!("Failed to synchronize admin chain {admin_id}: {error}");
+                        false
+                    }
+                };
+                admin_chains.insert(admin_id, synchronized);
+            }
+            let synchronized_admin_chains = admin_chains
+                .into_iter()
+                .filter_map(|(admin_id, synchronized)| synchronized.then_some(admin_id))
+                .collect::<BTreeSet<_>>();
+            let synchronized_admin_chains = Arc::new(synchronized_admin_chains);
+            stream::iter(clients).map(move |client| (client, synchronized_admin_chains.clone()))
+        };
+        stream::once(synchronize_admin_chains)
+            .flatten()
+            .map(|(mut client, synchronized_admin_chains)| async move {
+                let result = if synchronized_admin_chains.contains(&client.admin_id()) {
+                    client.synchronize_from_validators_after_admin_sync().await
+                } else {
+                    // Let the client retry on its own, and report the error if needed.
+                    client.synchronize_from_validators().await
+                };
+                (client, result)
+            })
+            .buffer_unordered(max_concurrent_chains)
+            .scan(progress, |progress, (client, result)| {
+                if result.is_ok() {
+                    progress.synchronized += 1;
+                } else {
+                    progress.failed += 1;
+                }
+                future::ready(Some(ChainSyncOutcome {
+                    client,
+                    result,
+                    progress: *progress,
+                }))
+            })
+    }
+}
@@ -4,12 +4,14 @@
 use crate::{
     client::{ChainClient, ChainClientBuilder, ValidatorNodeProvider},
     data_types::*,
-    node::{CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    node::{
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+    },
     notifier::Notifier,
     worker::{Notification, ValidatorWorker, WorkerState},
 };
 use async_trait::async_trait;
-use futures::{lock::Mutex, Future};
+use futures::{lock::Mutex, Future, StreamExt};
 use linera_base::{
     crypto::*,
     data_types::*,
@@ -139,6 +141,17 @@ where
     async fn get_version_info(&mut self) -> Result<VersionInfo, NodeError> {
         Ok(Default::default())
     }
+
+    async fn download_certificates(
+        &mut self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError> {
+        self.spawn_and_receive(move |validator, sender| {
+            validator.do_download_certificates(chain_id, range, sender)
+        })
+        .await
+    }
 }
 
 impl<S> LocalValidatorClient<S>
@@ -292,6 +305,30 @@ where
         sender.send(result.map(|(info, _actions)| info))
     }
 
+    async fn do_download_certificates(
+        self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+        sender: oneshot::Sender<Result<CertificateStream, NodeError>>,
+    ) -> Result<(), Result<CertificateStream, NodeError>> {
+        let validator = self.client.lock().await;
+        let result = if validator.fault_type == FaultType::Offline {
+            Err(NodeError::ClientIoError {
+                error: "offline".to_string(),
+            })
+        } else {
+            match validator.state.certificates_in_range(chain_id, range).await {
+                Ok(certificates) => {
+                    let stream: CertificateStream =
+                        Box::pin(certificates.map(|result| result.map_err(NodeError::from)));
+                    Ok(stream)
+                }
+                Err(error) => Err(error.into()),
+            }
+        };
+        sender.send(result)
+    }
+
     async fn do_subscribe(
         self,
         chains: Vec<ChainId>,
@@ -564,6 +601,7 @@ where
             Timestamp::from(0),
             block_height,
             None,
+            Vec::new(),
         ))
     }
 
@@ -27,7 +27,9 @@ use linera_base::{
     ownership::{ChainOwnership, TimeoutConfig},
 };
 use linera_chain::{
-    data_types::{CertificateValue, Event, ExecutedBlock, IncomingMessage, Medium, Origin},
+    data_types::{
+        BlockApproval, CertificateValue, Event, ExecutedBlock, IncomingMessage, Medium, Origin,
+    },
     ChainError, ChainExecutionContext,
 };
 use linera_execution::{
@@ -1722,6 +1724,8 @@ where
         owners: vec![(pub_key0, 100), (pub_key1, 100)],
         multi_leader_rounds: 0,
         timeout_config: TimeoutConfig::default(),
+        approval_threshold: None,
+        open_multi_leader_rounds: false,
     }
     .into();
     client.execute_operation(owner_change_op).await.unwrap();
@@ -1869,6 +1873,8 @@ where
             fast_round_duration: Some(Duration::from_secs(5)),
             ..TimeoutConfig::default()
         },
+        approval_threshold: None,
+        open_multi_leader_rounds: false,
     }
     .into();
     client0.execute_operation(owner_change_op).await.unwrap();
@@ -1935,6 +1941,220 @@ where
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_memory_approval_threshold() -> Result<(), anyhow::Error> {
+    run_test_approval_threshold(MakeMemoryStorage::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_approval_threshold() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_approval_threshold(MakeRocksDbStorage::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_approval_threshold() -> Result<(), anyhow::Error> {
+    run_test_approval_threshold(MakeDynamoDbStorage::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_approval_threshold() -> Result<(), anyhow::Error> {
+    run_test_approval_threshold(MakeScyllaDbStorage::default()).await
+}
+
+async fn run_test_approval_threshold<B>(storage_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StorageBuilder,
+    ViewError: From<<B::Storage as Storage>::ContextError>,
+{
+    // Configure a chain with three regular owners, two of which must approve each block, and
+    // a super owner.
+    let mut builder = TestBuilder::new(storage_builder, 4, 1).await?;
+    let description = ChainDescription::Root(1);
+    let chain_id = ChainId::from(description);
+    let mut client0 = builder
+        .add_initial_chain(description, Amount::from_tokens(10))
+        .await?;
+    let pub_key0 = client0.public_key().await.unwrap();
+    let key_pair1 = KeyPair::generate();
+    let pub_key1 = key_pair1.public();
+    let pub_key2 = KeyPair::generate().public();
+    let owners = vec![(pub_key0, 100), (pub_key1, 100), (pub_key2, 100)];
+    let super_key_pair = KeyPair::generate();
+
+    // A threshold that the owners can't reach is rejected.
+    let owner_change_op = SystemOperation::ChangeOwnership {
+        super_owners: Vec::new(),
+        owners: owners.clone(),
+        multi_leader_rounds: 10,
+        timeout_config: TimeoutConfig::default(),
+        approval_threshold: Some(400),
+        open_multi_leader_rounds: false,
+    };
+    assert!(client0
+        .execute_operation(owner_change_op.into())
+        .await
+        .is_err());
+    client0.clear_pending_block();
+
+    let owner_change_op = SystemOperation::ChangeOwnership {
+        super_owners: vec![super_key_pair.public()],
+        owners,
+        multi_leader_rounds: 10,
+        timeout_config: TimeoutConfig::default(),
+        approval_threshold: Some(200),
+        open_multi_leader_rounds: false,
+    };
+    client0
+        .execute_operation(owner_change_op.into())
+        .await
+        .unwrap();
+    let mut client1 = builder
+        .make_client(
+            chain_id,
+            key_pair1,
+            client0.block_hash,
+            BlockHeight::from(1),
+        )
+        .await?;
+
+    // Without approvals, client 0 can't burn any tokens, but it keeps a pending block.
+    let result = client0
+        .burn(None, Amount::from_tokens(3), UserData::default())
+        .await;
+    assert!(result.is_err());
+    let block = client0.pending_block().clone().unwrap();
+
+    // Super owners have no weight, so their approvals don't count and they can't approve
+    // blocks.
+    client0.add_block_approvals([BlockApproval::new(&block, &super_key_pair)]);
+    assert!(client0.process_pending_block().await.is_err());
+    let mut super_client = builder
+        .make_client(
+            chain_id,
+            super_key_pair,
+            client0.block_hash,
+            BlockHeight::from(1),
+        )
+        .await?;
+    super_client.synchronize_from_validators().await.unwrap();
+    assert!(super_client.approve_block(&block).await.is_err());
+
+    // Client 1 approves the pending block, and client 0 can now commit it.
+    client1.synchronize_from_validators().await.unwrap();
+    let approval = client1.approve_block(&block).await.unwrap();
+    client0.add_block_approvals([approval]);
+    let certificate = client0.process_pending_block().await.unwrap();
+    assert!(matches!(certificate, ClientOutcome::Committed(Some(_))));
+    assert_eq!(
+        client0.local_balance().await.unwrap(),
+        Amount::from_tokens(7)
+    );
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_memory_open_multi_leader_rounds() -> Result<(), anyhow::Error> {
+    run_test_open_multi_leader_rounds(MakeMemoryStorage::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_open_multi_leader_rounds() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_open_multi_leader_rounds(MakeRocksDbStorage::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_open_multi_leader_rounds() -> Result<(), anyhow::Error> {
+    run_test_open_multi_leader_rounds(MakeDynamoDbStorage::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_open_multi_leader_rounds() -> Result<(), anyhow::Error> {
+    run_test_open_multi_leader_rounds(MakeScyllaDbStorage::default()).await
+}
+
+async fn run_test_open_multi_leader_rounds<B>(storage_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StorageBuilder,
+    ViewError: From<<B::Storage as Storage>::ContextError>,
+{
+    // Make the chain public, with a single regular owner. Blocks by other proposers cost
+    // an additional 0.1 tokens.
+    let policy = ResourceControlPolicy {
+        open_proposer_block: Amount::from_millis(100),
+        ..ResourceControlPolicy::fuel_and_block()
+    };
+    let mut builder = TestBuilder::new(storage_builder, 4, 1)
+        .await?
+        .with_policy(policy);
+    let description = ChainDescription::Root(1);
+    let chain_id = ChainId::from(description);
+    let mut client0 = builder
+        .add_initial_chain(description, Amount::from_tokens(10))
+        .await?;
+    let pub_key0 = client0.public_key().await.unwrap();
+    let owner_change_op = SystemOperation::ChangeOwnership {
+        super_owners: Vec::new(),
+        owners: vec![(pub_key0, 100)],
+        multi_leader_rounds: u32::MAX,
+        timeout_config: TimeoutConfig::default(),
+        approval_threshold: None,
+        open_multi_leader_rounds: true,
+    };
+    client0
+        .execute_operation(owner_change_op.into())
+        .await
+        .unwrap();
+
+    // The owner funds the account of a user who doesn't own the chain.
+    let key_pair1 = KeyPair::generate();
+    let owner1 = Owner::from(key_pair1.public());
+    client0
+        .transfer_to_account(
+            None,
+            Amount::from_tokens(3),
+            Account::owner(chain_id, owner1),
+            UserData::default(),
+        )
+        .await
+        .unwrap();
+    let mut client1 = builder
+        .make_client(
+            chain_id,
+            key_pair1,
+            client0.block_hash,
+            BlockHeight::from(2),
+        )
+        .await?;
+    client1.synchronize_from_validators().await.unwrap();
+
+    // The user can propose a block, accepting the transfer and paying the fee themselves.
+    client1
+        .burn(Some(owner1), Amount::ONE, UserData::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        client1.local_owner_balance(owner1).await.unwrap(),
+        Amount::from_millis(1_899)
+    );
+    assert_eq!(
+        client1.local_balance().await.unwrap(),
+        Amount::from_millis(6_998)
+    );
+
+    // But they can't spend the chain's funds.
+    let result = client1.burn(None, Amount::ONE, UserData::default()).await;
+    assert!(result.is_err());
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_memory_propose_pending_block() -> Result<(), anyhow::Error> {
     run_test_propose_pending_block(MakeMemoryStorage::default()).await
@@ -1997,3 +2217,56 @@ where
     );
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_memory_verified_chain_state() -> Result<(), anyhow::Error> {
+    run_test_verified_chain_state(MakeMemoryStorage::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_verified_chain_state() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_verified_chain_state(MakeRocksDbStorage::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_verified_chain_state() -> Result<(), anyhow::Error> {
+    run_test_verified_chain_state(MakeDynamoDbStorage::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_verified_chain_state() -> Result<(), anyhow::Error> {
+    run_test_verified_chain_state(MakeScyllaDbStorage::default()).await
+}
+
+async fn run_test_verified_chain_state<B>(storage_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StorageBuilder,
+    ViewError: From<<B::Storage as Storage>::ContextError>,
+{
+    let mut builder = TestBuilder::new(storage_builder, 4, 1).await?;
+    let mut client = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(10))
+        .await?;
+    client
+        .burn(None, Amount::from_tokens(3), UserData::default())
+        .await
+        .unwrap();
+
+    // The faulty validator reports a wrong balance, but the honest ones form a quorum.
+    let state = client.verified_chain_state().await?;
+    let info = client.chain_info().await?;
+    assert_eq!(state.chain_id, client.chain_id());
+    assert_eq!(state.next_block_height, BlockHeight::from(1));
+    assert_eq!(state.block_hash, info.block_hash);
+    assert_eq!(state.state_hash, info.state_hash);
+    assert_eq!(state.chain_balance, Amount::from_tokens(7));
+
+    // Without a quorum of honest validators, nothing can be verified.
+    builder.set_fault_type(2..3, FaultType::Offline).await;
+    assert!(client.verified_chain_state().await.is_err());
+    Ok(())
+}
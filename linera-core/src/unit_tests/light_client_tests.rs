@@ -0,0 +1,177 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{verify_signed_responses, VerifiedChainState};
+use crate::data_types::{ChainInfo, ChainInfoResponse};
+use linera_base::{
+    crypto::KeyPair,
+    data_types::{Amount, BlockHeight, Timestamp},
+    identifiers::{ChainDescription, ChainId},
+};
+use linera_execution::{
+    committee::{Committee, Epoch, ValidatorName, ValidatorState},
+    test_utils::{create_dummy_user_application_description, SystemExecutionState},
+    ResourceControlPolicy, UserApplicationId,
+};
+use linera_views::{proof::ProofError, views::CryptoHashView};
+
+fn make_info(chain_id: ChainId, balance: Amount) -> ChainInfo {
+    ChainInfo {
+        chain_id,
+        epoch: Some(Epoch::ZERO),
+        description: None,
+        manager: Box::default(),
+        chain_balance: balance,
+        block_hash: None,
+        timestamp: Timestamp::from(0),
+        next_block_height: BlockHeight::from(3),
+        state_hash: None,
+        requested_owner_balance: None,
+        requested_committees: None,
+        requested_pending_messages: Vec::new(),
+        requested_sent_certificates: Vec::new(),
+        count_received_log: 0,
+        requested_received_log: Vec::new(),
+        requested_blob: None,
+    }
+}
+
+#[test]
+fn test_verify_signed_responses() {
+    let key_pairs = (0..4).map(|_| KeyPair::generate()).collect::<Vec<_>>();
+    let names = key_pairs
+        .iter()
+        .map(|key_pair| ValidatorName(key_pair.public()))
+        .collect::<Vec<_>>();
+    let validators = names
+        .iter()
+        .map(|name| {
+            let state = ValidatorState {
+                network_address: name.to_string(),
+                votes: 1,
+                fee_recipient: None,
+            };
+            (*name, state)
+        })
+        .collect();
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+    let chain_id = ChainId::root(1);
+    let honest = make_info(chain_id, Amount::from_tokens(10));
+    let forged = make_info(chain_id, Amount::from_tokens(1000));
+
+    let mut responses = key_pairs[..2]
+        .iter()
+        .map(|key_pair| ChainInfoResponse::new(honest.clone(), Some(key_pair)))
+        .collect::<Vec<_>>();
+    // A malicious validator signs a different balance.
+    responses.push(ChainInfoResponse::new(forged, Some(&key_pairs[2])));
+    // Unsigned responses are ignored.
+    responses.push(ChainInfoResponse::new(honest.clone(), None));
+    let pairs = names.iter().copied().zip(&responses);
+    assert_eq!(
+        verify_signed_responses(&committee, chain_id, BlockHeight::ZERO, pairs),
+        None
+    );
+
+    // Counting the same validator twice does not help.
+    let duplicated = names
+        .iter()
+        .copied()
+        .zip(&responses)
+        .chain([(names[0], &responses[0])]);
+    assert_eq!(
+        verify_signed_responses(&committee, chain_id, BlockHeight::ZERO, duplicated),
+        None
+    );
+
+    responses[3] = ChainInfoResponse::new(honest, Some(&key_pairs[3]));
+    let pairs = || names.iter().copied().zip(&responses);
+    let state = verify_signed_responses(&committee, chain_id, BlockHeight::ZERO, pairs()).unwrap();
+    assert_eq!(state.chain_balance, Amount::from_tokens(10));
+    assert_eq!(state.next_block_height, BlockHeight::from(3));
+    assert_eq!(
+        verify_signed_responses(&committee, ChainId::root(2), BlockHeight::ZERO, pairs()),
+        None
+    );
+}
+
+#[test]
+fn test_old_signed_responses_are_not_replayed() {
+    let key_pairs = (0..4).map(|_| KeyPair::generate()).collect::<Vec<_>>();
+    let names = key_pairs
+        .iter()
+        .map(|key_pair| ValidatorName(key_pair.public()))
+        .collect::<Vec<_>>();
+    let validators = names
+        .iter()
+        .map(|name| {
+            let state = ValidatorState {
+                network_address: name.to_string(),
+                votes: 1,
+                fee_recipient: None,
+            };
+            (*name, state)
+        })
+        .collect();
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+    let chain_id = ChainId::root(1);
+    let old = make_info(chain_id, Amount::from_tokens(10));
+    let mut new = make_info(chain_id, Amount::from_tokens(3));
+    new.next_block_height = BlockHeight::from(4);
+
+    // A quorum of responses about an old state is valid until the caller knows better.
+    let old_responses = key_pairs
+        .iter()
+        .map(|key_pair| ChainInfoResponse::new(old.clone(), Some(key_pair)))
+        .collect::<Vec<_>>();
+    let pairs = || names.iter().copied().zip(&old_responses);
+    let state =
+        verify_signed_responses(&committee, chain_id, BlockHeight::from(3), pairs()).unwrap();
+    assert_eq!(state.chain_balance, Amount::from_tokens(10));
+    assert_eq!(
+        verify_signed_responses(&committee, chain_id, BlockHeight::from(4), pairs()),
+        None
+    );
+
+    // Old responses do not count towards a quorum for a newer state either.
+    let mixed = key_pairs[..2]
+        .iter()
+        .map(|key_pair| ChainInfoResponse::new(new.clone(), Some(key_pair)))
+        .chain(old_responses[2..].iter().cloned())
+        .collect::<Vec<_>>();
+    let pairs = || names.iter().copied().zip(&mixed);
+    assert_eq!(
+        verify_signed_responses(&committee, chain_id, BlockHeight::from(4), pairs()),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_verify_state_proof() -> anyhow::Result<()> {
+    let chain_id = ChainId::root(1);
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(1)),
+        ..SystemExecutionState::default()
+    };
+    let mut view = state.into_view().await;
+    let application_a = UserApplicationId::from(&create_dummy_user_application_description(1));
+    let application_b = UserApplicationId::from(&create_dummy_user_application_description(2));
+    for (application_id, value) in [(application_a, 1), (application_b, 2)] {
+        let mut store = view.users.try_load_entry_mut(&application_id).await?;
+        store.insert(vec![0], vec![value]).await?;
+    }
+    let mut info = make_info(chain_id, Amount::ZERO);
+    info.state_hash = Some(view.crypto_hash().await?);
+    let state = VerifiedChainState::from(&info);
+
+    let proof = view.prove_application_state(&application_a).await?;
+    state.verify_state_proof(&application_a, &proof)?;
+    assert_eq!(proof.leaf.map_value(&[0]), Some(&[1][..]));
+
+    // The proof about the storage of A cannot be passed off as the storage of B.
+    assert!(matches!(
+        state.verify_state_proof(&application_b, &proof),
+        Err(ProofError::UnexpectedPath)
+    ));
+    Ok(())
+}
@@ -0,0 +1,143 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    client::client_test_utils::{MakeMemoryStorage, StorageBuilder, TestBuilder},
+    local_node::PendingDownloads,
+    sync::{ChainSynchronizer, SyncProgress},
+};
+use futures::{channel::oneshot, StreamExt};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ChainDescription, ChainId},
+};
+use linera_execution::system::UserData;
+use linera_storage::Storage;
+use linera_views::views::ViewError;
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use test_log::test;
+
+#[cfg(feature = "rocksdb")]
+use crate::client::client_test_utils::{MakeRocksDbStorage, ROCKS_DB_SEMAPHORE};
+
+#[cfg(feature = "aws")]
+use crate::client::client_test_utils::MakeDynamoDbStorage;
+
+#[cfg(feature = "scylladb")]
+use crate::client::client_test_utils::MakeScyllaDbStorage;
+
+#[test(tokio::test)]
+async fn test_memory_synchronize_many_chains() -> Result<(), anyhow::Error> {
+    run_test_synchronize_many_chains(MakeMemoryStorage::default()).await
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_synchronize_many_chains() -> Result<(), anyhow::Error> {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    run_test_synchronize_many_chains(MakeRocksDbStorage::default()).await
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_synchronize_many_chains() -> Result<(), anyhow::Error> {
+    run_test_synchronize_many_chains(MakeDynamoDbStorage::default()).await
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_synchronize_many_chains() -> Result<(), anyhow::Error> {
+    run_test_synchronize_many_chains(MakeScyllaDbStorage::default()).await
+}
+
+async fn run_test_synchronize_many_chains<B>(storage_builder: B) -> Result<(), anyhow::Error>
+where
+    B: StorageBuilder,
+    ViewError: From<<B::Storage as Storage>::ContextError>,
+{
+    let mut builder = TestBuilder::new(storage_builder, 4, 1).await?;
+    let mut sender = builder
+        .add_initial_chain(ChainDescription::Root(1), Amount::from_tokens(20))
+        .await?;
+    let mut receivers = Vec::new();
+    let mut expected_balances = BTreeMap::new();
+    for i in 2..6 {
+        let receiver = builder
+            .add_initial_chain(ChainDescription::Root(i), Amount::ZERO)
+            .await?;
+        let amount = Amount::from_tokens(i.into());
+        sender
+            .transfer_to_account(
+                None,
+                amount,
+                Account::chain(receiver.chain_id()),
+                UserData::default(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        expected_balances.insert(receiver.chain_id(), amount);
+        receivers.push(receiver);
+    }
+
+    let mut outcomes = ChainSynchronizer::new(receivers)
+        .with_max_concurrent_chains(2)
+        .run()
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outcomes.len(), 4);
+    assert_eq!(
+        outcomes.last().unwrap().progress,
+        SyncProgress {
+            total: 4,
+            synchronized: 4,
+            failed: 0,
+        }
+    );
+    assert!(outcomes.last().unwrap().progress.is_done());
+    for outcome in &mut outcomes {
+        outcome.result.as_ref().unwrap();
+        // The transfers are now in the inboxes.
+        let expected_balance = expected_balances[&outcome.client.chain_id()];
+        assert_eq!(
+            outcome.client.query_balance().await.unwrap(),
+            expected_balance
+        );
+    }
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_concurrent_synchronizations_of_a_chain_are_merged() {
+    let downloads = &PendingDownloads::default();
+    let synchronizations = &AtomicUsize::new(0);
+    let synchronize = move |chain_id: ChainId, done: Option<oneshot::Receiver<()>>| {
+        downloads.synchronize_chain(chain_id, async move {
+            synchronizations.fetch_add(1, Ordering::SeqCst);
+            if let Some(done) = done {
+                done.await.unwrap();
+            }
+        })
+    };
+    let (sender, receiver) = oneshot::channel();
+
+    // The second synchronization of chain 1 waits for the first one instead of running, but
+    // chain 2 is synchronized independently.
+    let (first, second, other, ()) = futures::join!(
+        synchronize(ChainId::root(1), Some(receiver)),
+        synchronize(ChainId::root(1), None),
+        synchronize(ChainId::root(2), None),
+        async { sender.send(()).unwrap() },
+    );
+    assert_eq!(first, Some(()));
+    assert_eq!(second, None);
+    assert_eq!(other, Some(()));
+    assert_eq!(synchronizations.load(Ordering::SeqCst), 2);
+
+    // Once it is over, the chain can be synchronized again.
+    assert_eq!(synchronize(ChainId::root(1), None).await, Some(()));
+    assert_eq!(synchronizations.load(Ordering::SeqCst), 3);
+}
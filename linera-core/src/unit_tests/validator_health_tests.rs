@@ -0,0 +1,136 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{ValidatorHealth, ValidatorStats, MAX_BACKOFF, MIN_BACKOFF};
+use crate::node::NodeError;
+use linera_base::{crypto::KeyPair, identifiers::ChainId};
+use linera_execution::{
+    committee::{Committee, ValidatorName, ValidatorState},
+    ResourceControlPolicy,
+};
+use std::time::{Duration, Instant};
+
+fn make_committee(names: &[ValidatorName]) -> Committee {
+    let validators = names
+        .iter()
+        .map(|name| {
+            let state = ValidatorState {
+                network_address: name.to_string(),
+                votes: 1,
+                fee_recipient: None,
+            };
+            (*name, state)
+        })
+        .collect();
+    Committee::new(validators, ResourceControlPolicy::default())
+}
+
+#[test]
+fn test_backoff_grows_exponentially_and_resets() {
+    let mut stats = ValidatorStats::default();
+    let now = Instant::now();
+    stats.record_failure(now);
+    assert_eq!(stats.backoff_until, Some(now + MIN_BACKOFF));
+    stats.record_failure(now);
+    assert_eq!(stats.backoff_until, Some(now + 2 * MIN_BACKOFF));
+    for _ in 0..100 {
+        stats.record_failure(now);
+    }
+    assert_eq!(stats.backoff_until, Some(now + MAX_BACKOFF));
+    assert!(stats.is_backed_off(now));
+    stats.record_success(Duration::from_millis(10));
+    assert!(!stats.is_backed_off(now));
+    assert_eq!(stats.consecutive_failures, 0);
+    assert_eq!(stats.failures, 102);
+}
+
+#[test]
+fn test_rank_prefers_healthy_validators() {
+    let names = (0..4)
+        .map(|_| ValidatorName(KeyPair::generate().public()))
+        .collect::<Vec<_>>();
+    let health = ValidatorHealth::default();
+    let unreachable = Err::<(), _>(NodeError::ClientIoError {
+        error: "connection refused".to_string(),
+    });
+    health.record(names[0], Duration::from_secs(1), &unreachable);
+    health.record(names[1], Duration::from_millis(900), &Ok(()));
+    health.record(names[2], Duration::from_millis(10), &Ok(()));
+    // Protocol errors do not count as failures.
+    health.record(
+        names[3],
+        Duration::from_millis(100),
+        &Err::<(), _>(NodeError::InactiveChain(ChainId::root(0))),
+    );
+    let nodes = names.iter().map(|name| (*name, ())).collect::<Vec<_>>();
+    let ranked = health
+        .rank(nodes.clone())
+        .into_iter()
+        .map(|(name, ())| name)
+        .collect::<Vec<_>>();
+    assert_eq!(ranked, vec![names[2], names[3], names[1], names[0]]);
+
+    let preferred = health.preferred(nodes.clone());
+    assert_eq!(preferred.len(), 3);
+
+    // Validators we are backing off from are contacted last, but still contacted.
+    let committee = make_committee(&names);
+    let selected = health
+        .select_for_quorum(&committee, nodes)
+        .into_iter()
+        .map(|(name, ())| name)
+        .collect::<Vec<_>>();
+    assert_eq!(selected, ranked);
+}
+
+#[test]
+fn test_grace_period_follows_latencies() {
+    let names = (0..4)
+        .map(|_| ValidatorName(KeyPair::generate().public()))
+        .collect::<Vec<_>>();
+    let committee = make_committee(&names);
+    let health = ValidatorHealth::default();
+    assert_eq!(health.grace_period(&committee), None);
+
+    health.record(names[0], Duration::from_millis(100), &Ok(()));
+    health.record(names[1], Duration::from_millis(200), &Ok(()));
+    health.record(names[2], Duration::from_millis(300), &Ok(()));
+    health.record(names[3], Duration::from_millis(400), &Ok(()));
+    // The quorum is reached after 300 ms, and the last validator answers 100 ms later.
+    assert_eq!(
+        health.grace_period(&committee),
+        Some(Duration::from_millis(100))
+    );
+
+    // We don't wait for validators we are backing off from.
+    let unreachable = Err::<(), _>(NodeError::ClientIoError {
+        error: "connection refused".to_string(),
+    });
+    health.record(names[3], Duration::from_secs(1), &unreachable);
+    assert_eq!(health.grace_period(&committee), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_only_comparable_requests_update_latency() {
+    let name = ValidatorName(KeyPair::generate().public());
+    let health = ValidatorHealth::default();
+    health.record(name, Duration::from_millis(100), &Ok(()));
+    // Executing a block takes much longer than answering a query, but is still a success.
+    health.record_outcome(name, &Ok(()));
+    let stats = health.stats(&name).unwrap();
+    assert_eq!(stats.latency, Some(Duration::from_millis(100)));
+    assert_eq!(stats.successes, 2);
+
+    let unreachable = Err::<(), _>(NodeError::ClientIoError {
+        error: "connection refused".to_string(),
+    });
+    health.record_outcome(name, &unreachable);
+    let stats = health.stats(&name).unwrap();
+    assert_eq!(stats.failures, 1);
+    assert!(stats.is_backed_off(Instant::now()));
+
+    assert!(!health.knows_version(&name));
+    health.record_version(name, &Default::default());
+    assert!(health.knows_version(&name));
+    assert_eq!(health.stats(&name).unwrap().version_compatible, Some(true));
+}
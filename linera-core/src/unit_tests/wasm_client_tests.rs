@@ -17,10 +17,10 @@ use linera_base::{
 };
 use linera_chain::data_types::{CertificateValue, MessageAction, OutgoingMessage};
 use linera_execution::{
-    Bytecode, Message, MessageKind, Operation, ResourceControlPolicy, SystemMessage,
-    UserApplicationDescription, WasmRuntime,
+    system::UserData, Bytecode, Message, MessageKind, Operation, ResourceControlPolicy,
+    SystemMessage, UserApplicationDescription, WasmRuntime,
 };
-use linera_storage::Storage;
+use linera_storage::{Clock as _, Storage};
 use linera_views::views::ViewError;
 use serde_json::json;
 use std::collections::BTreeMap;
@@ -194,6 +194,7 @@ where
     B: StorageBuilder,
     ViewError: From<<B::Storage as Storage>::ContextError>,
 {
+    let clock = storage_builder.clock().clone();
     let mut builder = TestBuilder::new(storage_builder, 4, 1)
         .await?
         .with_policy(ResourceControlPolicy::all_categories());
@@ -350,6 +351,33 @@ where
     assert_eq!(incoming_messages[1].event.kind, MessageKind::Bouncing);
     assert_matches!(incoming_messages[1].event.message, Message::User { .. });
 
+    // A tracked message with a deadline that passes before the receiver learns about it.
+    let deadline = clock.current_time().saturating_add_micros(1_000);
+    let mut operation = meta_counter::Operation::increment(receiver_id, 3);
+    operation.is_tracked = true;
+    operation.deadline = Some(deadline);
+    creator
+        .execute_operation(Operation::user(application_id2, &operation)?)
+        .await
+        .unwrap()
+        .unwrap();
+    clock.set(deadline.saturating_add_micros(1));
+
+    // The validators require the receiver's next block to reject the message. The client
+    // synchronizes its inbox and retries.
+    let cert = receiver
+        .burn(None, Amount::from_millis(1), UserData::default())
+        .await
+        .unwrap()
+        .unwrap();
+    let incoming_messages = &cert.value().block().unwrap().incoming_messages;
+    assert_eq!(incoming_messages.len(), 1);
+    assert_eq!(incoming_messages[0].action, MessageAction::Reject);
+    assert_eq!(incoming_messages[0].event.deadline, Some(deadline));
+    let messages = cert.value().messages().unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].kind, MessageKind::Bouncing);
+
     Ok(())
 }
 
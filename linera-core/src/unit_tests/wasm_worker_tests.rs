@@ -143,6 +143,8 @@ where
             grant: Amount::ZERO,
             refund_grant_to: None,
             kind: MessageKind::Protected,
+            deadline: None,
+            response_to: None,
             message: Message::System(publish_message.clone()),
         }],
         message_counts: vec![1],
@@ -174,11 +176,14 @@ where
             refund_grant_to: None,
             kind: MessageKind::Protected,
             timestamp: Timestamp::from(1),
+            deadline: None,
+            response_to: None,
             message: Message::System(publish_message),
         },
         action: MessageAction::Accept,
     };
     let broadcast_block = make_child_block(&publish_certificate.value)
+        .with_previous_block_signatures(&publish_certificate, &committee)
         .with_timestamp(1)
         .with_incoming_message(broadcast_message);
     let bytecode_id = BytecodeId::new(MessageId {
@@ -208,6 +213,8 @@ where
             grant: Amount::ZERO,
             refund_grant_to: None,
             kind: MessageKind::Simple,
+            deadline: None,
+            response_to: None,
             message: Message::System(broadcast_message.clone()),
         }],
         message_counts: vec![1],
@@ -260,6 +267,8 @@ where
             grant: Amount::ZERO,
             refund_grant_to: None,
             kind: MessageKind::Protected,
+            deadline: None,
+            response_to: None,
             message: Message::System(subscribe_message.clone()),
         }],
         message_counts: vec![1],
@@ -291,11 +300,14 @@ where
             refund_grant_to: None,
             kind: MessageKind::Protected,
             timestamp: Timestamp::from(2),
+            deadline: None,
+            response_to: None,
             message: subscribe_message.into(),
         },
         action: MessageAction::Accept,
     };
     let accept_block = make_child_block(&broadcast_certificate.value)
+        .with_previous_block_signatures(&broadcast_certificate, &committee)
         .with_timestamp(3)
         .with_incoming_message(accept_message);
     publisher_system_state.timestamp = Timestamp::from(3);
@@ -308,6 +320,8 @@ where
             grant: Amount::ZERO,
             refund_grant_to: None,
             kind: MessageKind::Protected,
+            deadline: None,
+            response_to: None,
             message: Message::System(SystemMessage::Notify {
                 id: creator_chain.into(),
             }),
@@ -353,12 +367,15 @@ where
         creation: application_id.creation,
         required_application_ids: vec![],
         parameters: parameters_bytes,
+        governance: None,
+        upgrades: Vec::new(),
     };
     let publish_admin_channel = ChannelFullName {
         application_id: GenericApplicationId::System,
         name: SystemChannel::PublishedBytecodes.name(),
     };
     let create_block = make_child_block(&subscribe_certificate.value)
+        .with_previous_block_signatures(&subscribe_certificate, &committee)
         .with_timestamp(4)
         .with_operation(create_operation)
         .with_incoming_message(IncomingMessage {
@@ -372,6 +389,8 @@ where
                 refund_grant_to: None,
                 kind: MessageKind::Simple,
                 timestamp: Timestamp::from(1),
+                deadline: None,
+                response_to: None,
                 message: Message::System(broadcast_message),
             },
             action: MessageAction::Accept,
@@ -401,6 +420,8 @@ where
             grant: Amount::ZERO,
             refund_grant_to: None,
             kind: MessageKind::Protected,
+            deadline: None,
+            response_to: None,
             message: Message::System(SystemMessage::ApplicationCreated),
         }],
         message_counts: vec![0, 1],
@@ -424,6 +445,7 @@ where
     let increment = 5_u64;
     let user_operation = bcs::to_bytes(&increment)?;
     let run_block = make_child_block(&create_certificate.value)
+        .with_previous_block_signatures(&create_certificate, &committee)
         .with_timestamp(5)
         .with_operation(Operation::User {
             application_id,
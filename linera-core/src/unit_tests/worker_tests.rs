@@ -14,6 +14,7 @@ use crate::{
     },
 };
 use assert_matches::assert_matches;
+use futures::TryStreamExt;
 use linera_base::{
     crypto::{CryptoHash, *},
     data_types::*,
@@ -224,6 +225,9 @@ async fn make_transfer_certificate_for_epoch<S>(
     };
     let block_template = match &previous_confirmed_block {
         None => make_first_block(chain_id),
+        Some(cert) if cert.value().epoch() == epoch => {
+            make_child_block(&cert.value).with_previous_block_signatures(cert, committee)
+        }
         Some(cert) => make_child_block(&cert.value),
     };
 
@@ -240,6 +244,8 @@ async fn make_transfer_certificate_for_epoch<S>(
                 grant: Amount::ZERO,
                 refund_grant_to: None,
                 kind: MessageKind::Bouncing,
+                deadline: None,
+                response_to: None,
                 message: incoming_message.event.message.clone(),
             });
             message_count += 1;
@@ -291,6 +297,8 @@ fn direct_outgoing_message(
         grant: Amount::ZERO,
         refund_grant_to: None,
         kind,
+        deadline: None,
+        response_to: None,
         message: Message::System(message),
     }
 }
@@ -306,6 +314,8 @@ fn channel_outgoing_message(
         grant: Amount::ZERO,
         refund_grant_to: None,
         kind,
+        deadline: None,
+        response_to: None,
         message: Message::System(message),
     }
 }
@@ -730,6 +740,7 @@ where
     )
     .await;
     let block_proposal1 = make_child_block(&certificate0.value)
+        .with_previous_block_signatures(&certificate0, &committee)
         .with_simple_transfer(ChainId::root(2), Amount::from_tokens(2))
         .into_fast_proposal(&sender_key_pair);
 
@@ -780,6 +791,104 @@ where
     );
 }
 
+#[test(tokio::test)]
+async fn test_memory_certificates_in_range() {
+    let storage = MemoryStorage::make_test_storage(None).await;
+    run_test_certificates_in_range(storage).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[test(tokio::test)]
+async fn test_rocks_db_certificates_in_range() {
+    let _lock = ROCKS_DB_SEMAPHORE.acquire().await;
+    let (storage, _dir) = RocksDbStorage::make_test_storage(None).await;
+    run_test_certificates_in_range(storage).await;
+}
+
+#[cfg(feature = "aws")]
+#[test(tokio::test)]
+async fn test_dynamo_db_certificates_in_range() {
+    let storage = DynamoDbStorage::make_test_storage(None).await;
+    run_test_certificates_in_range(storage).await;
+}
+
+#[cfg(feature = "scylladb")]
+#[test(tokio::test)]
+async fn test_scylla_db_certificates_in_range() {
+    let storage = ScyllaDbStorage::make_test_storage(None).await;
+    run_test_certificates_in_range(storage).await;
+}
+
+async fn run_test_certificates_in_range<S>(storage: S)
+where
+    S: Storage + Clone + Send + Sync + 'static,
+    ViewError: From<S::ContextError>,
+{
+    let sender_key_pair = KeyPair::generate();
+    let (committee, mut worker) = init_worker_with_chains(
+        storage,
+        vec![(
+            ChainDescription::Root(1),
+            sender_key_pair.public(),
+            Amount::from_tokens(5),
+        )],
+    )
+    .await;
+    let certificate0 = make_simple_transfer_certificate(
+        ChainDescription::Root(1),
+        &sender_key_pair,
+        ChainId::root(2),
+        Amount::ONE,
+        Vec::new(),
+        &committee,
+        Amount::from_tokens(4),
+        &worker,
+        None,
+    )
+    .await;
+    worker
+        .fully_handle_certificate(certificate0.clone(), vec![])
+        .await
+        .unwrap();
+    let certificate1 = make_simple_transfer_certificate(
+        ChainDescription::Root(1),
+        &sender_key_pair,
+        ChainId::root(2),
+        Amount::ONE,
+        Vec::new(),
+        &committee,
+        Amount::from_tokens(3),
+        &worker,
+        Some(&certificate0),
+    )
+    .await;
+    worker
+        .fully_handle_certificate(certificate1.clone(), vec![])
+        .await
+        .unwrap();
+
+    let ranges = [
+        (0, None, vec![certificate0.clone(), certificate1.clone()]),
+        (0, Some(1), vec![certificate0]),
+        (1, Some(5), vec![certificate1]),
+        (2, None, vec![]),
+    ];
+    for (start, limit, expected) in ranges {
+        let range = BlockHeightRange {
+            start: BlockHeight::from(start),
+            limit,
+        };
+        let certificates = worker
+            .certificates_in_range(ChainId::root(1), range)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(certificates, expected);
+    }
+}
+
 #[test(tokio::test)]
 async fn test_memory_handle_block_proposal_with_incoming_messages() {
     let storage = MemoryStorage::make_test_storage(None).await;
@@ -862,6 +971,7 @@ where
         &worker,
         HashedValue::new_confirmed(ExecutedBlock {
             block: make_child_block(&certificate0.value)
+                .with_previous_block_signatures(&certificate0, &committee)
                 .with_simple_transfer(ChainId::root(2), Amount::from_tokens(3)),
             messages: vec![direct_credit_message(
                 ChainId::root(2),
@@ -970,6 +1080,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::ONE),
                 },
                 action: MessageAction::Accept,
@@ -985,6 +1097,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(2)),
                 },
                 action: MessageAction::Accept,
@@ -1000,6 +1114,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(2)), // wrong amount
                 },
                 action: MessageAction::Accept,
@@ -1026,6 +1142,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(2)),
                 },
                 action: MessageAction::Accept,
@@ -1052,6 +1170,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(3)),
                 },
                 action: MessageAction::Accept,
@@ -1067,6 +1187,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::ONE),
                 },
                 action: MessageAction::Accept,
@@ -1082,6 +1204,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(2)),
                 },
                 action: MessageAction::Accept,
@@ -1108,6 +1232,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::ONE),
                 },
                 action: MessageAction::Accept,
@@ -1141,6 +1267,7 @@ where
 
         // Then receive the next two messages.
         let block_proposal = make_child_block(&certificate.value)
+            .with_previous_block_signatures(&certificate, &committee)
             .with_simple_transfer(ChainId::root(3), Amount::from_tokens(3))
             .with_incoming_message(IncomingMessage {
                 origin: Origin::chain(ChainId::root(1)),
@@ -1153,6 +1280,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(2)),
                 },
                 action: MessageAction::Accept,
@@ -1168,6 +1297,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: system_credit_message(Amount::from_tokens(3)),
                 },
                 action: MessageAction::Accept,
@@ -1533,6 +1664,8 @@ where
             refund_grant_to: None,
             kind: MessageKind::Protected,
             timestamp: Timestamp::from(0),
+            deadline: None,
+            response_to: None,
             message: Message::System(SystemMessage::OpenChain(OpenChainConfig {
                 ownership,
                 admin_id,
@@ -1762,6 +1895,8 @@ where
                 refund_grant_to: None,
                 kind: MessageKind::Tracked,
                 timestamp: Timestamp::from(0),
+                deadline: None,
+                response_to: None,
                 message: system_credit_message(Amount::from_tokens(995)),
             },
             action: MessageAction::Accept,
@@ -1812,6 +1947,8 @@ where
             refund_grant_to: None,
             kind: MessageKind::Tracked,
             timestamp,
+            deadline: None,
+            response_to: None,
             message: Message::System(SystemMessage::Credit { amount, .. }),
         } if certificate_hash == CryptoHash::test_hash("certificate")
             && height == BlockHeight::ZERO
@@ -2009,6 +2146,8 @@ where
             refund_grant_to: None,
             kind: MessageKind::Tracked,
             timestamp,
+            deadline: None,
+            response_to: None,
             message: Message::System(SystemMessage::Credit { amount, .. })
         } if certificate_hash == certificate.hash()
             && height == BlockHeight::ZERO
@@ -2115,6 +2254,8 @@ where
             refund_grant_to: None,
             kind: MessageKind::Tracked,
             timestamp,
+            deadline: None,
+            response_to: None,
             message: Message::System(SystemMessage::Credit { amount, .. })
         } if certificate_hash == certificate.hash()
             && height == BlockHeight::ZERO
@@ -2378,6 +2519,8 @@ where
                 refund_grant_to: None,
                 kind: MessageKind::Tracked,
                 timestamp: Timestamp::from(0),
+                deadline: None,
+                response_to: None,
                 message: system_credit_message(Amount::from_tokens(5)),
             },
             action: MessageAction::Accept,
@@ -2612,6 +2755,8 @@ where
                 refund_grant_to: None,
                 kind: MessageKind::Tracked,
                 timestamp: Timestamp::from(0),
+                deadline: None,
+                response_to: None,
                 message: Message::System(SystemMessage::Credit {
                     source: None,
                     target: Some(sender),
@@ -2702,6 +2847,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: Message::System(SystemMessage::Credit {
                         source: Some(sender),
                         target: Some(recipient),
@@ -2721,6 +2868,8 @@ where
                     refund_grant_to: None,
                     kind: MessageKind::Tracked,
                     timestamp: Timestamp::from(0),
+                    deadline: None,
+                    response_to: None,
                     message: Message::System(SystemMessage::Credit {
                         source: Some(sender),
                         target: Some(recipient),
@@ -2770,6 +2919,8 @@ where
                 refund_grant_to: None,
                 kind: MessageKind::Bouncing,
                 timestamp: Timestamp::from(0),
+                deadline: None,
+                response_to: None,
                 message: Message::System(SystemMessage::Credit {
                     source: Some(sender),
                     target: Some(recipient),
@@ -2949,6 +3100,7 @@ where
         &worker,
         HashedValue::new_confirmed(ExecutedBlock {
             block: make_child_block(&certificate0.value)
+                .with_previous_block_signatures(&certificate0, &committee)
                 .with_operation(SystemOperation::Admin(AdminOperation::CreateCommittee {
                     epoch: Epoch::from(1),
                     committee: committee.clone(),
@@ -2995,6 +3147,8 @@ where
                         refund_grant_to: None,
                         kind: MessageKind::Protected,
                         timestamp: Timestamp::from(0),
+                        deadline: None,
+                        response_to: None,
                         message: Message::System(SystemMessage::Subscribe {
                             id: user_id,
                             subscription: admin_channel_subscription.clone(),
@@ -3120,6 +3274,8 @@ where
                         refund_grant_to: None,
                         kind: MessageKind::Protected,
                         timestamp: Timestamp::from(0),
+                        deadline: None,
+                        response_to: None,
                         message: Message::System(SystemMessage::OpenChain(OpenChainConfig {
                             ownership: ChainOwnership::single(key_pair.public()),
                             epoch: Epoch::from(0),
@@ -3142,6 +3298,8 @@ where
                         refund_grant_to: None,
                         kind: MessageKind::Protected,
                         timestamp: Timestamp::from(0),
+                        deadline: None,
+                        response_to: None,
                         message: Message::System(SystemMessage::SetCommittees {
                             epoch: Epoch::from(1),
                             committees: committees2.clone(),
@@ -3160,6 +3318,8 @@ where
                         refund_grant_to: None,
                         kind: MessageKind::Tracked,
                         timestamp: Timestamp::from(0),
+                        deadline: None,
+                        response_to: None,
                         message: system_credit_message(Amount::from_tokens(2)),
                     },
                     action: MessageAction::Accept,
@@ -3175,6 +3335,8 @@ where
                         refund_grant_to: None,
                         kind: MessageKind::Protected,
                         timestamp: Timestamp::from(0),
+                        deadline: None,
+                        response_to: None,
                         message: Message::System(SystemMessage::Notify { id: user_id }),
                     },
                     action: MessageAction::Accept,
@@ -3557,6 +3719,8 @@ where
                         refund_grant_to: None,
                         kind: MessageKind::Tracked,
                         timestamp: Timestamp::from(0),
+                        deadline: None,
+                        response_to: None,
                         message: system_credit_message(Amount::ONE),
                     },
                     action: MessageAction::Accept,
@@ -3865,12 +4029,14 @@ where
         owners: vec![(pub_key0, 100), (pub_key1, 100)],
         multi_leader_rounds: 0,
         timeout_config: TimeoutConfig::default(),
+        approval_threshold: None,
+        open_multi_leader_rounds: false,
     });
     let (executed_block0, _) = worker.stage_block_execution(block0).await.unwrap();
     let value0 = HashedValue::new_confirmed(executed_block0);
     let certificate0 = make_certificate(&committee, &worker, value0.clone());
     let response = worker
-        .fully_handle_certificate(certificate0, vec![])
+        .fully_handle_certificate(certificate0.clone(), vec![])
         .await
         .unwrap();
 
@@ -3917,7 +4083,8 @@ where
     assert_eq!(response.info.manager.leader, Some(Owner::from(pub_key0)));
 
     // Now owner 0 can propose a block, but owner 1 can't.
-    let block1 = make_child_block(&value0);
+    let block1 =
+        make_child_block(&value0).with_previous_block_signatures(&certificate0, &committee);
     let (executed_block1, _) = worker.stage_block_execution(block1.clone()).await.unwrap();
     let proposal1_wrong_owner = block1
         .clone()
@@ -3957,7 +4124,9 @@ where
 
     // Create block2, also at height 1, but different from block 1.
     let amount = Amount::from_tokens(1);
-    let block2 = make_child_block(&value0).with_simple_transfer(ChainId::root(1), amount);
+    let block2 = make_child_block(&value0)
+        .with_previous_block_signatures(&certificate0, &committee)
+        .with_simple_transfer(ChainId::root(1), amount);
     let (executed_block2, _) = worker.stage_block_execution(block2.clone()).await.unwrap();
 
     // Since round 3 is already over, a validated block from round 3 won't update the validator's
@@ -4104,12 +4273,14 @@ where
             fast_round_duration: Some(Duration::from_secs(5)),
             ..TimeoutConfig::default()
         },
+        approval_threshold: None,
+        open_multi_leader_rounds: false,
     });
     let (executed_block0, _) = worker.stage_block_execution(block0).await.unwrap();
     let value0 = HashedValue::new_confirmed(executed_block0);
     let certificate0 = make_certificate(&committee, &worker, value0.clone());
     let response = worker
-        .fully_handle_certificate(certificate0, vec![])
+        .fully_handle_certificate(certificate0.clone(), vec![])
         .await
         .unwrap();
 
@@ -4156,7 +4327,8 @@ where
     assert_eq!(response.info.manager.leader, None);
 
     // Now any owner can propose a block. And multi-leader rounds can be skipped without timeout.
-    let block1 = make_child_block(&value0);
+    let block1 =
+        make_child_block(&value0).with_previous_block_signatures(&certificate0, &committee);
     let proposal1 = block1
         .clone()
         .into_proposal_with_round(&key_pairs[1], Round::MultiLeader(1));
@@ -4217,12 +4389,14 @@ where
             fast_round_duration: Some(Duration::from_millis(5)),
             ..TimeoutConfig::default()
         },
+        approval_threshold: None,
+        open_multi_leader_rounds: false,
     });
     let (executed_block0, _) = worker.stage_block_execution(block0).await.unwrap();
     let value0 = HashedValue::new_confirmed(executed_block0);
     let certificate0 = make_certificate(&committee, &worker, value0.clone());
     let response = worker
-        .fully_handle_certificate(certificate0, vec![])
+        .fully_handle_certificate(certificate0.clone(), vec![])
         .await
         .unwrap();
 
@@ -4231,7 +4405,8 @@ where
     assert_eq!(response.info.manager.leader, None);
 
     // Owner 0 proposes another block. The validator votes to confirm.
-    let block1 = make_child_block(&value0);
+    let block1 =
+        make_child_block(&value0).with_previous_block_signatures(&certificate0, &committee);
     let proposal1 = block1
         .clone()
         .into_proposal_with_round(&key_pairs[0], Round::Fast);
@@ -4257,7 +4432,9 @@ where
     assert_eq!(response.info.manager.leader, None);
 
     // Now any owner can propose a block. But block1 is locked.
-    let block2 = make_child_block(&value0).with_simple_transfer(ChainId::root(1), Amount::ONE);
+    let block2 = make_child_block(&value0)
+        .with_previous_block_signatures(&certificate0, &committee)
+        .with_simple_transfer(ChainId::root(1), Amount::ONE);
     let proposal2 = block2
         .clone()
         .into_proposal_with_round(&key_pairs[1], Round::MultiLeader(0));
@@ -26,7 +26,7 @@ use thiserror::Error;
 use tracing::{error, warn};
 
 /// The amount of time we wait for additional validators to contribute to the result, as a fraction
-/// of how long it took to reach a quorum.
+/// of how long it took to reach a quorum, if no grace period is given explicitly.
 const GRACE_PERIOD: f64 = 0.2;
 /// The maximum timeout for `communicate_with_quorum` if no quorum is reached.
 const MAX_TIMEOUT: Duration = Duration::from_secs(60 * 60 * 24); // 1 day.
@@ -70,12 +70,13 @@ pub enum CommunicationError<E: fmt::Debug> {
 
 /// Executes a sequence of actions in parallel for all validators.
 ///
-/// Tries to stop early when a quorum is reached. If `grace_period` is not zero, other validators
-/// are given this much additional time to contribute to the result, as a fraction of how long it
-/// took to reach the quorum.
+/// Tries to stop early when a quorum is reached. Other validators are then given the
+/// `grace_period` to contribute to the result, or, if it is `None`, a fixed fraction of how long
+/// it took to reach the quorum.
 pub async fn communicate_with_quorum<'a, A, V, K, F, G>(
     validator_clients: &'a [(ValidatorName, A)],
     committee: &Committee,
+    grace_period: Option<Duration>,
     group_by: G,
     execute: F,
 ) -> Result<(K, Vec<V>), CommunicationError<NodeError>>
@@ -139,7 +140,9 @@ where
             && (highest_key_score >= committee.quorum_threshold()
                 || highest_key_score + remaining_votes < committee.quorum_threshold())
         {
-            end_time = Some(Instant::now() + start_time.elapsed().mul_f64(GRACE_PERIOD));
+            let grace_period =
+                grace_period.unwrap_or_else(|| start_time.elapsed().mul_f64(GRACE_PERIOD));
+            end_time = Some(Instant::now() + grace_period);
         }
     }
 
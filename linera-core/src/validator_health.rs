@@ -0,0 +1,432 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracking of the observed health of validators, used by clients to prefer responsive
+//! validators and to back off from failing ones.
+
+use crate::{
+    data_types::{BlockHeightRange, ChainInfoQuery, ChainInfoResponse},
+    node::{
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+    },
+};
+use async_trait::async_trait;
+use linera_base::identifiers::ChainId;
+use linera_chain::data_types::{BlockProposal, Certificate, HashedValue, LiteCertificate};
+use linera_execution::committee::{Committee, ValidatorName};
+use linera_version::VersionInfo;
+use rand::prelude::SliceRandom;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+#[path = "unit_tests/validator_health_tests.rs"]
+mod validator_health_tests;
+
+/// The weight of the latest observation in the moving averages.
+const SMOOTHING_FACTOR: f64 = 0.2;
+/// The latency assumed for validators we have not heard from yet.
+const DEFAULT_LATENCY: Duration = Duration::from_millis(500);
+/// How much a validator's latency is inflated for an error rate of 100%.
+const ERROR_RATE_PENALTY: f64 = 10.0;
+/// The penalty, in seconds, for validators running an incompatible version.
+const INCOMPATIBLE_VERSION_PENALTY: f64 = 60.0;
+/// The back-off period after the first failure in a row.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+/// The maximal back-off period, no matter how many failures in a row.
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Statistics about the past interactions with a validator.
+#[derive(Clone, Debug, Default)]
+pub struct ValidatorStats {
+    /// The number of successful requests.
+    pub successes: u64,
+    /// The number of requests that failed because the validator was unavailable or
+    /// misbehaved.
+    pub failures: u64,
+    /// The number of failures since the last success.
+    pub consecutive_failures: u32,
+    /// The exponential moving average of the latency of successful chain information and
+    /// version queries. Other requests take too long, or too variable a time, to compare.
+    pub latency: Option<Duration>,
+    /// The exponential moving average of the error rate, between 0 and 1.
+    pub error_rate: f64,
+    /// Whether the last known version of the validator is compatible with ours.
+    pub version_compatible: Option<bool>,
+    /// The instant until which the validator should not be contacted for reads.
+    pub backoff_until: Option<Instant>,
+}
+
+impl ValidatorStats {
+    /// Records a successful request that took `latency`.
+    pub fn record_success(&mut self, latency: Duration) {
+        self.record_response();
+        self.latency = Some(match self.latency {
+            None => latency,
+            Some(average) => average
+                .mul_f64(1.0 - SMOOTHING_FACTOR)
+                .saturating_add(latency.mul_f64(SMOOTHING_FACTOR)),
+        });
+    }
+
+    /// Records a successful request whose latency is not comparable to the others.
+    pub fn record_response(&mut self) {
+        self.successes += 1;
+        self.consecutive_failures = 0;
+        self.backoff_until = None;
+        self.error_rate *= 1.0 - SMOOTHING_FACTOR;
+    }
+
+    /// Records a failed request, and backs off exponentially from this validator.
+    pub fn record_failure(&mut self, now: Instant) {
+        self.failures += 1;
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.error_rate = self.error_rate * (1.0 - SMOOTHING_FACTOR) + SMOOTHING_FACTOR;
+        let exponent = (self.consecutive_failures - 1).min(16);
+        let backoff = MIN_BACKOFF.saturating_mul(1 << exponent).min(MAX_BACKOFF);
+        self.backoff_until = Some(now + backoff);
+    }
+
+    /// Records the version reported by the validator.
+    pub fn record_version(&mut self, version: &VersionInfo) {
+        self.version_compatible = Some(version.is_compatible_with(&VersionInfo::default()));
+    }
+
+    /// Returns whether we are currently backing off from this validator.
+    pub fn is_backed_off(&self, now: Instant) -> bool {
+        self.backoff_until.is_some_and(|until| now < until)
+    }
+
+    /// Returns a penalty score for this validator: lower is better.
+    pub fn penalty(&self) -> f64 {
+        let latency = self.latency.unwrap_or(DEFAULT_LATENCY).as_secs_f64();
+        let mut penalty = latency * (1.0 + ERROR_RATE_PENALTY * self.error_rate);
+        if self.version_compatible == Some(false) {
+            penalty += INCOMPATIBLE_VERSION_PENALTY;
+        }
+        penalty
+    }
+}
+
+impl fmt::Display for ValidatorStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let latency = match self.latency {
+            Some(latency) => format!("{} ms", latency.as_millis()),
+            None => "unknown".to_string(),
+        };
+        let version = match self.version_compatible {
+            Some(true) => "compatible",
+            Some(false) => "incompatible",
+            None => "unknown",
+        };
+        write!(
+            f,
+            "latency: {latency}, successes: {}, failures: {} ({} in a row), \
+             error rate: {:.1}%, version: {version}",
+            self.successes,
+            self.failures,
+            self.consecutive_failures,
+            100.0 * self.error_rate,
+        )?;
+        if self.is_backed_off(Instant::now()) {
+            write!(f, ", backing off")?;
+        }
+        Ok(())
+    }
+}
+
+/// Shared statistics about the health of all the validators a client talks to.
+///
+/// The statistics are only kept in memory, so they start from scratch every time the client
+/// is started.
+#[derive(Clone, Default)]
+pub struct ValidatorHealth {
+    stats: Arc<Mutex<HashMap<ValidatorName, ValidatorStats>>>,
+}
+
+impl ValidatorHealth {
+    /// Returns the statistics of the given validator, if we have interacted with it.
+    pub fn stats(&self, name: &ValidatorName) -> Option<ValidatorStats> {
+        self.stats.lock().unwrap().get(name).cloned()
+    }
+
+    /// Returns the statistics of all the validators we have interacted with.
+    pub fn all_stats(&self) -> HashMap<ValidatorName, ValidatorStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Records the outcome of a request to a validator that took `latency`.
+    pub fn record<T>(&self, name: ValidatorName, latency: Duration, result: &Result<T, NodeError>) {
+        self.record_with_latency(name, Some(latency), result);
+    }
+
+    /// Records the outcome of a request to a validator whose latency is not comparable to the
+    /// others, e.g. because the validator has to execute a block to answer it.
+    pub fn record_outcome<T>(&self, name: ValidatorName, result: &Result<T, NodeError>) {
+        self.record_with_latency(name, None, result);
+    }
+
+    fn record_with_latency<T>(
+        &self,
+        name: ValidatorName,
+        latency: Option<Duration>,
+        result: &Result<T, NodeError>,
+    ) {
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(name).or_default();
+        match (result, latency) {
+            (Err(error), _) if is_availability_failure(error) => {
+                entry.record_failure(Instant::now())
+            }
+            // Other errors are legitimate protocol answers, e.g. about missing messages.
+            (_, Some(latency)) => entry.record_success(latency),
+            (_, None) => entry.record_response(),
+        }
+    }
+
+    /// Records the version reported by a validator.
+    pub fn record_version(&self, name: ValidatorName, version: &VersionInfo) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.entry(name).or_default().record_version(version);
+    }
+
+    /// Returns whether we know the version of the given validator.
+    pub fn knows_version(&self, name: &ValidatorName) -> bool {
+        self.stats
+            .lock()
+            .unwrap()
+            .get(name)
+            .is_some_and(|stats| stats.version_compatible.is_some())
+    }
+
+    /// Wraps the given nodes so that their requests update these statistics.
+    pub fn track<A>(&self, nodes: Vec<(ValidatorName, A)>) -> Vec<(ValidatorName, TrackedNode<A>)> {
+        nodes
+            .into_iter()
+            .map(|(name, node)| {
+                let node = TrackedNode {
+                    name,
+                    node,
+                    health: self.clone(),
+                };
+                (name, node)
+            })
+            .collect()
+    }
+
+    /// Orders the given validators from the healthiest to the least healthy. Validators
+    /// with equal scores are shuffled.
+    pub fn rank<A>(&self, mut nodes: Vec<(ValidatorName, A)>) -> Vec<(ValidatorName, A)> {
+        nodes.shuffle(&mut rand::thread_rng());
+        let now = Instant::now();
+        let stats = self.stats.lock().unwrap();
+        let key = |name: &ValidatorName| match stats.get(name) {
+            None => (false, ValidatorStats::default().penalty()),
+            Some(stats) => (stats.is_backed_off(now), stats.penalty()),
+        };
+        nodes.sort_by(|(name1, _), (name2, _)| {
+            let (backed_off1, penalty1) = key(name1);
+            let (backed_off2, penalty2) = key(name2);
+            backed_off1
+                .cmp(&backed_off2)
+                .then(penalty1.total_cmp(&penalty2))
+        });
+        nodes
+    }
+
+    /// Returns the validators to use for reads: the healthy ones, best first. If we are
+    /// backing off from every validator, all of them are returned anyway.
+    pub fn preferred<A>(&self, nodes: Vec<(ValidatorName, A)>) -> Vec<(ValidatorName, A)> {
+        let mut nodes = self.rank(nodes);
+        let now = Instant::now();
+        let stats = self.stats.lock().unwrap();
+        let healthy = nodes
+            .iter()
+            .take_while(|(name, _)| !stats.get(name).is_some_and(|s| s.is_backed_off(now)))
+            .count();
+        if healthy > 0 {
+            nodes.truncate(healthy);
+        }
+        nodes
+    }
+
+    /// Returns the validators to contact for an operation requiring a quorum, best first.
+    ///
+    /// Validators we are backing off from come last, but are never left out: their votes may
+    /// still be needed, and a successful answer ends the back-off.
+    pub fn select_for_quorum<A>(
+        &self,
+        committee: &Committee,
+        nodes: Vec<(ValidatorName, A)>,
+    ) -> Vec<(ValidatorName, A)> {
+        self.rank(
+            nodes
+                .into_iter()
+                .filter(|(name, _)| committee.weight(name) > 0)
+                .collect(),
+        )
+    }
+
+    /// Returns how long to keep waiting for the remaining validators once a quorum of
+    /// `committee` has answered, based on the measured latencies.
+    ///
+    /// This is the expected time between the answer completing a quorum and the answer of the
+    /// slowest validator we are not backing off from, but at most the time to reach the quorum.
+    /// Returns `None` if we have no latency measurements for this committee yet.
+    pub fn grace_period(&self, committee: &Committee) -> Option<Duration> {
+        let now = Instant::now();
+        let stats = self.stats.lock().unwrap();
+        let mut latencies = Vec::new();
+        let mut measured = false;
+        for (name, validator) in committee.validators() {
+            let stats = stats.get(name);
+            if stats.is_some_and(|stats| stats.is_backed_off(now)) {
+                continue;
+            }
+            let latency = stats.and_then(|stats| stats.latency);
+            measured |= latency.is_some();
+            latencies.push((latency.unwrap_or(DEFAULT_LATENCY), validator.votes));
+        }
+        if !measured {
+            return None;
+        }
+        latencies.sort();
+        let mut weight = 0;
+        let quorum_latency = latencies.iter().find_map(|(latency, votes)| {
+            weight += votes;
+            (weight >= committee.quorum_threshold()).then_some(*latency)
+        })?;
+        let slowest_latency = latencies.last()?.0;
+        Some(
+            slowest_latency
+                .saturating_sub(quorum_latency)
+                .min(quorum_latency),
+        )
+    }
+}
+
+/// Returns whether an error indicates that the validator was unavailable or misbehaved,
+/// as opposed to a regular answer to an invalid request.
+fn is_availability_failure(error: &NodeError) -> bool {
+    matches!(
+        error,
+        NodeError::GrpcError { .. }
+            | NodeError::ClientIoError { .. }
+            | NodeError::CannotResolveValidatorAddress { .. }
+            | NodeError::SubscriptionFailed { .. }
+            | NodeError::InvalidDecoding
+            | NodeError::UnexpectedMessage
+            | NodeError::InvalidChainInfoResponse
+            | NodeError::CryptoError { .. }
+    )
+}
+
+/// A validator node that reports the outcome of each request to a [`ValidatorHealth`].
+///
+/// Only the latency of chain information and version queries is measured. The first time a
+/// chain information query succeeds, the validator's version is queried too.
+#[derive(Clone)]
+pub struct TrackedNode<A> {
+    name: ValidatorName,
+    node: A,
+    health: ValidatorHealth,
+}
+
+impl<A> TrackedNode<A> {
+    fn record<T>(&self, start: Instant, result: &Result<T, NodeError>) {
+        self.health.record(self.name, start.elapsed(), result);
+    }
+
+    fn record_outcome<T>(&self, result: &Result<T, NodeError>) {
+        self.health.record_outcome(self.name, result);
+    }
+}
+
+#[async_trait]
+impl<A> ValidatorNode for TrackedNode<A>
+where
+    A: ValidatorNode + Send + Sync,
+{
+    async fn handle_block_proposal(
+        &mut self,
+        proposal: BlockProposal,
+    ) -> Result<ChainInfoResponse, NodeError> {
+        let result = self.node.handle_block_proposal(proposal).await;
+        self.record_outcome(&result);
+        result
+    }
+
+    async fn handle_lite_certificate(
+        &mut self,
+        certificate: LiteCertificate<'_>,
+        delivery: CrossChainMessageDelivery,
+    ) -> Result<ChainInfoResponse, NodeError> {
+        let result = self
+            .node
+            .handle_lite_certificate(certificate, delivery)
+            .await;
+        self.record_outcome(&result);
+        result
+    }
+
+    async fn handle_certificate(
+        &mut self,
+        certificate: Certificate,
+        blobs: Vec<HashedValue>,
+        delivery: CrossChainMessageDelivery,
+    ) -> Result<ChainInfoResponse, NodeError> {
+        let result = self
+            .node
+            .handle_certificate(certificate, blobs, delivery)
+            .await;
+        self.record_outcome(&result);
+        result
+    }
+
+    async fn handle_chain_info_query(
+        &mut self,
+        query: ChainInfoQuery,
+    ) -> Result<ChainInfoResponse, NodeError> {
+        let start = Instant::now();
+        let result = self.node.handle_chain_info_query(query).await;
+        self.record(start, &result);
+        if result.is_ok() && !self.health.knows_version(&self.name) {
+            // This is only a probe: if it fails, it is retried with the next query, without
+            // backing off from a validator that just answered.
+            if let Ok(version) = self.node.get_version_info().await {
+                self.health.record_version(self.name, &version);
+            }
+        }
+        result
+    }
+
+    async fn get_version_info(&mut self) -> Result<VersionInfo, NodeError> {
+        let start = Instant::now();
+        let result = self.node.get_version_info().await;
+        self.record(start, &result);
+        if let Ok(version) = &result {
+            self.health.record_version(self.name, version);
+        }
+        result
+    }
+
+    async fn subscribe(&mut self, chains: Vec<ChainId>) -> Result<NotificationStream, NodeError> {
+        let result = self.node.subscribe(chains).await;
+        self.record_outcome(&result);
+        result
+    }
+
+    async fn download_certificates(
+        &mut self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError> {
+        let result = self.node.download_certificates(chain_id, range).await;
+        self.record_outcome(&result);
+        result
+    }
+}
@@ -2,9 +2,11 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse, CrossChainRequest};
+use crate::data_types::{
+    BlockHeightRange, ChainInfo, ChainInfoQuery, ChainInfoResponse, CrossChainRequest,
+};
 use async_trait::async_trait;
-use futures::{future, FutureExt};
+use futures::{future, stream, FutureExt, Stream, StreamExt};
 use linera_base::{
     crypto::{CryptoHash, KeyPair},
     data_types::{ArithmeticError, BlockHeight, Round},
@@ -34,6 +36,7 @@ use std::{
     borrow::Cow,
     collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     num::NonZeroUsize,
+    ops::Range,
     sync::Arc,
     time::Duration,
 };
@@ -666,6 +669,7 @@ where
         tip.num_incoming_messages += block.incoming_messages.len() as u32;
         tip.num_operations += block.operations.len() as u32;
         tip.num_outgoing_messages += messages.len() as u32;
+        chain.tip_epoch.set(Some(block.epoch));
         chain.confirmed_log.push(certificate.hash());
         let info = ChainInfoResponse::new(&chain, self.key_pair());
         let mut actions = self.create_network_actions(&chain).await?;
@@ -922,6 +926,31 @@ where
         }
     }
 
+    /// Returns the confirmed certificates of a chain in the given range of heights, as a
+    /// stream that reads them from storage one at a time.
+    pub async fn certificates_in_range(
+        &self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<impl Stream<Item = Result<Certificate, WorkerError>> + Send + 'static, WorkerError>
+    {
+        let hashes = {
+            let chain = self.storage.load_chain(chain_id).await?;
+            let indices = confirmed_log_indices(range, chain.confirmed_log.count())?;
+            chain.confirmed_log.read(indices).await?
+        };
+        let storage = self.storage.clone();
+        Ok(stream::iter(hashes).then(move |hash| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .read_certificate(hash)
+                    .await
+                    .map_err(WorkerError::from)
+            }
+        }))
+    }
+
     /// Returns a stored [`Certificate`] for a chain's block.
     #[cfg(any(test, feature = "test"))]
     pub async fn read_certificate(
@@ -1045,9 +1074,11 @@ where
         let BlockProposal {
             content: BlockAndRound { block, .. },
             owner,
+            public_key: _,
             signature,
             blobs,
             validated,
+            approvals: _,
         } = &proposal;
         let chain_id = block.chain_id;
         let mut chain = self.storage.load_active_chain(chain_id).await?;
@@ -1071,6 +1102,12 @@ where
         // Check the authentication of the operations in the block.
         if let Some(signer) = block.authenticated_signer {
             ensure!(signer == *owner, WorkerError::InvalidSigner(signer));
+        } else {
+            // Proposers who don't own the chain must sign their blocks to pay the fees.
+            ensure!(
+                !chain.manager.get().ownership.is_open_proposer(owner),
+                WorkerError::InvalidSigner(*owner)
+            );
         }
         // Check if the chain is ready for this new block proposal.
         // This should always pass for nodes without voting key.
@@ -1102,8 +1139,10 @@ where
         let outcome = chain.execute_block(block, local_time).await?;
         // Check if the counters of tip_state would be valid.
         chain.tip_state.get().verify_counters(block, &outcome)?;
-        // Verify that the resulting chain would have no unconfirmed incoming messages.
+        // Verify that the resulting chain would have no unconfirmed incoming messages, and
+        // no expired messages left to reject.
         chain.validate_incoming_messages().await?;
+        chain.validate_message_deadlines(block, local_time).await?;
         // Reset all the staged changes as we were only validating things.
         chain.rollback();
         // Create the vote and store it in the chain state.
@@ -1278,15 +1317,8 @@ where
             info.requested_pending_messages = messages;
         }
         if let Some(range) = query.request_sent_certificates_in_range {
-            let start: usize = range.start.try_into()?;
-            let end = match range.limit {
-                None => chain.confirmed_log.count(),
-                Some(limit) => start
-                    .checked_add(usize::try_from(limit).map_err(|_| ArithmeticError::Overflow)?)
-                    .ok_or(ArithmeticError::Overflow)?
-                    .min(chain.confirmed_log.count()),
-            };
-            let keys = chain.confirmed_log.read(start..end).await?;
+            let indices = confirmed_log_indices(range, chain.confirmed_log.count())?;
+            let keys = chain.confirmed_log.read(indices).await?;
             let certs = self.storage.read_certificates(keys).await?;
             info.requested_sent_certificates = certs;
         }
@@ -1400,6 +1432,23 @@ where
     }
 }
 
+/// Returns the positions in a confirmed log of `count` entries that correspond to the given
+/// range of block heights.
+fn confirmed_log_indices(
+    range: BlockHeightRange,
+    count: usize,
+) -> Result<Range<usize>, WorkerError> {
+    let start: usize = range.start.try_into()?;
+    let end = match range.limit {
+        None => count,
+        Some(limit) => start
+            .checked_add(usize::try_from(limit).map_err(|_| ArithmeticError::Overflow)?)
+            .ok_or(ArithmeticError::Overflow)?
+            .min(count),
+    };
+    Ok(start..end)
+}
+
 struct CrossChainUpdateHelper<'a> {
     nickname: &'a str,
     allow_messages_from_deprecated_epochs: bool,
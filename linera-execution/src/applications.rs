@@ -5,16 +5,22 @@ use crate::SystemExecutionError;
 use custom_debug_derive::Debug;
 use linera_base::{
     crypto::CryptoHash,
-    hex_debug,
-    identifiers::{BytecodeId, MessageId},
+    data_types::ArithmeticError,
+    ensure, hex_debug,
+    identifiers::{BytecodeId, Destination, MessageId, Owner},
 };
 use linera_views::{
+    collection_view::CollectionView,
     common::Context,
     map_view::MapView,
+    set_view::SetView,
     views::{HashableView, ViewError},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+};
 
 #[cfg(any(test, feature = "test"))]
 use {
@@ -66,6 +72,66 @@ pub struct UserApplicationDescription {
     pub parameters: Vec<u8>,
     /// Required dependencies.
     pub required_application_ids: Vec<UserApplicationId>,
+    /// The owner allowed to upgrade the application, if any.
+    pub governance: Option<Owner>,
+    /// The upgrades of the application, in order: the `i`-th one created version `i + 1`.
+    pub upgrades: Vec<ApplicationUpgrade>,
+}
+
+impl UserApplicationDescription {
+    /// Returns the ID of the bytecode currently used by the application.
+    pub fn current_bytecode_id(&self) -> BytecodeId {
+        self.upgrades
+            .last()
+            .map_or(self.bytecode_id, |upgrade| upgrade.bytecode_id)
+    }
+
+    /// Returns the location of the bytecode currently used by the application.
+    pub fn current_bytecode_location(&self) -> BytecodeLocation {
+        self.upgrades
+            .last()
+            .map_or(self.bytecode_location, |upgrade| upgrade.bytecode_location)
+    }
+
+    /// Returns the number of times the application was upgraded.
+    pub fn version(&self) -> u32 {
+        u32::try_from(self.upgrades.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the description of the application as it was at the given version.
+    pub fn at_version(&self, version: u32) -> Self {
+        let mut description = self.clone();
+        description.upgrades.truncate(version as usize);
+        description
+    }
+
+    /// Returns the locations of the bytecode needed to run the application, and the
+    /// migrations of all its versions for chains that missed some of them.
+    pub fn bytecode_locations(&self) -> Box<dyn Iterator<Item = BytecodeLocation> + '_> {
+        if self.upgrades.is_empty() {
+            Box::new(iter::once(self.bytecode_location))
+        } else {
+            Box::new(
+                self.upgrades
+                    .iter()
+                    .map(|upgrade| upgrade.bytecode_location),
+            )
+        }
+    }
+}
+
+/// The bytecode used by an application after an upgrade.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ApplicationUpgrade {
+    /// The unique ID of the new bytecode.
+    pub bytecode_id: BytecodeId,
+    /// The location of the new bytecode.
+    pub bytecode_location: BytecodeLocation,
+    /// The argument of the `migrate` entrypoint, run on each chain where the application has
+    /// a state.
+    #[serde(with = "serde_bytes")]
+    #[debug(with = "hex_debug")]
+    pub migration_argument: Vec<u8>,
 }
 
 impl From<&UserApplicationDescription> for UserApplicationId {
@@ -98,6 +164,8 @@ pub struct ApplicationRegistryView<C> {
     pub published_bytecodes: MapView<C, BytecodeId, BytecodeLocation>,
     /// The applications that are known by the chain.
     pub known_applications: MapView<C, UserApplicationId, UserApplicationDescription>,
+    /// The destinations that this chain sent the description of each application to.
+    pub registered_destinations: CollectionView<C, UserApplicationId, SetView<C, Destination>>,
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -159,7 +227,8 @@ where
 
     /// Registers an existing application.
     ///
-    /// Keeps track of an existing application that the current chain is seeing for the first time.
+    /// Keeps track of an existing application that the current chain is seeing for the first time,
+    /// or of a new version of a known application. Older versions are ignored.
     pub async fn register_application(
         &mut self,
         application: UserApplicationDescription,
@@ -169,16 +238,106 @@ where
             self.describe_application(*required_id).await?;
         }
         let id = UserApplicationId::from(&application);
+        if let Some(known) = self.known_applications.get(&id).await? {
+            if known.version() >= application.version() {
+                return Ok(id);
+            }
+        }
         self.known_applications.insert(&id, application)?;
         Ok(id)
     }
 
+    /// Returns the IDs of the applications in `applications` that are already known by the
+    /// chain with an older version, together with that version.
+    pub async fn upgraded_applications(
+        &self,
+        applications: &[UserApplicationDescription],
+    ) -> Result<Vec<(UserApplicationId, u32)>, SystemExecutionError> {
+        let mut upgraded = Vec::new();
+        for application in applications {
+            let id = UserApplicationId::from(application);
+            if let Some(known) = self.known_applications.get(&id).await? {
+                if known.version() < application.version() {
+                    upgraded.push((id, known.version()));
+                }
+            }
+        }
+        Ok(upgraded)
+    }
+
+    /// Replaces the bytecode of a known application, returning its new description.
+    pub async fn upgrade_application(
+        &mut self,
+        id: UserApplicationId,
+        bytecode_id: BytecodeId,
+        migration_argument: Vec<u8>,
+    ) -> Result<UserApplicationDescription, SystemExecutionError> {
+        let mut description = self.describe_application(id).await?;
+        let bytecode_location = self
+            .published_bytecodes
+            .get(&bytecode_id)
+            .await?
+            .ok_or(SystemExecutionError::UnknownBytecodeId(bytecode_id))?;
+        ensure!(description.version() < u32::MAX, ArithmeticError::Overflow);
+        description.upgrades.push(ApplicationUpgrade {
+            bytecode_id,
+            bytecode_location,
+            migration_argument,
+        });
+        self.known_applications.insert(&id, description.clone())?;
+        Ok(description)
+    }
+
+    /// Records that the description of an application was sent to the `destination`, so that
+    /// future upgrades are sent there too.
+    pub async fn record_registration(
+        &mut self,
+        id: UserApplicationId,
+        destination: &Destination,
+    ) -> Result<(), SystemExecutionError> {
+        self.registered_destinations
+            .load_entry_mut(&id)
+            .await?
+            .insert(destination)?;
+        Ok(())
+    }
+
+    /// Returns at most `limit` of the destinations that the description of an application was
+    /// sent to, skipping the first `start` ones, and whether there are more after them.
+    pub async fn registered_destinations(
+        &self,
+        id: &UserApplicationId,
+        start: u32,
+        limit: u32,
+    ) -> Result<(Vec<Destination>, bool), SystemExecutionError> {
+        let mut destinations = Vec::new();
+        let mut has_more = false;
+        let Some(registered) = self.registered_destinations.try_load_entry(id).await? else {
+            return Ok((destinations, has_more));
+        };
+        let mut skipped = 0;
+        registered
+            .for_each_index_while(|destination| {
+                if skipped < start {
+                    skipped += 1;
+                } else if destinations.len() < limit as usize {
+                    destinations.push(destination);
+                } else {
+                    has_more = true;
+                }
+                Ok(!has_more)
+            })
+            .await?;
+        Ok((destinations, has_more))
+    }
+
     /// Registers a newly created application.
     pub async fn register_new_application(
         &mut self,
         application_id: UserApplicationId,
         parameters: Vec<u8>,
         required_application_ids: Vec<UserApplicationId>,
+        governance: Option<Owner>,
     ) -> Result<(), SystemExecutionError> {
         // Make sure that referenced applications ids have been registered.
         for required_id in &required_application_ids {
@@ -200,6 +359,8 @@ where
             parameters,
             creation,
             required_application_ids,
+            governance,
+            upgrades: Vec::new(),
         };
         self.known_applications
             .insert(&application_id, description)?;
@@ -7,6 +7,7 @@ use async_graphql::InputObject;
 use linera_base::{
     crypto::{CryptoError, PublicKey},
     data_types::ArithmeticError,
+    identifiers::Account,
 };
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::BTreeMap, str::FromStr};
@@ -95,6 +96,8 @@ pub struct ValidatorState {
     pub network_address: String,
     /// The voting power.
     pub votes: u64,
+    /// The account receiving the validator's share of the fees, if any.
+    pub fee_recipient: Option<Account>,
 }
 
 /// A set of validators (identified by their public keys) and their voting rights.
@@ -318,6 +321,7 @@ impl Committee {
                     ValidatorState {
                         network_address: k.to_string(),
                         votes: 1,
+                        fee_recipient: None,
                     },
                 )
             })
@@ -2,26 +2,32 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    resources::ResourceController, system::SystemExecutionStateView, ContractSyncRuntime,
-    ExecutionError, ExecutionOutcome, ExecutionRuntimeConfig, ExecutionRuntimeContext, Message,
-    MessageContext, MessageKind, Operation, OperationContext, Query, QueryContext,
-    RawExecutionOutcome, RawOutgoingMessage, Response, ServiceSyncRuntime, SystemMessage,
-    UserApplicationDescription, UserApplicationId,
+    resources::ResourceController,
+    system::{SystemExecutionStateView, UserEntrypoint},
+    ContractSyncRuntime, ExecutionError, ExecutionOutcome, ExecutionRuntimeConfig,
+    ExecutionRuntimeContext, Message, MessageContext, MessageKind, Operation, OperationContext,
+    Query, QueryContext, RawExecutionOutcome, RawOutgoingMessage, Response, ServiceSyncRuntime,
+    SystemMessage, UserApplicationDescription, UserApplicationId,
 };
-use futures::{stream::FuturesUnordered, StreamExt, TryStreamExt};
+use futures::StreamExt;
 use linera_base::{
-    data_types::{Amount, BlockHeight},
+    data_types::{Amount, ArithmeticError, BlockHeight},
+    ensure,
     identifiers::{Account, ChainId, Destination, Owner},
 };
 use linera_views::{
     common::Context,
     key_value_store_view::KeyValueStoreView,
+    proof::{ProofPosition, ViewProof},
     reentrant_collection_view::ReentrantCollectionView,
     views::{View, ViewError},
 };
 use linera_views_derive::CryptoHashView;
 use std::collections::{BTreeSet, HashMap};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{validate_application, BytecodeLimits, SystemOperation};
+
 #[cfg(with_testing)]
 use {
     crate::{
@@ -40,6 +46,20 @@ pub struct ExecutionStateView<C> {
     pub users: ReentrantCollectionView<C, UserApplicationId, KeyValueStoreView<C>>,
 }
 
+/// Returns the positions that a proof of the storage of the given application, as created by
+/// [`ExecutionStateView::prove_application_state`], goes through: the entry of the
+/// application in `users`, then the `users` field of the [`ExecutionStateView`].
+pub fn application_state_positions(
+    application_id: &UserApplicationId,
+) -> Result<Vec<ProofPosition>, ViewError> {
+    Ok(vec![
+        ProofPosition::CollectionEntry {
+            key: bcs::to_bytes(application_id)?,
+        },
+        ProofPosition::Field { len: 2, index: 1 },
+    ])
+}
+
 #[cfg(with_testing)]
 impl ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>
 where
@@ -74,7 +94,7 @@ where
         self.context()
             .extra()
             .user_contracts()
-            .insert(application_id, contract);
+            .insert(application_id.bytecode_id, contract);
 
         let tracker = ResourceTracker::default();
         let policy = ResourceControlPolicy::default();
@@ -89,6 +109,7 @@ where
             action,
             context.refund_grant_to(),
             None,
+            None,
             &mut resource_controller,
         )
         .await?;
@@ -99,6 +120,7 @@ where
 
 pub enum UserAction {
     Initialize(OperationContext, Vec<u8>),
+    Migrate(OperationContext, Vec<u8>),
     Operation(OperationContext, Vec<u8>),
     Message(MessageContext, Vec<u8>),
 }
@@ -108,6 +130,7 @@ impl UserAction {
         use UserAction::*;
         match self {
             Initialize(context, _) => context.authenticated_signer,
+            Migrate(context, _) => context.authenticated_signer,
             Operation(context, _) => context.authenticated_signer,
             Message(context, _) => context.authenticated_signer,
         }
@@ -116,6 +139,7 @@ impl UserAction {
     pub(crate) fn height(&self) -> BlockHeight {
         match self {
             UserAction::Initialize(context, _) => context.height,
+            UserAction::Migrate(context, _) => context.height,
             UserAction::Operation(context, _) => context.height,
             UserAction::Message(context, _) => context.height,
         }
@@ -124,10 +148,30 @@ impl UserAction {
     pub(crate) fn next_message_index(&self) -> u32 {
         match self {
             UserAction::Initialize(context, _) => context.next_message_index,
+            UserAction::Migrate(context, _) => context.next_message_index,
             UserAction::Operation(context, _) => context.next_message_index,
             UserAction::Message(context, _) => context.next_message_index,
         }
     }
+
+    /// Returns the name of the kind of action, as recorded in execution traces.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            UserAction::Initialize(..) => "initialize",
+            UserAction::Migrate(..) => "migrate",
+            UserAction::Operation(..) => "execute_operation",
+            UserAction::Message(..) => "execute_message",
+        }
+    }
+
+    pub(crate) fn argument(&self) -> &[u8] {
+        match self {
+            UserAction::Initialize(_, argument) => argument,
+            UserAction::Migrate(_, argument) => argument,
+            UserAction::Operation(_, operation) => operation,
+            UserAction::Message(_, message) => message,
+        }
+    }
 }
 
 impl<C> ExecutionStateView<C>
@@ -136,6 +180,27 @@ where
     ViewError: From<C::Error>,
     C::Extra: ExecutionRuntimeContext,
 {
+    /// Creates a proof of the storage of the given application. The proof can be checked
+    /// against the `execution_state_hash` of the chain with
+    /// [`ViewProof::verify_crypto_hash`], using the name `ExecutionStateView` and the
+    /// [`application_state_positions`] of the application.
+    pub async fn prove_application_state(
+        &self,
+        application_id: &UserApplicationId,
+    ) -> Result<ViewProof, ViewError> {
+        let proof = {
+            let view = self
+                .users
+                .try_load_entry(application_id)
+                .await?
+                .ok_or_else(|| ViewError::not_found("no state for application", application_id))?;
+            view.prove().await?
+        };
+        let proof = self.users.prove_subview(application_id, proof).await?;
+        let field_hashes = self.field_hashes().await?;
+        Ok(proof.through_fields(field_hashes, 1))
+    }
+
     async fn run_user_action(
         &mut self,
         application_id: UserApplicationId,
@@ -143,6 +208,7 @@ where
         action: UserAction,
         refund_grant_to: Option<Account>,
         grant: Option<&mut Amount>,
+        sponsor: Option<&mut Amount>,
         resource_controller: &mut ResourceController<Option<Owner>>,
     ) -> Result<Vec<ExecutionOutcome>, ExecutionError> {
         let execution_outcomes = match self.context().extra().execution_runtime_config() {
@@ -153,6 +219,7 @@ where
                     action,
                     refund_grant_to,
                     grant,
+                    sponsor,
                     resource_controller,
                 )
                 .await?
@@ -169,11 +236,13 @@ where
         action: UserAction,
         refund_grant_to: Option<Account>,
         grant: Option<&mut Amount>,
+        sponsor: Option<&mut Amount>,
         resource_controller: &mut ResourceController<Option<Owner>>,
     ) -> Result<Vec<ExecutionOutcome>, ExecutionError> {
         let mut cloned_grant = grant.as_ref().map(|x| **x);
+        let mut cloned_sponsor = sponsor.as_ref().map(|x| **x);
         let initial_balance = resource_controller
-            .with_state_and_grant(self, cloned_grant.as_mut())
+            .with_state_and_funding(self, cloned_grant.as_mut(), cloned_sponsor.as_mut())
             .await?
             .balance()?;
         let controller = ResourceController {
@@ -181,6 +250,9 @@ where
             tracker: resource_controller.tracker,
             account: initial_balance,
         };
+        let execution_tracer = self.context().extra().execution_tracer().cloned();
+        let fuel_profiler = self.context().extra().fuel_profiler().cloned();
+        let application_logger = self.context().extra().application_logger().cloned();
         let (execution_state_sender, mut execution_state_receiver) =
             futures::channel::mpsc::unbounded();
         let execution_outcomes_future = tokio::task::spawn_blocking(move || {
@@ -190,6 +262,9 @@ where
                 chain_id,
                 refund_grant_to,
                 controller,
+                execution_tracer,
+                fuel_profiler,
+                application_logger,
                 action,
             )
         });
@@ -198,7 +273,7 @@ where
         }
         let (execution_outcomes, controller) = execution_outcomes_future.await??;
         resource_controller
-            .with_state_and_grant(self, grant)
+            .with_state_and_funding(self, grant, sponsor)
             .await?
             .merge_balance(initial_balance, controller.balance()?)?;
         resource_controller.tracker = controller.tracker;
@@ -208,63 +283,80 @@ where
     /// Schedules application registration messages when needed.
     ///
     /// Ensures that the outgoing messages in `results` are preceded by a system message that
-    /// registers the application that will handle the messages.
+    /// registers the application that will handle the messages. The registration message for a
+    /// destination is inserted right before the first outcome with messages to it, so that the
+    /// indices of the messages created earlier in the transaction do not change. The
+    /// destinations are recorded so that future upgrades of the applications are sent there too.
     async fn update_execution_outcomes_with_app_registrations(
-        &self,
-        mut results: Vec<ExecutionOutcome>,
+        &mut self,
+        results: Vec<ExecutionOutcome>,
     ) -> Result<Vec<ExecutionOutcome>, ExecutionError> {
-        let user_application_outcomes = results.iter().filter_map(|outcome| match outcome {
-            ExecutionOutcome::User(application_id, result) => Some((application_id, result)),
-            _ => None,
-        });
-
-        let mut applications_to_register_per_destination = HashMap::<_, BTreeSet<_>>::new();
-
-        for (application_id, result) in user_application_outcomes {
-            for message in &result.messages {
-                applications_to_register_per_destination
+        // The destinations in the order they are first used, with the index of the first
+        // outcome sending messages to them and the applications to register there.
+        let mut destinations = Vec::<(&Destination, usize, BTreeSet<UserApplicationId>)>::new();
+        let mut destination_indices = HashMap::<&Destination, usize>::new();
+
+        for (outcome_index, outcome) in results.iter().enumerate() {
+            let ExecutionOutcome::User(application_id, outcome) = outcome else {
+                continue;
+            };
+            for message in &outcome.messages {
+                let index = *destination_indices
                     .entry(&message.destination)
-                    .or_default()
-                    .insert(*application_id);
+                    .or_insert_with(|| {
+                        destinations.push((&message.destination, outcome_index, BTreeSet::new()));
+                        destinations.len() - 1
+                    });
+                destinations[index].2.insert(*application_id);
             }
         }
 
-        if applications_to_register_per_destination.is_empty() {
+        if destinations.is_empty() {
             return Ok(results);
         }
 
-        let messages = applications_to_register_per_destination
-            .into_iter()
-            .map(|(destination, applications_to_describe)| async {
-                let applications = self
-                    .system
+        let mut registrations = HashMap::<usize, Vec<_>>::new();
+        for (destination, outcome_index, applications_to_describe) in destinations {
+            let applications = self
+                .system
+                .registry
+                .describe_applications_with_dependencies(
+                    applications_to_describe.into_iter().collect(),
+                    &HashMap::new(),
+                )
+                .await?;
+            for application in &applications {
+                self.system
                     .registry
-                    .describe_applications_with_dependencies(
-                        applications_to_describe.into_iter().collect(),
-                        &HashMap::new(),
-                    )
+                    .record_registration(application.into(), destination)
                     .await?;
-
-                Ok::<_, ExecutionError>(RawOutgoingMessage {
+            }
+            registrations
+                .entry(outcome_index)
+                .or_default()
+                .push(RawOutgoingMessage {
                     destination: destination.clone(),
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Simple,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::RegisterApplications { applications },
-                })
-            })
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-            .await?;
-
-        let system_outcome = RawExecutionOutcome {
-            messages,
-            ..RawExecutionOutcome::default()
-        };
+                });
+        }
 
-        results.insert(0, ExecutionOutcome::System(system_outcome));
+        let mut outcomes = Vec::with_capacity(results.len() + registrations.len());
+        for (outcome_index, outcome) in results.into_iter().enumerate() {
+            if let Some(messages) = registrations.remove(&outcome_index) {
+                outcomes.push(ExecutionOutcome::System(RawExecutionOutcome {
+                    messages,
+                    ..RawExecutionOutcome::default()
+                }));
+            }
+            outcomes.push(outcome);
+        }
 
-        Ok(results)
+        Ok(outcomes)
     }
 
     pub async fn execute_operation(
@@ -272,17 +364,50 @@ where
         context: OperationContext,
         operation: Operation,
         resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<Vec<ExecutionOutcome>, ExecutionError> {
+        self.execute_operation_with_sponsor(context, operation, None, resource_controller)
+            .await
+    }
+
+    /// Executes an operation, paying the fees with the funds of the sponsoring application
+    /// first, if any.
+    pub async fn execute_operation_with_sponsor(
+        &mut self,
+        context: OperationContext,
+        operation: Operation,
+        sponsor: Option<&mut Amount>,
+        resource_controller: &mut ResourceController<Option<Owner>>,
     ) -> Result<Vec<ExecutionOutcome>, ExecutionError> {
         assert_eq!(context.chain_id, self.context().extra().chain_id());
         match operation {
             Operation::System(op) => {
-                let (mut result, new_application) =
-                    self.system.execute_operation(context, op).await?;
+                // Validators never run in Web clients, which can't decompress bytecodes.
+                #[cfg(not(target_arch = "wasm32"))]
+                validate_published_bytecode(&op, &resource_controller.policy)?;
+                let (mut result, entrypoint) = self.system.execute_operation(context, op).await?;
                 result.authenticated_signer = context.authenticated_signer;
                 result.refund_grant_to = context.refund_grant_to();
+                // The messages of the application come after the ones of the system operation.
+                let next_message_index = u32::try_from(result.messages.len())
+                    .ok()
+                    .and_then(|len| context.next_message_index.checked_add(len))
+                    .ok_or(ArithmeticError::Overflow)?;
+                let user_context = OperationContext {
+                    next_message_index,
+                    ..context
+                };
                 let mut outcomes = vec![ExecutionOutcome::System(result)];
-                if let Some((application_id, argument)) = new_application {
-                    let user_action = UserAction::Initialize(context, argument);
+                if let Some(entrypoint) = entrypoint {
+                    let (application_id, user_action) = match entrypoint {
+                        UserEntrypoint::Initialize(application_id, argument) => (
+                            application_id,
+                            UserAction::Initialize(user_context, argument),
+                        ),
+                        UserEntrypoint::Migrate(application_id, argument) => {
+                            (application_id, UserAction::Migrate(user_context, argument))
+                        }
+                    };
+                    let is_upgrade = matches!(user_action, UserAction::Migrate(..));
                     outcomes.extend(
                         self.run_user_action(
                             application_id,
@@ -290,10 +415,21 @@ where
                             user_action,
                             context.refund_grant_to(),
                             None,
+                            None,
                             resource_controller,
                         )
                         .await?,
                     );
+                    if is_upgrade {
+                        self.propagate_upgrade(
+                            context.chain_id,
+                            application_id,
+                            0,
+                            &mut outcomes,
+                            resource_controller,
+                        )
+                        .await?;
+                    }
                 }
                 Ok(outcomes)
             }
@@ -307,6 +443,7 @@ where
                     UserAction::Operation(context, bytes),
                     context.refund_grant_to(),
                     None,
+                    sponsor,
                     resource_controller,
                 )
                 .await
@@ -324,19 +461,79 @@ where
         assert_eq!(context.chain_id, self.context().extra().chain_id());
         match message {
             Message::System(message) => {
+                let upgraded_applications = match &message {
+                    SystemMessage::RegisterApplications { applications } => {
+                        self.system
+                            .registry
+                            .upgraded_applications(applications)
+                            .await?
+                    }
+                    _ => Vec::new(),
+                };
+                let propagation = match &message {
+                    SystemMessage::PropagateUpgrade {
+                        application_id,
+                        start,
+                    } => Some((*application_id, *start)),
+                    _ => None,
+                };
                 let outcome = self.system.execute_message(context, message).await?;
-                Ok(vec![ExecutionOutcome::System(outcome)])
+                let mut outcomes = vec![ExecutionOutcome::System(outcome)];
+                for (application_id, known_version) in upgraded_applications {
+                    // Applications without a state on this chain have nothing to migrate.
+                    if self.users.contains_key(&application_id).await? {
+                        self.migrate_received_upgrade(
+                            context,
+                            application_id,
+                            known_version,
+                            &mut outcomes,
+                            resource_controller,
+                        )
+                        .await?;
+                    }
+                    self.propagate_upgrade(
+                        context.chain_id,
+                        application_id,
+                        0,
+                        &mut outcomes,
+                        resource_controller,
+                    )
+                    .await?;
+                }
+                if let Some((application_id, start)) = propagation {
+                    self.propagate_upgrade(
+                        context.chain_id,
+                        application_id,
+                        start,
+                        &mut outcomes,
+                        resource_controller,
+                    )
+                    .await?;
+                }
+                Ok(outcomes)
             }
             Message::User {
                 application_id,
                 bytes,
             } => {
+                if let Some(request_id) = context.response_to {
+                    let sender = self.system.pending_requests.get(&request_id).await?;
+                    // Bounced messages refer to the original message, which may not be a request.
+                    ensure!(
+                        context.is_bouncing || sender == Some(application_id),
+                        ExecutionError::UnexpectedResponse(request_id)
+                    );
+                    if sender == Some(application_id) {
+                        self.system.pending_requests.remove(&request_id)?;
+                    }
+                }
                 self.run_user_action(
                     application_id,
                     context.chain_id,
                     UserAction::Message(context, bytes),
                     context.refund_grant_to,
                     grant,
+                    None,
                     resource_controller,
                 )
                 .await
@@ -344,6 +541,98 @@ where
         }
     }
 
+    /// Runs the `migrate` entrypoints of an application whose new description was received
+    /// in a message, for every version after `known_version`, in order. Each migration runs
+    /// with the bytecode and the argument of its own version, so a chain that missed some
+    /// upgrades goes through all of them.
+    async fn migrate_received_upgrade(
+        &mut self,
+        context: MessageContext,
+        application_id: UserApplicationId,
+        known_version: u32,
+        outcomes: &mut Vec<ExecutionOutcome>,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<(), ExecutionError> {
+        let description = self
+            .system
+            .registry
+            .describe_application(application_id)
+            .await?;
+        let upgrades = description.upgrades.iter().zip(1..);
+        for (upgrade, version) in upgrades.skip(known_version as usize) {
+            let argument = upgrade.migration_argument.clone();
+            // The migration loads the application with the bytecode of its version.
+            self.system
+                .registry
+                .known_applications
+                .insert(&application_id, description.at_version(version))?;
+            let next_message_index = u32::try_from(count_messages(outcomes))
+                .ok()
+                .and_then(|count| context.next_message_index.checked_add(count))
+                .ok_or(ArithmeticError::Overflow)?;
+            // The migration is not triggered by an operation, nor authenticated by anyone.
+            let user_context = OperationContext {
+                chain_id: context.chain_id,
+                authenticated_signer: None,
+                height: context.height,
+                index: 0,
+                next_message_index,
+            };
+            outcomes.extend(
+                self.run_user_action(
+                    application_id,
+                    context.chain_id,
+                    UserAction::Migrate(user_context, argument),
+                    None,
+                    None,
+                    None,
+                    resource_controller,
+                )
+                .await?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Sends the description of an upgraded application to the destinations that received it
+    /// from this chain, starting with the `start`-th one, in a new outcome added to
+    /// `outcomes`. Only as many destinations are served as the messages of the block allow,
+    /// given the ones of the previous transactions and of `outcomes`.
+    async fn propagate_upgrade(
+        &self,
+        chain_id: ChainId,
+        application_id: UserApplicationId,
+        start: u32,
+        outcomes: &mut Vec<ExecutionOutcome>,
+        resource_controller: &ResourceController<Option<Owner>>,
+    ) -> Result<(), ExecutionError> {
+        let sent_messages = u64::from(resource_controller.tracker.messages)
+            .saturating_add(u64::try_from(count_messages(outcomes)).unwrap_or(u64::MAX));
+        // One message is kept to continue the propagation in a later block.
+        let limit = resource_controller
+            .policy
+            .maximum_outgoing_messages_per_block
+            .saturating_sub(sent_messages)
+            .saturating_sub(1)
+            .max(1);
+        let messages = self
+            .system
+            .propagate_upgrade(
+                chain_id,
+                application_id,
+                start,
+                u32::try_from(limit).unwrap_or(u32::MAX),
+            )
+            .await?;
+        if !messages.is_empty() {
+            outcomes.push(ExecutionOutcome::System(RawExecutionOutcome {
+                messages,
+                ..RawExecutionOutcome::default()
+            }));
+        }
+        Ok(())
+    }
+
     pub async fn bounce_message(
         &self,
         context: MessageContext,
@@ -364,6 +653,8 @@ where
                     authenticated: true,
                     grant,
                     kind: MessageKind::Bouncing,
+                    deadline: None,
+                    response_to: Some(context.message_id),
                     message,
                 });
                 Ok(vec![ExecutionOutcome::System(outcome)])
@@ -382,6 +673,8 @@ where
                     authenticated: true,
                     grant,
                     kind: MessageKind::Bouncing,
+                    deadline: None,
+                    response_to: Some(context.message_id),
                     message: bytes,
                 });
                 Ok(vec![ExecutionOutcome::User(application_id, outcome)])
@@ -402,6 +695,8 @@ where
             authenticated: false,
             grant: Amount::ZERO,
             kind: MessageKind::Tracked,
+            deadline: None,
+            response_to: None,
             message: SystemMessage::Credit {
                 amount,
                 source: context.authenticated_signer,
@@ -446,8 +741,15 @@ where
     ) -> Result<Vec<u8>, ExecutionError> {
         let (execution_state_sender, mut execution_state_receiver) =
             futures::channel::mpsc::unbounded();
+        let http_oracle = self.context().extra().http_oracle().cloned();
         let query_result_future = tokio::task::spawn_blocking(move || {
-            ServiceSyncRuntime::run_query(execution_state_sender, application_id, context, query)
+            ServiceSyncRuntime::run_query(
+                execution_state_sender,
+                application_id,
+                context,
+                query,
+                http_oracle,
+            )
         });
         while let Some(request) = execution_state_receiver.next().await {
             self.handle_request(request).await?;
@@ -470,3 +772,36 @@ where
         Ok(applications)
     }
 }
+
+/// Rejects the bytecode published by the `operation`, if any, unless it is small enough once
+/// decompressed and can be executed deterministically.
+#[cfg(not(target_arch = "wasm32"))]
+fn validate_published_bytecode(
+    operation: &SystemOperation,
+    policy: &crate::ResourceControlPolicy,
+) -> Result<(), ExecutionError> {
+    let limits = BytecodeLimits::from(policy);
+    match operation {
+        SystemOperation::PublishBytecode { contract, service } => {
+            validate_application(contract, service, &limits)?;
+        }
+        SystemOperation::PublishCompressedBytecode { contract, service } => {
+            let contract = contract.decompress(limits.maximum_bytecode_size)?;
+            let service = service.decompress(limits.maximum_bytecode_size)?;
+            validate_application(&contract, &service, &limits)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns the number of messages sent by the `outcomes`.
+fn count_messages(outcomes: &[ExecutionOutcome]) -> usize {
+    outcomes
+        .iter()
+        .map(|outcome| match outcome {
+            ExecutionOutcome::System(outcome) => outcome.messages.len(),
+            ExecutionOutcome::User(_, outcome) => outcome.messages.len(),
+        })
+        .sum()
+}
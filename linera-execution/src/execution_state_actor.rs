@@ -12,7 +12,8 @@ use crate::{
 };
 use futures::channel::mpsc;
 use linera_base::{
-    data_types::{Amount, Timestamp},
+    crypto::CryptoHash,
+    data_types::{Amount, FeeSponsorship, Timestamp},
     identifiers::{Account, MessageId, Owner},
     ownership::ChainOwnership,
 };
@@ -26,6 +27,7 @@ use linera_base::{
 use linera_views::{
     batch::Batch,
     common::Context,
+    key_value_store_view::SizeData,
     views::{View, ViewError},
 };
 use oneshot::Sender;
@@ -153,11 +155,74 @@ where
                 callback.respond(execution_outcome);
             }
 
+            ApplicationBalance {
+                application_id,
+                callback,
+            } => {
+                let balance = self
+                    .system
+                    .application_balances
+                    .get(&application_id)
+                    .await?
+                    .unwrap_or_default();
+                callback.respond(balance);
+            }
+
+            FundApplication {
+                source,
+                application_id,
+                amount,
+                signer,
+                callback,
+            } => {
+                self.system
+                    .fund_application(signer, source, application_id, amount)
+                    .await?;
+                callback.respond(());
+            }
+
+            WithdrawFromApplication {
+                application_id,
+                destination,
+                amount,
+                callback,
+            } => {
+                let mut execution_outcome = RawExecutionOutcome::default();
+                let message = self
+                    .system
+                    .withdraw_from_application(application_id, destination, amount)
+                    .await?;
+                execution_outcome.messages.push(message);
+                callback.respond(execution_outcome);
+            }
+
+            SetFeeSponsorship {
+                application_id,
+                sponsorship,
+                callback,
+            } => {
+                self.system
+                    .set_fee_sponsorship(application_id, sponsorship)?;
+                callback.respond(());
+            }
+
             SystemTimestamp { callback } => {
                 let timestamp = *self.system.timestamp.get();
                 callback.respond(timestamp);
             }
 
+            BlockRandomness {
+                application_id,
+                callback,
+            } => {
+                let mut randomness = *self.system.randomness.get();
+                let value = randomness
+                    .as_mut()
+                    .map(|randomness| randomness.next_value(application_id));
+                self.system.randomness.set(randomness);
+                callback.respond(value);
+            }
+
             ChainOwnership { callback } => {
                 let ownership = self.system.ownership.get().clone();
                 callback.respond(ownership);
@@ -207,7 +272,43 @@ where
                 callback,
             } => {
                 let mut view = self.users.try_load_entry_mut(&id).await?;
+                let initial_size = view.total_size();
                 view.write_batch(batch).await?;
+                let final_size = view.total_size();
+                let total = |size: SizeData| i64::from(size.key) + i64::from(size.value);
+                callback.respond(total(final_size) - total(initial_size));
+            }
+
+            TakeStorageDeposit {
+                id,
+                signer,
+                amount,
+                callback,
+            } => {
+                self.system.take_storage_deposit(id, signer, amount).await?;
+                callback.respond(());
+            }
+
+            RefundStorageDeposit {
+                id,
+                signer,
+                amount,
+                callback,
+            } => {
+                self.system
+                    .refund_storage_deposit(id, signer, amount)
+                    .await?;
+                callback.respond(());
+            }
+
+            RecordRequest {
+                request_id,
+                application_id,
+                callback,
+            } => {
+                self.system
+                    .pending_requests
+                    .insert(&request_id, application_id)?;
                 callback.respond(());
             }
 
@@ -216,8 +317,12 @@ where
                 balance,
                 next_message_id,
                 application_permissions,
+                signer,
                 callback,
             } => {
+                if balance > Amount::ZERO {
+                    self.system.check_chain_authority(signer)?;
+                }
                 let inactive_err = || SystemExecutionError::InactiveChain;
                 let config = OpenChainConfig {
                     ownership,
@@ -233,8 +338,10 @@ where
 
             CloseChain {
                 application_id,
+                signer,
                 callback,
             } => {
+                self.system.check_chain_authority(signer)?;
                 let app_permissions = self.system.application_permissions.get();
                 if !app_permissions.can_close_chain(&application_id) {
                     callback.respond(Err(ExecutionError::UnauthorizedApplication(application_id)));
@@ -287,10 +394,41 @@ pub enum Request {
         callback: Sender<RawExecutionOutcome<SystemMessage, Amount>>,
     },
 
+    ApplicationBalance {
+        application_id: UserApplicationId,
+        callback: Sender<Amount>,
+    },
+
+    FundApplication {
+        source: Option<Owner>,
+        application_id: UserApplicationId,
+        amount: Amount,
+        signer: Option<Owner>,
+        callback: Sender<()>,
+    },
+
+    WithdrawFromApplication {
+        application_id: UserApplicationId,
+        destination: Account,
+        amount: Amount,
+        callback: Sender<RawExecutionOutcome<SystemMessage, Amount>>,
+    },
+
+    SetFeeSponsorship {
+        application_id: UserApplicationId,
+        sponsorship: Option<FeeSponsorship>,
+        callback: Sender<()>,
+    },
+
     SystemTimestamp {
         callback: Sender<Timestamp>,
     },
 
+    BlockRandomness {
+        application_id: UserApplicationId,
+        callback: Sender<Option<CryptoHash>>,
+    },
+
     ChainOwnership {
         callback: Sender<ChainOwnership>,
     },
@@ -328,6 +466,26 @@ pub enum Request {
     WriteBatch {
         id: UserApplicationId,
         batch: Batch,
+        callback: Sender<i64>,
+    },
+
+    TakeStorageDeposit {
+        id: UserApplicationId,
+        signer: Option<Owner>,
+        amount: Amount,
+        callback: Sender<()>,
+    },
+
+    RefundStorageDeposit {
+        id: UserApplicationId,
+        signer: Option<Owner>,
+        amount: Amount,
+        callback: Sender<()>,
+    },
+
+    RecordRequest {
+        request_id: MessageId,
+        application_id: UserApplicationId,
         callback: Sender<()>,
     },
 
@@ -336,11 +494,13 @@ pub enum Request {
         balance: Amount,
         next_message_id: MessageId,
         application_permissions: ApplicationPermissions,
+        signer: Option<Owner>,
         callback: Sender<[RawOutgoingMessage<SystemMessage, Amount>; 2]>,
     },
 
     CloseChain {
         application_id: UserApplicationId,
+        signer: Option<Owner>,
         callback: oneshot::Sender<Result<(), ExecutionError>>,
     },
 }
@@ -395,10 +555,56 @@ impl Debug for Request {
                 .field("signer", signer)
                 .finish_non_exhaustive(),
 
+            Request::ApplicationBalance { application_id, .. } => formatter
+                .debug_struct("Request::ApplicationBalance")
+                .field("application_id", application_id)
+                .finish_non_exhaustive(),
+
+            Request::FundApplication {
+                source,
+                application_id,
+                amount,
+                signer,
+                ..
+            } => formatter
+                .debug_struct("Request::FundApplication")
+                .field("source", source)
+                .field("application_id", application_id)
+                .field("amount", amount)
+                .field("signer", signer)
+                .finish_non_exhaustive(),
+
+            Request::WithdrawFromApplication {
+                application_id,
+                destination,
+                amount,
+                ..
+            } => formatter
+                .debug_struct("Request::WithdrawFromApplication")
+                .field("application_id", application_id)
+                .field("destination", destination)
+                .field("amount", amount)
+                .finish_non_exhaustive(),
+
+            Request::SetFeeSponsorship {
+                application_id,
+                sponsorship,
+                ..
+            } => formatter
+                .debug_struct("Request::SetFeeSponsorship")
+                .field("application_id", application_id)
+                .field("sponsorship", sponsorship)
+                .finish_non_exhaustive(),
+
             Request::SystemTimestamp { .. } => formatter
                 .debug_struct("Request::SystemTimestamp")
                 .finish_non_exhaustive(),
 
+            Request::BlockRandomness { application_id, .. } => formatter
+                .debug_struct("Request::BlockRandomness")
+                .field("application_id", application_id)
+                .finish_non_exhaustive(),
+
             Request::ChainOwnership { .. } => formatter
                 .debug_struct("Request::ChainOwnership")
                 .finish_non_exhaustive(),
@@ -439,6 +645,34 @@ impl Debug for Request {
                 .field("batch", batch)
                 .finish_non_exhaustive(),
 
+            Request::TakeStorageDeposit {
+                id, signer, amount, ..
+            } => formatter
+                .debug_struct("Request::TakeStorageDeposit")
+                .field("id", id)
+                .field("signer", signer)
+                .field("amount", amount)
+                .finish_non_exhaustive(),
+
+            Request::RefundStorageDeposit {
+                id, signer, amount, ..
+            } => formatter
+                .debug_struct("Request::RefundStorageDeposit")
+                .field("id", id)
+                .field("signer", signer)
+                .field("amount", amount)
+                .finish_non_exhaustive(),
+
+            Request::RecordRequest {
+                request_id,
+                application_id,
+                ..
+            } => formatter
+                .debug_struct("Request::RecordRequest")
+                .field("request_id", request_id)
+                .field("application_id", application_id)
+                .finish_non_exhaustive(),
+
             Request::OpenChain { balance, .. } => formatter
                 .debug_struct("Request::OpenChain")
                 .field("balance", balance)
@@ -5,7 +5,7 @@ use crate::{
     committee::{Committee, Epoch, ValidatorName, ValidatorState},
     system::{Recipient, UserData},
     Bytecode, ChannelSubscription, ExecutionStateView, GenericApplicationId,
-    SystemExecutionStateView, UserApplicationDescription,
+    SystemExecutionStateView, UserApplicationDescription, UserApplicationId,
 };
 use async_graphql::{Error, Object};
 use linera_base::{
@@ -58,14 +58,24 @@ impl Committee {
 }
 
 #[async_graphql::Object]
-impl<C: Send + Sync + Context> ExecutionStateView<C>
+impl<C> ExecutionStateView<C>
 where
+    C: Context + Clone + Send + Sync + 'static,
     ViewError: From<C::Error>,
 {
     #[graphql(derived(name = "system"))]
     async fn _system(&self) -> &SystemExecutionStateView<C> {
         &self.system
     }
+
+    /// The number of bytes stored by an application on this chain.
+    async fn storage_footprint(&self, application_id: UserApplicationId) -> Result<u64, Error> {
+        let Some(view) = self.users.try_load_entry(&application_id).await? else {
+            return Ok(0);
+        };
+        let size = view.total_size();
+        Ok(u64::from(size.key) + u64::from(size.value))
+    }
 }
 
 #[async_graphql::Object]
@@ -113,6 +123,16 @@ where
         &self.balances
     }
 
+    #[graphql(derived(name = "application_balances"))]
+    async fn _application_balances(&self) -> &MapView<C, UserApplicationId, Amount> {
+        &self.application_balances
+    }
+
+    #[graphql(derived(name = "collected_fees"))]
+    async fn _collected_fees(&self) -> &Amount {
+        self.collected_fees.get()
+    }
+
     #[graphql(derived(name = "timestamp"))]
     async fn _timestamp(&self) -> &Timestamp {
         self.timestamp.get()
@@ -0,0 +1,345 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Outbound HTTP requests made by application services.
+//!
+//! Services are only executed by the nodes answering queries, so their answers may depend on
+//! off-chain data. Contracts can't make HTTP requests, since their execution must be
+//! deterministic.
+//!
+//! A node only sends the requests allowed by its [`HttpOracle`]: the host of the URL must be
+//! allow-listed, the bodies of requests and responses are limited in size, and requests time
+//! out. Each query may only make a limited number of requests, within a limited time, since
+//! the chain can't process blocks while it is being queried.
+
+use async_trait::async_trait;
+use custom_debug_derive::Debug;
+use linera_base::ensure;
+pub use linera_base::http::{HttpMethod, HttpRequest, HttpResponse};
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// The limits applied to the HTTP requests of services.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpOracleConfig {
+    /// The hosts that services may send requests to.
+    pub allowed_hosts: BTreeSet<String>,
+    /// The maximum size of the body of a request, in bytes.
+    pub max_request_bytes: usize,
+    /// The maximum size of the body of a response, in bytes.
+    pub max_response_bytes: usize,
+    /// The maximum duration of a request, including reading the response.
+    pub timeout: Duration,
+    /// The maximum number of requests made while answering a single query.
+    pub max_requests_per_query: usize,
+    /// The maximum total duration of the requests made while answering a single query.
+    pub max_query_duration: Duration,
+}
+
+impl Default for HttpOracleConfig {
+    fn default() -> Self {
+        HttpOracleConfig {
+            allowed_hosts: BTreeSet::new(),
+            max_request_bytes: 64 * 1024,
+            max_response_bytes: 1024 * 1024,
+            timeout: Duration::from_secs(10),
+            max_requests_per_query: 16,
+            max_query_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Sends the HTTP requests allowed by an [`HttpOracle`].
+#[async_trait]
+pub trait HttpClient: Send + Sync {
+    /// Sends the `request`, failing if it takes longer than `timeout` or if the body of the
+    /// response exceeds `max_response_bytes`.
+    async fn send(
+        &self,
+        request: HttpRequest,
+        timeout: Duration,
+        max_response_bytes: usize,
+    ) -> Result<HttpResponse, HttpError>;
+}
+
+/// Performs the HTTP requests of services, within the limits of its configuration.
+#[derive(Clone, Debug)]
+pub struct HttpOracle {
+    config: Arc<HttpOracleConfig>,
+    #[debug(skip)]
+    client: Arc<dyn HttpClient>,
+}
+
+impl HttpOracle {
+    /// Creates an oracle sending the requests allowed by `config` with the `client`.
+    pub fn new(config: HttpOracleConfig, client: impl HttpClient + 'static) -> Self {
+        HttpOracle {
+            config: Arc::new(config),
+            client: Arc::new(client),
+        }
+    }
+
+    /// Returns the limits applied to the requests.
+    pub fn config(&self) -> &HttpOracleConfig {
+        &self.config
+    }
+
+    /// Starts answering a query, which may then make requests within the per-query limits.
+    pub fn start_query(&self) -> QueryHttpOracle {
+        QueryHttpOracle {
+            oracle: self.clone(),
+            remaining_requests: self.config.max_requests_per_query,
+            remaining_time: self.config.max_query_duration,
+        }
+    }
+
+    /// Sends the `request` if it is allowed, and returns the response.
+    pub async fn perform(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        self.perform_with_timeout(request, self.config.timeout)
+            .await
+    }
+
+    async fn perform_with_timeout(
+        &self,
+        request: HttpRequest,
+        timeout: Duration,
+    ) -> Result<HttpResponse, HttpError> {
+        let host = url_host(&request.url)?;
+        ensure!(
+            self.config.allowed_hosts.contains(&host),
+            HttpError::HostNotAllowed(host)
+        );
+        ensure!(
+            request.body.len() <= self.config.max_request_bytes,
+            HttpError::RequestTooLarge {
+                size: request.body.len(),
+                maximum: self.config.max_request_bytes,
+            }
+        );
+        let response = self
+            .client
+            .send(request, timeout, self.config.max_response_bytes)
+            .await?;
+        ensure!(
+            response.body.len() <= self.config.max_response_bytes,
+            HttpError::ResponseTooLarge {
+                maximum: self.config.max_response_bytes,
+            }
+        );
+        Ok(response)
+    }
+}
+
+/// Performs the HTTP requests made while answering a single query.
+#[derive(Debug)]
+pub struct QueryHttpOracle {
+    oracle: HttpOracle,
+    remaining_requests: usize,
+    remaining_time: Duration,
+}
+
+impl QueryHttpOracle {
+    /// Sends the `request` if it is allowed and the query hasn't exhausted its limits.
+    pub async fn perform(&mut self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let config = self.oracle.config();
+        ensure!(
+            self.remaining_requests > 0,
+            HttpError::TooManyRequests {
+                maximum: config.max_requests_per_query,
+            }
+        );
+        ensure!(
+            !self.remaining_time.is_zero(),
+            HttpError::QueryTimeout {
+                maximum: config.max_query_duration,
+            }
+        );
+        self.remaining_requests -= 1;
+        let timeout = config.timeout.min(self.remaining_time);
+        let start = Instant::now();
+        let response = self.oracle.perform_with_timeout(request, timeout).await;
+        self.remaining_time = self.remaining_time.saturating_sub(start.elapsed());
+        response
+    }
+}
+
+/// Returns the lowercase host of an `http` or `https` URL.
+///
+/// URLs that other parsers could interpret differently, e.g. with credentials or
+/// backslashes, are rejected, so that the host that is checked is the one that is contacted.
+fn url_host(url: &str) -> Result<String, HttpError> {
+    let invalid_url = || HttpError::InvalidUrl(url.to_owned());
+    ensure!(
+        !url.chars().any(|c| c.is_whitespace() || c.is_control()),
+        invalid_url()
+    );
+    let (scheme, rest) = url.split_once("://").ok_or_else(invalid_url)?;
+    ensure!(
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"),
+        invalid_url()
+    );
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    ensure!(!authority.contains(['@', '\\', '[']), invalid_url());
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            ensure!(port.parse::<u16>().is_ok(), invalid_url());
+            host
+        }
+        None => authority,
+    };
+    ensure!(!host.is_empty(), invalid_url());
+    Ok(host.to_ascii_lowercase())
+}
+
+/// An error preventing a service from obtaining the response to an HTTP request.
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("This node does not allow services to make HTTP requests")]
+    Disabled,
+    #[error("Invalid URL for an HTTP request: {0}")]
+    InvalidUrl(String),
+    #[error("HTTP requests to host {0:?} are not allowed by this node")]
+    HostNotAllowed(String),
+    #[error("The HTTP request has {size} bytes of body, but at most {maximum} are allowed")]
+    RequestTooLarge { size: usize, maximum: usize },
+    #[error("The HTTP response has more than the {maximum} bytes of body that are allowed")]
+    ResponseTooLarge { maximum: usize },
+    #[error("The HTTP request timed out")]
+    Timeout,
+    #[error("A query can make at most {maximum} HTTP requests")]
+    TooManyRequests { maximum: usize },
+    #[error("The HTTP requests of a query can take at most {maximum:?}")]
+    QueryTimeout { maximum: Duration },
+    #[error("The HTTP request failed: {0}")]
+    Failed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{url_host, HttpError, HttpOracle, HttpOracleConfig, HttpRequest, HttpResponse};
+    use crate::test_utils::MockHttpClient;
+    use assert_matches::assert_matches;
+    use std::time::Duration;
+
+    fn make_oracle(client: MockHttpClient) -> HttpOracle {
+        let config = HttpOracleConfig {
+            allowed_hosts: ["example.com".to_owned()].into(),
+            max_request_bytes: 4,
+            max_response_bytes: 8,
+            max_requests_per_query: 2,
+            ..HttpOracleConfig::default()
+        };
+        HttpOracle::new(config, client)
+    }
+
+    #[test]
+    fn hosts_are_parsed_conservatively() {
+        assert_eq!(url_host("https://Example.com/path").unwrap(), "example.com");
+        assert_eq!(
+            url_host("http://example.com:8080?q=1").unwrap(),
+            "example.com"
+        );
+        for url in [
+            "ftp://example.com",
+            "example.com",
+            "https://user@example.com",
+            "https://evil.com\\@example.com",
+            "https://example.com:port",
+            "https://[::1]/",
+            "https:// example.com",
+            "https:///path",
+        ] {
+            assert_matches!(url_host(url), Err(HttpError::InvalidUrl(_)), "{url}");
+        }
+    }
+
+    #[tokio::test]
+    async fn only_allowed_requests_are_sent() {
+        let client = MockHttpClient::new(|request| {
+            Ok(HttpResponse::new(200, request.url.as_bytes()[..8].to_vec()))
+        });
+        let oracle = make_oracle(client.clone());
+
+        let response = oracle
+            .perform(HttpRequest::get("https://example.com/price"))
+            .await
+            .unwrap();
+        assert_eq!(response.body, b"https://");
+
+        assert_matches!(
+            oracle.perform(HttpRequest::get("https://other.com/")).await,
+            Err(HttpError::HostNotAllowed(host)) if host == "other.com"
+        );
+        assert_matches!(
+            oracle
+                .perform(HttpRequest::post("https://example.com/", b"12345".to_vec()))
+                .await,
+            Err(HttpError::RequestTooLarge {
+                size: 5,
+                maximum: 4
+            })
+        );
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn large_responses_are_rejected() {
+        let oracle = make_oracle(MockHttpClient::new(|_| {
+            Ok(HttpResponse::new(200, vec![0; 9]))
+        }));
+        assert_matches!(
+            oracle.perform(HttpRequest::get("http://example.com")).await,
+            Err(HttpError::ResponseTooLarge { maximum: 8 })
+        );
+    }
+
+    #[tokio::test]
+    async fn queries_have_a_limited_number_of_requests() {
+        let client = MockHttpClient::new(|_| Ok(HttpResponse::new(200, Vec::new())));
+        let oracle = make_oracle(client.clone());
+        let mut query = oracle.start_query();
+        for _ in 0..2 {
+            query
+                .perform(HttpRequest::get("https://example.com/"))
+                .await
+                .unwrap();
+        }
+        assert_matches!(
+            query
+                .perform(HttpRequest::get("https://example.com/"))
+                .await,
+            Err(HttpError::TooManyRequests { maximum: 2 })
+        );
+        assert_eq!(client.requests().len(), 2);
+
+        // Each query has its own limits.
+        let mut other_query = oracle.start_query();
+        assert!(other_query
+            .perform(HttpRequest::get("https://example.com/"))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn queries_have_a_limited_duration() {
+        let config = HttpOracleConfig {
+            allowed_hosts: ["example.com".to_owned()].into(),
+            max_query_duration: Duration::ZERO,
+            ..HttpOracleConfig::default()
+        };
+        let client = MockHttpClient::new(|_| Ok(HttpResponse::new(200, Vec::new())));
+        let oracle = HttpOracle::new(config, client.clone());
+        assert_matches!(
+            oracle
+                .start_query()
+                .perform(HttpRequest::get("https://example.com/"))
+                .await,
+            Err(HttpError::QueryTimeout { .. })
+        );
+        assert!(client.requests().is_empty());
+    }
+}
@@ -8,12 +8,17 @@ pub mod committee;
 mod execution;
 mod execution_state_actor;
 mod graphql;
+pub mod http;
+mod logs;
 mod policy;
+mod profiler;
+mod randomness;
 mod resources;
 mod runtime;
 pub mod system;
 #[cfg(with_testing)]
 pub mod test_utils;
+mod trace;
 mod util;
 mod wasm;
 
@@ -21,21 +26,30 @@ pub use crate::runtime::{ContractSyncRuntime, ServiceSyncRuntime};
 #[cfg(with_testing)]
 pub use applications::ApplicationRegistry;
 pub use applications::{
-    ApplicationRegistryView, BytecodeLocation, GenericApplicationId, UserApplicationDescription,
-    UserApplicationId,
+    ApplicationRegistryView, ApplicationUpgrade, BytecodeLocation, GenericApplicationId,
+    UserApplicationDescription, UserApplicationId,
 };
-pub use execution::ExecutionStateView;
-pub use policy::ResourceControlPolicy;
+pub use execution::{application_state_positions, ExecutionStateView};
+pub use logs::{ApplicationLog, ApplicationLogger, LogLevel};
+pub use policy::{HostCallFuel, HostCallFuelCosts, ResourceControlPolicy};
+pub use profiler::{FuelProfile, FuelProfiler};
+pub use randomness::BlockRandomness;
 pub use resources::{ResourceController, ResourceTracker};
 pub use system::{
     SystemExecutionError, SystemExecutionStateView, SystemMessage, SystemOperation, SystemQuery,
     SystemResponse,
 };
+pub use trace::{ExecutionTrace, ExecutionTracer, TraceDivergence, TraceEntry};
 #[cfg(all(with_testing, any(with_wasmer, with_wasmtime)))]
 pub use wasm::test as wasm_test;
+pub use wasm::{
+    validate_application, BytecodeLimits, BytecodeValidationError, LATEST_BYTECODE_RULES,
+    LOADING_RULES,
+};
 #[cfg(with_wasm_runtime)]
 pub use wasm::{WasmContractModule, WasmExecutionError, WasmServiceModule};
 
+use crate::http::{HttpError, HttpOracle, HttpRequest, HttpResponse};
 use async_graphql::SimpleObject;
 use async_trait::async_trait;
 use custom_debug_derive::Debug;
@@ -43,8 +57,8 @@ use dashmap::DashMap;
 use derive_more::Display;
 use linera_base::{
     abi::Abi,
-    crypto::CryptoHash,
-    data_types::{Amount, ArithmeticError, BlockHeight, Resources, Timestamp},
+    crypto::{BcsHashable, CryptoHash},
+    data_types::{Amount, ArithmeticError, BlockHeight, FeeSponsorship, Resources, Timestamp},
     doc_scalar, hex_debug,
     identifiers::{
         Account, BytecodeId, ChainId, ChannelName, Destination, MessageId, Owner, SessionId,
@@ -100,6 +114,8 @@ pub enum ExecutionError {
     WasmError(#[from] WasmExecutionError),
     #[error(transparent)]
     JoinError(#[from] tokio::task::JoinError),
+    #[error(transparent)]
+    HttpError(#[from] HttpError),
     #[error("The given promise is invalid or was polled once already")]
     InvalidPromise,
 
@@ -131,10 +147,20 @@ pub enum ExecutionError {
     MissingRuntimeResponse,
     #[error("Bytecode ID {0:?} is invalid")]
     InvalidBytecodeId(BytecodeId),
+    #[error(transparent)]
+    DecompressionError(#[from] DecompressionError),
     #[error("Owner is None")]
     OwnerIsNone,
     #[error("Application is not authorized to perform system operations on this chain: {0:}")]
     UnauthorizedApplication(UserApplicationId),
+    #[error("There is no request to answer, or it was answered already")]
+    NoRequestToAnswer,
+    #[error("Message {0:} is not a pending request of the application receiving its response")]
+    UnexpectedResponse(MessageId),
+    #[error(transparent)]
+    InvalidBytecode(#[from] BytecodeValidationError),
+    #[error("Execution ran out of fuel")]
+    MaximumFuelExceeded,
 }
 
 impl ExecutionError {
@@ -160,6 +186,16 @@ pub trait UserContract {
         argument: Vec<u8>,
     ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError>;
 
+    /// Migrates the application state left by the previous bytecode, after an upgrade on the
+    /// chain that owns the application, or when another chain with a state for the application
+    /// receives its new description. In the latter case, the context is not authenticated and
+    /// its `index` is zero.
+    fn migrate(
+        &mut self,
+        context: OperationContext,
+        argument: Vec<u8>,
+    ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError>;
+
     /// Applies an operation from the current block.
     fn execute_operation(
         &mut self,
@@ -254,9 +290,21 @@ pub trait ExecutionRuntimeContext {
 
     fn execution_runtime_config(&self) -> ExecutionRuntimeConfig;
 
-    fn user_contracts(&self) -> &Arc<DashMap<UserApplicationId, UserContractCode>>;
+    /// The tracer recording the execution of user applications, if any.
+    fn execution_tracer(&self) -> Option<&ExecutionTracer>;
+
+    /// The profiler attributing the fuel consumed by user applications, if any.
+    fn fuel_profiler(&self) -> Option<&FuelProfiler>;
+
+    /// The oracle performing the HTTP requests of services, if they are allowed.
+    fn http_oracle(&self) -> Option<&HttpOracle>;
+
+    /// The logger recording the messages logged by contracts, if they are kept.
+    fn application_logger(&self) -> Option<&ApplicationLogger>;
 
-    fn user_services(&self) -> &Arc<DashMap<UserApplicationId, UserServiceCode>>;
+    fn user_contracts(&self) -> &Arc<DashMap<BytecodeId, UserContractCode>>;
+
+    fn user_services(&self) -> &Arc<DashMap<BytecodeId, UserServiceCode>>;
 
     async fn get_user_contract(
         &self,
@@ -300,6 +348,9 @@ pub struct MessageContext {
     /// The id of the message (based on the operation height and index in the remote
     /// certificate).
     pub message_id: MessageId,
+    /// The request that this message answers if it is a response, or the ID of the original
+    /// message if it is bouncing.
+    pub response_to: Option<MessageId>,
     /// The index of the next message to be created.
     pub next_message_index: u32,
 }
@@ -352,6 +403,14 @@ pub trait BaseRuntime {
     /// Reads the current ownership configuration for this chain.
     fn chain_ownership(&mut self) -> Result<ChainOwnership, ExecutionError>;
 
+    /// Consumes the fuel charged for calling a function of the system API, as computed by
+    /// `fuel` from the [`HostCallFuelCosts`] of the current policy. Fails with
+    /// [`ExecutionError::MaximumFuelExceeded`] if not enough fuel remains.
+    fn consume_host_call_fuel(
+        &mut self,
+        fuel: impl FnOnce(&HostCallFuelCosts) -> u64,
+    ) -> Result<(), ExecutionError>;
+
     /// Tests whether a key exists in the key-value store
     #[cfg(feature = "test")]
     fn contains_key(&mut self, key: Vec<u8>) -> Result<bool, ExecutionError> {
@@ -455,9 +514,13 @@ pub trait ServiceRuntime: BaseRuntime {
         queried_id: UserApplicationId,
         argument: Vec<u8>,
     ) -> Result<Vec<u8>, ExecutionError>;
+
+    /// Performs an HTTP request, if the node allows it.
+    fn http_request(&mut self, request: HttpRequest) -> Result<HttpResponse, ExecutionError>;
 }
 
 /// The result of calling into an application or a session.
+#[derive(Debug)]
 pub struct CallOutcome {
     /// The return value.
     pub value: Vec<u8>,
@@ -472,6 +535,9 @@ pub trait ContractRuntime: BaseRuntime {
     /// Consumes some of the execution fuel.
     fn consume_fuel(&mut self, fuel: u64) -> Result<(), ExecutionError>;
 
+    /// Returns the profiler attributing the consumed fuel, if the execution is being profiled.
+    fn fuel_profiler(&mut self) -> Result<Option<FuelProfiler>, ExecutionError>;
+
     /// Transfers amount from source to destination.
     fn transfer(
         &mut self,
@@ -488,6 +554,38 @@ pub trait ContractRuntime: BaseRuntime {
         amount: Amount,
     ) -> Result<(), ExecutionError>;
 
+    /// Reads the balance of the current application's account on this chain.
+    fn read_application_balance(&mut self) -> Result<Amount, ExecutionError>;
+
+    /// Returns the next random value of the current block for the current application, or
+    /// `None` if the block has no source of randomness.
+    fn block_randomness(&mut self) -> Result<Option<CryptoHash>, ExecutionError>;
+
+    /// Logs a `message` of the current application.
+    fn log(&mut self, level: LogLevel, message: &str) -> Result<(), ExecutionError>;
+
+    /// Moves amount from the chain balance, or from the account of `source`, to the
+    /// current application's account.
+    fn fund_application(
+        &mut self,
+        source: Option<Owner>,
+        amount: Amount,
+    ) -> Result<(), ExecutionError>;
+
+    /// Sends amount from the current application's account to destination.
+    fn withdraw_from_application(
+        &mut self,
+        destination: Account,
+        amount: Amount,
+    ) -> Result<(), ExecutionError>;
+
+    /// Sets whether and up to which limits the current application pays the fees of the
+    /// operations that target it.
+    fn set_fee_sponsorship(
+        &mut self,
+        sponsorship: Option<FeeSponsorship>,
+    ) -> Result<(), ExecutionError>;
+
     /// Calls another application. Forwarded sessions will now be visible to
     /// `callee_id` (but not to the caller any more).
     fn try_call_application(
@@ -517,6 +615,20 @@ pub trait ContractRuntime: BaseRuntime {
 
     /// Closes the current chain.
     fn close_chain(&mut self) -> Result<(), ExecutionError>;
+
+    /// Sends a tracked request to the current application on the `destination` chain.
+    /// Returns the ID of the request message, which the response will refer to.
+    fn send_request(
+        &mut self,
+        destination: ChainId,
+        authenticated: bool,
+        message: Vec<u8>,
+    ) -> Result<MessageId, ExecutionError>;
+
+    /// Sends a response to the request currently being executed, back to the current
+    /// application on the chain that sent it. Fails outside the execution of a request, or if
+    /// the request was already answered.
+    fn send_response(&mut self, message: Vec<u8>) -> Result<(), ExecutionError>;
 }
 
 /// An operation to be executed in a block.
@@ -586,6 +698,11 @@ pub struct RawOutgoingMessage<Message, Grant = Resources> {
     pub grant: Grant,
     /// The kind of outgoing message being sent.
     pub kind: MessageKind,
+    /// The time after which the receiver can no longer accept the message, if any. Tracked
+    /// messages then bounce back to the sender.
+    pub deadline: Option<Timestamp>,
+    /// The request that this message answers, if it is a response.
+    pub response_to: Option<MessageId>,
     /// The message itself.
     pub message: Message,
 }
@@ -692,6 +809,8 @@ impl<Message> RawOutgoingMessage<Message, Resources> {
             authenticated,
             grant,
             kind,
+            deadline,
+            response_to,
             message,
         } = self;
         Ok(RawOutgoingMessage {
@@ -699,6 +818,8 @@ impl<Message> RawOutgoingMessage<Message, Resources> {
             authenticated,
             grant: policy.total_price(&grant)?,
             kind,
+            deadline,
+            response_to,
             message,
         })
     }
@@ -752,8 +873,11 @@ impl OperationContext {
 pub struct TestExecutionRuntimeContext {
     chain_id: ChainId,
     execution_runtime_config: ExecutionRuntimeConfig,
-    user_contracts: Arc<DashMap<UserApplicationId, UserContractCode>>,
-    user_services: Arc<DashMap<UserApplicationId, UserServiceCode>>,
+    user_contracts: Arc<DashMap<BytecodeId, UserContractCode>>,
+    user_services: Arc<DashMap<BytecodeId, UserServiceCode>>,
+    execution_tracer: Option<ExecutionTracer>,
+    http_oracle: Option<HttpOracle>,
+    application_logger: Option<ApplicationLogger>,
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -764,8 +888,29 @@ impl TestExecutionRuntimeContext {
             execution_runtime_config,
             user_contracts: Arc::default(),
             user_services: Arc::default(),
+            execution_tracer: None,
+            http_oracle: None,
+            application_logger: None,
         }
     }
+
+    /// Allows the services to make the HTTP requests accepted by the `http_oracle`.
+    pub fn with_http_oracle(mut self, http_oracle: HttpOracle) -> Self {
+        self.http_oracle = Some(http_oracle);
+        self
+    }
+
+    /// Records the messages logged by contracts with the `application_logger`.
+    pub fn with_application_logger(mut self, application_logger: ApplicationLogger) -> Self {
+        self.application_logger = Some(application_logger);
+        self
+    }
+
+    /// Records the execution of user applications with the `execution_tracer`.
+    pub fn with_execution_tracer(mut self, execution_tracer: ExecutionTracer) -> Self {
+        self.execution_tracer = Some(execution_tracer);
+        self
+    }
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -779,11 +924,27 @@ impl ExecutionRuntimeContext for TestExecutionRuntimeContext {
         self.execution_runtime_config
     }
 
-    fn user_contracts(&self) -> &Arc<DashMap<UserApplicationId, UserContractCode>> {
+    fn execution_tracer(&self) -> Option<&ExecutionTracer> {
+        self.execution_tracer.as_ref()
+    }
+
+    fn fuel_profiler(&self) -> Option<&FuelProfiler> {
+        None
+    }
+
+    fn http_oracle(&self) -> Option<&HttpOracle> {
+        self.http_oracle.as_ref()
+    }
+
+    fn application_logger(&self) -> Option<&ApplicationLogger> {
+        self.application_logger.as_ref()
+    }
+
+    fn user_contracts(&self) -> &Arc<DashMap<BytecodeId, UserContractCode>> {
         &self.user_contracts
     }
 
-    fn user_services(&self) -> &Arc<DashMap<UserApplicationId, UserServiceCode>> {
+    fn user_services(&self) -> &Arc<DashMap<BytecodeId, UserServiceCode>> {
         &self.user_services
     }
 
@@ -791,10 +952,9 @@ impl ExecutionRuntimeContext for TestExecutionRuntimeContext {
         &self,
         description: &UserApplicationDescription,
     ) -> Result<UserContractCode, ExecutionError> {
-        let application_id = description.into();
         Ok(self
             .user_contracts()
-            .get(&application_id)
+            .get(&description.current_bytecode_id())
             .ok_or_else(|| {
                 ExecutionError::ApplicationBytecodeNotFound(Box::new(description.clone()))
             })?
@@ -805,10 +965,9 @@ impl ExecutionRuntimeContext for TestExecutionRuntimeContext {
         &self,
         description: &UserApplicationDescription,
     ) -> Result<UserServiceCode, ExecutionError> {
-        let application_id = description.into();
         Ok(self
             .user_services()
-            .get(&application_id)
+            .get(&description.current_bytecode_id())
             .ok_or_else(|| {
                 ExecutionError::ApplicationBytecodeNotFound(Box::new(description.clone()))
             })?
@@ -943,6 +1102,8 @@ impl Bytecode {
     }
 }
 
+impl BcsHashable for Bytecode {}
+
 impl AsRef<[u8]> for Bytecode {
     fn as_ref(&self) -> &[u8] {
         self.bytes.as_ref()
@@ -955,6 +1116,69 @@ impl std::fmt::Debug for Bytecode {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl Bytecode {
+    /// Compresses the bytecode, so that it can be published at a lower cost.
+    pub fn compress(&self) -> CompressedBytecode {
+        let compressed_bytes = zstd::stream::encode_all(self.bytes.as_slice(), 19)
+            .expect("Compressing bytes in memory should not fail");
+        CompressedBytecode { compressed_bytes }
+    }
+}
+
+/// A WebAssembly module's bytecode, compressed with Zstandard.
+#[derive(Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CompressedBytecode {
+    #[serde(with = "serde_bytes")]
+    compressed_bytes: Vec<u8>,
+}
+
+impl CompressedBytecode {
+    /// The maximum size of a bytecode once decompressed, regardless of the current
+    /// [`ResourceControlPolicy`].
+    pub const MAXIMUM_DECOMPRESSED_SIZE: u64 = 128 /* MiB */ * 1024 /* KiB */ * 1024 /* bytes */;
+
+    /// Decompresses the bytecode, failing if it would be larger than `maximum_size` bytes.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn decompress(&self, maximum_size: u64) -> Result<Bytecode, DecompressionError> {
+        use std::io::Read as _;
+
+        let maximum_size = maximum_size.min(Self::MAXIMUM_DECOMPRESSED_SIZE);
+        let decoder = zstd::stream::read::Decoder::new(self.compressed_bytes.as_slice())?;
+        let mut bytes = Vec::new();
+        // Read one byte past the limit to detect oversized bytecodes without decompressing
+        // them entirely.
+        decoder
+            .take(maximum_size.saturating_add(1))
+            .read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > maximum_size {
+            return Err(DecompressionError::BytecodeTooLarge(maximum_size));
+        }
+        Ok(Bytecode { bytes })
+    }
+}
+
+impl AsRef<[u8]> for CompressedBytecode {
+    fn as_ref(&self) -> &[u8] {
+        self.compressed_bytes.as_ref()
+    }
+}
+
+impl std::fmt::Debug for CompressedBytecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_tuple("CompressedBytecode").finish()
+    }
+}
+
+/// Errors that can occur when decompressing a [`CompressedBytecode`].
+#[derive(Debug, Error)]
+pub enum DecompressionError {
+    #[error("Bytecode is larger than {0} bytes once decompressed")]
+    BytecodeTooLarge(u64),
+    #[error("Failed to decompress bytecode: {0}")]
+    InvalidCompressedBytecode(#[from] std::io::Error),
+}
+
 /// The runtime to use for running the application.
 #[derive(Clone, Copy, Display)]
 #[cfg_attr(with_wasm_runtime, derive(Debug, Default))]
@@ -973,6 +1197,38 @@ pub enum WasmRuntime {
     WasmtimeWithSanitizer,
 }
 
+/// Enables a cache of compiled WebAssembly modules in `directory`, limited to `max_size` bytes,
+/// so that applications are not compiled again after a restart.
+///
+/// Does nothing if no WebAssembly runtime is enabled.
+pub fn enable_persistent_module_cache(
+    directory: impl Into<std::path::PathBuf>,
+    max_size: u64,
+) -> Result<(), std::io::Error> {
+    #[cfg(with_wasm_runtime)]
+    return wasm::enable_persistent_module_cache(directory, max_size);
+
+    #[cfg(not(with_wasm_runtime))]
+    {
+        let _ = (directory.into(), max_size);
+        Ok(())
+    }
+}
+
+/// Enables the instrumentation of the contracts loaded from now on, so that a [`FuelProfiler`]
+/// attributes the fuel they consume to their WebAssembly functions instead of only to the
+/// applications.
+///
+/// Applies to the whole process, and should be called before any application is loaded, since
+/// compiled modules are cached. Instrumented contracts may run out of fuel slightly earlier, but
+/// are charged the same fees.
+///
+/// Does nothing if no WebAssembly runtime is enabled.
+pub fn enable_fuel_profiling() {
+    #[cfg(with_wasm_runtime)]
+    wasm::enable_fuel_profiling();
+}
+
 /// Trait used to select a default WasmRuntime, if one is available.
 pub trait WithWasmDefault {
     fn with_wasm_default(self) -> Self;
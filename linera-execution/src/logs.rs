@@ -0,0 +1,94 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Capture of the messages logged by contracts.
+//!
+//! Contract logs are always emitted as `tracing` events with the chain, block height and
+//! application that produced them. When the
+//! [`ExecutionRuntimeContext`][`crate::ExecutionRuntimeContext`] provides an
+//! [`ApplicationLogger`], they are also recorded, so that the chain can keep the logs of its
+//! latest blocks, or return them with the error if the execution of a block fails.
+//!
+//! Validators don't record logs: only the nodes that enable log retention, such as the node
+//! service of a client, capture the logs of the blocks they execute themselves.
+
+use crate::UserApplicationId;
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+/// The severity of a logged message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, Enum)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A message logged by an application while a block was executed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct ApplicationLog {
+    /// The index of the incoming message being executed, if any.
+    pub incoming_message_index: Option<u32>,
+    /// The index of the operation being executed, if any.
+    pub operation_index: Option<u32>,
+    /// The application that logged the message.
+    pub application_id: UserApplicationId,
+    /// The severity of the message.
+    pub level: LogLevel,
+    /// The logged message.
+    pub message: String,
+}
+
+/// A handle to record the messages logged by the applications of a chain.
+#[derive(Clone, Debug)]
+pub struct ApplicationLogger {
+    retained_blocks: u64,
+    logs: Arc<Mutex<Vec<ApplicationLog>>>,
+}
+
+impl ApplicationLogger {
+    /// Creates a logger for a chain that keeps the logs of its latest `retained_blocks` blocks.
+    pub fn new(retained_blocks: u64) -> Self {
+        ApplicationLogger {
+            retained_blocks,
+            logs: Arc::default(),
+        }
+    }
+
+    /// Returns the number of blocks for which the logs are kept.
+    pub fn retained_blocks(&self) -> u64 {
+        self.retained_blocks
+    }
+
+    /// Records a message logged by the application with the given ID.
+    pub fn record(&self, application_id: UserApplicationId, level: LogLevel, message: String) {
+        self.logs
+            .lock()
+            .expect("Application logger lock should not be poisoned")
+            .push(ApplicationLog {
+                incoming_message_index: None,
+                operation_index: None,
+                application_id,
+                level,
+                message,
+            });
+    }
+
+    /// Returns the logs recorded since the last call.
+    ///
+    /// The caller is responsible for setting the index of the transaction being executed.
+    pub fn take(&self) -> Vec<ApplicationLog> {
+        mem::take(
+            &mut *self
+                .logs
+                .lock()
+                .expect("Application logger lock should not be poisoned"),
+        )
+    }
+}
@@ -3,9 +3,10 @@
 
 //! This module contains types related to fees and pricing.
 
+use crate::LATEST_BYTECODE_RULES;
 use async_graphql::InputObject;
 use linera_base::data_types::{Amount, ArithmeticError, Resources};
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 
 /// A collection of prices and limits associated with block execution.
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize, InputObject)]
@@ -22,24 +23,54 @@ pub struct ResourceControlPolicy {
     pub byte_read: Amount,
     /// The price of writing a byte
     pub byte_written: Amount,
-    /// The price of increasing storage by a byte.
-    // TODO(#1536): This is not fully supported.
+    /// The price of increasing storage by a byte, paid as a deposit. Freeing storage
+    /// refunds the deposit to the account that paid it.
     pub byte_stored: Amount,
     /// The base price of adding an operation to a block.
     pub operation: Amount,
-    /// The additional price for each byte in the argument of a user operation.
+    /// The additional price for each byte in the argument of a user operation, or in the
+    /// bytecode published by a system operation, as stored in the block.
     pub operation_byte: Amount,
     /// The base price of sending a message from a block.
     pub message: Amount,
     /// The additional price for each byte in the argument of a user message.
     pub message_byte: Amount,
+    /// The percentage of the fees that is paid out to the validators at the end of each
+    /// epoch, in proportion to their votes. The rest of the fees is burned. Values above 100
+    /// are rejected.
+    #[serde(deserialize_with = "deserialize_percent")]
+    #[graphql(validator(maximum = 100))]
+    pub validator_fee_percent: u8,
 
-    // TODO(#1538): Cap the number of transactions per block and the total size of their
-    // arguments.
     /// The maximum data to read per block
     pub maximum_bytes_read_per_block: u64,
     /// The maximum data to write per block
     pub maximum_bytes_written_per_block: u64,
+    /// The maximum number of operations per block.
+    pub maximum_operations_per_block: u64,
+    /// The maximum number of incoming messages per block.
+    pub maximum_incoming_messages_per_block: u64,
+    /// The maximum number of outgoing messages per block.
+    pub maximum_outgoing_messages_per_block: u64,
+    /// The maximum total size of the arguments of the user operations in a block.
+    pub maximum_operation_bytes_per_block: u64,
+    /// The maximum size of a serialized block.
+    pub maximum_block_size: u64,
+    /// The maximum fuel consumed per block.
+    pub maximum_fuel_per_block: u64,
+    /// The maximum size of a published contract or service bytecode, in bytes.
+    pub maximum_bytecode_size: u64,
+    /// The maximum number of 64 KiB pages that the linear memory of a published contract or
+    /// service bytecode can declare.
+    pub maximum_wasm_memory_pages: u64,
+    /// The fuel charged for calling each function of the system API.
+    pub host_call_fuel: HostCallFuelCosts,
+    /// The additional price of a block proposed on a public chain by a signer who doesn't
+    /// own it. This protects public chains against spam.
+    pub open_proposer_block: Amount,
+    /// The version of the rules that published bytecodes must follow, or zero to only check
+    /// their size.
+    pub bytecode_rules_version: u32,
 }
 
 impl Default for ResourceControlPolicy {
@@ -56,8 +87,20 @@ impl Default for ResourceControlPolicy {
             operation_byte: Amount::default(),
             message: Amount::default(),
             message_byte: Amount::default(),
+            validator_fee_percent: 0,
             maximum_bytes_read_per_block: u64::MAX,
             maximum_bytes_written_per_block: u64::MAX,
+            maximum_operations_per_block: u64::MAX,
+            maximum_incoming_messages_per_block: u64::MAX,
+            maximum_outgoing_messages_per_block: u64::MAX,
+            maximum_operation_bytes_per_block: u64::MAX,
+            maximum_block_size: u64::MAX,
+            maximum_fuel_per_block: u64::MAX,
+            maximum_bytecode_size: u64::MAX,
+            maximum_wasm_memory_pages: u64::MAX,
+            host_call_fuel: HostCallFuelCosts::default(),
+            open_proposer_block: Amount::default(),
+            bytecode_rules_version: LATEST_BYTECODE_RULES,
         }
     }
 }
@@ -104,8 +147,6 @@ impl ResourceControlPolicy {
         self.byte_written.try_mul(count as u128)
     }
 
-    // TODO(#1536): This is not fully implemented.
-    #[allow(dead_code)]
     pub(crate) fn bytes_stored_price(&self, count: u64) -> Result<Amount, ArithmeticError> {
         self.byte_stored.try_mul(count as u128)
     }
@@ -118,6 +159,121 @@ impl ResourceControlPolicy {
     pub(crate) fn remaining_fuel(&self, balance: Amount) -> u64 {
         u64::try_from(balance.saturating_div(self.fuel_unit)).unwrap_or(u64::MAX)
     }
+
+    /// Returns the share of the given fees that is paid out to the validators.
+    pub fn validator_fees(&self, fees: Amount) -> Amount {
+        let fees = u128::from(fees);
+        let percent = u128::from(self.validator_fee_percent);
+        // Split the fees to avoid overflowing when multiplying by the percentage.
+        Amount::from_attos(fees / 100 * percent + fees % 100 * percent / 100)
+    }
+}
+
+/// Deserializes a percentage, rejecting values above 100.
+fn deserialize_percent<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let percent = u8::deserialize(deserializer)?;
+    if percent > 100 {
+        return Err(D::Error::custom(format!(
+            "invalid percentage {percent}: must be at most 100"
+        )));
+    }
+    Ok(percent)
+}
+
+/// The fuel charged for calling a function of the system API.
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug, Default, Serialize, Deserialize, InputObject)]
+pub struct HostCallFuel {
+    /// The fuel charged for each call.
+    pub base: u64,
+    /// The additional fuel charged for each byte of variable-length data (keys, values,
+    /// arguments, messages...) passed to or returned by the call.
+    pub per_byte: u64,
+}
+
+impl HostCallFuel {
+    /// Returns the fuel charged for a call that is passed `bytes` bytes.
+    pub fn fuel(&self, bytes: usize) -> u64 {
+        self.base.saturating_add(self.per_byte_fuel(bytes))
+    }
+
+    /// Returns the fuel charged for `bytes` bytes returned by a call, whose base price was
+    /// already charged.
+    pub fn per_byte_fuel(&self, bytes: usize) -> u64 {
+        self.per_byte.saturating_mul(bytes as u64)
+    }
+}
+
+/// The fuel charged for calling each function of the system API, in addition to the fuel
+/// consumed by the WebAssembly instructions of the application.
+///
+/// The base price and the bytes passed to a function are charged when it is called. For
+/// functions that read from storage or call other applications, the bytes returned are
+/// charged when the result is obtained.
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Default, Serialize, Deserialize, InputObject)]
+#[serde(default)]
+pub struct HostCallFuelCosts {
+    /// Reading the chain ID.
+    pub chain_id: HostCallFuel,
+    /// Reading the application ID.
+    pub application_id: HostCallFuel,
+    /// Reading the application parameters.
+    pub application_parameters: HostCallFuel,
+    /// Reading the balance of the chain.
+    pub read_chain_balance: HostCallFuel,
+    /// Reading the balance of an owner.
+    pub read_owner_balance: HostCallFuel,
+    /// Reading the system timestamp.
+    pub read_system_timestamp: HostCallFuel,
+    /// Reading the ownership of the chain.
+    pub chain_ownership: HostCallFuel,
+    /// Reading the balance of the application.
+    pub read_application_balance: HostCallFuel,
+    /// Obtaining a random value of the block.
+    pub block_randomness: HostCallFuel,
+    /// Transferring tokens.
+    pub transfer: HostCallFuel,
+    /// Claiming tokens from another chain.
+    pub claim: HostCallFuel,
+    /// Funding the application.
+    pub fund_application: HostCallFuel,
+    /// Withdrawing tokens from the application.
+    pub withdraw_from_application: HostCallFuel,
+    /// Setting the sponsorship of the fees.
+    pub set_fee_sponsorship: HostCallFuel,
+    /// Opening a new chain.
+    pub open_chain: HostCallFuel,
+    /// Closing the chain.
+    pub close_chain: HostCallFuel,
+    /// Sending a request to another chain, per byte of the message.
+    pub send_request: HostCallFuel,
+    /// Sending a response to a request, per byte of the message.
+    pub send_response: HostCallFuel,
+    /// Calling another application, per byte of the argument and of the returned value.
+    pub try_call_application: HostCallFuel,
+    /// Calling a session, per byte of the argument and of the returned value.
+    pub try_call_session: HostCallFuel,
+    /// Querying another application, per byte of the argument and of the response.
+    pub try_query_application: HostCallFuel,
+    /// Performing an HTTP request from a service, per byte of the request and response bodies.
+    pub http_request: HostCallFuel,
+    /// Logging a message, per byte of the message.
+    pub log: HostCallFuel,
+    /// Testing whether a key exists, per byte of the key.
+    pub contains_key: HostCallFuel,
+    /// Reading several values, per byte of the keys and of the values.
+    pub read_multi_values_bytes: HostCallFuel,
+    /// Reading a value, per byte of the key and of the value.
+    pub read_value_bytes: HostCallFuel,
+    /// Finding the keys with a prefix, per byte of the prefix and of the keys found.
+    pub find_keys: HostCallFuel,
+    /// Finding the key-value pairs with a prefix, per byte of the prefix and of the pairs
+    /// found.
+    pub find_key_values: HostCallFuel,
+    /// Writing a batch of changes, per byte of the keys and values in the batch.
+    pub write_batch: HostCallFuel,
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -176,6 +332,7 @@ impl ResourceControlPolicy {
             message: Amount::from_micros(10),
             maximum_bytes_read_per_block: 100_000_000,
             maximum_bytes_written_per_block: 10_000_000,
+            ..Self::default()
         }
     }
 }
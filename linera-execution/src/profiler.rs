@@ -0,0 +1,161 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of the fuel consumed by user applications, to find out which parts of a contract
+//! are responsible for its fees.
+//!
+//! When the [`ExecutionRuntimeContext`][`crate::ExecutionRuntimeContext`] provides a
+//! [`FuelProfiler`], the fuel consumed by contracts is attributed to the stack of frames that
+//! consumed it: the applications, their WebAssembly functions (if fuel profiling was enabled with
+//! [`enable_fuel_profiling`][`crate::enable_fuel_profiling`]) and the system API calls they make.
+//! The resulting [`FuelProfile`] uses the folded stacks format, which can be rendered by
+//! flamegraph tools such as `inferno-flamegraph`.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// A handle to record a [`FuelProfile`], shared by the runtimes executing a block.
+///
+/// Only one execution should be profiled at a time, since the frames of concurrent executions
+/// would be mixed up.
+#[derive(Clone, Debug, Default)]
+pub struct FuelProfiler {
+    state: Arc<Mutex<ProfilerState>>,
+}
+
+/// The state of a [`FuelProfiler`].
+#[derive(Debug, Default)]
+struct ProfilerState {
+    /// The current stack of frames, separated by `;`.
+    path: String,
+    /// The length of the `path` before each frame was pushed.
+    frame_offsets: Vec<usize>,
+    /// The fuel recorded so far.
+    profile: FuelProfile,
+}
+
+impl FuelProfiler {
+    /// Pushes a new `frame` on top of the stack of frames.
+    pub fn push(&self, frame: impl fmt::Display) {
+        let mut state = self.lock();
+        let offset = state.path.len();
+        state.frame_offsets.push(offset);
+        if offset > 0 {
+            state.path.push(';');
+        }
+        // Semicolons separate the frames, and new lines separate the stacks.
+        let frame = frame.to_string().replace([';', '\n'], ":");
+        state.path.push_str(&frame);
+    }
+
+    /// Removes the frame on top of the stack of frames, if there is one.
+    pub fn pop(&self) {
+        let mut state = self.lock();
+        if let Some(offset) = state.frame_offsets.pop() {
+            state.path.truncate(offset);
+        }
+    }
+
+    /// Removes all the frames from the stack.
+    pub fn clear_stack(&self) {
+        let mut state = self.lock();
+        state.path.clear();
+        state.frame_offsets.clear();
+    }
+
+    /// Attributes `fuel` to the current stack of frames.
+    pub fn charge(&self, fuel: u64) {
+        if fuel == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        let path = if state.path.is_empty() {
+            "[unknown]".to_owned()
+        } else {
+            state.path.clone()
+        };
+        let total = state.profile.stacks.entry(path).or_default();
+        *total = total.saturating_add(fuel);
+    }
+
+    /// Returns the profile recorded so far, and starts recording a new one.
+    pub fn take(&self) -> FuelProfile {
+        std::mem::take(&mut self.lock().profile)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ProfilerState> {
+        self.state
+            .lock()
+            .expect("Fuel profiler lock should not be poisoned")
+    }
+}
+
+/// The fuel consumed by user applications, attributed to the stacks of frames that consumed it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FuelProfile {
+    /// The fuel consumed directly by the top frame of each stack, indexed by the stack's frames
+    /// separated by `;`.
+    pub stacks: BTreeMap<String, u64>,
+}
+
+impl FuelProfile {
+    /// Returns the total fuel recorded in the profile.
+    pub fn total_fuel(&self) -> u64 {
+        self.stacks
+            .values()
+            .fold(0, |total, fuel| total.saturating_add(*fuel))
+    }
+
+    /// Saves the profile at `path`, using the folded stacks format.
+    pub fn save_folded(&self, path: impl AsRef<Path>) -> Result<(), io::Error> {
+        fs::write(path, self.to_string())
+    }
+}
+
+/// Formats the profile using the folded stacks format, with one stack per line.
+impl fmt::Display for FuelProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stack, fuel) in &self.stacks {
+            writeln!(f, "{stack} {fuel}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FuelProfiler;
+
+    /// Tests that fuel is attributed to the stack of frames active when it is charged.
+    #[test]
+    fn attributes_fuel_to_stacks() {
+        let profiler = FuelProfiler::default();
+
+        profiler.push("app");
+        profiler.charge(1);
+        profiler.push("main");
+        profiler.charge(10);
+        profiler.push("helper;inlined");
+        profiler.charge(100);
+        profiler.pop();
+        profiler.charge(1_000);
+        profiler.push("helper;inlined");
+        profiler.charge(10_000);
+        profiler.clear_stack();
+        profiler.charge(0);
+
+        let profile = profiler.take();
+        assert_eq!(profile.total_fuel(), 11_111);
+        assert_eq!(
+            profile.to_string(),
+            "app 1\n\
+            app;main 1010\n\
+            app;main;helper:inlined 10100\n"
+        );
+        assert!(profiler.take().stacks.is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Randomness available to contracts during the execution of a block.
+//!
+//! Each block must include the validators' signatures on the certificate of the previous
+//! block of its chain, if that block was certified in the same epoch. Only the first
+//! signatures by validator name that form a quorum are included. The hash of these
+//! signatures is the seed of the block, and every
+//! value returned to an application is the hash of the seed, the application ID and the
+//! number of values already drawn in the block. Executing the same block therefore always
+//! yields the same values.
+//!
+//! The values have the following properties, which applications should take into account:
+//!
+//! * Nobody can predict the seed of a block before a quorum of validators has signed the
+//!   previous block, unless a quorum of validators colludes.
+//! * The proposer of a block knows the seed before proposing the block, and can choose its
+//!   operations and incoming messages accordingly. Randomness should therefore only be used
+//!   to resolve commitments that were made in earlier blocks, e.g. tickets bought before the
+//!   draw of a lottery.
+//! * The proposer can neither omit the signatures nor add superfluous ones. However, a
+//!   proposer who obtained signatures beyond the first quorum can still pretend that some
+//!   of the first validators did not sign, and thus choose among a few different seeds.
+//! * Randomness is unavailable in the first block of a chain and in blocks following a
+//!   change of epoch, since there are no signatures of the current committee to use.
+
+use crate::UserApplicationId;
+use linera_base::crypto::{BcsHashable, CryptoHash};
+use serde::{Deserialize, Serialize};
+
+/// The source of randomness of the block being executed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRandomness {
+    /// The seed derived from the signatures on the previous block.
+    pub seed: CryptoHash,
+    /// The number of values already drawn in this block.
+    pub counter: u64,
+}
+
+/// The data hashed to obtain a random value.
+#[derive(Serialize, Deserialize)]
+struct RandomValueInput {
+    seed: CryptoHash,
+    application_id: UserApplicationId,
+    counter: u64,
+}
+
+impl BcsHashable for RandomValueInput {}
+
+impl BlockRandomness {
+    /// Creates the randomness of a block with the given `seed`.
+    pub fn new(seed: CryptoHash) -> Self {
+        BlockRandomness { seed, counter: 0 }
+    }
+
+    /// Returns the next random value for the application with the given ID.
+    pub fn next_value(&mut self, application_id: UserApplicationId) -> CryptoHash {
+        let value = CryptoHash::new(&RandomValueInput {
+            seed: self.seed,
+            application_id,
+            counter: self.counter,
+        });
+        self.counter += 1;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockRandomness;
+    use crate::{test_utils::create_dummy_user_application_description, UserApplicationId};
+    use linera_base::{
+        crypto::CryptoHash,
+        data_types::BlockHeight,
+        identifiers::{BytecodeId, ChainId, MessageId},
+    };
+
+    #[test]
+    fn values_are_deterministic_and_distinct() {
+        let seed = CryptoHash::test_hash("seed");
+        let application_id = UserApplicationId::from(&create_dummy_user_application_description(1));
+        let mut randomness = BlockRandomness::new(seed);
+        let first = randomness.next_value(application_id);
+        let second = randomness.next_value(application_id);
+        assert_ne!(first, second);
+        assert_eq!(randomness.counter, 2);
+
+        let mut replayed = BlockRandomness::new(seed);
+        assert_eq!(replayed.next_value(application_id), first);
+        assert_eq!(replayed.next_value(application_id), second);
+
+        let other_id = UserApplicationId::from(&create_dummy_user_application_description(2));
+        assert_ne!(BlockRandomness::new(seed).next_value(other_id), first);
+
+        let mut other_seed = BlockRandomness::new(CryptoHash::test_hash("other seed"));
+        assert_ne!(other_seed.next_value(application_id), first);
+    }
+
+    /// Changing these values would change the outcome of existing blocks.
+    #[test]
+    fn values_match_test_vector() {
+        let application_id = UserApplicationId {
+            bytecode_id: BytecodeId::new(MessageId {
+                chain_id: ChainId(CryptoHash::from([5, 6, 7, 8])),
+                height: BlockHeight(9),
+                index: 10,
+            }),
+            creation: MessageId {
+                chain_id: ChainId(CryptoHash::from([11, 12, 13, 14])),
+                height: BlockHeight(15),
+                index: 16,
+            },
+        };
+        let mut randomness = BlockRandomness::new(CryptoHash::from([1, 2, 3, 4]));
+        assert_eq!(
+            randomness.next_value(application_id).to_string(),
+            "fb641da086b47dfb805ffdaf86cbc4d2cafcba30d469d7d6220555703f0926ba"
+        );
+        assert_eq!(
+            randomness.next_value(application_id).to_string(),
+            "0739afbc28ca48f0960c20f9a646b48f6199146341951f98a34bbf24e5801bf6"
+        );
+    }
+}
@@ -41,7 +41,7 @@ pub struct ResourceTracker {
     /// The number of bytes written.
     pub bytes_written: u64,
     /// The change in the number of bytes being stored by user applications.
-    pub bytes_stored: i32,
+    pub bytes_stored: i64,
     /// The number of operations executed.
     pub operations: u32,
     /// The total size of the arguments of user operations.
@@ -52,6 +52,8 @@ pub struct ResourceTracker {
     pub message_bytes: u64,
     /// The amount allocated to message grants.
     pub grants: Amount,
+    /// The fees paid so far.
+    pub fees: Amount,
 }
 
 /// How to access the balance of an account.
@@ -91,9 +93,16 @@ where
         Ok(())
     }
 
-    /// Subtracts an amount from a balance and reports an error if that is impossible.
+    /// Subtracts fees from a balance and reports an error if that is impossible.
     fn update_balance(&mut self, fees: Amount) -> Result<(), ExecutionError> {
-        self.account.try_sub_assign(fees).map_err(|_| {
+        self.debit(fees)?;
+        self.tracker.as_mut().fees.try_add_assign(fees)?;
+        Ok(())
+    }
+
+    /// Subtracts an amount from a balance and reports an error if that is impossible.
+    fn debit(&mut self, amount: Amount) -> Result<(), ExecutionError> {
+        self.account.try_sub_assign(amount).map_err(|_| {
             SystemExecutionError::InsufficientFundingForFees {
                 balance: self.balance().unwrap_or(Amount::MAX),
             }
@@ -101,16 +110,22 @@ where
         Ok(())
     }
 
-    /// Obtains the amount of fuel that could be spent by consuming the entire balance.
-    pub(crate) fn remaining_fuel(&self) -> u64 {
+    /// Obtains the amount of fuel that could be spent by consuming the entire balance,
+    /// without exceeding the fuel limit of the block.
+    pub(crate) fn remaining_fuel(&mut self) -> u64 {
+        let fuel_left_in_block = self
+            .policy
+            .maximum_fuel_per_block
+            .saturating_sub(self.tracker.as_mut().fuel);
         self.policy
             .remaining_fuel(self.balance().unwrap_or(Amount::MAX))
+            .min(fuel_left_in_block)
     }
 
     /// Tracks the allocation of a grant.
     pub fn track_grant(&mut self, grant: Amount) -> Result<(), ExecutionError> {
         self.tracker.as_mut().grants.try_add_assign(grant)?;
-        self.update_balance(grant)
+        self.debit(grant)
     }
 
     /// Tracks the creation of a block.
@@ -124,6 +139,11 @@ where
         self.update_balance(self.policy.block)
     }
 
+    /// Tracks the creation of a block by a signer who doesn't own the public chain.
+    pub fn track_open_proposal(&mut self) -> Result<(), ExecutionError> {
+        self.update_balance(self.policy.open_proposer_block)
+    }
+
     /// Tracks the execution of an operation in block.
     pub fn track_operation(&mut self, operation: &Operation) -> Result<(), ExecutionError> {
         self.tracker.as_mut().operations = self
@@ -134,7 +154,11 @@ where
             .ok_or(ArithmeticError::Overflow)?;
         self.update_balance(self.policy.operation)?;
         match operation {
-            Operation::System(_) => Ok(()),
+            Operation::System(operation) => {
+                // Published bytecode is charged by its size in the block.
+                let size = operation.published_bytecode_size();
+                self.update_balance(self.policy.operation_bytes_price(size as u64)?)
+            }
             Operation::User { bytes, .. } => {
                 let size = bytes.len();
                 self.tracker.as_mut().operation_bytes = self
@@ -174,8 +198,11 @@ where
         }
     }
 
-    /// Tracks a number of fuel units used.
+    /// Tracks a number of fuel units used, failing if they exceed the remaining fuel.
     pub(crate) fn track_fuel(&mut self, fuel: u64) -> Result<(), ExecutionError> {
+        if fuel > self.remaining_fuel() {
+            return Err(ExecutionError::MaximumFuelExceeded);
+        }
         self.tracker.as_mut().fuel = self
             .tracker
             .as_mut()
@@ -237,10 +264,9 @@ where
         Ok(())
     }
 
-    /// Tracks a change in the number of bytes stored.
-    // TODO(#1536): This is not fully implemented.
-    #[allow(dead_code)]
-    pub(crate) fn track_stored_bytes(&mut self, delta: i32) -> Result<(), ExecutionError> {
+    /// Tracks a change in the number of bytes stored. Storage is paid with deposits, which
+    /// are not fees: see [`crate::SystemExecutionStateView::take_storage_deposit`].
+    pub(crate) fn track_stored_bytes(&mut self, delta: i64) -> Result<(), ExecutionError> {
         self.tracker.as_mut().bytes_stored = self
             .tracker
             .as_mut()
@@ -333,22 +359,63 @@ impl ResourceController<Option<Owner>, ResourceTracker> {
         view: &'a mut ExecutionStateView<C>,
         grant: Option<&'a mut Amount>,
     ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    where
+        C: Context + Clone + Send + Sync + 'static,
+        ViewError: From<C::Error>,
+    {
+        self.with_state_and_funding(view, grant, None).await
+    }
+
+    /// Provides a reference to the current execution state as well as the funds that an
+    /// application makes available for the fees of an operation, and obtains a temporary
+    /// object where the accounting functions of [`ResourceController`] are available.
+    pub async fn with_state_and_sponsor<'a, C>(
+        &mut self,
+        view: &'a mut ExecutionStateView<C>,
+        sponsor: Option<&'a mut Amount>,
+    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    where
+        C: Context + Clone + Send + Sync + 'static,
+        ViewError: From<C::Error>,
+    {
+        self.with_state_and_funding(view, None, sponsor).await
+    }
+
+    pub(crate) async fn with_state_and_funding<'a, C>(
+        &mut self,
+        view: &'a mut ExecutionStateView<C>,
+        grant: Option<&'a mut Amount>,
+        sponsor: Option<&'a mut Amount>,
+    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + Send + Sync + 'static,
         ViewError: From<C::Error>,
     {
         let mut sources = Vec::new();
-        // First, use the grant (e.g. for messages) and otherwise use the chain account
-        // (e.g. for blocks and operations).
+        // Signers who don't own the chain, but can propose blocks because it is public, must
+        // pay with their own funds.
+        let is_open_proposer = self
+            .account
+            .as_ref()
+            .is_some_and(|owner| view.system.ownership.get().is_open_proposer(owner));
+        // First, use the grant (e.g. for messages) and otherwise the funds of the sponsoring
+        // application, if any, then the chain account (e.g. for blocks and operations).
         if let Some(grant) = grant {
             sources.push(grant);
         } else {
-            sources.push(view.system.balance.get_mut());
+            if let Some(sponsor) = sponsor {
+                sources.push(sponsor);
+            }
+            if !is_open_proposer {
+                sources.push(view.system.balance.get_mut());
+            }
         }
         // Then the local account, if any. Currently, any negative fee (e.g. storage
         // refund) goes preferably to this account.
         if let Some(owner) = &self.account {
-            if let Some(balance) = view.system.balances.get_mut(owner).await? {
+            if is_open_proposer {
+                sources.push(view.system.balances.get_mut_or_default(owner).await?);
+            } else if let Some(balance) = view.system.balances.get_mut(owner).await? {
                 sources.push(balance);
             }
         }
@@ -4,25 +4,29 @@
 use crate::{
     execution::UserAction,
     execution_state_actor::{ExecutionStateSender, Request},
+    http::{HttpError, HttpOracle, HttpRequest, HttpResponse, QueryHttpOracle},
     resources::ResourceController,
     system::ApplicationPermissions,
     util::{ReceiverExt, UnboundedSenderExt},
-    ApplicationCallOutcome, BaseRuntime, CallOutcome, CalleeContext, ContractRuntime,
-    ExecutionError, ExecutionOutcome, RawExecutionOutcome, ServiceRuntime, SessionId,
-    UserApplicationDescription, UserApplicationId, UserContractCode, UserContractInstance,
-    UserServiceInstance,
+    ApplicationCallOutcome, ApplicationLogger, BaseRuntime, CallOutcome, CalleeContext,
+    ContractRuntime, ExecutionError, ExecutionOutcome, ExecutionTracer, FuelProfiler,
+    HostCallFuelCosts, LogLevel, MessageKind, RawExecutionOutcome, RawOutgoingMessage,
+    ServiceRuntime, SessionId, TraceEntry, UserApplicationDescription, UserApplicationId,
+    UserContractCode, UserContractInstance, UserServiceInstance,
 };
 use custom_debug_derive::Debug;
 use linera_base::{
-    data_types::{Amount, ArithmeticError, BlockHeight, Timestamp},
+    crypto::CryptoHash,
+    data_types::{Amount, ArithmeticError, BlockHeight, FeeSponsorship, Timestamp},
     ensure,
-    identifiers::{Account, ChainId, MessageId, Owner},
+    identifiers::{Account, ChainId, Destination, MessageId, Owner},
     ownership::ChainOwnership,
 };
 use linera_views::batch::Batch;
 use oneshot::Receiver;
 use std::{
     collections::{hash_map, BTreeMap, HashMap, HashSet},
+    fmt,
     sync::{Arc, Mutex},
 };
 
@@ -72,22 +76,54 @@ pub struct SyncRuntimeInternal<UserInstance> {
     refund_grant_to: Option<Account>,
     /// Controller to track fuel and storage consumption.
     resource_controller: ResourceController,
+    /// Records the execution, if it's being traced.
+    execution_tracer: Option<ExecutionTracer>,
+    /// Attributes the consumed fuel, if the execution is being profiled.
+    fuel_profiler: Option<FuelProfiler>,
+    /// Records the messages logged by applications, if they are kept.
+    application_logger: Option<ApplicationLogger>,
+    /// The application and the ID of the request being executed, until it is answered.
+    request_to_answer: Option<(UserApplicationId, MessageId)>,
+    /// Performs the HTTP requests of the service being queried, if they are allowed.
+    http_oracle: Option<QueryHttpOracle>,
 }
 
 impl<UserInstance> SyncRuntimeInternal<UserInstance> {
     /// Returns the index of the next outcome's first message.
+    ///
+    /// Accounts for the messages registering applications, which are inserted before the first
+    /// outcome sending user messages to each destination.
     fn next_message_index(&self) -> Result<u32, ArithmeticError> {
         let mut index = self.next_message_index;
+        let mut destinations = HashSet::new();
         for outcome in &self.execution_outcomes {
             let len = match outcome {
                 ExecutionOutcome::System(outcome) => outcome.messages.len(),
-                ExecutionOutcome::User(_, outcome) => outcome.messages.len(),
+                ExecutionOutcome::User(_, outcome) => {
+                    let registrations = outcome
+                        .messages
+                        .iter()
+                        .filter(|message| destinations.insert(&message.destination))
+                        .count();
+                    registrations + outcome.messages.len()
+                }
             };
             let len = u32::try_from(len).map_err(|_| ArithmeticError::Overflow)?;
             index = index.checked_add(len).ok_or(ArithmeticError::Overflow)?;
         }
         Ok(index)
     }
+
+    /// Returns whether a previous outcome already sends user messages to the `destination`.
+    fn has_user_messages_to(&self, destination: &Destination) -> bool {
+        self.execution_outcomes.iter().any(|outcome| match outcome {
+            ExecutionOutcome::System(_) => false,
+            ExecutionOutcome::User(_, outcome) => outcome
+                .messages
+                .iter()
+                .any(|message| message.destination == *destination),
+        })
+    }
 }
 
 /// The runtime status of an application.
@@ -255,6 +291,7 @@ struct SessionState {
 }
 
 impl<UserInstance> SyncRuntimeInternal<UserInstance> {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         chain_id: ChainId,
         height: BlockHeight,
@@ -263,6 +300,9 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
         execution_state_sender: ExecutionStateSender,
         refund_grant_to: Option<Account>,
         resource_controller: ResourceController,
+        execution_tracer: Option<ExecutionTracer>,
+        fuel_profiler: Option<FuelProfiler>,
+        application_logger: Option<ApplicationLogger>,
     ) -> Self {
         Self {
             chain_id,
@@ -279,9 +319,47 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
             view_user_states: BTreeMap::default(),
             refund_grant_to,
             resource_controller,
+            execution_tracer,
+            fuel_profiler,
+            application_logger,
+            request_to_answer: None,
+            http_oracle: None,
         }
     }
 
+    /// Formats the `arguments` of a host call, if the execution is being traced.
+    fn trace_arguments(&self, arguments: &[&dyn fmt::Debug]) -> Option<String> {
+        self.execution_tracer.as_ref()?;
+        let arguments = arguments
+            .iter()
+            .map(|argument| format!("{argument:?}"))
+            .collect::<Vec<_>>();
+        Some(format!("({})", arguments.join(", ")))
+    }
+
+    /// Records a call to the `function` of the runtime by the current application in the
+    /// execution trace, given its `arguments` formatted by [`Self::trace_arguments`].
+    fn record_host_call<T: fmt::Debug>(
+        &mut self,
+        function: &str,
+        arguments: Option<String>,
+        result: &Result<T, ExecutionError>,
+    ) {
+        let (Some(tracer), Some(arguments)) = (&self.execution_tracer, arguments) else {
+            return;
+        };
+        let Some(application) = self.call_stack.last() else {
+            return;
+        };
+        tracer.record(TraceEntry::HostCall {
+            depth: self.call_stack.len() - 1,
+            application_id: application.id,
+            function: function.to_owned(),
+            arguments,
+            result: format!("{result:?}"),
+        });
+    }
+
     /// Returns the [`ApplicationStatus`] of the current application.
     ///
     /// The current application is the last to be pushed to the `call_stack`.
@@ -299,6 +377,9 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
     ///
     /// Ensures the application's ID is also tracked in the `active_applications` set.
     fn push_application(&mut self, status: ApplicationStatus) {
+        if let Some(profiler) = &self.fuel_profiler {
+            profiler.push(status.id);
+        }
         self.active_applications.insert(status.id);
         self.call_stack.push(status);
     }
@@ -316,6 +397,9 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
             .pop()
             .expect("Can't remove application from empty call stack");
         assert!(self.active_applications.remove(&status.id));
+        if let Some(profiler) = &self.fuel_profiler {
+            profiler.pop();
+        }
         status
     }
 
@@ -603,6 +687,22 @@ impl<UserInstance> SyncRuntime<UserInstance> {
             .try_lock()
             .expect("Synchronous runtimes run on a single execution thread")
     }
+
+    /// Executes a host `call` to the `function` of the runtime, recording it in the execution
+    /// trace with its `arguments` formatted by [`SyncRuntimeInternal::trace_arguments`].
+    ///
+    /// The runtime must not be locked while executing the `call`, since it may call other
+    /// applications.
+    fn traced<T: fmt::Debug>(
+        &mut self,
+        function: &str,
+        arguments: Option<String>,
+        call: impl FnOnce(&mut Self) -> Result<T, ExecutionError>,
+    ) -> Result<T, ExecutionError> {
+        let result = call(self);
+        self.inner().record_host_call(function, arguments, &result);
+        result
+    }
 }
 
 impl<UserInstance> BaseRuntime for SyncRuntime<UserInstance> {
@@ -616,99 +716,158 @@ impl<UserInstance> BaseRuntime for SyncRuntime<UserInstance> {
         <SyncRuntimeInternal<UserInstance> as BaseRuntime>::FindKeyValuesByPrefix;
 
     fn chain_id(&mut self) -> Result<ChainId, ExecutionError> {
-        self.inner().chain_id()
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("chain_id", arguments, |runtime| runtime.inner().chain_id())
     }
 
     fn application_id(&mut self) -> Result<UserApplicationId, ExecutionError> {
-        self.inner().application_id()
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("application_id", arguments, |runtime| {
+            runtime.inner().application_id()
+        })
     }
 
     fn application_parameters(&mut self) -> Result<Vec<u8>, ExecutionError> {
-        self.inner().application_parameters()
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("application_parameters", arguments, |runtime| {
+            runtime.inner().application_parameters()
+        })
     }
 
     fn read_chain_balance(&mut self) -> Result<Amount, ExecutionError> {
-        self.inner().read_chain_balance()
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("read_chain_balance", arguments, |runtime| {
+            runtime.inner().read_chain_balance()
+        })
     }
 
     fn read_owner_balance(&mut self, owner: Owner) -> Result<Amount, ExecutionError> {
-        self.inner().read_owner_balance(owner)
+        let arguments = self.inner().trace_arguments(&[&owner]);
+        self.traced("read_owner_balance", arguments, |runtime| {
+            runtime.inner().read_owner_balance(owner)
+        })
     }
 
     fn read_system_timestamp(&mut self) -> Result<Timestamp, ExecutionError> {
-        self.inner().read_system_timestamp()
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("read_system_timestamp", arguments, |runtime| {
+            runtime.inner().read_system_timestamp()
+        })
     }
 
     fn chain_ownership(&mut self) -> Result<ChainOwnership, ExecutionError> {
-        self.inner().chain_ownership()
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("chain_ownership", arguments, |runtime| {
+            runtime.inner().chain_ownership()
+        })
+    }
+
+    fn consume_host_call_fuel(
+        &mut self,
+        fuel: impl FnOnce(&HostCallFuelCosts) -> u64,
+    ) -> Result<(), ExecutionError> {
+        self.inner().consume_host_call_fuel(fuel)
     }
 
     fn write_batch(&mut self, batch: Batch) -> Result<(), ExecutionError> {
-        self.inner().write_batch(batch)
+        let arguments = self.inner().trace_arguments(&[&batch]);
+        self.traced("write_batch", arguments, |runtime| {
+            runtime.inner().write_batch(batch)
+        })
     }
 
     fn contains_key_new(&mut self, key: Vec<u8>) -> Result<Self::ContainsKey, ExecutionError> {
-        self.inner().contains_key_new(key)
+        let arguments = self.inner().trace_arguments(&[&key]);
+        self.traced("contains_key_new", arguments, |runtime| {
+            runtime.inner().contains_key_new(key)
+        })
     }
 
     fn contains_key_wait(&mut self, promise: &Self::ContainsKey) -> Result<bool, ExecutionError> {
-        self.inner().contains_key_wait(promise)
+        let arguments = self.inner().trace_arguments(&[promise]);
+        self.traced("contains_key_wait", arguments, |runtime| {
+            runtime.inner().contains_key_wait(promise)
+        })
     }
 
     fn read_multi_values_bytes_new(
         &mut self,
         keys: Vec<Vec<u8>>,
     ) -> Result<Self::ReadMultiValuesBytes, ExecutionError> {
-        self.inner().read_multi_values_bytes_new(keys)
+        let arguments = self.inner().trace_arguments(&[&keys]);
+        self.traced("read_multi_values_bytes_new", arguments, |runtime| {
+            runtime.inner().read_multi_values_bytes_new(keys)
+        })
     }
 
     fn read_multi_values_bytes_wait(
         &mut self,
         promise: &Self::ReadMultiValuesBytes,
     ) -> Result<Vec<Option<Vec<u8>>>, ExecutionError> {
-        self.inner().read_multi_values_bytes_wait(promise)
+        let arguments = self.inner().trace_arguments(&[promise]);
+        self.traced("read_multi_values_bytes_wait", arguments, |runtime| {
+            runtime.inner().read_multi_values_bytes_wait(promise)
+        })
     }
 
     fn read_value_bytes_new(
         &mut self,
         key: Vec<u8>,
     ) -> Result<Self::ReadValueBytes, ExecutionError> {
-        self.inner().read_value_bytes_new(key)
+        let arguments = self.inner().trace_arguments(&[&key]);
+        self.traced("read_value_bytes_new", arguments, |runtime| {
+            runtime.inner().read_value_bytes_new(key)
+        })
     }
 
     fn read_value_bytes_wait(
         &mut self,
         promise: &Self::ReadValueBytes,
     ) -> Result<Option<Vec<u8>>, ExecutionError> {
-        self.inner().read_value_bytes_wait(promise)
+        let arguments = self.inner().trace_arguments(&[promise]);
+        self.traced("read_value_bytes_wait", arguments, |runtime| {
+            runtime.inner().read_value_bytes_wait(promise)
+        })
     }
 
     fn find_keys_by_prefix_new(
         &mut self,
         key_prefix: Vec<u8>,
     ) -> Result<Self::FindKeysByPrefix, ExecutionError> {
-        self.inner().find_keys_by_prefix_new(key_prefix)
+        let arguments = self.inner().trace_arguments(&[&key_prefix]);
+        self.traced("find_keys_by_prefix_new", arguments, |runtime| {
+            runtime.inner().find_keys_by_prefix_new(key_prefix)
+        })
     }
 
     fn find_keys_by_prefix_wait(
         &mut self,
         promise: &Self::FindKeysByPrefix,
     ) -> Result<Vec<Vec<u8>>, ExecutionError> {
-        self.inner().find_keys_by_prefix_wait(promise)
+        let arguments = self.inner().trace_arguments(&[promise]);
+        self.traced("find_keys_by_prefix_wait", arguments, |runtime| {
+            runtime.inner().find_keys_by_prefix_wait(promise)
+        })
     }
 
     fn find_key_values_by_prefix_new(
         &mut self,
         key_prefix: Vec<u8>,
     ) -> Result<Self::FindKeyValuesByPrefix, ExecutionError> {
-        self.inner().find_key_values_by_prefix_new(key_prefix)
+        let arguments = self.inner().trace_arguments(&[&key_prefix]);
+        self.traced("find_key_values_by_prefix_new", arguments, |runtime| {
+            runtime.inner().find_key_values_by_prefix_new(key_prefix)
+        })
     }
 
     fn find_key_values_by_prefix_wait(
         &mut self,
         promise: &Self::FindKeyValuesByPrefix,
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ExecutionError> {
-        self.inner().find_key_values_by_prefix_wait(promise)
+        let arguments = self.inner().trace_arguments(&[promise]);
+        self.traced("find_key_values_by_prefix_wait", arguments, |runtime| {
+            runtime.inner().find_key_values_by_prefix_wait(promise)
+        })
     }
 }
 
@@ -756,6 +915,17 @@ impl<UserInstance> BaseRuntime for SyncRuntimeInternal<UserInstance> {
             .recv_response()
     }
 
+    fn consume_host_call_fuel(
+        &mut self,
+        fuel: impl FnOnce(&HostCallFuelCosts) -> u64,
+    ) -> Result<(), ExecutionError> {
+        let fuel = fuel(&self.resource_controller.policy.host_call_fuel);
+        if let Some(profiler) = &self.fuel_profiler {
+            profiler.charge(fuel);
+        }
+        self.resource_controller.track_fuel(fuel)
+    }
+
     fn write_batch(&mut self, batch: Batch) -> Result<(), ExecutionError> {
         let id = self.application_id()?;
         let state = self.view_user_states.entry(id).or_default();
@@ -768,13 +938,42 @@ impl<UserInstance> BaseRuntime for SyncRuntimeInternal<UserInstance> {
         )?;
         self.resource_controller
             .track_bytes_written(batch.size() as u64)?;
-        self.execution_state_sender
+        let stored_bytes_delta = self
+            .execution_state_sender
             .send_request(|callback| Request::WriteBatch {
                 id,
                 batch,
                 callback,
             })?
             .recv_response()?;
+        self.resource_controller
+            .track_stored_bytes(stored_bytes_delta)?;
+        let amount = self
+            .resource_controller
+            .policy
+            .bytes_stored_price(stored_bytes_delta.unsigned_abs())?;
+        if amount > Amount::ZERO {
+            let signer = self.current_application().signer;
+            if stored_bytes_delta > 0 {
+                self.execution_state_sender
+                    .send_request(|callback| Request::TakeStorageDeposit {
+                        id,
+                        signer,
+                        amount,
+                        callback,
+                    })?
+                    .recv_response()?;
+            } else {
+                self.execution_state_sender
+                    .send_request(|callback| Request::RefundStorageDeposit {
+                        id,
+                        signer,
+                        amount,
+                        callback,
+                    })?
+                    .recv_response()?;
+            }
+        }
         Ok(())
     }
 
@@ -926,14 +1125,29 @@ impl<UserInstance> Clone for SyncRuntime<UserInstance> {
 
 impl ContractSyncRuntime {
     /// Main entry point to start executing a user action.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn run_action(
         execution_state_sender: ExecutionStateSender,
         application_id: UserApplicationId,
         chain_id: ChainId,
         refund_grant_to: Option<Account>,
         resource_controller: ResourceController,
+        execution_tracer: Option<ExecutionTracer>,
+        fuel_profiler: Option<FuelProfiler>,
+        application_logger: Option<ApplicationLogger>,
         action: UserAction,
     ) -> Result<(Vec<ExecutionOutcome>, ResourceController), ExecutionError> {
+        if let Some(tracer) = &execution_tracer {
+            tracer.record(TraceEntry::ActionStarted {
+                application_id,
+                action: action.name().to_owned(),
+                argument: format!("{:?}", action.argument()),
+            });
+        }
+        if let Some(profiler) = &fuel_profiler {
+            profiler.clear_stack();
+        }
+        let initial_fuel = resource_controller.tracker.fuel;
         let mut runtime = SyncRuntimeInternal::new(
             chain_id,
             action.height(),
@@ -942,7 +1156,16 @@ impl ContractSyncRuntime {
             execution_state_sender,
             refund_grant_to,
             resource_controller,
+            execution_tracer.clone(),
+            fuel_profiler.clone(),
+            application_logger,
         );
+        if let UserAction::Message(context, _) = &action {
+            // Responses and bounced messages are not requests.
+            if context.response_to.is_none() {
+                runtime.request_to_answer = Some((application_id, context.message_id));
+            }
+        }
         let (code, description) = runtime.load_contract(application_id)?;
         let signer = action.signer();
         runtime.push_application(ApplicationStatus {
@@ -955,6 +1178,7 @@ impl ContractSyncRuntime {
             let mut code = code.instantiate(runtime.clone())?;
             match action {
                 UserAction::Initialize(context, argument) => code.initialize(context, argument),
+                UserAction::Migrate(context, argument) => code.migrate(context, argument),
                 UserAction::Operation(context, operation) => {
                     code.execute_operation(context, operation)
                 }
@@ -967,6 +1191,20 @@ impl ContractSyncRuntime {
         let mut runtime = runtime
             .into_inner()
             .expect("Runtime clones should have been freed by now");
+        if let Some(profiler) = &fuel_profiler {
+            profiler.clear_stack();
+        }
+        if let Some(tracer) = &execution_tracer {
+            tracer.record(TraceEntry::ActionFinished {
+                application_id,
+                fuel: runtime
+                    .resource_controller
+                    .tracker
+                    .fuel
+                    .saturating_sub(initial_fuel),
+                result: format!("{execution_result:?}"),
+            });
+        }
         let execution_outcome = execution_result?;
         assert_eq!(runtime.call_stack.len(), 1);
         assert_eq!(runtime.call_stack[0].id, application_id);
@@ -994,12 +1232,22 @@ impl ContractSyncRuntime {
 
 impl ContractRuntime for ContractSyncRuntime {
     fn remaining_fuel(&mut self) -> Result<u64, ExecutionError> {
-        Ok(self.inner().resource_controller.remaining_fuel())
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("remaining_fuel", arguments, |runtime| {
+            Ok(runtime.inner().resource_controller.remaining_fuel())
+        })
     }
 
     fn consume_fuel(&mut self, fuel: u64) -> Result<(), ExecutionError> {
-        let mut this = self.inner();
-        this.resource_controller.track_fuel(fuel)
+        let arguments = self.inner().trace_arguments(&[&fuel]);
+        self.traced("consume_fuel", arguments, |runtime| {
+            let mut this = runtime.inner();
+            this.resource_controller.track_fuel(fuel)
+        })
+    }
+
+    fn fuel_profiler(&mut self) -> Result<Option<FuelProfiler>, ExecutionError> {
+        Ok(self.inner().fuel_profiler.clone())
     }
 
     fn transfer(
@@ -1008,22 +1256,28 @@ impl ContractRuntime for ContractSyncRuntime {
         destination: Account,
         amount: Amount,
     ) -> Result<(), ExecutionError> {
-        let signer = self.inner().current_application().signer;
-        let execution_outcome = self
+        let arguments = self
             .inner()
-            .execution_state_sender
-            .send_request(|callback| Request::Transfer {
-                source,
-                destination,
-                amount,
-                signer,
-                callback,
-            })?
-            .recv_response()?;
-        self.inner()
-            .execution_outcomes
-            .push(ExecutionOutcome::System(execution_outcome));
-        Ok(())
+            .trace_arguments(&[&source, &destination, &amount]);
+        self.traced("transfer", arguments, |runtime| {
+            let signer = runtime.inner().current_application().signer;
+            let execution_outcome = runtime
+                .inner()
+                .execution_state_sender
+                .send_request(|callback| Request::Transfer {
+                    source,
+                    destination,
+                    amount,
+                    signer,
+                    callback,
+                })?
+                .recv_response()?;
+            runtime
+                .inner()
+                .execution_outcomes
+                .push(ExecutionOutcome::System(execution_outcome));
+            Ok(())
+        })
     }
 
     fn claim(
@@ -1032,25 +1286,142 @@ impl ContractRuntime for ContractSyncRuntime {
         destination: Account,
         amount: Amount,
     ) -> Result<(), ExecutionError> {
-        let signer = self.inner().current_application().signer;
-        let execution_outcome = self
+        let arguments = self
             .inner()
-            .execution_state_sender
-            .send_request(|callback| Request::Claim {
-                source,
-                destination,
-                amount,
-                signer,
-                callback,
-            })?
-            .recv_response()?
-            .with_authenticated_signer(signer);
-        self.inner()
-            .execution_outcomes
-            .push(ExecutionOutcome::System(execution_outcome));
+            .trace_arguments(&[&source, &destination, &amount]);
+        self.traced("claim", arguments, |runtime| {
+            let signer = runtime.inner().current_application().signer;
+            let execution_outcome = runtime
+                .inner()
+                .execution_state_sender
+                .send_request(|callback| Request::Claim {
+                    source,
+                    destination,
+                    amount,
+                    signer,
+                    callback,
+                })?
+                .recv_response()?
+                .with_authenticated_signer(signer);
+            runtime
+                .inner()
+                .execution_outcomes
+                .push(ExecutionOutcome::System(execution_outcome));
+            Ok(())
+        })
+    }
+
+    fn read_application_balance(&mut self) -> Result<Amount, ExecutionError> {
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("read_application_balance", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application_id = this.current_application().id;
+            this.execution_state_sender
+                .send_request(|callback| Request::ApplicationBalance {
+                    application_id,
+                    callback,
+                })?
+                .recv_response()
+        })
+    }
+
+    fn block_randomness(&mut self) -> Result<Option<CryptoHash>, ExecutionError> {
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("block_randomness", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application_id = this.current_application().id;
+            this.execution_state_sender
+                .send_request(|callback| Request::BlockRandomness {
+                    application_id,
+                    callback,
+                })?
+                .recv_response()
+        })
+    }
+
+    fn log(&mut self, level: LogLevel, message: &str) -> Result<(), ExecutionError> {
+        let this = self.inner();
+        let chain_id = this.chain_id;
+        let height = this.height;
+        let application_id = this.current_application().id;
+        match level {
+            LogLevel::Trace => tracing::trace!(%chain_id, %height, %application_id, "{message}"),
+            LogLevel::Debug => tracing::debug!(%chain_id, %height, %application_id, "{message}"),
+            LogLevel::Info => tracing::info!(%chain_id, %height, %application_id, "{message}"),
+            LogLevel::Warn => tracing::warn!(%chain_id, %height, %application_id, "{message}"),
+            LogLevel::Error => tracing::error!(%chain_id, %height, %application_id, "{message}"),
+        }
+        if let Some(logger) = &this.application_logger {
+            logger.record(application_id, level, message.to_owned());
+        }
         Ok(())
     }
 
+    fn fund_application(
+        &mut self,
+        source: Option<Owner>,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let arguments = self.inner().trace_arguments(&[&source, &amount]);
+        self.traced("fund_application", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application = this.current_application();
+            let application_id = application.id;
+            let signer = application.signer;
+            this.execution_state_sender
+                .send_request(|callback| Request::FundApplication {
+                    source,
+                    application_id,
+                    amount,
+                    signer,
+                    callback,
+                })?
+                .recv_response()
+        })
+    }
+
+    fn withdraw_from_application(
+        &mut self,
+        destination: Account,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let arguments = self.inner().trace_arguments(&[&destination, &amount]);
+        self.traced("withdraw_from_application", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application_id = this.current_application().id;
+            let execution_outcome = this
+                .execution_state_sender
+                .send_request(|callback| Request::WithdrawFromApplication {
+                    application_id,
+                    destination,
+                    amount,
+                    callback,
+                })?
+                .recv_response()?;
+            this.execution_outcomes
+                .push(ExecutionOutcome::System(execution_outcome));
+            Ok(())
+        })
+    }
+
+    fn set_fee_sponsorship(
+        &mut self,
+        sponsorship: Option<FeeSponsorship>,
+    ) -> Result<(), ExecutionError> {
+        let arguments = self.inner().trace_arguments(&[&sponsorship]);
+        self.traced("set_fee_sponsorship", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application_id = this.current_application().id;
+            this.execution_state_sender
+                .send_request(|callback| Request::SetFeeSponsorship {
+                    application_id,
+                    sponsorship,
+                    callback,
+                })?
+                .recv_response()
+        })
+    }
+
     fn try_call_application(
         &mut self,
         authenticated: bool,
@@ -1058,20 +1429,28 @@ impl ContractRuntime for ContractSyncRuntime {
         argument: Vec<u8>,
         forwarded_sessions: Vec<SessionId>,
     ) -> Result<CallOutcome, ExecutionError> {
-        let cloned_self = self.clone().0;
-        let (contract, callee_context) = self.inner().prepare_for_call(
-            cloned_self,
-            authenticated,
-            callee_id,
+        let arguments = self.inner().trace_arguments(&[
+            &authenticated,
+            &callee_id,
+            &argument,
             &forwarded_sessions,
-        )?;
+        ]);
+        self.traced("try_call_application", arguments, |runtime| {
+            let cloned_self = runtime.clone().0;
+            let (contract, callee_context) = runtime.inner().prepare_for_call(
+                cloned_self,
+                authenticated,
+                callee_id,
+                &forwarded_sessions,
+            )?;
 
-        let raw_outcome = contract
-            .try_lock()
-            .expect("Applications should not have reentrant calls")
-            .handle_application_call(callee_context, argument, forwarded_sessions)?;
+            let raw_outcome = contract
+                .try_lock()
+                .expect("Applications should not have reentrant calls")
+                .handle_application_call(callee_context, argument, forwarded_sessions)?;
 
-        self.inner().finish_call(raw_outcome)
+            runtime.inner().finish_call(raw_outcome)
+        })
     }
 
     fn try_call_session(
@@ -1081,44 +1460,56 @@ impl ContractRuntime for ContractSyncRuntime {
         argument: Vec<u8>,
         forwarded_sessions: Vec<SessionId>,
     ) -> Result<CallOutcome, ExecutionError> {
-        let callee_id = session_id.application_id;
-
-        let (contract, callee_context, session_state) = {
-            let cloned_self = self.clone().0;
-            let mut this = self.inner();
-
-            // Load the session.
-            let caller_id = this.application_id()?;
-            let session_state = this.try_load_session(session_id, caller_id)?;
-
-            let (contract, callee_context) =
-                this.prepare_for_call(cloned_self, authenticated, callee_id, &forwarded_sessions)?;
-
-            Ok::<_, ExecutionError>((contract, callee_context, session_state))
-        }?;
-
-        let (raw_outcome, session_state) = contract
-            .try_lock()
-            .expect("Applications should not have reentrant calls")
-            .handle_session_call(callee_context, session_state, argument, forwarded_sessions)?;
-
-        {
-            let mut this = self.inner();
-
-            let outcome = this.finish_call(raw_outcome.inner)?;
+        let arguments = self.inner().trace_arguments(&[
+            &authenticated,
+            &session_id,
+            &argument,
+            &forwarded_sessions,
+        ]);
+        self.traced("try_call_session", arguments, |runtime| {
+            let callee_id = session_id.application_id;
+
+            let (contract, callee_context, session_state) = {
+                let cloned_self = runtime.clone().0;
+                let mut this = runtime.inner();
+
+                // Load the session.
+                let caller_id = this.application_id()?;
+                let session_state = this.try_load_session(session_id, caller_id)?;
+
+                let (contract, callee_context) = this.prepare_for_call(
+                    cloned_self,
+                    authenticated,
+                    callee_id,
+                    &forwarded_sessions,
+                )?;
+
+                Ok::<_, ExecutionError>((contract, callee_context, session_state))
+            }?;
+
+            let (raw_outcome, session_state) = contract
+                .try_lock()
+                .expect("Applications should not have reentrant calls")
+                .handle_session_call(callee_context, session_state, argument, forwarded_sessions)?;
+
+            {
+                let mut this = runtime.inner();
+
+                let outcome = this.finish_call(raw_outcome.inner)?;
+
+                // Update the session.
+                let caller_id = this.application_id()?;
+                if raw_outcome.close_session {
+                    // Terminate the session.
+                    this.try_close_session(session_id, caller_id)?;
+                } else {
+                    // Save the session.
+                    this.try_save_session(session_id, caller_id, session_state)?;
+                }
 
-            // Update the session.
-            let caller_id = this.application_id()?;
-            if raw_outcome.close_session {
-                // Terminate the session.
-                this.try_close_session(session_id, caller_id)?;
-            } else {
-                // Save the session.
-                this.try_save_session(session_id, caller_id, session_state)?;
+                Ok(outcome)
             }
-
-            Ok(outcome)
-        }
+        })
     }
 
     fn open_chain(
@@ -1126,42 +1517,126 @@ impl ContractRuntime for ContractSyncRuntime {
         ownership: ChainOwnership,
         balance: Amount,
     ) -> Result<ChainId, ExecutionError> {
-        let mut this = self.inner();
-        let id = this.current_application().id;
-        let next_message_id = MessageId {
-            chain_id: this.chain_id,
-            height: this.height,
-            index: this.next_message_index()?,
-        };
-        let chain_id = ChainId::child(next_message_id);
-        let application_permissions = ApplicationPermissions::new_single(id);
-        let [open_chain_message, subscribe_message] = this
-            .execution_state_sender
-            .send_request(|callback| Request::OpenChain {
-                ownership,
-                balance,
-                next_message_id,
-                application_permissions,
-                callback,
-            })?
-            .recv_response()?;
-        let outcome = RawExecutionOutcome::default()
-            .with_message(open_chain_message)
-            .with_message(subscribe_message);
-        this.execution_outcomes
-            .push(ExecutionOutcome::System(outcome));
-        Ok(chain_id)
+        let arguments = self.inner().trace_arguments(&[&ownership, &balance]);
+        self.traced("open_chain", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application = this.current_application();
+            let (id, signer) = (application.id, application.signer);
+            let next_message_id = MessageId {
+                chain_id: this.chain_id,
+                height: this.height,
+                index: this.next_message_index()?,
+            };
+            let chain_id = ChainId::child(next_message_id);
+            let application_permissions = ApplicationPermissions::new_single(id);
+            let [open_chain_message, subscribe_message] = this
+                .execution_state_sender
+                .send_request(|callback| Request::OpenChain {
+                    ownership,
+                    balance,
+                    next_message_id,
+                    application_permissions,
+                    signer,
+                    callback,
+                })?
+                .recv_response()?;
+            let outcome = RawExecutionOutcome::default()
+                .with_message(open_chain_message)
+                .with_message(subscribe_message);
+            this.execution_outcomes
+                .push(ExecutionOutcome::System(outcome));
+            Ok(chain_id)
+        })
     }
 
     fn close_chain(&mut self) -> Result<(), ExecutionError> {
-        let mut this = self.inner();
-        let application_id = this.current_application().id;
-        this.execution_state_sender
-            .send_request(|callback| Request::CloseChain {
-                application_id,
-                callback,
-            })?
-            .recv_response()?
+        let arguments = self.inner().trace_arguments(&[]);
+        self.traced("close_chain", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application = this.current_application();
+            let (application_id, signer) = (application.id, application.signer);
+            this.execution_state_sender
+                .send_request(|callback| Request::CloseChain {
+                    application_id,
+                    signer,
+                    callback,
+                })?
+                .recv_response()?
+        })
+    }
+
+    fn send_request(
+        &mut self,
+        destination: ChainId,
+        authenticated: bool,
+        message: Vec<u8>,
+    ) -> Result<MessageId, ExecutionError> {
+        let arguments = self
+            .inner()
+            .trace_arguments(&[&destination, &authenticated, &message]);
+        self.traced("send_request", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application = this.current_application();
+            let (application_id, signer) = (application.id, application.signer);
+            let destination = Destination::Recipient(destination);
+            let mut index = this.next_message_index()?;
+            if !this.has_user_messages_to(&destination) {
+                // The request will be preceded by a message registering the application.
+                index = index.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+            }
+            let request_id = MessageId {
+                chain_id: this.chain_id,
+                height: this.height,
+                index,
+            };
+            // Requests are tracked, so that the sender is notified if they are rejected.
+            let outcome = RawExecutionOutcome::default()
+                .with_authenticated_signer(signer)
+                .with_message(RawOutgoingMessage {
+                    destination,
+                    authenticated,
+                    grant: Amount::ZERO,
+                    kind: MessageKind::Tracked,
+                    deadline: None,
+                    response_to: None,
+                    message,
+                });
+            this.execution_outcomes
+                .push(ExecutionOutcome::User(application_id, outcome));
+            this.execution_state_sender
+                .send_request(|callback| Request::RecordRequest {
+                    request_id,
+                    application_id,
+                    callback,
+                })?
+                .recv_response()?;
+            Ok(request_id)
+        })
+    }
+
+    fn send_response(&mut self, message: Vec<u8>) -> Result<(), ExecutionError> {
+        let arguments = self.inner().trace_arguments(&[&message]);
+        self.traced("send_response", arguments, |runtime| {
+            let mut this = runtime.inner();
+            let application_id = this.current_application().id;
+            let request_id = match this.request_to_answer {
+                Some((requested_id, request_id)) if requested_id == application_id => request_id,
+                _ => return Err(ExecutionError::NoRequestToAnswer),
+            };
+            this.request_to_answer = None;
+            let outcome = RawExecutionOutcome::default().with_message(RawOutgoingMessage {
+                destination: Destination::Recipient(request_id.chain_id),
+                authenticated: false,
+                grant: Amount::ZERO,
+                kind: MessageKind::Simple,
+                deadline: None,
+                response_to: Some(request_id),
+                message,
+            });
+            this.execution_outcomes
+                .push(ExecutionOutcome::User(application_id, outcome));
+            Ok(())
+        })
     }
 }
 
@@ -1171,8 +1646,9 @@ impl ServiceSyncRuntime {
         application_id: UserApplicationId,
         context: crate::QueryContext,
         query: Vec<u8>,
+        http_oracle: Option<HttpOracle>,
     ) -> Result<Vec<u8>, ExecutionError> {
-        let runtime_internal = SyncRuntimeInternal::new(
+        let mut runtime_internal = SyncRuntimeInternal::new(
             context.chain_id,
             context.next_block_height,
             None,
@@ -1180,7 +1656,11 @@ impl ServiceSyncRuntime {
             execution_state_sender,
             None,
             ResourceController::default(),
+            None,
+            None,
+            None,
         );
+        runtime_internal.http_oracle = http_oracle.as_ref().map(HttpOracle::start_query);
         let mut runtime = ServiceSyncRuntime::new(runtime_internal);
 
         let result = runtime.try_query_application(application_id, query);
@@ -1224,4 +1704,12 @@ impl ServiceRuntime for ServiceSyncRuntime {
         self.inner().pop_application();
         Ok(response)
     }
+
+    fn http_request(&mut self, request: HttpRequest) -> Result<HttpResponse, ExecutionError> {
+        let mut this = self.inner();
+        let http_oracle = this.http_oracle.as_mut().ok_or(HttpError::Disabled)?;
+        // Unlike the other system APIs, this doesn't need the execution state of the chain.
+        let response = tokio::runtime::Handle::current().block_on(http_oracle.perform(request))?;
+        Ok(response)
+    }
 }
@@ -4,15 +4,16 @@
 
 use crate::{
     committee::{Committee, Epoch},
-    ApplicationRegistryView, Bytecode, BytecodeLocation, ChannelName, ChannelSubscription,
-    Destination, GenericApplicationId, MessageContext, MessageKind, OperationContext, QueryContext,
-    RawExecutionOutcome, RawOutgoingMessage, UserApplicationDescription, UserApplicationId,
+    ApplicationRegistryView, BlockRandomness, Bytecode, BytecodeLocation, ChannelName,
+    ChannelSubscription, CompressedBytecode, Destination, GenericApplicationId, MessageContext,
+    MessageKind, OperationContext, QueryContext, RawExecutionOutcome, RawOutgoingMessage,
+    UserApplicationDescription, UserApplicationId,
 };
 use async_graphql::Enum;
 use custom_debug_derive::Debug;
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
-    data_types::{Amount, ArithmeticError, Timestamp},
+    data_types::{Amount, ArithmeticError, FeeSponsorship, Timestamp},
     ensure, hex_debug,
     identifiers::{
         Account, ApplicationId, BytecodeId, ChainDescription, ChainId, MessageId, Owner,
@@ -23,6 +24,7 @@ use linera_base::{
 #[cfg(test)]
 use crate::test_utils::SystemExecutionState;
 use linera_views::{
+    collection_view::CollectionView,
     common::Context,
     map_view::MapView,
     register_view::RegisterView,
@@ -47,8 +49,8 @@ pub static OPEN_CHAIN_MESSAGE_INDEX: u32 = 0;
 /// The relative index of the `ApplicationCreated` message created by the `CreateApplication`
 /// operation.
 pub static CREATE_APPLICATION_MESSAGE_INDEX: u32 = 0;
-/// The relative index of the `BytecodePublished` message created by the `PublishBytecode`
-/// operation.
+/// The relative index of the `BytecodePublished` message created by the `PublishBytecode` and
+/// `PublishCompressedBytecode` operations.
 pub static PUBLISH_BYTECODE_MESSAGE_INDEX: u32 = 0;
 
 /// The number of times the [`SystemOperation::OpenChain`] was executed.
@@ -92,6 +94,20 @@ pub struct SystemExecutionStateView<C> {
     pub closed: RegisterView<C, bool>,
     /// Permissions for applications on this chain.
     pub application_permissions: RegisterView<C, ApplicationPermissions>,
+    /// How applications pay the fees of the operations that target them.
+    pub fee_sponsorships: MapView<C, UserApplicationId, FeeSponsorship>,
+    /// Balances attributed to the applications of this chain.
+    pub application_balances: MapView<C, UserApplicationId, Amount>,
+    /// The deposits paid for the storage of each application, by paying account (`None` for
+    /// the chain balance). Freed storage is refunded from these deposits only.
+    pub storage_deposits: CollectionView<C, UserApplicationId, MapView<C, Option<Owner>, Amount>>,
+    /// The validators' share of the fees paid on this chain during the current epoch.
+    pub collected_fees: RegisterView<C, Amount>,
+    /// The requests sent by applications on this chain that were not answered yet, with the
+    /// application that sent each of them.
+    pub pending_requests: MapView<C, MessageId, UserApplicationId>,
+    /// The source of randomness of the current block, if any.
+    pub randomness: RegisterView<C, Option<BlockRandomness>>,
 }
 
 /// The configuration for a new chain.
@@ -177,6 +193,10 @@ pub enum SystemOperation {
         multi_leader_rounds: u32,
         /// The timeout configuration: how long fast, multi-leader and single-leader rounds last.
         timeout_config: TimeoutConfig,
+        /// If set, the total weight of the regular owners that must approve each block.
+        approval_threshold: Option<u64>,
+        /// Whether anyone can propose blocks in the multi-leader rounds, paying their own fees.
+        open_multi_leader_rounds: bool,
     },
     /// Changes the application permissions configuration on this chain.
     ChangeApplicationPermissions(ApplicationPermissions),
@@ -213,6 +233,22 @@ pub enum SystemOperation {
     },
     /// Operations that are only allowed on the admin chain.
     Admin(AdminOperation),
+    /// Replaces the bytecode of an application created by this chain, then runs its `migrate`
+    /// entrypoint. Must be signed by the owner who created the application. The other chains
+    /// that have a state for the application run `migrate` with the same argument when they
+    /// receive the new description.
+    UpgradeApplication {
+        application_id: UserApplicationId,
+        bytecode_id: BytecodeId,
+        #[serde(with = "serde_bytes")]
+        #[debug(with = "hex_debug")]
+        migration_argument: Vec<u8>,
+    },
+    /// Publishes a new application bytecode, compressed to reduce the size of the block.
+    PublishCompressedBytecode {
+        contract: CompressedBytecode,
+        service: CompressedBytecode,
+    },
 }
 
 /// Operations that are only allowed on the admin chain.
@@ -228,6 +264,31 @@ pub enum AdminOperation {
     RemoveCommittee { epoch: Epoch },
 }
 
+impl SystemOperation {
+    /// Returns the number of bytes of bytecode published by this operation, as stored in the
+    /// block, i.e. after compression if the bytecode is compressed.
+    pub fn published_bytecode_size(&self) -> usize {
+        match self {
+            SystemOperation::PublishBytecode { contract, service } => {
+                contract.as_ref().len() + service.as_ref().len()
+            }
+            SystemOperation::PublishCompressedBytecode { contract, service } => {
+                contract.as_ref().len() + service.as_ref().len()
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// An entrypoint of a user application to run after a system operation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UserEntrypoint {
+    /// Initializes a newly created application with the given argument.
+    Initialize(UserApplicationId, Vec<u8>),
+    /// Migrates the state of an upgraded application with the given argument.
+    Migrate(UserApplicationId, Vec<u8>),
+}
+
 /// A system message meant to be executed on a remote chain.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum SystemMessage {
@@ -282,6 +343,20 @@ pub enum SystemMessage {
     /// Requests a `RegisterApplication` message from the target chain to register the specified
     /// application on the sender chain.
     RequestApplication(UserApplicationId),
+    /// Credits `amount` units of value, withdrawn from the account of the application
+    /// `source`, to the account `target` -- unless the message is bouncing, in which case
+    /// the application is credited instead.
+    ApplicationCredit {
+        target: Option<Owner>,
+        amount: Amount,
+        source: UserApplicationId,
+    },
+    /// Sent by a chain to itself to continue sending the description of an upgraded
+    /// application to the destinations it was registered at, from the `start`-th one.
+    PropagateUpgrade {
+        application_id: UserApplicationId,
+        start: u32,
+    },
 }
 
 impl SystemMessage {
@@ -302,9 +377,10 @@ impl SystemMessage {
                 locations: new_locations,
             } => Box::new(new_locations.iter().map(|(_id, location)| *location)),
             SystemMessage::RegisterApplications { applications } => {
-                Box::new(applications.iter().map(|app| app.bytecode_location))
+                Box::new(applications.iter().flat_map(|app| app.bytecode_locations()))
             }
             SystemMessage::Credit { .. }
+            | SystemMessage::ApplicationCredit { .. }
             | SystemMessage::Withdraw { .. }
             | SystemMessage::OpenChain(_)
             | SystemMessage::SetCommittees { .. }
@@ -312,7 +388,8 @@ impl SystemMessage {
             | SystemMessage::Unsubscribe { .. }
             | SystemMessage::ApplicationCreated
             | SystemMessage::Notify { .. }
-            | SystemMessage::RequestApplication(_) => Box::new(iter::empty()),
+            | SystemMessage::RequestApplication(_)
+            | SystemMessage::PropagateUpgrade { .. } => Box::new(iter::empty()),
         }
     }
 }
@@ -444,8 +521,18 @@ pub enum SystemExecutionError {
     UnknownBytecodeId(BytecodeId),
     #[error("Application {0:?} is not registered by the chain")]
     UnknownApplicationId(Box<UserApplicationId>),
+    #[error("Application {0:?} can only be upgraded on the chain that created it")]
+    UpgradeOutsideCreatorChain(Box<UserApplicationId>),
+    #[error("Upgrading application {0:?} must be authenticated by its governance owner")]
+    UnauthorizedApplicationUpgrade(Box<UserApplicationId>),
     #[error("Chain is not active yet.")]
     InactiveChain,
+    #[error("The approval threshold exceeds the total weight of the chain owners")]
+    UnreachableApprovalThreshold,
+    #[error("Proposers who don't own the chain cannot spend its balance or close it")]
+    UnauthorizedOpenProposer,
+    #[error("The validator fee percentage {0} is above 100")]
+    InvalidValidatorFeePercent(u8),
 }
 
 impl<C> SystemExecutionStateView<C>
@@ -453,6 +540,17 @@ where
     C: Context + Clone + Send + Sync + 'static,
     ViewError: From<C::Error>,
 {
+    /// Returns an error if `signer` is only allowed to propose blocks because the chain is
+    /// public. Applications cannot spend the chain's balance or close the chain on behalf
+    /// of such signers.
+    pub fn check_chain_authority(&self, signer: Option<Owner>) -> Result<(), SystemExecutionError> {
+        ensure!(
+            !signer.is_some_and(|signer| self.ownership.get().is_open_proposer(&signer)),
+            SystemExecutionError::UnauthorizedOpenProposer
+        );
+        Ok(())
+    }
+
     /// Invariant for the states of active chains.
     pub fn is_active(&self) -> bool {
         self.description.get().is_some()
@@ -477,13 +575,13 @@ where
     ) -> Result<
         (
             RawExecutionOutcome<SystemMessage, Amount>,
-            Option<(UserApplicationId, Vec<u8>)>,
+            Option<UserEntrypoint>,
         ),
         SystemExecutionError,
     > {
         use SystemOperation::*;
         let mut outcome = RawExecutionOutcome::default();
-        let mut new_application = None;
+        let mut entrypoint = None;
         match operation {
             OpenChain(config) => {
                 let next_message_id = context.next_message_id();
@@ -497,8 +595,10 @@ where
                 owners,
                 multi_leader_rounds,
                 timeout_config,
+                approval_threshold,
+                open_multi_leader_rounds,
             } => {
-                self.ownership.set(ChainOwnership {
+                let ownership = ChainOwnership {
                     super_owners: super_owners
                         .into_iter()
                         .map(|public_key| (Owner::from(public_key), public_key))
@@ -509,7 +609,14 @@ where
                         .collect(),
                     multi_leader_rounds,
                     timeout_config,
-                });
+                    approval_threshold,
+                    open_multi_leader_rounds,
+                };
+                ensure!(
+                    ownership.is_approval_threshold_reachable(),
+                    SystemExecutionError::UnreachableApprovalThreshold
+                );
+                self.ownership.set(ownership);
             }
             ChangeApplicationPermissions(application_permissions) => {
                 self.application_permissions.set(application_permissions);
@@ -563,6 +670,13 @@ where
                             epoch == self.epoch.get().expect("chain is active").try_add_one()?,
                             SystemExecutionError::InvalidCommitteeCreation
                         );
+                        ensure!(
+                            committee.policy().validator_fee_percent <= 100,
+                            SystemExecutionError::InvalidValidatorFeePercent(
+                                committee.policy().validator_fee_percent
+                            )
+                        );
+                        outcome.messages.extend(self.distribute_collected_fees()?);
                         self.committees.get_mut().insert(epoch, committee);
                         self.epoch.set(Some(epoch));
                         let message = RawOutgoingMessage {
@@ -570,6 +684,8 @@ where
                             authenticated: false,
                             grant: Amount::ZERO,
                             kind: MessageKind::Protected,
+                            deadline: None,
+                            response_to: None,
                             message: SystemMessage::SetCommittees {
                                 epoch,
                                 committees: self.committees.get().clone(),
@@ -587,6 +703,8 @@ where
                             authenticated: false,
                             grant: Amount::ZERO,
                             kind: MessageKind::Protected,
+                            deadline: None,
+                            response_to: None,
                             message: SystemMessage::SetCommittees {
                                 epoch: self.epoch.get().expect("chain is active"),
                                 committees: self.committees.get().clone(),
@@ -621,6 +739,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::Subscribe {
                         id: context.chain_id,
                         subscription,
@@ -643,6 +763,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::Unsubscribe {
                         id: context.chain_id,
                         subscription,
@@ -650,7 +772,7 @@ where
                 };
                 outcome.messages.push(message);
             }
-            PublishBytecode { .. } => {
+            PublishBytecode { .. } | PublishCompressedBytecode { .. } => {
                 // Send a `BytecodePublished` message to ourself so that we can broadcast
                 // the bytecode-id next.
                 let message = RawOutgoingMessage {
@@ -658,6 +780,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::BytecodePublished {
                         operation_index: context.index,
                     },
@@ -679,6 +803,7 @@ where
                         id,
                         parameters.clone(),
                         required_application_ids.clone(),
+                        context.authenticated_signer,
                     )
                     .await?;
                 // Send a message to ourself to increment the message ID.
@@ -687,10 +812,15 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::ApplicationCreated,
                 };
                 outcome.messages.push(message);
-                new_application = Some((id, initialization_argument.clone()));
+                entrypoint = Some(UserEntrypoint::Initialize(
+                    id,
+                    initialization_argument.clone(),
+                ));
             }
             RequestApplication {
                 chain_id,
@@ -701,13 +831,86 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Simple,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::RequestApplication(application_id),
                 };
                 outcome.messages.push(message);
             }
+            UpgradeApplication {
+                application_id,
+                bytecode_id,
+                migration_argument,
+            } => {
+                ensure!(
+                    application_id.creation.chain_id == context.chain_id,
+                    SystemExecutionError::UpgradeOutsideCreatorChain(Box::new(application_id))
+                );
+                let description = self.registry.describe_application(application_id).await?;
+                ensure!(
+                    description.governance.is_some()
+                        && description.governance == context.authenticated_signer,
+                    SystemExecutionError::UnauthorizedApplicationUpgrade(Box::new(application_id))
+                );
+                self.registry
+                    .upgrade_application(application_id, bytecode_id, migration_argument.clone())
+                    .await?;
+                entrypoint = Some(UserEntrypoint::Migrate(application_id, migration_argument));
+            }
         }
 
-        Ok((outcome, new_application))
+        Ok((outcome, entrypoint))
+    }
+
+    /// Sends the new description of an upgraded application to at most `limit` of the
+    /// destinations that previously received its description from this chain, skipping the
+    /// first `start` ones. If more destinations remain, the chain also sends itself a
+    /// [`SystemMessage::PropagateUpgrade`] to continue in a later block.
+    ///
+    /// The descriptions are sent as tracked messages, so that they bounce back to this chain
+    /// if a receiver rejects them, e.g. because its migration failed.
+    pub async fn propagate_upgrade(
+        &self,
+        chain_id: ChainId,
+        application_id: UserApplicationId,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<RawOutgoingMessage<SystemMessage, Amount>>, SystemExecutionError> {
+        let description = self.registry.describe_application(application_id).await?;
+        let (destinations, has_more) = self
+            .registry
+            .registered_destinations(&application_id, start, limit)
+            .await?;
+        let sent = u32::try_from(destinations.len()).map_err(|_| ArithmeticError::Overflow)?;
+        let mut messages = destinations
+            .into_iter()
+            .map(|destination| RawOutgoingMessage {
+                destination,
+                authenticated: false,
+                grant: Amount::ZERO,
+                kind: MessageKind::Tracked,
+                deadline: None,
+                response_to: None,
+                message: SystemMessage::RegisterApplications {
+                    applications: vec![description.clone()],
+                },
+            })
+            .collect::<Vec<_>>();
+        if has_more {
+            messages.push(RawOutgoingMessage {
+                destination: Destination::Recipient(chain_id),
+                authenticated: false,
+                grant: Amount::ZERO,
+                kind: MessageKind::Simple,
+                deadline: None,
+                response_to: None,
+                message: SystemMessage::PropagateUpgrade {
+                    application_id,
+                    start: start.checked_add(sent).ok_or(ArithmeticError::Overflow)?,
+                },
+            });
+        }
+        Ok(messages)
     }
 
     pub async fn transfer(
@@ -722,13 +925,15 @@ where
                 authenticated_signer == owner,
                 SystemExecutionError::UnauthenticatedTransferOwner
             );
+        } else {
+            self.check_chain_authority(authenticated_signer)?;
         }
         ensure!(
             amount > Amount::ZERO,
             SystemExecutionError::IncorrectTransferAmount
         );
-        let balance = match &owner {
-            Some(owner) => self.balances.get_mut_or_default(owner).await?,
+        let balance = match owner {
+            Some(owner) => self.balances.get_mut_or_default(&owner).await?,
             None => self.balance.get_mut(),
         };
         balance
@@ -741,6 +946,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Tracked,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::Credit {
                         amount,
                         source: owner,
@@ -777,6 +984,8 @@ where
             authenticated: true,
             grant: Amount::ZERO,
             kind: MessageKind::Simple,
+            deadline: None,
+            response_to: None,
             message: SystemMessage::Withdraw {
                 amount,
                 owner,
@@ -786,6 +995,259 @@ where
         })
     }
 
+    /// Moves tokens from the chain balance, or from the balance of `source`, to the account
+    /// of an application.
+    pub async fn fund_application(
+        &mut self,
+        authenticated_signer: Option<Owner>,
+        source: Option<Owner>,
+        application_id: UserApplicationId,
+        amount: Amount,
+    ) -> Result<(), SystemExecutionError> {
+        if source.is_some() {
+            ensure!(
+                authenticated_signer == source,
+                SystemExecutionError::UnauthenticatedTransferOwner
+            );
+        } else {
+            self.check_chain_authority(authenticated_signer)?;
+        }
+        ensure!(
+            amount > Amount::ZERO,
+            SystemExecutionError::IncorrectTransferAmount
+        );
+        let balance = match source {
+            Some(owner) => self.balances.get_mut_or_default(&owner).await?,
+            None => self.balance.get_mut(),
+        };
+        balance
+            .try_sub_assign(amount)
+            .map_err(|_| SystemExecutionError::InsufficientFunding { balance: *balance })?;
+        let application_balance = self
+            .application_balances
+            .get_mut_or_default(&application_id)
+            .await?;
+        application_balance.try_add_assign(amount)?;
+        Ok(())
+    }
+
+    /// Sends tokens from the account of an application to the given account. If the
+    /// message bounces, the tokens are returned to the application.
+    pub async fn withdraw_from_application(
+        &mut self,
+        application_id: UserApplicationId,
+        destination: Account,
+        amount: Amount,
+    ) -> Result<RawOutgoingMessage<SystemMessage, Amount>, SystemExecutionError> {
+        ensure!(
+            amount > Amount::ZERO,
+            SystemExecutionError::IncorrectTransferAmount
+        );
+        let balance = self
+            .application_balances
+            .get_mut_or_default(&application_id)
+            .await?;
+        balance
+            .try_sub_assign(amount)
+            .map_err(|_| SystemExecutionError::InsufficientFunding { balance: *balance })?;
+        Ok(RawOutgoingMessage {
+            destination: Destination::Recipient(destination.chain_id),
+            authenticated: false,
+            grant: Amount::ZERO,
+            kind: MessageKind::Tracked,
+            deadline: None,
+            response_to: None,
+            message: SystemMessage::ApplicationCredit {
+                amount,
+                source: application_id,
+                target: destination.owner,
+            },
+        })
+    }
+
+    /// Sets or removes the fee sponsorship of an application.
+    pub fn set_fee_sponsorship(
+        &mut self,
+        application_id: UserApplicationId,
+        sponsorship: Option<FeeSponsorship>,
+    ) -> Result<(), SystemExecutionError> {
+        match sponsorship {
+            Some(sponsorship) => self.fee_sponsorships.insert(&application_id, sponsorship)?,
+            None => self.fee_sponsorships.remove(&application_id)?,
+        }
+        Ok(())
+    }
+
+    /// Takes the funds that an application makes available to pay the fees of one of its
+    /// operations, given the fees it already paid in the current block. Returns `None` if
+    /// the application does not sponsor fees.
+    pub async fn take_sponsored_fees(
+        &mut self,
+        application_id: UserApplicationId,
+        paid_in_block: Amount,
+    ) -> Result<Option<Amount>, SystemExecutionError> {
+        let Some(sponsorship) = self.fee_sponsorships.get(&application_id).await? else {
+            return Ok(None);
+        };
+        let Some(balance) = self.application_balances.get_mut(&application_id).await? else {
+            return Ok(None);
+        };
+        let amount = sponsorship
+            .max_fees_per_operation
+            .min(sponsorship.max_fees_per_block.saturating_sub(paid_in_block))
+            .min(*balance);
+        balance.try_sub_assign(amount)?;
+        Ok(Some(amount))
+    }
+
+    /// Returns the sponsored funds that were not spent to the account of the application.
+    pub async fn return_sponsored_fees(
+        &mut self,
+        application_id: UserApplicationId,
+        amount: Amount,
+    ) -> Result<(), SystemExecutionError> {
+        if amount > Amount::ZERO {
+            let balance = self
+                .application_balances
+                .get_mut_or_default(&application_id)
+                .await?;
+            balance.try_add_assign(amount)?;
+        }
+        Ok(())
+    }
+
+    /// Takes a deposit of `amount` for the storage of an application, from the chain
+    /// balance (unless `signer` is an open proposer) and then from the balance of `signer`.
+    pub async fn take_storage_deposit(
+        &mut self,
+        application_id: UserApplicationId,
+        signer: Option<Owner>,
+        amount: Amount,
+    ) -> Result<(), SystemExecutionError> {
+        let mut payers = Vec::new();
+        if !signer.is_some_and(|owner| self.ownership.get().is_open_proposer(&owner)) {
+            payers.push(None);
+        }
+        if signer.is_some() {
+            payers.push(signer);
+        }
+        let deposits = self
+            .storage_deposits
+            .load_entry_mut(&application_id)
+            .await?;
+        let mut remaining = amount;
+        for payer in payers {
+            let balance = match payer {
+                Some(owner) => {
+                    let Some(balance) = self.balances.get_mut(&owner).await? else {
+                        continue;
+                    };
+                    balance
+                }
+                None => self.balance.get_mut(),
+            };
+            let paid = remaining.min(*balance);
+            if paid == Amount::ZERO {
+                continue;
+            }
+            balance.try_sub_assign(paid)?;
+            remaining.try_sub_assign(paid)?;
+            deposits
+                .get_mut_or_default(&payer)
+                .await?
+                .try_add_assign(paid)?;
+        }
+        ensure!(
+            remaining == Amount::ZERO,
+            SystemExecutionError::InsufficientFundingForFees {
+                balance: amount.saturating_sub(remaining),
+            }
+        );
+        Ok(())
+    }
+
+    /// Refunds up to `amount` from the storage deposits of an application, each to the
+    /// account that paid it. The deposits of `signer` are refunded first, then those of the
+    /// chain, then those of the other accounts.
+    pub async fn refund_storage_deposit(
+        &mut self,
+        application_id: UserApplicationId,
+        signer: Option<Owner>,
+        amount: Amount,
+    ) -> Result<(), SystemExecutionError> {
+        let deposits = self
+            .storage_deposits
+            .load_entry_mut(&application_id)
+            .await?;
+        let mut payers = vec![signer, None];
+        payers.extend(deposits.indices().await?);
+        let mut remaining = amount;
+        for payer in payers {
+            if remaining == Amount::ZERO {
+                break;
+            }
+            let Some(deposit) = deposits.get_mut(&payer).await? else {
+                continue;
+            };
+            let refund = remaining.min(*deposit);
+            deposit.try_sub_assign(refund)?;
+            if *deposit == Amount::ZERO {
+                deposits.remove(&payer)?;
+            }
+            remaining.try_sub_assign(refund)?;
+            let balance = match payer {
+                Some(owner) => self.balances.get_mut_or_default(&owner).await?,
+                None => self.balance.get_mut(),
+            };
+            balance.try_add_assign(refund)?;
+        }
+        Ok(())
+    }
+
+    /// Pays out the fees collected during the current epoch to the validators of its
+    /// committee, in proportion to their votes, and resets the collected fees. The shares of
+    /// validators without a fee recipient are burned.
+    fn distribute_collected_fees(
+        &mut self,
+    ) -> Result<Vec<RawOutgoingMessage<SystemMessage, Amount>>, SystemExecutionError> {
+        let collected_fees = u128::from(*self.collected_fees.get());
+        let mut messages = Vec::new();
+        let Some((_, committee)) = self.current_committee() else {
+            return Ok(messages);
+        };
+        if collected_fees == 0 || committee.total_votes() == 0 {
+            return Ok(messages);
+        }
+        let total_votes = u128::from(committee.total_votes());
+        for state in committee.validators().values() {
+            let Some(recipient) = state.fee_recipient else {
+                continue;
+            };
+            let amount = collected_fees
+                .checked_mul(u128::from(state.votes))
+                .ok_or(ArithmeticError::Overflow)?
+                / total_votes;
+            if amount == 0 {
+                continue;
+            }
+            messages.push(RawOutgoingMessage {
+                destination: Destination::Recipient(recipient.chain_id),
+                authenticated: false,
+                grant: Amount::ZERO,
+                kind: MessageKind::Simple,
+                deadline: None,
+                response_to: None,
+                message: SystemMessage::Credit {
+                    amount: Amount::from_attos(amount),
+                    source: None,
+                    target: recipient.owner,
+                },
+            });
+        }
+        self.collected_fees.set(Amount::ZERO);
+        Ok(messages)
+    }
+
     /// Executes a cross-chain message that represents the recipient's side of an operation.
     pub async fn execute_message(
         &mut self,
@@ -812,6 +1274,22 @@ where
                     }
                 }
             }
+            ApplicationCredit {
+                amount,
+                source,
+                target,
+            } => {
+                let balance = match (context.is_bouncing, target) {
+                    (true, _) => {
+                        self.application_balances
+                            .get_mut_or_default(&source)
+                            .await?
+                    }
+                    (false, Some(owner)) => self.balances.get_mut_or_default(&owner).await?,
+                    (false, None) => self.balance.get_mut(),
+                };
+                *balance = balance.saturating_add(amount);
+            }
             Withdraw {
                 amount,
                 owner,
@@ -834,6 +1312,8 @@ where
                             authenticated: false,
                             grant: Amount::ZERO,
                             kind: MessageKind::Tracked,
+                            deadline: None,
+                            response_to: None,
                             message: SystemMessage::Credit {
                                 amount,
                                 source: Some(owner),
@@ -850,6 +1330,9 @@ where
                     epoch >= self.epoch.get().expect("chain is active"),
                     SystemExecutionError::CannotRewindEpoch
                 );
+                if Some(epoch) != *self.epoch.get() {
+                    outcome.messages.extend(self.distribute_collected_fees()?);
+                }
                 self.epoch.set(Some(epoch));
                 self.committees.set(committees);
             }
@@ -865,6 +1348,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::Notify { id },
                 };
                 outcome.messages.push(message);
@@ -880,6 +1365,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::Notify { id },
                 };
                 outcome.messages.push(message);
@@ -899,6 +1386,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Simple,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::BytecodeLocations { locations },
                 };
                 outcome.messages.push(message);
@@ -910,9 +1399,7 @@ where
             }
             RegisterApplications { applications } => {
                 for application in applications {
-                    self.registry
-                        .register_application(application.clone())
-                        .await?;
+                    self.registry.register_application(application).await?;
                 }
             }
             RequestApplication(application_id) => {
@@ -923,11 +1410,19 @@ where
                         &Default::default(),
                     )
                     .await?;
+                let destination = Destination::Recipient(context.message_id.chain_id);
+                for application in &applications {
+                    self.registry
+                        .record_registration(application.into(), &destination)
+                        .await?;
+                }
                 let message = RawOutgoingMessage {
-                    destination: Destination::Recipient(context.message_id.chain_id),
+                    destination,
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Simple,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::RegisterApplications { applications },
                 };
                 outcome.messages.push(message);
@@ -935,6 +1430,14 @@ where
             OpenChain(_) => {
                 // This special message is executed immediately when cross-chain requests are received.
             }
+            PropagateUpgrade { .. } => {
+                // The messages are sent by the execution state, which knows how many more
+                // messages fit in the block.
+                ensure!(
+                    context.message_id.chain_id == context.chain_id,
+                    SystemExecutionError::IncorrectChainId(context.message_id.chain_id)
+                );
+            }
             ApplicationCreated | Notify { .. } => (),
         }
         Ok(outcome)
@@ -1005,6 +1508,10 @@ where
             self.committees.get() == &config.committees,
             SystemExecutionError::InvalidCommittees
         );
+        ensure!(
+            config.ownership.is_approval_threshold_reachable(),
+            SystemExecutionError::UnreachableApprovalThreshold
+        );
         ensure!(
             self.epoch.get().as_ref() == Some(&config.epoch),
             SystemExecutionError::InvalidEpoch {
@@ -1021,6 +1528,8 @@ where
             authenticated: false,
             grant: Amount::ZERO,
             kind: MessageKind::Protected,
+            deadline: None,
+            response_to: None,
             message: SystemMessage::OpenChain(config),
         };
         let subscription = ChannelSubscription {
@@ -1032,6 +1541,8 @@ where
             authenticated: false,
             grant: Amount::ZERO,
             kind: MessageKind::Protected,
+            deadline: None,
+            response_to: None,
             message: SystemMessage::Subscribe {
                 id: child_id,
                 subscription,
@@ -1053,6 +1564,8 @@ where
                     authenticated: false,
                     grant: Amount::ZERO,
                     kind: MessageKind::Protected,
+                    deadline: None,
+                    response_to: None,
                     message: SystemMessage::Unsubscribe { id, subscription },
                 };
                 messages.push(message);
@@ -1068,9 +1581,15 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ExecutionStateView, TestExecutionRuntimeContext};
+    use crate::{
+        committee::{ValidatorName, ValidatorState},
+        DecompressionError, ExecutionStateView, Operation, ResourceControlPolicy,
+        ResourceController, ResourceTracker, TestExecutionRuntimeContext,
+    };
+    use assert_matches::assert_matches;
     use linera_base::{data_types::BlockHeight, identifiers::ApplicationId};
     use linera_views::memory::MemoryContext;
+    use std::sync::Arc;
 
     /// Returns an execution state view and a matching operation context, for epoch 1, with root
     /// chain 0 as the admin ID and one empty committee.
@@ -1104,12 +1623,51 @@ mod tests {
             contract: Bytecode::new(vec![]),
             service: Bytecode::new(vec![]),
         };
-        let (result, new_application) = view
+        let (result, entrypoint) = view
+            .system
+            .execute_operation(context, operation)
+            .await
+            .unwrap();
+        assert_eq!(entrypoint, None);
+        let operation_index = context.index;
+        assert_eq!(
+            result.messages[PUBLISH_BYTECODE_MESSAGE_INDEX as usize].message,
+            SystemMessage::BytecodePublished { operation_index }
+        );
+    }
+
+    #[tokio::test]
+    async fn compressed_bytecode_message_index() {
+        let (mut view, context) = new_view_and_context().await;
+        let contract = Bytecode::new(vec![0; 4096]);
+        let service = Bytecode::new(b"service".repeat(512));
+        let operation = SystemOperation::PublishCompressedBytecode {
+            contract: contract.compress(),
+            service: service.compress(),
+        };
+        assert!(
+            operation.published_bytecode_size() < contract.as_ref().len() + service.as_ref().len()
+        );
+        let SystemOperation::PublishCompressedBytecode {
+            contract: compressed_contract,
+            service: compressed_service,
+        } = &operation
+        else {
+            unreachable!();
+        };
+        assert_eq!(compressed_contract.decompress(4096).unwrap(), contract);
+        assert_eq!(compressed_service.decompress(u64::MAX).unwrap(), service);
+        assert_matches!(
+            compressed_contract.decompress(4095),
+            Err(DecompressionError::BytecodeTooLarge(4095))
+        );
+
+        let (result, entrypoint) = view
             .system
             .execute_operation(context, operation)
             .await
             .unwrap();
-        assert_eq!(new_application, None);
+        assert_eq!(entrypoint, None);
         let operation_index = context.index;
         assert_eq!(
             result.messages[PUBLISH_BYTECODE_MESSAGE_INDEX as usize].message,
@@ -1117,6 +1675,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn published_bytecode_is_charged_by_its_stored_size() {
+        let contract = Bytecode::new(vec![0; 4096]);
+        let service = Bytecode::new(b"service".repeat(512));
+        let uncompressed = Operation::System(SystemOperation::PublishBytecode {
+            contract: contract.clone(),
+            service: service.clone(),
+        });
+        let compressed = Operation::System(SystemOperation::PublishCompressedBytecode {
+            contract: contract.compress(),
+            service: service.compress(),
+        });
+        let policy = Arc::new(ResourceControlPolicy {
+            operation_byte: Amount::from_attos(1),
+            ..ResourceControlPolicy::default()
+        });
+        let fees = |operation: &Operation| {
+            let mut controller = ResourceController {
+                policy: policy.clone(),
+                tracker: ResourceTracker::default(),
+                account: Amount::MAX,
+            };
+            controller.track_operation(operation).unwrap();
+            controller.tracker.fees
+        };
+        let uncompressed_size = contract.as_ref().len() + service.as_ref().len();
+        assert_eq!(
+            fees(&uncompressed),
+            Amount::from_attos(uncompressed_size as u128)
+        );
+        assert!(fees(&compressed) < fees(&uncompressed));
+    }
+
     #[tokio::test]
     async fn application_message_index() {
         let (mut view, context) = new_view_and_context().await;
@@ -1140,7 +1731,7 @@ mod tests {
             initialization_argument: vec![],
             required_application_ids: vec![],
         };
-        let (result, new_application) = view
+        let (result, entrypoint) = view
             .system
             .execute_operation(context, operation)
             .await
@@ -1158,7 +1749,7 @@ mod tests {
             bytecode_id,
             creation,
         };
-        assert_eq!(new_application, Some((id, vec![])));
+        assert_eq!(entrypoint, Some(UserEntrypoint::Initialize(id, vec![])));
     }
 
     #[tokio::test]
@@ -1177,15 +1768,262 @@ mod tests {
             application_permissions: Default::default(),
         };
         let operation = SystemOperation::OpenChain(config.clone());
-        let (result, new_application) = view
+        let (result, entrypoint) = view
             .system
             .execute_operation(context, operation)
             .await
             .unwrap();
-        assert_eq!(new_application, None);
+        assert_eq!(entrypoint, None);
         assert_eq!(
             result.messages[OPEN_CHAIN_MESSAGE_INDEX as usize].message,
             SystemMessage::OpenChain(config)
         );
     }
+
+    #[tokio::test]
+    async fn collected_fees_are_distributed_on_epoch_change() {
+        let (mut view, context) = new_view_and_context().await;
+        let recipient = |i| Account::chain(ChainId::root(i));
+        let validators = [(1, Some(recipient(1))), (3, Some(recipient(2))), (4, None)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (votes, fee_recipient))| {
+                let state = ValidatorState {
+                    network_address: String::new(),
+                    votes,
+                    fee_recipient,
+                };
+                (ValidatorName(PublicKey::test_key(i as u8)), state)
+            })
+            .collect();
+        let committee = Committee::new(validators, ResourceControlPolicy::default());
+        view.system
+            .committees
+            .set(BTreeMap::from([(Epoch(1), committee.clone())]));
+        view.system.collected_fees.set(Amount::from_tokens(8));
+
+        let context = MessageContext {
+            chain_id: context.chain_id,
+            is_bouncing: false,
+            authenticated_signer: None,
+            refund_grant_to: None,
+            height: context.height,
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            message_id: MessageId {
+                chain_id: ChainId::root(0),
+                height: BlockHeight::from(1),
+                index: 0,
+            },
+            response_to: None,
+            next_message_index: 0,
+        };
+        let message = SystemMessage::SetCommittees {
+            epoch: Epoch(2),
+            committees: BTreeMap::from([(Epoch(1), committee.clone()), (Epoch(2), committee)]),
+        };
+        let outcome = view.system.execute_message(context, message).await.unwrap();
+        let credits = outcome
+            .messages
+            .iter()
+            .map(|message| (message.destination.clone(), message.message.clone()))
+            .collect::<Vec<_>>();
+        // The third validator has no fee recipient: its share is burned.
+        assert_eq!(
+            credits,
+            vec![
+                (
+                    Destination::Recipient(ChainId::root(1)),
+                    SystemMessage::Credit {
+                        amount: Amount::from_tokens(1),
+                        source: None,
+                        target: None,
+                    }
+                ),
+                (
+                    Destination::Recipient(ChainId::root(2)),
+                    SystemMessage::Credit {
+                        amount: Amount::from_tokens(3),
+                        source: None,
+                        target: None,
+                    }
+                ),
+            ]
+        );
+        assert_eq!(*view.system.collected_fees.get(), Amount::ZERO);
+        assert_eq!(*view.system.epoch.get(), Some(Epoch(2)));
+    }
+
+    #[test]
+    fn validator_fee_percent_above_100_is_rejected() {
+        let policy = ResourceControlPolicy {
+            validator_fee_percent: 100,
+            ..ResourceControlPolicy::default()
+        };
+        let bytes = bcs::to_bytes(&policy).unwrap();
+        assert_eq!(
+            bcs::from_bytes::<ResourceControlPolicy>(&bytes).unwrap(),
+            policy
+        );
+        let policy = ResourceControlPolicy {
+            validator_fee_percent: 101,
+            ..ResourceControlPolicy::default()
+        };
+        let bytes = bcs::to_bytes(&policy).unwrap();
+        assert!(bcs::from_bytes::<ResourceControlPolicy>(&bytes).is_err());
+    }
+
+    #[tokio::test]
+    async fn committee_with_validator_fee_percent_above_100_is_rejected() {
+        let (mut view, context) = new_view_and_context().await;
+        view.system.admin_id.set(Some(context.chain_id));
+        let policy = ResourceControlPolicy {
+            validator_fee_percent: 101,
+            ..ResourceControlPolicy::default()
+        };
+        let operation = SystemOperation::Admin(AdminOperation::CreateCommittee {
+            epoch: Epoch(2),
+            committee: Committee::new(BTreeMap::new(), policy),
+        });
+        let result = view.system.execute_operation(context, operation).await;
+        assert_matches!(
+            result,
+            Err(SystemExecutionError::InvalidValidatorFeePercent(101))
+        );
+        assert_eq!(*view.system.epoch.get(), Some(Epoch(1)));
+    }
+
+    #[tokio::test]
+    async fn upgrade_application() {
+        let (mut view, context) = new_view_and_context().await;
+        let owner = Owner::from(PublicKey::test_key(1));
+        let context = OperationContext {
+            authenticated_signer: Some(owner),
+            ..context
+        };
+        let location = |operation_index| BytecodeLocation {
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            operation_index,
+        };
+        let bytecode_id = |height| {
+            BytecodeId::new(MessageId {
+                chain_id: context.chain_id,
+                height: BlockHeight::from(height),
+                index: 0,
+            })
+        };
+        view.system
+            .registry
+            .register_published_bytecode(bytecode_id(5), location(1))
+            .unwrap();
+        view.system
+            .registry
+            .register_published_bytecode(bytecode_id(6), location(2))
+            .unwrap();
+
+        let operation = SystemOperation::CreateApplication {
+            bytecode_id: bytecode_id(5),
+            parameters: vec![],
+            initialization_argument: vec![],
+            required_application_ids: vec![],
+        };
+        let (_, entrypoint) = view
+            .system
+            .execute_operation(context, operation)
+            .await
+            .unwrap();
+        let Some(UserEntrypoint::Initialize(application_id, _)) = entrypoint else {
+            panic!("Unexpected entrypoint: {entrypoint:?}");
+        };
+        let destinations = (1..=3)
+            .map(|index| Destination::Recipient(ChainId::root(index)))
+            .collect::<Vec<_>>();
+        for destination in &destinations {
+            view.system
+                .registry
+                .record_registration(application_id, destination)
+                .await
+                .unwrap();
+        }
+
+        let operation = SystemOperation::UpgradeApplication {
+            application_id,
+            bytecode_id: bytecode_id(6),
+            migration_argument: b"migrate".to_vec(),
+        };
+        let unauthenticated_context = OperationContext {
+            authenticated_signer: None,
+            ..context
+        };
+        let result = view
+            .system
+            .execute_operation(unauthenticated_context, operation.clone())
+            .await;
+        assert_matches!(
+            result,
+            Err(SystemExecutionError::UnauthorizedApplicationUpgrade(_))
+        );
+
+        let (outcome, entrypoint) = view
+            .system
+            .execute_operation(context, operation)
+            .await
+            .unwrap();
+        assert_eq!(
+            entrypoint,
+            Some(UserEntrypoint::Migrate(application_id, b"migrate".to_vec()))
+        );
+        let description = view
+            .system
+            .registry
+            .describe_application(application_id)
+            .await
+            .unwrap();
+        assert_eq!(UserApplicationId::from(&description), application_id);
+        assert_eq!(description.governance, Some(owner));
+        assert_eq!(description.version(), 1);
+        assert_eq!(description.current_bytecode_id(), bytecode_id(6));
+        assert_eq!(description.current_bytecode_location(), location(2));
+        assert!(outcome.messages.is_empty());
+
+        // The new description is sent in batches, continued by a message to the chain itself.
+        let messages = view
+            .system
+            .propagate_upgrade(context.chain_id, application_id, 0, 2)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 3);
+        let mut sent_to = Vec::new();
+        for message in &messages[..2] {
+            assert_eq!(message.kind, MessageKind::Tracked);
+            assert_eq!(
+                message.message,
+                SystemMessage::RegisterApplications {
+                    applications: vec![description.clone()]
+                }
+            );
+            sent_to.push(message.destination.clone());
+        }
+        assert_eq!(
+            messages[2].destination,
+            Destination::Recipient(context.chain_id)
+        );
+        assert_eq!(
+            messages[2].message,
+            SystemMessage::PropagateUpgrade {
+                application_id,
+                start: 2
+            }
+        );
+        let messages = view
+            .system
+            .propagate_upgrade(context.chain_id, application_id, 2, 2)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        sent_to.push(messages[0].destination.clone());
+        assert_eq!(sent_to.len(), destinations.len());
+        assert!(destinations
+            .iter()
+            .all(|destination| sent_to.contains(destination)));
+    }
 }
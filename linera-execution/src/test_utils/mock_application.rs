@@ -65,6 +65,15 @@ type InitializeHandler = Box<
         + Send
         + Sync,
 >;
+type MigrateHandler = Box<
+    dyn FnOnce(
+            &mut ContractSyncRuntime,
+            OperationContext,
+            Vec<u8>,
+        ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError>
+        + Send
+        + Sync,
+>;
 type ExecuteOperationHandler = Box<
     dyn FnOnce(
             &mut ContractSyncRuntime,
@@ -114,6 +123,8 @@ type HandleQueryHandler = Box<
 pub enum ExpectedCall {
     /// An expected call to [`UserContract::initialize`].
     Initialize(InitializeHandler),
+    /// An expected call to [`UserContract::migrate`].
+    Migrate(MigrateHandler),
     /// An expected call to [`UserContract::execute_operation`].
     ExecuteOperation(ExecuteOperationHandler),
     /// An expected call to [`UserContract::execute_message`].
@@ -130,6 +141,7 @@ impl Display for ExpectedCall {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let name = match self {
             ExpectedCall::Initialize(_) => "initialize",
+            ExpectedCall::Migrate(_) => "migrate",
             ExpectedCall::ExecuteOperation(_) => "execute_operation",
             ExpectedCall::ExecuteMessage(_) => "execute_message",
             ExpectedCall::HandleApplicationCall(_) => "handle_application_call",
@@ -157,6 +169,21 @@ impl ExpectedCall {
         ExpectedCall::Initialize(Box::new(handler))
     }
 
+    /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s
+    /// [`UserContract::migrate`] implementation, which is handled by the provided `handler`.
+    pub fn migrate(
+        handler: impl FnOnce(
+                &mut ContractSyncRuntime,
+                OperationContext,
+                Vec<u8>,
+            ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        ExpectedCall::Migrate(Box::new(handler))
+    }
+
     /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s
     /// [`UserContract::execute_operation`] implementation, which is handled by the provided
     /// `handler`.
@@ -282,6 +309,20 @@ impl UserContract for MockApplicationInstance<ContractSyncRuntime> {
         }
     }
 
+    fn migrate(
+        &mut self,
+        context: OperationContext,
+        argument: Vec<u8>,
+    ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError> {
+        match self.next_expected_call() {
+            Some(ExpectedCall::Migrate(handler)) => handler(&mut self.runtime, context, argument),
+            Some(unexpected_call) => {
+                panic!("Expected a call to `migrate`, got a call to `{unexpected_call}` instead.")
+            }
+            None => panic!("Unexpected call to `migrate`"),
+        }
+    }
+
     fn execute_operation(
         &mut self,
         context: OperationContext,
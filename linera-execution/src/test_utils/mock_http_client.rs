@@ -0,0 +1,59 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mocking of the HTTP requests made by services.
+
+use crate::http::{HttpClient, HttpError, HttpRequest, HttpResponse};
+use async_trait::async_trait;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The function answering the requests sent to a [`MockHttpClient`].
+type Handler = dyn Fn(&HttpRequest) -> Result<HttpResponse, HttpError> + Send + Sync;
+
+/// An [`HttpClient`] that answers requests using a handler function instead of the network,
+/// and records the requests it receives.
+#[derive(Clone)]
+pub struct MockHttpClient {
+    handler: Arc<Handler>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MockHttpClient {
+    /// Creates a [`MockHttpClient`] answering requests with the `handler`.
+    pub fn new(
+        handler: impl Fn(&HttpRequest) -> Result<HttpResponse, HttpError> + Send + Sync + 'static,
+    ) -> Self {
+        MockHttpClient {
+            handler: Arc::new(handler),
+            requests: Arc::default(),
+        }
+    }
+
+    /// Returns the requests received so far.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests
+            .lock()
+            .expect("Mutex should not be poisoned")
+            .clone()
+    }
+}
+
+#[async_trait]
+impl HttpClient for MockHttpClient {
+    async fn send(
+        &self,
+        request: HttpRequest,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponse, HttpError> {
+        let response = (self.handler)(&request);
+        self.requests
+            .lock()
+            .expect("Mutex should not be poisoned")
+            .push(request);
+        response
+    }
+}
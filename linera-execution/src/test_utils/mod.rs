@@ -6,10 +6,12 @@
 #![allow(unused_imports)]
 
 mod mock_application;
+mod mock_http_client;
 mod system_execution_state;
 
 pub use self::{
     mock_application::{ExpectedCall, MockApplication, MockApplicationInstance},
+    mock_http_client::MockHttpClient,
     system_execution_state::SystemExecutionState,
 };
 use crate::{
@@ -48,6 +50,8 @@ pub fn create_dummy_user_application_description(index: u64) -> UserApplicationD
         },
         required_application_ids: vec![],
         parameters: vec![],
+        governance: None,
+        upgrades: Vec::new(),
     }
 }
 
@@ -79,10 +83,10 @@ where
     for (id, mock_application) in &mock_applications {
         extra
             .user_contracts()
-            .insert(*id, Arc::new(mock_application.clone()));
+            .insert(id.bytecode_id, Arc::new(mock_application.clone()));
         extra
             .user_services()
-            .insert(*id, Arc::new(mock_application.clone()));
+            .insert(id.bytecode_id, Arc::new(mock_application.clone()));
     }
 
     Ok(mock_applications.into_iter())
@@ -6,13 +6,14 @@ use crate::{
     committee::{Committee, Epoch},
     execution::UserAction,
     system::{ApplicationPermissions, SystemChannel},
-    ChannelSubscription, ExecutionError, ExecutionRuntimeConfig, ExecutionRuntimeContext,
-    ExecutionStateView, OperationContext, ResourceControlPolicy, ResourceController,
-    ResourceTracker, TestExecutionRuntimeContext, UserApplicationDescription, UserContractCode,
+    BlockRandomness, ChannelSubscription, ExecutionError, ExecutionRuntimeConfig,
+    ExecutionRuntimeContext, ExecutionStateView, OperationContext, ResourceControlPolicy,
+    ResourceController, ResourceTracker, TestExecutionRuntimeContext, UserApplicationDescription,
+    UserApplicationId, UserContractCode,
 };
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{Amount, Timestamp},
+    data_types::{Amount, FeeSponsorship, Timestamp},
     identifiers::{ApplicationId, ChainDescription, ChainId, Owner},
     ownership::ChainOwnership,
 };
@@ -37,7 +38,11 @@ pub struct SystemExecutionState {
     pub ownership: ChainOwnership,
     pub balance: Amount,
     pub balances: BTreeMap<Owner, Amount>,
+    pub application_balances: BTreeMap<UserApplicationId, Amount>,
+    pub fee_sponsorships: BTreeMap<UserApplicationId, FeeSponsorship>,
+    pub collected_fees: Amount,
     pub timestamp: Timestamp,
+    pub randomness: Option<BlockRandomness>,
     pub registry: ApplicationRegistry,
     pub closed: bool,
     pub application_permissions: ApplicationPermissions,
@@ -94,7 +99,11 @@ impl SystemExecutionState {
             ownership,
             balance,
             balances,
+            application_balances,
+            fee_sponsorships,
+            collected_fees,
             timestamp,
+            randomness,
             registry,
             closed,
             application_permissions,
@@ -122,7 +131,21 @@ impl SystemExecutionState {
                 .insert(&owner, balance)
                 .expect("insertion of balances should not fail");
         }
+        for (application_id, balance) in application_balances {
+            view.system
+                .application_balances
+                .insert(&application_id, balance)
+                .expect("insertion of application balances should not fail");
+        }
+        for (application_id, sponsorship) in fee_sponsorships {
+            view.system
+                .fee_sponsorships
+                .insert(&application_id, sponsorship)
+                .expect("insertion of fee sponsorships should not fail");
+        }
+        view.system.collected_fees.set(collected_fees);
         view.system.timestamp.set(timestamp);
+        view.system.randomness.set(randomness);
         view.system
             .registry
             .import(registry)
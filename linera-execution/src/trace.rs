@@ -0,0 +1,158 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of the execution of user applications, to diagnose failed blocks and unexpected
+//! outcomes.
+//!
+//! When the [`ExecutionRuntimeContext`][`crate::ExecutionRuntimeContext`] provides an
+//! [`ExecutionTracer`], the runtime records every action executed by an application, every
+//! call it makes to the runtime with its arguments and result (including calls to other
+//! applications), and the fuel it consumes. Since execution is deterministic, re-executing the
+//! same block from the same state must produce the same [`ExecutionTrace`], so a saved trace
+//! can be compared with the trace of a later re-execution to find where they diverge.
+
+use crate::UserApplicationId;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, mem,
+    sync::{Arc, Mutex},
+};
+
+/// A handle to record an [`ExecutionTrace`], shared by the runtimes executing a block.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionTracer {
+    trace: Arc<Mutex<ExecutionTrace>>,
+}
+
+impl ExecutionTracer {
+    /// Appends an `entry` to the trace.
+    pub fn record(&self, entry: TraceEntry) {
+        self.trace
+            .lock()
+            .expect("Execution tracer lock should not be poisoned")
+            .entries
+            .push(entry);
+    }
+
+    /// Returns the trace recorded so far, and starts recording a new one.
+    pub fn take(&self) -> ExecutionTrace {
+        mem::take(
+            &mut *self
+                .trace
+                .lock()
+                .expect("Execution tracer lock should not be poisoned"),
+        )
+    }
+}
+
+/// The record of the execution of user applications.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// The recorded steps, in execution order.
+    pub entries: Vec<TraceEntry>,
+}
+
+/// A step of an [`ExecutionTrace`].
+///
+/// Arguments and results are recorded using their [`Debug`][`fmt::Debug`] representation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TraceEntry {
+    /// An application started executing an operation, a message, its initialization or its
+    /// migration.
+    ActionStarted {
+        application_id: UserApplicationId,
+        action: String,
+        argument: String,
+    },
+    /// An application made a call to the runtime, at the given `depth` of the call stack.
+    ///
+    /// Calls to other applications are recorded after the entries of the callee.
+    HostCall {
+        depth: usize,
+        application_id: UserApplicationId,
+        function: String,
+        arguments: String,
+        result: String,
+    },
+    /// An application finished executing an action, consuming `fuel` in total, including the
+    /// fuel of the applications it called.
+    ActionFinished {
+        application_id: UserApplicationId,
+        fuel: u64,
+        result: String,
+    },
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEntry::ActionStarted {
+                application_id,
+                action,
+                argument,
+            } => write!(f, "{application_id}: {action}({argument})"),
+            TraceEntry::HostCall {
+                depth,
+                application_id,
+                function,
+                arguments,
+                result,
+            } => write!(
+                f,
+                "{:indent$}{application_id}: {function}{arguments} -> {result}",
+                "",
+                indent = 2 * depth
+            ),
+            TraceEntry::ActionFinished {
+                application_id,
+                fuel,
+                result,
+            } => write!(
+                f,
+                "{application_id}: finished with {result} using {fuel} fuel"
+            ),
+        }
+    }
+}
+
+/// The first difference between two [`ExecutionTrace`]s.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraceDivergence<'a> {
+    /// The index of the first entry that differs.
+    pub index: usize,
+    /// The entry in the expected trace, if it has one at this index.
+    pub expected: Option<&'a TraceEntry>,
+    /// The entry in the actual trace, if it has one at this index.
+    pub actual: Option<&'a TraceEntry>,
+}
+
+impl ExecutionTrace {
+    /// Compares this trace with the `expected` one, returning their first difference if any.
+    pub fn first_divergence<'a>(
+        &'a self,
+        expected: &'a ExecutionTrace,
+    ) -> Option<TraceDivergence<'a>> {
+        let length = self.entries.len().max(expected.entries.len());
+        (0..length)
+            .map(|index| TraceDivergence {
+                index,
+                expected: expected.entries.get(index),
+                actual: self.entries.get(index),
+            })
+            .find(|divergence| divergence.expected != divergence.actual)
+    }
+}
+
+impl fmt::Display for TraceDivergence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Execution traces diverge at entry {}:", self.index)?;
+        match self.expected {
+            Some(entry) => writeln!(f, "  expected: {entry}")?,
+            None => writeln!(f, "  expected: end of trace")?,
+        }
+        match self.actual {
+            Some(entry) => write!(f, "  actual:   {entry}"),
+            None => write!(f, "  actual:   end of trace"),
+        }
+    }
+}
@@ -37,6 +37,8 @@ fn app_description(index: u32, deps: Vec<u32>) -> UserApplicationDescription {
         creation: message_id(index),
         parameters: vec![],
         required_application_ids: deps.into_iter().map(app_id).collect(),
+        governance: None,
+        upgrades: Vec::new(),
     }
 }
 
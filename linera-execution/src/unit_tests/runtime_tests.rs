@@ -5,9 +5,10 @@
 
 #![cfg(with_tokio_multi_thread)]
 
-use super::{ApplicationStatus, SyncRuntimeInternal};
+use super::{ApplicationStatus, ContractSyncRuntime, SyncRuntimeInternal};
 use crate::{
-    execution_state_actor::Request, runtime::ResourceController, BaseRuntime, UserContractInstance,
+    execution_state_actor::Request, runtime::ResourceController, BaseRuntime, ContractRuntime,
+    ExecutionTrace, ExecutionTracer, TraceEntry, UserContractInstance,
 };
 use futures::{channel::mpsc, StreamExt};
 use linera_base::{
@@ -59,7 +60,7 @@ async fn test_write_batch() {
         assert_eq!(batch, expected_batch);
 
         callback
-            .send(())
+            .send(0)
             .expect("Failed to notify that writing the batch finished");
     });
 
@@ -77,6 +78,55 @@ async fn test_write_batch() {
     );
 }
 
+/// Test recording the calls to the runtime in an execution trace.
+///
+/// Ensure that the calls are recorded with their arguments and results, and that traces
+/// that differ are detected.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_execution_trace() {
+    let (mut runtime_internal, _execution_state_receiver) = create_contract_runtime();
+    let application_id = runtime_internal.current_application().id;
+    let chain_id = runtime_internal.chain_id;
+    let tracer = ExecutionTracer::default();
+    runtime_internal.execution_tracer = Some(tracer.clone());
+    let mut runtime = ContractSyncRuntime::new(runtime_internal);
+
+    runtime.chain_id().expect("Failed to read the chain ID");
+    runtime.consume_fuel(7).expect("Failed to consume fuel");
+
+    let trace = tracer.take();
+    let expected_trace = ExecutionTrace {
+        entries: vec![
+            TraceEntry::HostCall {
+                depth: 0,
+                application_id,
+                function: "chain_id".to_owned(),
+                arguments: "()".to_owned(),
+                result: format!("Ok({chain_id:?})"),
+            },
+            TraceEntry::HostCall {
+                depth: 0,
+                application_id,
+                function: "consume_fuel".to_owned(),
+                arguments: "(7)".to_owned(),
+                result: "Ok(())".to_owned(),
+            },
+        ],
+    };
+    assert_eq!(trace, expected_trace);
+    assert!(tracer.take().entries.is_empty());
+    assert_eq!(trace.first_divergence(&expected_trace), None);
+
+    let mut truncated_trace = expected_trace.clone();
+    truncated_trace.entries.pop();
+    let divergence = trace
+        .first_divergence(&truncated_trace)
+        .expect("Traces with different lengths should diverge");
+    assert_eq!(divergence.index, 1);
+    assert_eq!(divergence.expected, None);
+    assert_eq!(divergence.actual, Some(&expected_trace.entries[1]));
+}
+
 /// Creates a [`SyncRuntimeInternal`] instance for contracts, and returns it and the receiver
 /// endpoint for the requests the runtime sends to the [`ExecutionStateView`] actor.
 fn create_contract_runtime() -> (
@@ -95,6 +145,8 @@ fn create_contract_runtime() -> (
         execution_state_sender,
         None,
         resource_controller,
+        None,
+        None,
     );
 
     runtime.push_application(create_dummy_application());
@@ -10,14 +10,14 @@
 
 use std::time::Duration;
 
-use super::{contract, contract_system_api, service_system_api};
+use super::{contract, contract_migration, contract_system_api, service_system_api};
 use crate::{
-    ApplicationCallOutcome, ChannelName, Destination, MessageKind, RawExecutionOutcome,
-    RawOutgoingMessage, SessionCallOutcome, SessionId, UserApplicationId,
+    http::HttpMethod, ApplicationCallOutcome, ChannelName, Destination, LogLevel, MessageKind,
+    RawExecutionOutcome, RawOutgoingMessage, SessionCallOutcome, SessionId, UserApplicationId,
 };
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
-    data_types::{Amount, BlockHeight, Resources},
+    data_types::{Amount, BlockHeight, FeeSponsorship, Resources, Timestamp},
     identifiers::{Account, BytecodeId, ChainId, MessageId, Owner},
     ownership::{ChainOwnership, TimeoutConfig},
 };
@@ -71,6 +71,8 @@ impl From<contract::OutgoingMessage> for RawOutgoingMessage<Vec<u8>, Resources>
             } else {
                 MessageKind::Simple
             },
+            deadline: message.deadline.map(Timestamp::from),
+            response_to: None,
             message: message.message,
         }
     }
@@ -136,6 +138,101 @@ impl From<contract::ChainId> for ChainId {
     }
 }
 
+impl From<contract_migration::Resources> for Resources {
+    fn from(value: contract_migration::Resources) -> Self {
+        Self {
+            fuel: value.fuel,
+            read_operations: value.read_operations,
+            write_operations: value.write_operations,
+            bytes_to_read: value.bytes_to_read,
+            bytes_to_write: value.bytes_to_write,
+            messages: value.messages,
+            message_size: value.message_size,
+            storage_size_delta: value.storage_size_delta,
+        }
+    }
+}
+
+impl From<contract_migration::OutgoingMessage> for RawOutgoingMessage<Vec<u8>, Resources> {
+    fn from(message: contract_migration::OutgoingMessage) -> Self {
+        Self {
+            destination: message.destination.into(),
+            authenticated: message.authenticated,
+            grant: message.resources.into(),
+            kind: if message.is_tracked {
+                MessageKind::Tracked
+            } else {
+                MessageKind::Simple
+            },
+            deadline: message.deadline.map(Timestamp::from),
+            response_to: None,
+            message: message.message,
+        }
+    }
+}
+
+impl From<contract_migration::ExecutionOutcome> for RawExecutionOutcome<Vec<u8>, Resources> {
+    fn from(outcome: contract_migration::ExecutionOutcome) -> Self {
+        let messages = outcome
+            .messages
+            .into_iter()
+            .map(RawOutgoingMessage::from)
+            .collect();
+
+        let subscribe = outcome
+            .subscribe
+            .into_iter()
+            .map(|(subscription, chain_id)| (subscription.into(), chain_id.into()))
+            .collect();
+
+        let unsubscribe = outcome
+            .unsubscribe
+            .into_iter()
+            .map(|(subscription, chain_id)| (subscription.into(), chain_id.into()))
+            .collect();
+
+        RawExecutionOutcome {
+            authenticated_signer: None,
+            refund_grant_to: None,
+            messages,
+            subscribe,
+            unsubscribe,
+        }
+    }
+}
+
+impl From<contract_migration::Destination> for Destination {
+    fn from(guest: contract_migration::Destination) -> Self {
+        match guest {
+            contract_migration::Destination::Recipient(chain_id) => {
+                Destination::Recipient(chain_id.into())
+            }
+            contract_migration::Destination::Subscribers(subscription) => {
+                Destination::Subscribers(subscription.into())
+            }
+        }
+    }
+}
+
+impl From<contract_migration::ChannelName> for ChannelName {
+    fn from(guest: contract_migration::ChannelName) -> Self {
+        guest.name.into()
+    }
+}
+
+impl From<contract_migration::CryptoHash> for CryptoHash {
+    fn from(guest: contract_migration::CryptoHash) -> Self {
+        let integers = [guest.part1, guest.part2, guest.part3, guest.part4];
+        CryptoHash::from(integers)
+    }
+}
+
+impl From<contract_migration::ChainId> for ChainId {
+    fn from(guest: contract_migration::ChainId) -> Self {
+        ChainId(guest.into())
+    }
+}
+
 impl From<contract_system_api::SessionId> for SessionId {
     fn from(guest: contract_system_api::SessionId) -> Self {
         SessionId {
@@ -217,6 +314,8 @@ impl<'a> From<contract_system_api::ChainOwnershipParam<'a>> for ChainOwnership {
             owners,
             multi_leader_rounds,
             timeout_config,
+            approval_threshold,
+            open_multi_leader_rounds,
         } = guest;
         let super_owners = super_owners.iter().map(|le| {
             let pub_key = PublicKey::from(le.get());
@@ -232,6 +331,8 @@ impl<'a> From<contract_system_api::ChainOwnershipParam<'a>> for ChainOwnership {
             owners: owners.collect(),
             multi_leader_rounds,
             timeout_config: timeout_config.into(),
+            approval_threshold,
+            open_multi_leader_rounds,
         }
     }
 }
@@ -259,6 +360,15 @@ impl From<contract_system_api::Amount> for Amount {
     }
 }
 
+impl From<contract_system_api::FeeSponsorship> for FeeSponsorship {
+    fn from(guest: contract_system_api::FeeSponsorship) -> Self {
+        FeeSponsorship {
+            max_fees_per_operation: guest.max_fees_per_operation.into(),
+            max_fees_per_block: guest.max_fees_per_block.into(),
+        }
+    }
+}
+
 impl From<service_system_api::ApplicationId> for UserApplicationId {
     fn from(guest: service_system_api::ApplicationId) -> Self {
         UserApplicationId {
@@ -303,3 +413,24 @@ impl From<service_system_api::CryptoHash> for Owner {
         Owner(CryptoHash::from(integers))
     }
 }
+
+impl From<contract_system_api::LogLevel> for LogLevel {
+    fn from(level: contract_system_api::LogLevel) -> Self {
+        match level {
+            contract_system_api::LogLevel::Trace => LogLevel::Trace,
+            contract_system_api::LogLevel::Debug => LogLevel::Debug,
+            contract_system_api::LogLevel::Info => LogLevel::Info,
+            contract_system_api::LogLevel::Warn => LogLevel::Warn,
+            contract_system_api::LogLevel::Error => LogLevel::Error,
+        }
+    }
+}
+
+impl From<service_system_api::HttpMethod> for HttpMethod {
+    fn from(guest: service_system_api::HttpMethod) -> Self {
+        match guest {
+            service_system_api::HttpMethod::Get => HttpMethod::Get,
+            service_system_api::HttpMethod::Post => HttpMethod::Post,
+        }
+    }
+}
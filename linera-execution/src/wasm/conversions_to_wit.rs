@@ -8,10 +8,10 @@
 
 #![allow(clippy::duplicate_mod)]
 
-use super::{contract, contract_system_api, service, service_system_api};
+use super::{contract, contract_migration, contract_system_api, service, service_system_api};
 use crate::{
-    CallOutcome, CalleeContext, MessageContext, MessageId, OperationContext, QueryContext,
-    SessionId, UserApplicationId,
+    http::HttpResponse, CallOutcome, CalleeContext, MessageContext, MessageId, OperationContext,
+    QueryContext, SessionId, UserApplicationId,
 };
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
@@ -31,6 +31,17 @@ impl From<OperationContext> for contract::OperationContext {
     }
 }
 
+impl From<OperationContext> for contract_migration::OperationContext {
+    fn from(host: OperationContext) -> Self {
+        contract_migration::OperationContext {
+            chain_id: host.chain_id.into(),
+            authenticated_signer: host.authenticated_signer.map(|owner| owner.0.into()),
+            height: host.height.0,
+            index: host.index,
+        }
+    }
+}
+
 impl From<MessageContext> for contract::MessageContext {
     fn from(host: MessageContext) -> Self {
         contract::MessageContext {
@@ -39,6 +50,7 @@ impl From<MessageContext> for contract::MessageContext {
             authenticated_signer: host.authenticated_signer.map(|owner| owner.0.into()),
             height: host.height.0,
             message_id: host.message_id.into(),
+            response_to: host.response_to.map(Into::into),
         }
     }
 }
@@ -157,6 +169,12 @@ impl From<ChainId> for contract::ChainId {
     }
 }
 
+impl From<ChainId> for contract_migration::ChainId {
+    fn from(chain_id: ChainId) -> Self {
+        chain_id.0.into()
+    }
+}
+
 impl From<ChainId> for service::ChainId {
     fn from(chain_id: ChainId) -> Self {
         chain_id.0.into()
@@ -175,6 +193,18 @@ impl From<CryptoHash> for contract::CryptoHash {
     }
 }
 
+impl From<CryptoHash> for contract_migration::CryptoHash {
+    fn from(crypto_hash: CryptoHash) -> Self {
+        let [part1, part2, part3, part4]: [u64; 4] = crypto_hash.into();
+        contract_migration::CryptoHash {
+            part1,
+            part2,
+            part3,
+            part4,
+        }
+    }
+}
+
 impl From<CryptoHash> for service::CryptoHash {
     fn from(crypto_hash: CryptoHash) -> Self {
         let [part1, part2, part3, part4]: [u64; 4] = crypto_hash.into();
@@ -277,6 +307,8 @@ impl From<ChainOwnership> for contract_system_api::ChainOwnershipResult {
             owners,
             multi_leader_rounds,
             timeout_config,
+            approval_threshold,
+            open_multi_leader_rounds,
         } = host;
         Self {
             super_owners: super_owners.into_values().map(Into::into).collect(),
@@ -286,6 +318,8 @@ impl From<ChainOwnership> for contract_system_api::ChainOwnershipResult {
                 .collect(),
             multi_leader_rounds,
             timeout_config: timeout_config.into(),
+            approval_threshold,
+            open_multi_leader_rounds,
         }
     }
 }
@@ -310,3 +344,13 @@ impl From<Owner> for service_system_api::CryptoHash {
         service_system_api::CryptoHash::from(owner.0)
     }
 }
+
+impl From<HttpResponse> for service_system_api::HttpResponse {
+    fn from(response: HttpResponse) -> Self {
+        service_system_api::HttpResponse {
+            status: response.status,
+            headers: response.headers,
+            body: response.body,
+        }
+    }
+}
@@ -7,33 +7,56 @@
 //!
 //! - `wasmer` enables the [Wasmer](https://wasmer.io/) runtime
 //! - `wasmtime` enables the [Wasmtime](https://wasmtime.dev/) runtime
+//!
+//! The [`validator`] of bytecodes is available without a runtime, since all validators must
+//! agree on the bytecodes that can be published, even if they never run them.
 
-#![cfg(with_wasm_runtime)]
-
+#[cfg(with_wasm_runtime)]
 mod module_cache;
+#[cfg(with_wasm_runtime)]
+mod persistent_cache;
+#[cfg(with_wasm_runtime)]
+mod profiler;
+#[cfg(with_wasm_runtime)]
 mod sanitizer;
+#[cfg(with_wasm_runtime)]
 #[macro_use]
 mod system_api;
+mod validator;
 #[cfg(with_wasmer)]
 mod wasmer;
 #[cfg(with_wasmtime)]
 mod wasmtime;
 
-use self::sanitizer::sanitize;
+pub use self::validator::{
+    validate_application, BytecodeLimits, BytecodeValidationError, LATEST_BYTECODE_RULES,
+    LOADING_RULES,
+};
+#[cfg(with_wasm_runtime)]
+pub use self::{persistent_cache::enable_persistent_module_cache, profiler::enable_fuel_profiling};
+#[cfg(with_wasm_runtime)]
+use self::{
+    profiler::{instrument, is_fuel_profiling_enabled, ProfiledFunctions},
+    sanitizer::sanitize,
+    validator::{validate_loaded_contract, validate_loaded_service},
+};
+#[cfg(with_wasm_runtime)]
 use crate::{
     Bytecode, ContractSyncRuntime, ExecutionError, ServiceSyncRuntime, UserContractInstance,
     UserContractModule, UserServiceInstance, UserServiceModule, WasmRuntime,
 };
 
-#[cfg(with_metrics)]
+#[cfg(all(with_wasm_runtime, with_metrics))]
 use linera_base::{
     prometheus_util::{self, MeasureLatency},
     sync::Lazy,
 };
 
-#[cfg(with_metrics)]
+#[cfg(all(with_wasm_runtime, with_metrics))]
 use prometheus::HistogramVec;
+#[cfg(with_wasm_runtime)]
 use std::sync::Arc;
+#[cfg(with_wasm_runtime)]
 use thiserror::Error;
 
 #[cfg(with_wasmer)]
@@ -41,7 +64,7 @@ use wasmer::{WasmerContractInstance, WasmerServiceInstance};
 #[cfg(with_wasmtime)]
 use wasmtime::{WasmtimeContractInstance, WasmtimeServiceInstance};
 
-#[cfg(with_metrics)]
+#[cfg(all(with_wasm_runtime, with_metrics))]
 static CONTRACT_INSTANTIATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     prometheus_util::register_histogram_vec(
         "contract_instantiation_latency",
@@ -54,7 +77,7 @@ static CONTRACT_INSTANTIATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     .expect("Histogram creation should not fail")
 });
 
-#[cfg(with_metrics)]
+#[cfg(all(with_wasm_runtime, with_metrics))]
 static SERVICE_INSTANTIATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     prometheus_util::register_histogram_vec(
         "service_instantiation_latency",
@@ -68,23 +91,30 @@ static SERVICE_INSTANTIATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
 });
 
 /// A user contract in a compiled WebAssembly module.
+#[cfg(with_wasm_runtime)]
 #[derive(Clone)]
 pub enum WasmContractModule {
     #[cfg(with_wasmer)]
     Wasmer {
         engine: ::wasmer::Engine,
         module: ::wasmer::Module,
+        functions: Option<Arc<ProfiledFunctions>>,
     },
     #[cfg(with_wasmtime)]
-    Wasmtime { module: Arc<::wasmtime::Module> },
+    Wasmtime {
+        module: Arc<::wasmtime::Module>,
+        functions: Option<Arc<ProfiledFunctions>>,
+    },
 }
 
+#[cfg(with_wasm_runtime)]
 impl WasmContractModule {
     /// Creates a new [`WasmContractModule`] using the WebAssembly module with the provided bytecodes.
     pub async fn new(
         contract_bytecode: Bytecode,
         runtime: WasmRuntime,
     ) -> Result<Self, WasmExecutionError> {
+        validate_loaded_contract(&contract_bytecode)?;
         let contract_bytecode = if runtime.needs_sanitizer() {
             // Ensure bytecode normalization whenever wasmer and wasmtime are possibly
             // compared.
@@ -92,14 +122,21 @@ impl WasmContractModule {
         } else {
             contract_bytecode
         };
+        let (contract_bytecode, functions) = if is_fuel_profiling_enabled() {
+            let (bytecode, functions) =
+                instrument(&contract_bytecode).map_err(WasmExecutionError::LoadContractModule)?;
+            (bytecode, Some(functions))
+        } else {
+            (contract_bytecode, None)
+        };
         match runtime {
             #[cfg(with_wasmer)]
             WasmRuntime::Wasmer | WasmRuntime::WasmerWithSanitizer => {
-                Self::from_wasmer(contract_bytecode).await
+                Self::from_wasmer(contract_bytecode, functions).await
             }
             #[cfg(with_wasmtime)]
             WasmRuntime::Wasmtime | WasmRuntime::WasmtimeWithSanitizer => {
-                Self::from_wasmtime(contract_bytecode).await
+                Self::from_wasmtime(contract_bytecode, functions).await
             }
         }
     }
@@ -121,6 +158,7 @@ impl WasmContractModule {
     }
 }
 
+#[cfg(with_wasm_runtime)]
 impl UserContractModule for WasmContractModule {
     fn instantiate(
         &self,
@@ -131,13 +169,20 @@ impl UserContractModule for WasmContractModule {
 
         let instance: UserContractInstance = match self {
             #[cfg(with_wasmtime)]
-            WasmContractModule::Wasmtime { module } => {
-                Box::new(WasmtimeContractInstance::prepare(module, runtime)?)
-            }
+            WasmContractModule::Wasmtime { module, functions } => Box::new(
+                WasmtimeContractInstance::prepare(module, functions.clone(), runtime)?,
+            ),
             #[cfg(with_wasmer)]
-            WasmContractModule::Wasmer { engine, module } => {
-                Box::new(WasmerContractInstance::prepare(engine, module, runtime)?)
-            }
+            WasmContractModule::Wasmer {
+                engine,
+                module,
+                functions,
+            } => Box::new(WasmerContractInstance::prepare(
+                engine,
+                module,
+                functions.clone(),
+                runtime,
+            )?),
         };
 
         Ok(instance)
@@ -145,6 +190,7 @@ impl UserContractModule for WasmContractModule {
 }
 
 /// A user service in a compiled WebAssembly module.
+#[cfg(with_wasm_runtime)]
 #[derive(Clone)]
 pub enum WasmServiceModule {
     #[cfg(with_wasmer)]
@@ -153,12 +199,14 @@ pub enum WasmServiceModule {
     Wasmtime { module: Arc<::wasmtime::Module> },
 }
 
+#[cfg(with_wasm_runtime)]
 impl WasmServiceModule {
     /// Creates a new [`WasmServiceModule`] using the WebAssembly module with the provided bytecodes.
     pub async fn new(
         service_bytecode: Bytecode,
         runtime: WasmRuntime,
     ) -> Result<Self, WasmExecutionError> {
+        validate_loaded_service(&service_bytecode)?;
         match runtime {
             #[cfg(with_wasmer)]
             WasmRuntime::Wasmer | WasmRuntime::WasmerWithSanitizer => {
@@ -188,6 +236,7 @@ impl WasmServiceModule {
     }
 }
 
+#[cfg(with_wasm_runtime)]
 impl UserServiceModule for WasmServiceModule {
     fn instantiate(
         &self,
@@ -212,13 +261,15 @@ impl UserServiceModule for WasmServiceModule {
 }
 
 /// Errors that can occur when executing a user application in a WebAssembly module.
-#[cfg(any(with_wasmer, with_wasmtime))]
+#[cfg(with_wasm_runtime)]
 #[derive(Debug, Error)]
 pub enum WasmExecutionError {
     #[error("Failed to load contract Wasm module: {_0}")]
     LoadContractModule(#[source] anyhow::Error),
     #[error("Failed to load service Wasm module: {_0}")]
     LoadServiceModule(#[source] anyhow::Error),
+    #[error(transparent)]
+    InvalidBytecode(#[from] BytecodeValidationError),
     #[cfg(with_wasmtime)]
     #[error("Failed to create and configure Wasmtime runtime")]
     CreateWasmtimeEngine(#[source] anyhow::Error),
@@ -233,7 +284,7 @@ pub enum WasmExecutionError {
 }
 
 /// This assumes that the current directory is one of the crates.
-#[cfg(any(test, feature = "test"))]
+#[cfg(all(with_wasm_runtime, any(test, feature = "test")))]
 pub mod test {
     #[cfg(with_fs)]
     use super::{WasmContractModule, WasmRuntime, WasmServiceModule};
@@ -0,0 +1,387 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A persistent cache of compiled WebAssembly modules, so that applications don't have to be
+//! compiled again every time a validator or a client restarts.
+//!
+//! Each compiled module is stored in its own file, named after the runtime, the version of its
+//! engine, the version of this crate (which determines how fuel is metered) and the hash of the
+//! bytecode. The file also records that name and a checksum of the compiled module, so that
+//! corrupted or misplaced files are discarded and the module is compiled again. When the total
+//! size of the files exceeds the configured maximum, the least recently used ones are evicted.
+//!
+//! The checksum only detects accidental corruption: anyone who can write to the cache directory
+//! can replace a module with arbitrary native code, which is then run by the node. The cache
+//! directory must therefore be trusted, i.e. only writable by the user running the node. On Unix
+//! it is created with owner-only permissions, and a warning is logged if an existing directory
+//! is writable by other users.
+
+use crate::Bytecode;
+use linera_base::crypto::{BcsHashable, CryptoHash};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+/// The extension of the files storing compiled modules.
+const ARTIFACT_EXTENSION: &str = "module";
+
+/// The persistent cache, if it was enabled.
+static PERSISTENT_CACHE: OnceCell<PersistentModuleCache> = OnceCell::new();
+
+/// Enables the persistent cache of compiled modules, using the `directory` to store at most
+/// `max_size` bytes of compiled modules.
+///
+/// The cache can only be enabled once per process. Later calls are ignored.
+pub fn enable_persistent_module_cache(
+    directory: impl Into<PathBuf>,
+    max_size: u64,
+) -> Result<(), io::Error> {
+    let directory = directory.into();
+    create_private_dir(&directory)?;
+    let cache = PersistentModuleCache {
+        directory,
+        max_size,
+        lock: Mutex::new(()),
+    };
+    if PERSISTENT_CACHE.set(cache).is_err() {
+        tracing::warn!("The persistent cache of compiled modules was already enabled");
+    }
+    Ok(())
+}
+
+/// Creates the cache `directory` so that only its owner can access it, and warns if an existing
+/// directory can be modified by other users.
+#[cfg(unix)]
+fn create_private_dir(directory: &Path) -> Result<(), io::Error> {
+    use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(directory)?;
+    let mode = fs::metadata(directory)?.permissions().mode();
+    if mode & 0o022 != 0 {
+        tracing::warn!(
+            "The persistent cache of compiled modules in {} is writable by other users, who \
+            could replace the compiled modules with malicious code",
+            directory.display()
+        );
+    }
+    Ok(())
+}
+
+/// Creates the cache `directory`.
+#[cfg(not(unix))]
+fn create_private_dir(directory: &Path) -> Result<(), io::Error> {
+    fs::create_dir_all(directory)
+}
+
+/// Returns the module compiled from a bytecode, loading it from the persistent cache if it is
+/// enabled and contains the module for the `key`.
+///
+/// Otherwise, the module is obtained from `compile` and stored in the persistent cache after
+/// being converted to bytes by `serialize`. Modules that fail to be restored by `deserialize`
+/// are compiled again.
+pub(crate) fn load_or_compile<Module>(
+    key: &ArtifactKey,
+    deserialize: impl FnOnce(Vec<u8>) -> Result<Module, anyhow::Error>,
+    compile: impl FnOnce() -> Result<Module, anyhow::Error>,
+    serialize: impl FnOnce(&Module) -> Result<Vec<u8>, anyhow::Error>,
+) -> Result<Module, anyhow::Error> {
+    let Some(cache) = PERSISTENT_CACHE.get() else {
+        return compile();
+    };
+
+    if let Some(artifact) = cache.load(key) {
+        match deserialize(artifact) {
+            Ok(module) => return Ok(module),
+            Err(error) => {
+                tracing::warn!("Failed to restore compiled module {}: {error}", key.name());
+                cache.remove(key);
+            }
+        }
+    }
+
+    let module = compile()?;
+    match serialize(&module) {
+        Ok(artifact) => cache.store(key, artifact),
+        Err(error) => tracing::warn!(
+            "Failed to serialize compiled module {}: {error}",
+            key.name()
+        ),
+    }
+    Ok(module)
+}
+
+/// The identity of a compiled module in the persistent cache.
+pub(crate) struct ArtifactKey {
+    runtime: &'static str,
+    engine_version: &'static str,
+    kind: &'static str,
+    bytecode_hash: CryptoHash,
+}
+
+impl ArtifactKey {
+    /// Creates the [`ArtifactKey`] of the `kind` of module (e.g. a contract or a service)
+    /// compiled from the `bytecode` by the `runtime` using an engine with the `engine_version`.
+    pub fn new(
+        runtime: &'static str,
+        engine_version: &'static str,
+        kind: &'static str,
+        bytecode: &Bytecode,
+    ) -> Self {
+        ArtifactKey {
+            runtime,
+            engine_version,
+            kind,
+            bytecode_hash: CryptoHash::new(bytecode),
+        }
+    }
+
+    /// Returns the name of the file storing the compiled module.
+    fn name(&self) -> String {
+        format!(
+            "{}-{}-linera-{}-{}-{}.{ARTIFACT_EXTENSION}",
+            self.runtime,
+            self.engine_version,
+            env!("CARGO_PKG_VERSION"),
+            self.kind,
+            self.bytecode_hash,
+        )
+    }
+}
+
+/// The contents of a file in the persistent cache.
+#[derive(Deserialize, Serialize)]
+struct StoredArtifact {
+    /// The name of the file, to detect files that were renamed or copied.
+    name: String,
+    /// The checksum of the `artifact`, to detect corrupted files. It does not protect against
+    /// files that were modified on purpose.
+    checksum: CryptoHash,
+    artifact: Artifact,
+}
+
+/// A compiled module, serialized by its runtime.
+#[derive(Deserialize, Serialize)]
+struct Artifact(#[serde(with = "serde_bytes")] Vec<u8>);
+
+impl BcsHashable for Artifact {}
+
+/// A directory storing compiled modules, limited in size.
+struct PersistentModuleCache {
+    directory: PathBuf,
+    max_size: u64,
+    /// Prevents concurrent evictions from removing more files than necessary.
+    lock: Mutex<()>,
+}
+
+impl PersistentModuleCache {
+    /// Loads the compiled module for the `key`, if it's present and intact.
+    fn load(&self, key: &ArtifactKey) -> Option<Vec<u8>> {
+        let name = key.name();
+        let path = self.directory.join(&name);
+        let contents = fs::read(&path).ok()?;
+
+        match bcs::from_bytes::<StoredArtifact>(&contents) {
+            Ok(stored)
+                if stored.name == name && stored.checksum == CryptoHash::new(&stored.artifact) =>
+            {
+                // Refresh the modification time, which is used to evict the least recently used
+                // modules.
+                if let Err(error) = fs::File::options()
+                    .write(true)
+                    .open(&path)
+                    .and_then(|file| file.set_modified(SystemTime::now()))
+                {
+                    tracing::debug!("Failed to refresh compiled module {name}: {error}");
+                }
+                Some(stored.artifact.0)
+            }
+            _ => {
+                tracing::warn!("Discarding corrupted compiled module {name}");
+                self.remove(key);
+                None
+            }
+        }
+    }
+
+    /// Stores the compiled module for the `key`, evicting older modules if necessary.
+    fn store(&self, key: &ArtifactKey, artifact: Vec<u8>) {
+        let name = key.name();
+        let artifact = Artifact(artifact);
+        let stored = StoredArtifact {
+            checksum: CryptoHash::new(&artifact),
+            name: name.clone(),
+            artifact,
+        };
+        let contents = bcs::to_bytes(&stored).expect("Serializing an artifact should not fail");
+
+        if contents.len() as u64 > self.max_size {
+            return;
+        }
+
+        let _guard = self.lock.lock().expect("Cache lock should not be poisoned");
+
+        // Write to a temporary file first, so that other processes never read a partial file.
+        let temporary_path = self.directory.join(format!("{name}.tmp"));
+        let result = fs::write(&temporary_path, &contents)
+            .and_then(|()| fs::rename(&temporary_path, self.directory.join(&name)))
+            .and_then(|()| self.evict_to(self.max_size));
+        if let Err(error) = result {
+            tracing::warn!("Failed to store compiled module {name}: {error}");
+            let _ = fs::remove_file(&temporary_path);
+        }
+    }
+
+    /// Removes the compiled module for the `key`.
+    fn remove(&self, key: &ArtifactKey) {
+        let _ = fs::remove_file(self.directory.join(key.name()));
+    }
+
+    /// Removes the least recently used modules until their total size is at most `max_size`.
+    fn evict_to(&self, max_size: u64) -> Result<(), io::Error> {
+        let mut artifacts = Vec::new();
+        let mut total_size = 0;
+
+        for entry in fs::read_dir(&self.directory)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension() != Some(OsStr::new(ARTIFACT_EXTENSION)) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            total_size += metadata.len();
+            artifacts.push((metadata.modified()?, metadata.len(), path));
+        }
+
+        artifacts.sort_unstable();
+
+        for (_, size, path) in artifacts {
+            if total_size <= max_size {
+                break;
+            }
+            remove_artifact_file(&path)?;
+            total_size -= size;
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes a file, ignoring files that were already removed by another process.
+fn remove_artifact_file(path: &Path) -> Result<(), io::Error> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_private_dir, ArtifactKey, PersistentModuleCache};
+    use crate::Bytecode;
+    use std::{
+        fs,
+        path::Path,
+        sync::Mutex,
+        time::{Duration, SystemTime},
+    };
+
+    /// Creates a [`PersistentModuleCache`] in a new temporary directory.
+    fn new_cache(max_size: u64) -> (PersistentModuleCache, tempfile::TempDir) {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = PersistentModuleCache {
+            directory: directory.path().to_owned(),
+            max_size,
+            lock: Mutex::new(()),
+        };
+        (cache, directory)
+    }
+
+    /// Sets the modification time of the file storing the module for the `key` to `seconds_ago`
+    /// seconds in the past.
+    fn set_age(directory: &Path, key: &ArtifactKey, seconds_ago: u64) {
+        let modified = SystemTime::now() - Duration::from_secs(seconds_ago);
+        fs::File::options()
+            .write(true)
+            .open(directory.join(key.name()))
+            .and_then(|file| file.set_modified(modified))
+            .unwrap();
+    }
+
+    fn key(bytecode: &[u8]) -> ArtifactKey {
+        ArtifactKey::new("test", "1.0", "contract", &Bytecode::new(bytecode.to_vec()))
+    }
+
+    /// Tests that stored modules are loaded back, but only for the same key.
+    #[test]
+    fn stores_and_loads_modules() {
+        let (cache, _directory) = new_cache(u64::MAX);
+
+        cache.store(&key(b"first"), vec![1, 2, 3]);
+
+        assert_eq!(cache.load(&key(b"first")), Some(vec![1, 2, 3]));
+        assert_eq!(cache.load(&key(b"second")), None);
+    }
+
+    /// Tests that corrupted files are detected and removed.
+    #[test]
+    fn discards_corrupted_modules() {
+        let (cache, directory) = new_cache(u64::MAX);
+        let key = key(b"bytecode");
+        cache.store(&key, vec![1, 2, 3]);
+
+        let path = directory.path().join(key.name());
+        let mut contents = fs::read(&path).unwrap();
+        *contents.last_mut().unwrap() ^= 0xff;
+        fs::write(&path, contents).unwrap();
+
+        assert_eq!(cache.load(&key), None);
+        assert!(!path.exists());
+    }
+
+    /// Tests that the oldest modules are evicted when the cache exceeds its maximum size.
+    #[test]
+    fn evicts_least_recently_used_modules() {
+        let (mut cache, directory) = new_cache(u64::MAX);
+        let (first, second, third) = (key(b"first"), key(b"second"), key(b"third"));
+        cache.store(&first, vec![0; 100]);
+        let file_size = fs::metadata(directory.path().join(first.name()))
+            .unwrap()
+            .len();
+        cache.max_size = 2 * file_size;
+        cache.store(&second, vec![0; 100]);
+
+        // Set the modification times explicitly, so that the order doesn't depend on the
+        // resolution of the file system's timestamps.
+        set_age(directory.path(), &first, 20);
+        set_age(directory.path(), &second, 10);
+        assert!(cache.load(&first).is_some());
+        cache.store(&third, vec![0; 100]);
+
+        assert!(cache.load(&first).is_some());
+        assert!(cache.load(&second).is_none());
+        assert!(cache.load(&third).is_some());
+    }
+
+    /// Tests that the cache directory is only accessible by its owner.
+    #[cfg(unix)]
+    #[test]
+    fn creates_private_directory() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let parent = tempfile::tempdir().unwrap();
+        let directory = parent.path().join("cache");
+        create_private_dir(&directory).unwrap();
+
+        let mode = fs::metadata(&directory).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+}
@@ -0,0 +1,731 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Instrumentation of contract bytecodes to attribute the fuel they consume to their functions.
+//!
+//! When fuel profiling is enabled, every function of a contract module, including the imported
+//! system API functions, is wrapped by a function that calls the `enter` and `exit` hooks
+//! imported from the [`PROFILER_MODULE`] around the call to the original function. All calls,
+//! exports and table entries are redirected to the wrappers. The hooks read the fuel consumed
+//! so far from the runtime and pass it to an [`InstanceProfiler`], which attributes it to the
+//! stack of functions of the [`FuelProfiler`].
+//!
+//! The instructions of the wrappers consume fuel too. Since their cost is known, it is excluded
+//! from the profile and refunded after the execution, so that profiling does not change the fuel
+//! charged for a transaction. An execution may however run out of fuel slightly earlier when it
+//! is profiled.
+
+use super::sanitizer::Sanitizer;
+use crate::{Bytecode, FuelProfiler};
+use anyhow::{bail, ensure};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use wasm_encoder::{
+    CodeSection, ConstExpr, CustomSection, DataCountSection, ElementSection, Elements, EntityType,
+    ExportKind, ExportSection, Function, FunctionSection, ImportSection, Instruction, Module,
+    RawSection, SectionId, StartSection, TypeSection,
+};
+use wasmparser::{
+    BinaryReader, ElementItems, ElementKind, ExternalKind, Operator, Parser, Payload, TypeRef,
+};
+
+/// The name of the module from which instrumented bytecodes import the profiling hooks.
+pub(crate) const PROFILER_MODULE: &str = "linera_profiler";
+
+/// The name of the hook called before entering a function.
+pub(crate) const ENTER_HOOK: &str = "enter";
+
+/// The name of the hook called after exiting a function.
+pub(crate) const EXIT_HOOK: &str = "exit";
+
+/// The fuel consumed by the `i32.const` and `call` instructions calling a hook.
+const HOOK_CALL_COST: u64 = 2;
+
+/// The fuel consumed by the `return` instruction at the end of a wrapper.
+const WRAPPER_RETURN_COST: u64 = 1;
+
+/// The ID of the custom section with the names of the functions.
+const NAME_SECTION: &str = "name";
+
+/// The ID of the subsection of the name section with the names of the functions.
+const FUNCTION_NAMES_SUBSECTION: u8 = 1;
+
+/// Whether the contract bytecodes loaded from now on should be instrumented.
+static FUEL_PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// Enables the instrumentation of the contract bytecodes loaded from now on, so that the fuel
+/// they consume is attributed to their functions.
+pub fn enable_fuel_profiling() {
+    FUEL_PROFILING.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether the contract bytecodes should be instrumented for fuel profiling.
+pub(crate) fn is_fuel_profiling_enabled() -> bool {
+    FUEL_PROFILING.load(Ordering::Relaxed)
+}
+
+/// The functions of an instrumented module, indexed by their index in the original module.
+#[derive(Debug, Default)]
+pub struct ProfiledFunctions {
+    names: Vec<String>,
+    parameter_counts: Vec<u64>,
+}
+
+impl ProfiledFunctions {
+    /// Returns the name of the function with the `index`.
+    fn name(&self, index: u32) -> &str {
+        self.names
+            .get(index as usize)
+            .map_or("[unknown]", String::as_str)
+    }
+
+    /// Returns the number of parameters of the function with the `index`.
+    fn parameter_count(&self, index: u32) -> u64 {
+        self.parameter_counts
+            .get(index as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Instruments a contract `bytecode` for fuel profiling.
+///
+/// Returns the instrumented bytecode, and the functions it reports to the hooks.
+pub(crate) fn instrument(
+    bytecode: &Bytecode,
+) -> Result<(Bytecode, Arc<ProfiledFunctions>), anyhow::Error> {
+    let mut instrumenter = Instrumenter::default();
+    let mut code_section_size = 0;
+
+    for payload in Parser::new(0).parse_all(bytecode.as_ref()) {
+        match payload? {
+            Payload::Version { .. } => {}
+            Payload::TypeSection(reader) => {
+                for ty in reader {
+                    #[allow(unreachable_patterns)]
+                    let function_type = match ty? {
+                        wasmparser::Type::Func(function_type) => function_type,
+                        _ => bail!("Unsupported type in the type section"),
+                    };
+                    instrumenter.types.function(
+                        function_type
+                            .params()
+                            .iter()
+                            .copied()
+                            .map(Sanitizer::convert_type),
+                        function_type
+                            .results()
+                            .iter()
+                            .copied()
+                            .map(Sanitizer::convert_type),
+                    );
+                    instrumenter
+                        .parameter_counts_by_type
+                        .push(function_type.params().len() as u64);
+                }
+            }
+            Payload::ImportSection(reader) => {
+                instrumenter.emit_types();
+                for import in reader {
+                    let import = import?;
+                    let TypeRef::Func(type_index) = import.ty else {
+                        bail!("Unsupported import of `{}::{}`", import.module, import.name);
+                    };
+                    instrumenter.imports.import(
+                        import.module,
+                        import.name,
+                        EntityType::Function(type_index),
+                    );
+                    // Imported functions from the system API have their signature in their name.
+                    let name = import.name.split(':').next().unwrap_or_default().trim();
+                    instrumenter
+                        .add_imported_function(format!("{}::{}", import.module, name), type_index);
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                instrumenter.emit_imports();
+                let mut section = FunctionSection::new();
+                let type_indices = reader.into_iter().collect::<Result<Vec<_>, _>>()?;
+                for &type_index in &type_indices {
+                    section.function(type_index);
+                }
+                instrumenter.defined_functions = type_indices.len() as u32;
+                for (index, &type_index) in type_indices.iter().enumerate() {
+                    let index = instrumenter.imported_functions + index as u32;
+                    instrumenter.add_defined_function(index, type_index);
+                }
+                let imported_types = instrumenter.imported_types.clone();
+                instrumenter.wrapper_types.extend(imported_types);
+                for &type_index in &instrumenter.wrapper_types {
+                    section.function(type_index);
+                }
+                instrumenter.module.section(&section);
+            }
+            Payload::TableSection(reader) => {
+                instrumenter.copy_section(SectionId::Table, bytecode, reader.range())
+            }
+            Payload::MemorySection(reader) => {
+                instrumenter.copy_section(SectionId::Memory, bytecode, reader.range())
+            }
+            Payload::TagSection(reader) => {
+                instrumenter.copy_section(SectionId::Tag, bytecode, reader.range())
+            }
+            Payload::GlobalSection(reader) => {
+                let range = reader.range();
+                for global in reader {
+                    for operator in global?.init_expr.get_operators_reader() {
+                        ensure!(
+                            !matches!(operator?, Operator::RefFunc { .. }),
+                            "Unsupported function reference in a global"
+                        );
+                    }
+                }
+                instrumenter.copy_section(SectionId::Global, bytecode, range)
+            }
+            Payload::ExportSection(reader) => {
+                instrumenter.emit_imports();
+                let mut section = ExportSection::new();
+                for export in reader {
+                    let export = export?;
+                    let (kind, index) = match export.kind {
+                        ExternalKind::Func => {
+                            instrumenter.name_function(export.index, export.name);
+                            (ExportKind::Func, instrumenter.remap(export.index))
+                        }
+                        ExternalKind::Table => (ExportKind::Table, export.index),
+                        ExternalKind::Memory => (ExportKind::Memory, export.index),
+                        ExternalKind::Global => (ExportKind::Global, export.index),
+                        ExternalKind::Tag => (ExportKind::Tag, export.index),
+                    };
+                    section.export(export.name, kind, index);
+                }
+                instrumenter.module.section(&section);
+            }
+            Payload::StartSection { func, .. } => {
+                instrumenter.emit_imports();
+                instrumenter.module.section(&StartSection {
+                    function_index: instrumenter.remap(func),
+                });
+            }
+            Payload::ElementSection(reader) => {
+                instrumenter.emit_imports();
+                let mut section = ElementSection::new();
+                for element in reader {
+                    let element = element?;
+                    let element_type = wasm_encoder::RefType {
+                        nullable: element.ty.nullable,
+                        heap_type: Sanitizer::convert_heap_type(element.ty.heap_type),
+                    };
+                    let functions;
+                    let expressions;
+                    let elements = match element.items {
+                        ElementItems::Functions(reader) => {
+                            functions = reader
+                                .into_iter()
+                                .map(|index| index.map(|index| instrumenter.remap(index)))
+                                .collect::<Result<Vec<_>, _>>()?;
+                            Elements::Functions(&functions)
+                        }
+                        ElementItems::Expressions(reader) => {
+                            expressions = reader
+                                .into_iter()
+                                .map(|expression| instrumenter.convert_const_expr(expression?))
+                                .collect::<Result<Vec<_>, _>>()?;
+                            Elements::Expressions(&expressions)
+                        }
+                    };
+                    match element.kind {
+                        ElementKind::Passive => section.passive(element_type, elements),
+                        ElementKind::Declared => section.declared(element_type, elements),
+                        ElementKind::Active {
+                            table_index,
+                            offset_expr,
+                        } => section.active(
+                            Some(table_index),
+                            &instrumenter.convert_const_expr(offset_expr)?,
+                            element_type,
+                            elements,
+                        ),
+                    };
+                }
+                instrumenter.module.section(&section);
+            }
+            Payload::DataCountSection { count, .. } => {
+                instrumenter.emit_imports();
+                instrumenter.module.section(&DataCountSection { count });
+            }
+            Payload::DataSection(reader) => {
+                instrumenter.copy_section(SectionId::Data, bytecode, reader.range())
+            }
+            Payload::CodeSectionStart { count, .. } => {
+                instrumenter.emit_imports();
+                code_section_size = count;
+                if count == 0 {
+                    instrumenter.emit_code();
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                let locals = Sanitizer::convert_locals(body.get_locals_reader()?)?;
+                let mut function = Function::new(locals);
+                for instruction in Sanitizer::convert_operators(body.get_operators_reader()?)? {
+                    let instruction = match instruction {
+                        Instruction::Call(index) => Instruction::Call(instrumenter.remap(index)),
+                        Instruction::RefFunc(index) => {
+                            Instruction::RefFunc(instrumenter.remap(index))
+                        }
+                        Instruction::ReturnCall(_) => bail!("Unsupported tail call"),
+                        instruction => instruction,
+                    };
+                    function.instruction(&instruction);
+                }
+                instrumenter.code.function(&function);
+                if instrumenter.code.len() == code_section_size {
+                    instrumenter.emit_code();
+                }
+            }
+            Payload::CustomSection(reader) if reader.name() == NAME_SECTION => {
+                // The function indices changed, so the names are only kept for the profiler.
+                instrumenter.read_function_names(reader.data(), reader.data_offset())?;
+            }
+            Payload::CustomSection(reader) => {
+                instrumenter.module.section(&CustomSection {
+                    name: reader.name(),
+                    data: reader.data(),
+                });
+            }
+            Payload::End(_) => break,
+            _ => bail!("Unsupported section in contract bytecode"),
+        }
+    }
+
+    ensure!(
+        instrumenter.code_emitted,
+        "Contract bytecode has no code section"
+    );
+    let functions = ProfiledFunctions {
+        names: instrumenter.names,
+        parameter_counts: instrumenter.parameter_counts,
+    };
+    Ok((
+        Bytecode::new(instrumenter.module.finish()),
+        Arc::new(functions),
+    ))
+}
+
+/// The state of the instrumentation of a module.
+///
+/// In the instrumented module, the function index space starts with the original imported
+/// functions, followed by the two hooks, the original defined functions, the wrappers of the
+/// defined functions and finally the wrappers of the imported functions.
+#[derive(Default)]
+struct Instrumenter {
+    module: Module,
+    types: TypeSection,
+    types_emitted: bool,
+    imports: ImportSection,
+    imports_emitted: bool,
+    code: CodeSection,
+    code_emitted: bool,
+    /// The number of parameters of each type.
+    parameter_counts_by_type: Vec<u64>,
+    /// The index of the type of the hooks.
+    hook_type: u32,
+    imported_functions: u32,
+    defined_functions: u32,
+    /// The types of the wrappers, in the order of their indices.
+    wrapper_types: Vec<u32>,
+    /// The names of the original functions.
+    names: Vec<String>,
+    /// The number of parameters of the original functions.
+    parameter_counts: Vec<u64>,
+    /// The original types of the imported functions.
+    imported_types: Vec<u32>,
+    /// The names of the defined functions found in the name section.
+    debug_names: HashMap<u32, String>,
+}
+
+impl Instrumenter {
+    /// Emits the type section, after adding the type of the hooks.
+    fn emit_types(&mut self) {
+        if !self.types_emitted {
+            self.hook_type = self.types.len();
+            self.types.function([wasm_encoder::ValType::I32], []);
+            self.module.section(&self.types);
+            self.types_emitted = true;
+        }
+    }
+
+    /// Emits the import section, after adding the imports of the hooks.
+    fn emit_imports(&mut self) {
+        self.emit_types();
+        if !self.imports_emitted {
+            for hook in [ENTER_HOOK, EXIT_HOOK] {
+                self.imports
+                    .import(PROFILER_MODULE, hook, EntityType::Function(self.hook_type));
+            }
+            self.module.section(&self.imports);
+            self.imports_emitted = true;
+        }
+    }
+
+    /// Emits the code section, after adding the bodies of the wrappers.
+    fn emit_code(&mut self) {
+        let enter_hook = self.imported_functions;
+        let exit_hook = enter_hook + 1;
+        let originals = (self.imported_functions..self.imported_functions + self.defined_functions)
+            .chain(0..self.imported_functions);
+
+        for original in originals {
+            let target = if original < self.imported_functions {
+                original
+            } else {
+                original + 2
+            };
+            let mut wrapper = Function::new([]);
+            wrapper.instruction(&Instruction::I32Const(original as i32));
+            wrapper.instruction(&Instruction::Call(enter_hook));
+            for parameter in 0..self.parameter_counts[original as usize] {
+                wrapper.instruction(&Instruction::LocalGet(parameter as u32));
+            }
+            wrapper.instruction(&Instruction::Call(target));
+            wrapper.instruction(&Instruction::I32Const(original as i32));
+            wrapper.instruction(&Instruction::Call(exit_hook));
+            wrapper.instruction(&Instruction::Return);
+            wrapper.instruction(&Instruction::End);
+            self.code.function(&wrapper);
+        }
+
+        self.module.section(&self.code);
+        self.code_emitted = true;
+    }
+
+    /// Copies a section of the `bytecode` that doesn't need to be changed.
+    fn copy_section(&mut self, id: SectionId, bytecode: &Bytecode, range: std::ops::Range<usize>) {
+        self.emit_imports();
+        self.module.section(&RawSection {
+            id: id as u8,
+            data: &bytecode.as_ref()[range],
+        });
+    }
+
+    /// Registers an imported function.
+    fn add_imported_function(&mut self, name: String, type_index: u32) {
+        self.imported_functions += 1;
+        self.imported_types.push(type_index);
+        self.names.push(name);
+        self.parameter_counts
+            .push(self.parameter_count_of_type(type_index));
+    }
+
+    /// Registers the defined function with the original `index`.
+    fn add_defined_function(&mut self, index: u32, type_index: u32) {
+        let name = self
+            .debug_names
+            .remove(&index)
+            .unwrap_or_else(|| format!("function[{index}]"));
+        self.names.push(name);
+        self.parameter_counts
+            .push(self.parameter_count_of_type(type_index));
+        self.wrapper_types.push(type_index);
+    }
+
+    /// Names a function after its export, unless it has a name from the name section.
+    fn name_function(&mut self, index: u32, name: &str) {
+        if let Some(entry) = self.names.get_mut(index as usize) {
+            if index >= self.imported_functions && entry.starts_with("function[") {
+                *entry = name.to_owned();
+            }
+        }
+    }
+
+    /// Reads the names of the functions from the data of the name section.
+    fn read_function_names(&mut self, data: &[u8], offset: usize) -> Result<(), anyhow::Error> {
+        let mut reader = BinaryReader::new_with_offset(data, offset);
+        while !reader.eof() {
+            let id = reader.read_u8()?;
+            let size = reader.read_var_u32()?;
+            let mut subsection =
+                BinaryReader::new_with_offset(reader.read_bytes(size as usize)?, 0);
+            if id != FUNCTION_NAMES_SUBSECTION {
+                continue;
+            }
+            for _ in 0..subsection.read_var_u32()? {
+                let index = subsection.read_var_u32()?;
+                let name = subsection.read_string()?;
+                match self.names.get_mut(index as usize) {
+                    Some(entry) if index >= self.imported_functions => *entry = name.to_owned(),
+                    Some(_) => {}
+                    None => {
+                        self.debug_names.insert(index, name.to_owned());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of parameters of the type with the `type_index`.
+    fn parameter_count_of_type(&self, type_index: u32) -> u64 {
+        self.parameter_counts_by_type
+            .get(type_index as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the index of the wrapper of the function with the original `index`.
+    fn remap(&self, index: u32) -> u32 {
+        let hooks_and_defined = 2 + self.defined_functions;
+        if index < self.imported_functions {
+            self.imported_functions + hooks_and_defined + self.defined_functions + index
+        } else {
+            index + hooks_and_defined
+        }
+    }
+
+    /// Converts a constant expression, redirecting function references to the wrappers.
+    fn convert_const_expr(
+        &self,
+        expression: wasmparser::ConstExpr<'_>,
+    ) -> Result<ConstExpr, anyhow::Error> {
+        let mut operators = expression.get_operators_reader();
+        let converted = match operators.read()? {
+            Operator::I32Const { value } => ConstExpr::i32_const(value),
+            Operator::I64Const { value } => ConstExpr::i64_const(value),
+            Operator::GlobalGet { global_index } => ConstExpr::global_get(global_index),
+            Operator::RefNull { hty } => ConstExpr::ref_null(Sanitizer::convert_heap_type(hty)),
+            Operator::RefFunc { function_index } => ConstExpr::ref_func(self.remap(function_index)),
+            operator => bail!("Unsupported constant expression {operator:?}"),
+        };
+        ensure!(
+            matches!(operators.read()?, Operator::End),
+            "Unsupported constant expression"
+        );
+        Ok(converted)
+    }
+}
+
+/// Attributes the fuel consumed by an instance of an instrumented module to its functions.
+///
+/// The runtime-specific hooks provide the fuel consumed by the instance so far, from which the
+/// cost of the instrumentation is excluded.
+pub struct InstanceProfiler {
+    functions: Arc<ProfiledFunctions>,
+    profiler: Option<FuelProfiler>,
+    /// The fuel consumed by the instance when the last hook was called.
+    last_reading: u64,
+    /// The cost of the instrumentation not yet excluded from a reading.
+    pending_overhead: u64,
+    /// The total cost of the instrumentation since the start of the current entrypoint.
+    overhead: u64,
+    /// The number of frames pushed by this instance.
+    depth: usize,
+}
+
+impl InstanceProfiler {
+    /// Creates an [`InstanceProfiler`] for an instance of a module with the `functions`, or of
+    /// a module that wasn't instrumented, in which case all the fuel is attributed to the
+    /// current stack of the [`FuelProfiler`].
+    pub fn new(functions: Option<Arc<ProfiledFunctions>>) -> Self {
+        InstanceProfiler {
+            functions: functions.unwrap_or_default(),
+            profiler: None,
+            last_reading: 0,
+            pending_overhead: 0,
+            overhead: 0,
+            depth: 0,
+        }
+    }
+
+    /// Starts profiling a call to an entrypoint, when the instance consumed `consumed_fuel`.
+    pub fn start(&mut self, profiler: Option<FuelProfiler>, consumed_fuel: u64) {
+        self.profiler = profiler;
+        self.last_reading = consumed_fuel;
+        self.pending_overhead = 0;
+        self.overhead = 0;
+        self.depth = 0;
+    }
+
+    /// Handles the call to the `enter` hook before the function with the `index`.
+    pub fn enter(&mut self, index: u32, consumed_fuel: u64) {
+        self.add_overhead(HOOK_CALL_COST);
+        self.charge(consumed_fuel);
+        if let Some(profiler) = &self.profiler {
+            profiler.push(self.functions.name(index));
+        }
+        self.depth += 1;
+    }
+
+    /// Handles the call to the `exit` hook after the function with the `index`.
+    pub fn exit(&mut self, index: u32, consumed_fuel: u64) {
+        // The wrapper also forwarded the parameters and called the original function.
+        let forwarding_cost = self.functions.parameter_count(index) + 1;
+        self.add_overhead(forwarding_cost + HOOK_CALL_COST);
+        self.charge(consumed_fuel);
+        if self.depth > 0 {
+            if let Some(profiler) = &self.profiler {
+                profiler.pop();
+            }
+            self.depth -= 1;
+        }
+        // The `return` of the wrapper is excluded from the next reading.
+        self.add_overhead(WRAPPER_RETURN_COST);
+    }
+
+    /// Finishes profiling the call to an entrypoint, when the instance consumed
+    /// `consumed_fuel`.
+    ///
+    /// Returns the fuel consumed by the instrumentation, which should be refunded.
+    pub fn finish(&mut self, consumed_fuel: u64) -> u64 {
+        self.charge(consumed_fuel);
+        if let Some(profiler) = &self.profiler {
+            // Frames are left on the stack if the execution was aborted.
+            for _ in 0..self.depth {
+                profiler.pop();
+            }
+        }
+        self.depth = 0;
+        self.overhead
+    }
+
+    /// Accounts for fuel consumed by the instrumentation.
+    fn add_overhead(&mut self, fuel: u64) {
+        self.overhead = self.overhead.saturating_add(fuel);
+        self.pending_overhead = self.pending_overhead.saturating_add(fuel);
+    }
+
+    /// Attributes the fuel consumed since the last reading to the current stack of functions,
+    /// excluding the cost of the instrumentation.
+    fn charge(&mut self, consumed_fuel: u64) {
+        let fuel = consumed_fuel
+            .saturating_sub(self.last_reading)
+            .saturating_sub(self.pending_overhead);
+        self.last_reading = consumed_fuel;
+        self.pending_overhead = 0;
+        if let Some(profiler) = &self.profiler {
+            profiler.charge(fuel);
+        }
+    }
+}
+
+#[cfg(all(test, with_wasmer))]
+mod tests {
+    use super::{instrument, InstanceProfiler};
+    use crate::{Bytecode, FuelProfiler};
+    use wasmparser::{Parser, Payload, TypeRef, Validator};
+
+    /// Tests that the instrumented module is valid, imports the hooks and exports the wrappers.
+    #[test]
+    fn instruments_functions() {
+        let wat = r#"
+            (module
+              (import "contract_system_api" "chain-id: func() -> unit" (func $chain_id))
+              (func $helper (param i32) (result i32)
+                call $chain_id
+                local.get 0
+                return)
+              (func $entrypoint (param i32) (result i32)
+                local.get 0
+                call $helper
+                return)
+              (table 1 funcref)
+              (elem (i32.const 0) func $helper)
+              (memory (;0;) 1)
+              (export "memory" (memory 0))
+              (export "entrypoint" (func $entrypoint))
+            )
+        "#;
+        let bytecode = Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned());
+
+        let (instrumented, functions) = instrument(&bytecode).unwrap();
+
+        Validator::new()
+            .validate_all(instrumented.as_ref())
+            .unwrap();
+        assert_eq!(
+            functions.names,
+            ["contract_system_api::chain-id", "helper", "entrypoint"]
+        );
+        assert_eq!(functions.parameter_counts, [0, 1, 1]);
+
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+        for payload in Parser::new(0).parse_all(instrumented.as_ref()) {
+            match payload.unwrap() {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.unwrap();
+                        assert!(matches!(import.ty, TypeRef::Func(_)));
+                        imports.push(format!("{}::{}", import.module, import.name));
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.unwrap();
+                        exports.push((export.name.to_owned(), export.index));
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            imports,
+            [
+                "contract_system_api::chain-id: func() -> unit",
+                "linera_profiler::enter",
+                "linera_profiler::exit"
+            ]
+        );
+        // Functions: import, 2 hooks, 2 originals, then the wrappers of `helper`, `entrypoint`
+        // and `chain-id`.
+        assert_eq!(
+            exports,
+            [("memory".to_owned(), 0), ("entrypoint".to_owned(), 6)]
+        );
+    }
+
+    /// Tests that the cost of the instrumentation is excluded from the profile.
+    #[test]
+    fn excludes_instrumentation_overhead() {
+        let wat = r#"
+            (module
+              (func $leaf (param i32 i32)
+                return)
+              (func $entrypoint
+                i32.const 1
+                i32.const 2
+                call $leaf
+                return)
+              (memory (;0;) 1)
+              (export "memory" (memory 0))
+              (export "entrypoint" (func $entrypoint))
+            )
+        "#;
+        let bytecode = Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned());
+        let (_, functions) = instrument(&bytecode).unwrap();
+        let fuel_profiler = FuelProfiler::default();
+        let mut profiler = InstanceProfiler::new(Some(functions));
+
+        // Simulates the fuel readings of the hooks, where the wrappers of `entrypoint` (no
+        // parameters) and `leaf` (two parameters) cost 6 and 8 units of fuel.
+        profiler.start(Some(fuel_profiler.clone()), 100);
+        profiler.enter(1, 102);
+        profiler.enter(0, 107);
+        profiler.exit(0, 113);
+        profiler.exit(1, 118);
+        let overhead = profiler.finish(119);
+
+        assert_eq!(overhead, 6 + 8);
+        let profile = fuel_profiler.take();
+        assert_eq!(
+            profile.to_string(),
+            "entrypoint 4\n\
+            entrypoint;leaf 1\n"
+        );
+        assert_eq!(profile.total_fuel() + overhead, 19);
+    }
+}
@@ -205,7 +205,7 @@ impl<'bytecode> Sanitizer<'bytecode> {
 
     /// Converts function locals parsed by [`wasmparser`] into locals encodable with
     /// [`wasm-encoder`].
-    fn convert_locals(
+    pub(super) fn convert_locals(
         locals: impl IntoIterator<Item = Result<(u32, wasmparser::ValType), BinaryReaderError>>,
     ) -> Result<Vec<(u32, wasm_encoder::ValType)>, BinaryReaderError> {
         locals
@@ -218,7 +218,7 @@ impl<'bytecode> Sanitizer<'bytecode> {
 
     /// Converts a WebAssembly type parsed by [`wasmparser`] into a type encodable with
     /// [`wasm_encoder`].
-    fn convert_type(parsed_type: wasmparser::ValType) -> wasm_encoder::ValType {
+    pub(super) fn convert_type(parsed_type: wasmparser::ValType) -> wasm_encoder::ValType {
         match parsed_type {
             wasmparser::ValType::I32 => wasm_encoder::ValType::I32,
             wasmparser::ValType::I64 => wasm_encoder::ValType::I64,
@@ -237,7 +237,7 @@ impl<'bytecode> Sanitizer<'bytecode> {
 
     /// Converts a WebAssembly heap type parsed by [`wasmparser`] into a heap type encodable with
     /// [`wasm_encoder`].
-    fn convert_heap_type(parsed_type: wasmparser::HeapType) -> wasm_encoder::HeapType {
+    pub(super) fn convert_heap_type(parsed_type: wasmparser::HeapType) -> wasm_encoder::HeapType {
         match parsed_type {
             wasmparser::HeapType::Func => wasm_encoder::HeapType::Func,
             wasmparser::HeapType::Extern => wasm_encoder::HeapType::Extern,
@@ -249,7 +249,7 @@ impl<'bytecode> Sanitizer<'bytecode> {
 
     /// Converts WebAssembly instructions parsed by [`wasmparser`] into instructions encodable with
     /// [`wasm-encoder`].
-    fn convert_operators<'op>(
+    pub(super) fn convert_operators<'op>(
         operators: impl IntoIterator<Item = Result<wasmparser::Operator<'op>, BinaryReaderError>>,
     ) -> Result<Vec<wasm_encoder::Instruction<'op>>, BinaryReaderError> {
         operators
@@ -16,20 +16,29 @@ macro_rules! impl_contract_system_api {
             }
 
             fn chain_id(&mut self) -> Result<contract_system_api::ChainId, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.chain_id.fuel(0))?;
                 BaseRuntime::chain_id(self).map(|chain_id| chain_id.into())
             }
 
             fn application_id(
                 &mut self,
             ) -> Result<contract_system_api::ApplicationId, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.application_id.fuel(0))?;
                 BaseRuntime::application_id(self).map(|application_id| application_id.into())
             }
 
             fn application_parameters(&mut self) -> Result<Vec<u8>, Self::Error> {
-                BaseRuntime::application_parameters(self)
+                let parameters = BaseRuntime::application_parameters(self)?;
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.application_parameters.fuel(parameters.len())
+                })?;
+                Ok(parameters)
             }
 
             fn read_chain_balance(&mut self) -> Result<contract_system_api::Amount, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_chain_balance.fuel(0)
+                })?;
                 BaseRuntime::read_chain_balance(self).map(|balance| balance.into())
             }
 
@@ -37,6 +46,9 @@ macro_rules! impl_contract_system_api {
                 &mut self,
                 owner: contract_system_api::Owner,
             ) -> Result<contract_system_api::Amount, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_owner_balance.fuel(0)
+                })?;
                 BaseRuntime::read_owner_balance(self, owner.into()).map(|balance| balance.into())
             }
 
@@ -46,6 +58,7 @@ macro_rules! impl_contract_system_api {
                 destination: contract_system_api::Account,
                 amount: contract_system_api::Amount,
             ) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.transfer.fuel(0))?;
                 ContractRuntime::transfer(
                     self,
                     source.map(|source| source.into()),
@@ -60,18 +73,73 @@ macro_rules! impl_contract_system_api {
                 destination: contract_system_api::Account,
                 amount: contract_system_api::Amount,
             ) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.claim.fuel(0))?;
                 ContractRuntime::claim(self, source.into(), destination.into(), amount.into())
             }
 
+            fn read_application_balance(
+                &mut self,
+            ) -> Result<contract_system_api::Amount, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_application_balance.fuel(0)
+                })?;
+                ContractRuntime::read_application_balance(self).map(|balance| balance.into())
+            }
+
+            fn fund_application(
+                &mut self,
+                source: Option<contract_system_api::Owner>,
+                amount: contract_system_api::Amount,
+            ) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.fund_application.fuel(0))?;
+                ContractRuntime::fund_application(
+                    self,
+                    source.map(|source| source.into()),
+                    amount.into(),
+                )
+            }
+
+            fn withdraw_from_application(
+                &mut self,
+                destination: contract_system_api::Account,
+                amount: contract_system_api::Amount,
+            ) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.withdraw_from_application.fuel(0)
+                })?;
+                ContractRuntime::withdraw_from_application(self, destination.into(), amount.into())
+            }
+
+            fn set_fee_sponsorship(
+                &mut self,
+                sponsorship: Option<contract_system_api::FeeSponsorship>,
+            ) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.set_fee_sponsorship.fuel(0)
+                })?;
+                ContractRuntime::set_fee_sponsorship(self, sponsorship.map(Into::into))
+            }
+
             fn read_system_timestamp(
                 &mut self,
             ) -> Result<contract_system_api::Timestamp, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_system_timestamp.fuel(0)
+                })?;
                 BaseRuntime::read_system_timestamp(self).map(|timestamp| timestamp.micros())
             }
 
+            fn read_block_randomness(
+                &mut self,
+            ) -> Result<Option<contract_system_api::CryptoHash>, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.block_randomness.fuel(0))?;
+                ContractRuntime::block_randomness(self).map(|value| value.map(|value| value.into()))
+            }
+
             fn chain_ownership(
                 &mut self,
             ) -> Result<contract_system_api::ChainOwnershipResult, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.chain_ownership.fuel(0))?;
                 BaseRuntime::chain_ownership(self).map(Into::into)
             }
 
@@ -80,14 +148,41 @@ macro_rules! impl_contract_system_api {
                 chain_ownership: contract_system_api::ChainOwnershipParam,
                 balance: contract_system_api::Amount,
             ) -> Result<contract_system_api::ChainId, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.open_chain.fuel(0))?;
                 ContractRuntime::open_chain(self, chain_ownership.into(), balance.into())
                     .map(Into::into)
             }
 
             fn close_chain(&mut self) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.close_chain.fuel(0))?;
                 ContractRuntime::close_chain(self)
             }
 
+            fn send_request(
+                &mut self,
+                destination: contract_system_api::ChainId,
+                authenticated: bool,
+                message: &[u8],
+            ) -> Result<contract_system_api::MessageId, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.send_request.fuel(message.len())
+                })?;
+                ContractRuntime::send_request(
+                    self,
+                    destination.into(),
+                    authenticated,
+                    message.to_vec(),
+                )
+                .map(Into::into)
+            }
+
+            fn send_response(&mut self, message: &[u8]) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.send_response.fuel(message.len())
+                })?;
+                ContractRuntime::send_response(self, message.to_vec())
+            }
+
             fn error_to_closechainerror(
                 &mut self,
                 error: Self::Error,
@@ -113,14 +208,22 @@ macro_rules! impl_contract_system_api {
                     .map(SessionId::from)
                     .collect();
 
-                ContractRuntime::try_call_application(
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.try_call_application.fuel(argument.len())
+                })?;
+                let call_outcome = ContractRuntime::try_call_application(
                     self,
                     authenticated,
                     application.into(),
                     argument.to_vec(),
                     forwarded_sessions,
-                )
-                .map(|call_outcome| call_outcome.into())
+                )?;
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs
+                        .try_call_application
+                        .per_byte_fuel(call_outcome.value.len())
+                })?;
+                Ok(call_outcome.into())
             }
 
             fn try_call_session(
@@ -136,14 +239,22 @@ macro_rules! impl_contract_system_api {
                     .map(SessionId::from)
                     .collect();
 
-                ContractRuntime::try_call_session(
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.try_call_session.fuel(argument.len())
+                })?;
+                let call_outcome = ContractRuntime::try_call_session(
                     self,
                     authenticated,
                     session.into(),
                     argument.to_vec(),
                     forwarded_sessions,
-                )
-                .map(|call_outcome| call_outcome.into())
+                )?;
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs
+                        .try_call_session
+                        .per_byte_fuel(call_outcome.value.len())
+                })?;
+                Ok(call_outcome.into())
             }
 
             fn log(
@@ -151,14 +262,8 @@ macro_rules! impl_contract_system_api {
                 message: &str,
                 level: contract_system_api::LogLevel,
             ) -> Result<(), Self::Error> {
-                match level {
-                    contract_system_api::LogLevel::Trace => tracing::trace!("{message}"),
-                    contract_system_api::LogLevel::Debug => tracing::debug!("{message}"),
-                    contract_system_api::LogLevel::Info => tracing::info!("{message}"),
-                    contract_system_api::LogLevel::Warn => tracing::warn!("{message}"),
-                    contract_system_api::LogLevel::Error => tracing::error!("{message}"),
-                }
-                Ok(())
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.log.fuel(message.len()))?;
+                ContractRuntime::log(self, level.into(), message)
             }
         }
     };
@@ -179,18 +284,27 @@ macro_rules! impl_service_system_api {
             }
 
             fn chain_id(&mut self) -> Result<service_system_api::ChainId, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.chain_id.fuel(0))?;
                 BaseRuntime::chain_id(self).map(|chain_id| chain_id.into())
             }
 
             fn application_id(&mut self) -> Result<service_system_api::ApplicationId, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.application_id.fuel(0))?;
                 BaseRuntime::application_id(self).map(|application_id| application_id.into())
             }
 
             fn application_parameters(&mut self) -> Result<Vec<u8>, Self::Error> {
-                BaseRuntime::application_parameters(self)
+                let parameters = BaseRuntime::application_parameters(self)?;
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.application_parameters.fuel(parameters.len())
+                })?;
+                Ok(parameters)
             }
 
             fn read_chain_balance(&mut self) -> Result<service_system_api::Amount, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_chain_balance.fuel(0)
+                })?;
                 BaseRuntime::read_chain_balance(self).map(|balance| balance.into())
             }
 
@@ -198,12 +312,18 @@ macro_rules! impl_service_system_api {
                 &mut self,
                 owner: service_system_api::Owner,
             ) -> Result<service_system_api::Amount, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_owner_balance.fuel(0)
+                })?;
                 BaseRuntime::read_owner_balance(self, owner.into()).map(|balance| balance.into())
             }
 
             fn read_system_timestamp(
                 &mut self,
             ) -> Result<service_system_api::Timestamp, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.read_system_timestamp.fuel(0)
+                })?;
                 BaseRuntime::read_system_timestamp(self).map(|timestamp| timestamp.micros())
             }
 
@@ -212,7 +332,50 @@ macro_rules! impl_service_system_api {
                 application: service_system_api::ApplicationId,
                 argument: &[u8],
             ) -> Result<Vec<u8>, Self::Error> {
-                ServiceRuntime::try_query_application(self, application.into(), argument.to_vec())
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.try_query_application.fuel(argument.len())
+                })?;
+                let response = ServiceRuntime::try_query_application(
+                    self,
+                    application.into(),
+                    argument.to_vec(),
+                )?;
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.try_query_application.per_byte_fuel(response.len())
+                })?;
+                Ok(response)
+            }
+
+            fn http_request(
+                &mut self,
+                method: service_system_api::HttpMethod,
+                url: &str,
+                headers: Vec<(&str, &str)>,
+                body: &[u8],
+            ) -> Result<Result<service_system_api::HttpResponse, String>, Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| {
+                    costs.http_request.fuel(body.len())
+                })?;
+                let request = crate::http::HttpRequest {
+                    method: method.into(),
+                    url: url.to_owned(),
+                    headers: headers
+                        .into_iter()
+                        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                        .collect(),
+                    body: body.to_vec(),
+                };
+                match ServiceRuntime::http_request(self, request) {
+                    Ok(response) => {
+                        BaseRuntime::consume_host_call_fuel(self, |costs| {
+                            costs.http_request.per_byte_fuel(response.body.len())
+                        })?;
+                        Ok(Ok(response.into()))
+                    }
+                    // Services may handle failed requests, e.g. by omitting some data.
+                    Err(ExecutionError::HttpError(error)) => Ok(Err(error.to_string())),
+                    Err(error) => Err(error),
+                }
             }
 
             fn log(
@@ -220,6 +383,7 @@ macro_rules! impl_service_system_api {
                 message: &str,
                 level: service_system_api::LogLevel,
             ) -> Result<(), Self::Error> {
+                BaseRuntime::consume_host_call_fuel(self, |costs| costs.log.fuel(message.len()))?;
                 match level {
                     service_system_api::LogLevel::Trace => tracing::trace!("{message}"),
                     service_system_api::LogLevel::Debug => tracing::debug!("{message}"),
@@ -254,6 +418,7 @@ macro_rules! impl_view_system_api {
             }
 
             fn contains_key_new(&mut self, key: &[u8]) -> Result<Self::ContainsKey, Self::Error> {
+                self.consume_host_call_fuel(|costs| costs.contains_key.fuel(key.len()))?;
                 self.contains_key_new(key.to_vec())
             }
 
@@ -268,6 +433,8 @@ macro_rules! impl_view_system_api {
                 &mut self,
                 keys: Vec<&[u8]>,
             ) -> Result<Self::ReadMultiValuesBytes, Self::Error> {
+                let keys_size = keys.iter().map(|key| key.len()).sum();
+                self.consume_host_call_fuel(|costs| costs.read_multi_values_bytes.fuel(keys_size))?;
                 let keys = keys.into_iter().map(Vec::from).collect();
                 self.read_multi_values_bytes_new(keys)
             }
@@ -276,13 +443,19 @@ macro_rules! impl_view_system_api {
                 &mut self,
                 promise: &Self::ReadMultiValuesBytes,
             ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
-                self.read_multi_values_bytes_wait(promise)
+                let values = self.read_multi_values_bytes_wait(promise)?;
+                let values_size = values.iter().flatten().map(|value| value.len()).sum();
+                self.consume_host_call_fuel(|costs| {
+                    costs.read_multi_values_bytes.per_byte_fuel(values_size)
+                })?;
+                Ok(values)
             }
 
             fn read_value_bytes_new(
                 &mut self,
                 key: &[u8],
             ) -> Result<Self::ReadValueBytes, Self::Error> {
+                self.consume_host_call_fuel(|costs| costs.read_value_bytes.fuel(key.len()))?;
                 self.read_value_bytes_new(key.to_vec())
             }
 
@@ -290,10 +463,16 @@ macro_rules! impl_view_system_api {
                 &mut self,
                 promise: &Self::ReadValueBytes,
             ) -> Result<Option<Vec<u8>>, Self::Error> {
-                self.read_value_bytes_wait(promise)
+                let value = self.read_value_bytes_wait(promise)?;
+                let value_size = value.as_ref().map_or(0, Vec::len);
+                self.consume_host_call_fuel(|costs| {
+                    costs.read_value_bytes.per_byte_fuel(value_size)
+                })?;
+                Ok(value)
             }
 
             fn find_keys_new(&mut self, key_prefix: &[u8]) -> Result<Self::FindKeys, Self::Error> {
+                self.consume_host_call_fuel(|costs| costs.find_keys.fuel(key_prefix.len()))?;
                 self.find_keys_by_prefix_new(key_prefix.to_vec())
             }
 
@@ -301,13 +480,17 @@ macro_rules! impl_view_system_api {
                 &mut self,
                 promise: &Self::FindKeys,
             ) -> Result<Vec<Vec<u8>>, Self::Error> {
-                self.find_keys_by_prefix_wait(promise)
+                let keys = self.find_keys_by_prefix_wait(promise)?;
+                let keys_size = keys.iter().map(Vec::len).sum();
+                self.consume_host_call_fuel(|costs| costs.find_keys.per_byte_fuel(keys_size))?;
+                Ok(keys)
             }
 
             fn find_key_values_new(
                 &mut self,
                 key_prefix: &[u8],
             ) -> Result<Self::FindKeyValues, Self::Error> {
+                self.consume_host_call_fuel(|costs| costs.find_key_values.fuel(key_prefix.len()))?;
                 self.find_key_values_by_prefix_new(key_prefix.to_vec())
             }
 
@@ -315,7 +498,15 @@ macro_rules! impl_view_system_api {
                 &mut self,
                 promise: &Self::FindKeyValues,
             ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
-                self.find_key_values_by_prefix_wait(promise)
+                let key_values = self.find_key_values_by_prefix_wait(promise)?;
+                let key_values_size = key_values
+                    .iter()
+                    .map(|(key, value)| key.len() + value.len())
+                    .sum();
+                self.consume_host_call_fuel(|costs| {
+                    costs.find_key_values.per_byte_fuel(key_values_size)
+                })?;
+                Ok(key_values)
             }
 
             // TODO(#1153): the wit name is wrong
@@ -337,6 +528,7 @@ macro_rules! impl_view_system_api {
                         }
                     }
                 }
+                self.consume_host_call_fuel(|costs| costs.write_batch.fuel(batch.size()))?;
                 // Hack: The following is a no-op for services.
                 self.write_batch(batch)
             }
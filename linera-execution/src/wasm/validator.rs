@@ -0,0 +1,796 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validator of WebAssembly bytecodes, ensuring they can be executed deterministically by all
+//! validators before they are published.
+//!
+//! When a bytecode is published, it is validated with the rules selected by the
+//! [`ResourceControlPolicy`] of the committee. Under the latest rules, a contract or service
+//! bytecode is rejected if:
+//!
+//! - it is larger than the configured limit;
+//! - it is not a valid WebAssembly module;
+//! - it uses features outside of the allowed set, which excludes SIMD and atomic instructions,
+//!   as their results may differ between machines;
+//! - it is a contract with instructions outside of the allow-list of deterministic
+//!   instructions, which excludes all floating-point instructions;
+//! - it imports anything other than functions from the system APIs it is allowed to use;
+//! - it does not export its linear memory and all the entrypoints of its interface;
+//! - it declares a linear memory larger than the configured limit.
+//!
+//! Services are allowed to use floating-point instructions, since they are only run locally to
+//! answer queries and never when executing blocks.
+//!
+//! When a bytecode is loaded to be run, it is validated again with the [`LOADING_RULES`], which
+//! only check what both runtimes need to run it: a valid module with the allowed features and
+//! the expected interface. This catches bytecodes published before they were validated, while
+//! bytecodes accepted under an older policy stay loadable.
+
+use crate::{policy::ResourceControlPolicy, Bytecode};
+use std::collections::BTreeSet;
+use thiserror::Error;
+use wasmparser::{
+    BinaryReaderError, FunctionBody, MemorySectionReader, Operator, Parser, Payload, TypeRef,
+    Validator, WasmFeatures,
+};
+
+/// The name of the module used by WIT bindings to import the destructors of resources.
+const CANONICAL_ABI_MODULE: &str = "canonical_abi";
+
+/// The name of the linear memory that must be exported by all modules.
+const MEMORY_EXPORT: &str = "memory";
+
+/// The version of the latest validation rules.
+///
+/// Changing the rules requires a new version, so that validators only apply them once the
+/// [`ResourceControlPolicy`] of their committee selects it. The versions are:
+///
+/// - 0: only the size of the bytecode and of its memory are checked;
+/// - 1: the module must also be valid, use only the allowed features and implement the expected
+///   interface;
+/// - 2: contracts must also only use the allow-list of deterministic instructions.
+pub const LATEST_BYTECODE_RULES: u32 = 2;
+
+/// The rules checked whenever a bytecode is loaded, whatever the policy was when it was
+/// published.
+pub const LOADING_RULES: u32 = 1;
+
+/// The limits that a bytecode must respect in order to be accepted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BytecodeLimits {
+    /// The maximum size of the bytecode, in bytes.
+    pub maximum_bytecode_size: u64,
+    /// The maximum number of 64 KiB pages the module may declare for its linear memory.
+    pub maximum_memory_pages: u64,
+    /// The version of the rules checking the instructions and the interface of the module, or
+    /// zero to only check the size limits.
+    pub rules_version: u32,
+}
+
+impl Default for BytecodeLimits {
+    fn default() -> Self {
+        BytecodeLimits {
+            maximum_bytecode_size: u64::MAX,
+            maximum_memory_pages: u64::MAX,
+            rules_version: LATEST_BYTECODE_RULES,
+        }
+    }
+}
+
+impl From<&ResourceControlPolicy> for BytecodeLimits {
+    fn from(policy: &ResourceControlPolicy) -> Self {
+        BytecodeLimits {
+            maximum_bytecode_size: policy.maximum_bytecode_size,
+            maximum_memory_pages: policy.maximum_wasm_memory_pages,
+            rules_version: policy.bytecode_rules_version,
+        }
+    }
+}
+
+/// The imports and exports expected from one of the parts of an application.
+struct Interface {
+    kind: &'static str,
+    imported_modules: &'static [&'static str],
+    exported_functions: &'static [&'static str],
+    /// Whether the module is run when executing blocks, and must therefore only use
+    /// deterministic instructions.
+    is_deterministic: bool,
+}
+
+/// The interface of the contract part of an application, as described by `contract.wit`.
+const CONTRACT_INTERFACE: Interface = Interface {
+    kind: "contract",
+    imported_modules: &[
+        "contract_system_api",
+        "view_system_api",
+        CANONICAL_ABI_MODULE,
+    ],
+    exported_functions: &[
+        "initialize",
+        "execute-operation",
+        "execute-message",
+        "handle-application-call",
+        "handle-session-call",
+    ],
+    is_deterministic: true,
+};
+
+/// The interface of the service part of an application, as described by `service.wit`.
+const SERVICE_INTERFACE: Interface = Interface {
+    kind: "service",
+    imported_modules: &[
+        "service_system_api",
+        "view_system_api",
+        CANONICAL_ABI_MODULE,
+    ],
+    exported_functions: &["handle-query"],
+    is_deterministic: false,
+};
+
+/// Validates both the contract and service bytecodes of an application.
+pub fn validate_application(
+    contract: &Bytecode,
+    service: &Bytecode,
+    limits: &BytecodeLimits,
+) -> Result<(), BytecodeValidationError> {
+    validate_contract(contract, limits)?;
+    validate_service(service, limits)
+}
+
+/// Validates the bytecode of the contract part of an application.
+pub fn validate_contract(
+    bytecode: &Bytecode,
+    limits: &BytecodeLimits,
+) -> Result<(), BytecodeValidationError> {
+    validate(bytecode.as_ref(), &CONTRACT_INTERFACE, limits)
+}
+
+/// Validates the bytecode of the service part of an application.
+pub fn validate_service(
+    bytecode: &Bytecode,
+    limits: &BytecodeLimits,
+) -> Result<(), BytecodeValidationError> {
+    validate(bytecode.as_ref(), &SERVICE_INTERFACE, limits)
+}
+
+/// Validates the bytecode of a contract that is being loaded, using the [`LOADING_RULES`].
+#[cfg(with_wasm_runtime)]
+pub fn validate_loaded_contract(bytecode: &Bytecode) -> Result<(), BytecodeValidationError> {
+    validate(bytecode.as_ref(), &CONTRACT_INTERFACE, &loading_limits())
+}
+
+/// Validates the bytecode of a service that is being loaded, using the [`LOADING_RULES`].
+#[cfg(with_wasm_runtime)]
+pub fn validate_loaded_service(bytecode: &Bytecode) -> Result<(), BytecodeValidationError> {
+    validate(bytecode.as_ref(), &SERVICE_INTERFACE, &loading_limits())
+}
+
+/// Returns the limits checked when loading a bytecode. The size limits depend on the policy at
+/// the time the bytecode was published, so they are only checked at that time.
+#[cfg(with_wasm_runtime)]
+fn loading_limits() -> BytecodeLimits {
+    BytecodeLimits {
+        rules_version: LOADING_RULES,
+        ..BytecodeLimits::default()
+    }
+}
+
+/// Validates the `bytecode` of a module that should implement the `interface`.
+fn validate(
+    bytecode: &[u8],
+    interface: &Interface,
+    limits: &BytecodeLimits,
+) -> Result<(), BytecodeValidationError> {
+    let kind = interface.kind;
+    let size = bytecode.len() as u64;
+    if size > limits.maximum_bytecode_size {
+        return Err(BytecodeValidationError::BytecodeTooLarge {
+            kind,
+            size,
+            maximum: limits.maximum_bytecode_size,
+        });
+    }
+
+    let check_instructions = match limits.rules_version {
+        0 => return validate_memory(bytecode, kind, limits),
+        1 => false,
+        2 => interface.is_deterministic,
+        version => return Err(BytecodeValidationError::UnsupportedRules(version)),
+    };
+    let features = allowed_features();
+    Validator::new_with_features(features)
+        .validate_all(bytecode)
+        .map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+
+    let mut exports = BTreeSet::new();
+    for payload in Parser::new(0).parse_all(bytecode) {
+        let payload =
+            payload.map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+        match payload {
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import
+                        .map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+                    if !matches!(import.ty, TypeRef::Func(_))
+                        || !interface.imported_modules.contains(&import.module)
+                    {
+                        return Err(BytecodeValidationError::UnexpectedImport {
+                            kind,
+                            module: import.module.to_owned(),
+                            name: import.name.to_owned(),
+                        });
+                    }
+                }
+            }
+            Payload::MemorySection(reader) => check_memory_section(reader, kind, limits)?,
+            Payload::CodeSectionEntry(body) if check_instructions => {
+                check_instructions_are_deterministic(body, kind)?
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export
+                        .map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+                    exports.insert(export.name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let is_exported = |expected: &str| {
+        exports.iter().any(|name| {
+            // WIT bindings may append the signature of the function to its name.
+            *name == expected
+                || name
+                    .strip_prefix(expected)
+                    .map_or(false, |signature| signature.starts_with(':'))
+        })
+    };
+    for &name in [MEMORY_EXPORT].iter().chain(interface.exported_functions) {
+        if !is_exported(name) {
+            return Err(BytecodeValidationError::MissingExport { kind, name });
+        }
+    }
+
+    Ok(())
+}
+
+/// Only checks the memory declared by the `bytecode`, which is all that version zero of the
+/// rules requires besides the bytecode size.
+fn validate_memory(
+    bytecode: &[u8],
+    kind: &'static str,
+    limits: &BytecodeLimits,
+) -> Result<(), BytecodeValidationError> {
+    for payload in Parser::new(0).parse_all(bytecode) {
+        let payload =
+            payload.map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+        if let Payload::MemorySection(reader) = payload {
+            check_memory_section(reader, kind, limits)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rejects the memories declared in a module if they are larger than the `limits`.
+fn check_memory_section(
+    reader: MemorySectionReader<'_>,
+    kind: &'static str,
+    limits: &BytecodeLimits,
+) -> Result<(), BytecodeValidationError> {
+    for memory in reader {
+        let memory =
+            memory.map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+        let pages = memory.maximum.unwrap_or(memory.initial).max(memory.initial);
+        if pages > limits.maximum_memory_pages {
+            return Err(BytecodeValidationError::MemoryTooLarge {
+                kind,
+                pages,
+                maximum: limits.maximum_memory_pages,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Rejects the function `body` if it uses an instruction outside of the allow-list of
+/// deterministic instructions.
+fn check_instructions_are_deterministic(
+    body: FunctionBody<'_>,
+    kind: &'static str,
+) -> Result<(), BytecodeValidationError> {
+    let mut reader = body
+        .get_operators_reader()
+        .map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+    while !reader.eof() {
+        let (operator, offset) = reader
+            .read_with_offset()
+            .map_err(|error| BytecodeValidationError::InvalidModule { kind, error })?;
+        if !is_deterministic(&operator) {
+            return Err(BytecodeValidationError::ForbiddenInstruction {
+                kind,
+                instruction: format!("{operator:?}"),
+                offset,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The allow-list of instructions that contracts may use.
+///
+/// It contains the control, variable, memory, reference, bulk memory and integer instructions.
+/// Floating-point instructions are excluded: even though the runtimes canonicalize the NaNs
+/// they produce, their results depend on the compiler and the machine in ways that have caused
+/// validators to disagree. The other instructions are rejected by [`allowed_features`] anyway.
+fn is_deterministic(operator: &Operator<'_>) -> bool {
+    use Operator::*;
+
+    matches!(
+        operator,
+        // Control instructions.
+        Unreachable
+            | Nop
+            | Block { .. }
+            | Loop { .. }
+            | If { .. }
+            | Else
+            | End
+            | Br { .. }
+            | BrIf { .. }
+            | BrTable { .. }
+            | Return
+            | Call { .. }
+            | CallIndirect { .. }
+            // Parametric and variable instructions.
+            | Drop
+            | Select
+            | LocalGet { .. }
+            | LocalSet { .. }
+            | LocalTee { .. }
+            | GlobalGet { .. }
+            | GlobalSet { .. }
+            // Memory instructions.
+            | I32Load { .. }
+            | I64Load { .. }
+            | I32Load8S { .. }
+            | I32Load8U { .. }
+            | I32Load16S { .. }
+            | I32Load16U { .. }
+            | I64Load8S { .. }
+            | I64Load8U { .. }
+            | I64Load16S { .. }
+            | I64Load16U { .. }
+            | I64Load32S { .. }
+            | I64Load32U { .. }
+            | I32Store { .. }
+            | I64Store { .. }
+            | I32Store8 { .. }
+            | I32Store16 { .. }
+            | I64Store8 { .. }
+            | I64Store16 { .. }
+            | I64Store32 { .. }
+            | MemorySize { .. }
+            | MemoryGrow { .. }
+            | MemoryInit { .. }
+            | DataDrop { .. }
+            | MemoryCopy { .. }
+            | MemoryFill { .. }
+            // Reference and table instructions.
+            | RefNull { .. }
+            | RefIsNull
+            | RefFunc { .. }
+            | TableInit { .. }
+            | ElemDrop { .. }
+            | TableCopy { .. }
+            | TableGet { .. }
+            | TableSet { .. }
+            | TableGrow { .. }
+            | TableSize { .. }
+            | TableFill { .. }
+            // Integer instructions.
+            | I32Const { .. }
+            | I64Const { .. }
+            | I32Eqz
+            | I32Eq
+            | I32Ne
+            | I32LtS
+            | I32LtU
+            | I32GtS
+            | I32GtU
+            | I32LeS
+            | I32LeU
+            | I32GeS
+            | I32GeU
+            | I64Eqz
+            | I64Eq
+            | I64Ne
+            | I64LtS
+            | I64LtU
+            | I64GtS
+            | I64GtU
+            | I64LeS
+            | I64LeU
+            | I64GeS
+            | I64GeU
+            | I32Clz
+            | I32Ctz
+            | I32Popcnt
+            | I32Add
+            | I32Sub
+            | I32Mul
+            | I32DivS
+            | I32DivU
+            | I32RemS
+            | I32RemU
+            | I32And
+            | I32Or
+            | I32Xor
+            | I32Shl
+            | I32ShrS
+            | I32ShrU
+            | I32Rotl
+            | I32Rotr
+            | I64Clz
+            | I64Ctz
+            | I64Popcnt
+            | I64Add
+            | I64Sub
+            | I64Mul
+            | I64DivS
+            | I64DivU
+            | I64RemS
+            | I64RemU
+            | I64And
+            | I64Or
+            | I64Xor
+            | I64Shl
+            | I64ShrS
+            | I64ShrU
+            | I64Rotl
+            | I64Rotr
+            | I32WrapI64
+            | I64ExtendI32S
+            | I64ExtendI32U
+            | I32Extend8S
+            | I32Extend16S
+            | I64Extend8S
+            | I64Extend16S
+            | I64Extend32S
+    )
+}
+
+/// The WebAssembly features that bytecodes are allowed to use.
+///
+/// Floating-point instructions are part of the core specification, so they are only excluded
+/// from contracts by [`is_deterministic`]. SIMD, atomic and other proposals are excluded because
+/// they are either non-deterministic or not supported by all the runtimes.
+fn allowed_features() -> WasmFeatures {
+    WasmFeatures {
+        simd: false,
+        relaxed_simd: false,
+        threads: false,
+        tail_call: false,
+        multi_memory: false,
+        exceptions: false,
+        memory64: false,
+        component_model: false,
+        ..WasmFeatures::default()
+    }
+}
+
+/// Reasons for rejecting a bytecode.
+#[derive(Debug, Error)]
+pub enum BytecodeValidationError {
+    #[error("Version {0} of the bytecode validation rules is not supported")]
+    UnsupportedRules(u32),
+    #[error("The {kind} bytecode has {size} bytes, exceeding the limit of {maximum} bytes")]
+    BytecodeTooLarge {
+        kind: &'static str,
+        size: u64,
+        maximum: u64,
+    },
+    #[error("The {kind} bytecode is invalid or uses a forbidden instruction: {error}")]
+    InvalidModule {
+        kind: &'static str,
+        #[source]
+        error: BinaryReaderError,
+    },
+    #[error("The {kind} bytecode uses the forbidden instruction {instruction} at offset {offset}")]
+    ForbiddenInstruction {
+        kind: &'static str,
+        instruction: String,
+        offset: usize,
+    },
+    #[error("The {kind} bytecode has an unexpected import `{name}` from module `{module}`")]
+    UnexpectedImport {
+        kind: &'static str,
+        module: String,
+        name: String,
+    },
+    #[error("The {kind} bytecode does not export `{name}`")]
+    MissingExport {
+        kind: &'static str,
+        name: &'static str,
+    },
+    #[error("The {kind} bytecode declares a memory of {pages} pages, exceeding the limit of {maximum} pages")]
+    MemoryTooLarge {
+        kind: &'static str,
+        pages: u64,
+        maximum: u64,
+    },
+}
+
+#[cfg(all(test, with_wasmer))]
+mod tests {
+    use super::{
+        validate_application, validate_contract, validate_loaded_contract, validate_loaded_service,
+        validate_service, BytecodeLimits, BytecodeValidationError, LATEST_BYTECODE_RULES,
+    };
+    use crate::{wasm::test::get_example_bytecode_paths, Bytecode};
+    use assert_matches::assert_matches;
+
+    /// Compiles a service module whose `handle-query` function has the provided `body`.
+    fn service_with_body(body: &str) -> Bytecode {
+        let wat = format!(
+            r#"
+            (module
+              (import "service_system_api" "log: func(message: string, level: enum) -> unit"
+                (func $log (param i32 i32 i32)))
+              (func $handle_query (param i32) (result i32)
+                {body}
+              )
+              (memory (;0;) 16)
+              (export "memory" (memory 0))
+              (export "handle-query" (func $handle_query))
+            )
+            "#
+        );
+        Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned())
+    }
+
+    /// Compiles a contract module whose `execute-operation` function has the provided `body`.
+    fn contract_with_body(body: &str) -> Bytecode {
+        let wat = format!(
+            r#"
+            (module
+              (func $entrypoint (param i32) (result i32)
+                {body}
+              )
+              (memory (;0;) 16)
+              (export "memory" (memory 0))
+              (export "initialize" (func $entrypoint))
+              (export "execute-operation" (func $entrypoint))
+              (export "execute-message" (func $entrypoint))
+              (export "handle-application-call" (func $entrypoint))
+              (export "handle-session-call" (func $entrypoint))
+            )
+            "#
+        );
+        Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned())
+    }
+
+    /// A function body using floating-point instructions.
+    const FLOATING_POINT_BODY: &str =
+        "local.get 0 f32.convert_i32_s f32.const 0.5 f32.mul i32.trunc_f32_s return";
+
+    /// Tests that a module with only integer instructions and the expected interface is accepted.
+    #[test]
+    fn accepts_deterministic_bytecode() {
+        let bytecode = service_with_body("local.get 0 i32.const 1 i32.add return");
+
+        validate_service(&bytecode, &BytecodeLimits::default()).unwrap();
+    }
+
+    /// Tests that contracts with only integer instructions are accepted.
+    #[test]
+    fn accepts_deterministic_contracts() {
+        let bytecode = contract_with_body("local.get 0 i64.extend_i32_u i32.wrap_i64 return");
+
+        validate_contract(&bytecode, &BytecodeLimits::default()).unwrap();
+    }
+
+    /// Tests that floating-point instructions are rejected in contracts, which are run when
+    /// executing blocks, unless an older version of the rules is used.
+    #[test]
+    fn rejects_floating_point_instructions_in_contracts() {
+        let bytecode = contract_with_body(FLOATING_POINT_BODY);
+
+        assert_matches!(
+            validate_contract(&bytecode, &BytecodeLimits::default()),
+            Err(BytecodeValidationError::ForbiddenInstruction {
+                kind: "contract",
+                instruction,
+                ..
+            }) if instruction == "F32ConvertI32S"
+        );
+
+        let limits = BytecodeLimits {
+            rules_version: 1,
+            ..BytecodeLimits::default()
+        };
+        validate_contract(&bytecode, &limits).unwrap();
+    }
+
+    /// Tests that floating-point instructions are accepted in services, which are never run
+    /// when executing blocks.
+    #[test]
+    fn accepts_floating_point_instructions_in_services() {
+        let bytecode = service_with_body(FLOATING_POINT_BODY);
+
+        validate_service(&bytecode, &BytecodeLimits::default()).unwrap();
+    }
+
+    /// Tests that loaded bytecodes are checked independently of the size limits and of the
+    /// allow-list of instructions, which depend on the policy when they were published.
+    #[test]
+    fn validates_loaded_bytecodes() {
+        let contract = contract_with_body(FLOATING_POINT_BODY);
+        validate_loaded_contract(&contract).unwrap();
+
+        let service = service_with_body("local.get 0 return");
+        validate_loaded_service(&service).unwrap();
+        assert_matches!(
+            validate_loaded_contract(&service),
+            Err(BytecodeValidationError::MissingExport {
+                kind: "contract",
+                ..
+            })
+        );
+
+        let service = service_with_body("local.get 0 i32x4.splat i32x4.extract_lane 0 return");
+        assert_matches!(
+            validate_loaded_service(&service),
+            Err(BytecodeValidationError::InvalidModule {
+                kind: "service",
+                ..
+            })
+        );
+    }
+
+    /// Tests that SIMD instructions are rejected.
+    #[test]
+    fn rejects_simd_instructions() {
+        let bytecode = service_with_body(
+            "local.get 0 i32x4.splat local.get 0 i32x4.splat i32x4.add i32x4.extract_lane 0 return",
+        );
+
+        assert_matches!(
+            validate_service(&bytecode, &BytecodeLimits::default()),
+            Err(BytecodeValidationError::InvalidModule {
+                kind: "service",
+                ..
+            })
+        );
+    }
+
+    /// Tests that the rules are selected by their version.
+    #[test]
+    fn applies_rules_of_the_configured_version() {
+        let bytecode = service_with_body("local.get 0 i32x4.splat i32x4.extract_lane 0 return");
+        let limits = BytecodeLimits {
+            rules_version: 0,
+            ..BytecodeLimits::default()
+        };
+        validate_service(&bytecode, &limits).unwrap();
+
+        let limits = BytecodeLimits {
+            rules_version: 0,
+            maximum_memory_pages: 15,
+            ..BytecodeLimits::default()
+        };
+        assert_matches!(
+            validate_service(&bytecode, &limits),
+            Err(BytecodeValidationError::MemoryTooLarge { pages: 16, .. })
+        );
+
+        let limits = BytecodeLimits {
+            rules_version: LATEST_BYTECODE_RULES + 1,
+            ..BytecodeLimits::default()
+        };
+        assert_matches!(
+            validate_service(&bytecode, &limits),
+            Err(BytecodeValidationError::UnsupportedRules(version))
+                if version == LATEST_BYTECODE_RULES + 1
+        );
+    }
+
+    /// Tests that imports from modules outside of the system API are rejected.
+    #[test]
+    fn rejects_unknown_imports() {
+        let wat = r#"
+            (module
+              (import "env" "clock" (func $clock (result i64)))
+              (func $handle_query (param i32) (result i32) local.get 0 return)
+              (memory (;0;) 16)
+              (export "memory" (memory 0))
+              (export "handle-query" (func $handle_query))
+            )
+        "#;
+        let bytecode = Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned());
+
+        assert_matches!(
+            validate_service(&bytecode, &BytecodeLimits::default()),
+            Err(BytecodeValidationError::UnexpectedImport { module, name, .. })
+                if module == "env" && name == "clock"
+        );
+    }
+
+    /// Tests that a module without the expected entrypoints is rejected.
+    #[test]
+    fn rejects_missing_exports() {
+        let wat = r#"
+            (module
+              (memory (;0;) 16)
+              (export "memory" (memory 0))
+            )
+        "#;
+        let bytecode = Bytecode::new(wasmer::wat2wasm(wat.as_bytes()).unwrap().into_owned());
+
+        assert_matches!(
+            validate_service(&bytecode, &BytecodeLimits::default()),
+            Err(BytecodeValidationError::MissingExport {
+                name: "handle-query",
+                ..
+            })
+        );
+    }
+
+    /// Tests that the size and memory limits are enforced.
+    #[test]
+    fn enforces_limits() {
+        let bytecode = service_with_body("local.get 0 return");
+        let size = bytecode.as_ref().len() as u64;
+
+        let limits = BytecodeLimits {
+            maximum_bytecode_size: size - 1,
+            ..BytecodeLimits::default()
+        };
+        assert_matches!(
+            validate_service(&bytecode, &limits),
+            Err(BytecodeValidationError::BytecodeTooLarge { maximum, .. }) if maximum == size - 1
+        );
+
+        let limits = BytecodeLimits {
+            maximum_memory_pages: 15,
+            ..BytecodeLimits::default()
+        };
+        assert_matches!(
+            validate_service(&bytecode, &limits),
+            Err(BytecodeValidationError::MemoryTooLarge { pages: 16, .. })
+        );
+
+        let limits = BytecodeLimits {
+            maximum_bytecode_size: size,
+            maximum_memory_pages: 16,
+            ..BytecodeLimits::default()
+        };
+        validate_service(&bytecode, &limits).unwrap();
+    }
+
+    /// Tests that the contracts and services of all the example applications are accepted.
+    #[test]
+    fn accepts_example_applications() {
+        for name in [
+            "amm",
+            "atomic-counter",
+            "counter",
+            "crowd-funding",
+            "fungible",
+            "matching-engine",
+            "meta-counter",
+            "native-fungible",
+            "social",
+        ] {
+            let (contract_path, service_path) = get_example_bytecode_paths(name).unwrap();
+            let contract = Bytecode::new(std::fs::read(contract_path).unwrap());
+            let service = Bytecode::new(std::fs::read(service_path).unwrap());
+
+            if let Err(error) =
+                validate_application(&contract, &service, &BytecodeLimits::default())
+            {
+                panic!("Example application {name} was rejected: {error}");
+            }
+        }
+    }
+}
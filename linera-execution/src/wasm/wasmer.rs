@@ -24,6 +24,9 @@ wit_bindgen_host_wasmer_rust::export!({
 // Import the interface implemented by a user contract.
 wit_bindgen_host_wasmer_rust::import!("contract.wit");
 
+// Import the optional migration entrypoint of a user contract.
+wit_bindgen_host_wasmer_rust::import!("contract_migration.wit");
+
 // Import the interface implemented by a user service.
 wit_bindgen_host_wasmer_rust::import!("service.wit");
 
@@ -32,7 +35,12 @@ mod conversions_from_wit;
 #[path = "conversions_to_wit.rs"]
 mod conversions_to_wit;
 
-use super::{module_cache::ModuleCache, WasmExecutionError};
+use super::{
+    module_cache::ModuleCache,
+    persistent_cache::{self, ArtifactKey},
+    profiler::{InstanceProfiler, ProfiledFunctions, ENTER_HOOK, EXIT_HOOK, PROFILER_MODULE},
+    WasmExecutionError,
+};
 use crate::{
     wasm::{WasmContractModule, WasmServiceModule},
     ApplicationCallOutcome, BaseRuntime, Bytecode, CalleeContext, ContractRuntime, ExecutionError,
@@ -44,12 +52,15 @@ use linera_base::{identifiers::SessionId, sync::Lazy};
 use std::{marker::Unpin, sync::Arc};
 use tokio::sync::Mutex;
 use wasmer::{
-    imports, wasmparser::Operator, CompilerConfig, Engine, EngineBuilder, Instance, Module,
-    Singlepass, Store,
+    imports, wasmparser::Operator, AsStoreMut, CompilerConfig, Engine, EngineBuilder, Function,
+    FunctionEnv, FunctionEnvMut, Imports, Instance, Module, Singlepass, Store,
 };
 use wasmer_middlewares::metering::{self, Metering, MeteringPoints};
 use wit_bindgen_host_wasmer_rust::Le;
 
+/// The name of this runtime in the persistent cache of compiled modules.
+const RUNTIME_NAME: &str = "wasmer";
+
 /// An [`Engine`] instance configured to run application services.
 static SERVICE_ENGINE: Lazy<Engine> = Lazy::new(|| {
     let compiler_config = Singlepass::default();
@@ -78,6 +89,64 @@ pub(crate) struct WasmerContractInstance<Runtime> {
 
     /// The starting amount of fuel.
     initial_fuel: u64,
+
+    /// The environment of the fuel profiling hooks.
+    profiler_env: FunctionEnv<ProfilerEnv>,
+
+    /// The compiled contract, to instantiate its optional migration entrypoint.
+    module: Module,
+
+    /// Whether the contract is instrumented for fuel profiling.
+    is_instrumented: bool,
+}
+
+/// The environment of the hooks called by a contract instrumented for fuel profiling.
+struct ProfilerEnv {
+    /// The Wasmer instance, once it is created.
+    instance: Option<Instance>,
+
+    /// The starting amount of fuel of the current entrypoint.
+    initial_fuel: u64,
+
+    /// The profiler attributing the consumed fuel to the functions of the contract.
+    profiler: InstanceProfiler,
+}
+
+impl ProfilerEnv {
+    /// Returns the fuel consumed since the start of the current entrypoint.
+    fn consumed_fuel(&self, store: &mut impl AsStoreMut) -> u64 {
+        let Some(instance) = &self.instance else {
+            return 0;
+        };
+        match metering::get_remaining_points(store, instance) {
+            MeteringPoints::Exhausted => self.initial_fuel,
+            MeteringPoints::Remaining(fuel) => self.initial_fuel.saturating_sub(fuel),
+        }
+    }
+
+    /// Adds the hooks called by a contract instrumented for fuel profiling to the `imports`.
+    fn add_hooks(store: &mut Store, env: &FunctionEnv<ProfilerEnv>, imports: &mut Imports) {
+        let enter = Function::new_typed_with_env(
+            store,
+            env,
+            |mut env: FunctionEnvMut<ProfilerEnv>, index: u32| {
+                let (data, mut store) = env.data_and_store_mut();
+                let consumed_fuel = data.consumed_fuel(&mut store);
+                data.profiler.enter(index, consumed_fuel);
+            },
+        );
+        let exit = Function::new_typed_with_env(
+            store,
+            env,
+            |mut env: FunctionEnvMut<ProfilerEnv>, index: u32| {
+                let (data, mut store) = env.data_and_store_mut();
+                let consumed_fuel = data.consumed_fuel(&mut store);
+                data.profiler.exit(index, consumed_fuel);
+            },
+        );
+        imports.define(PROFILER_MODULE, ENTER_HOOK, enter);
+        imports.define(PROFILER_MODULE, EXIT_HOOK, exit);
+    }
 }
 
 impl<Runtime> WasmerContractInstance<Runtime>
@@ -86,9 +155,14 @@ where
 {
     fn configure_initial_fuel(&mut self) -> Result<(), ExecutionError> {
         self.initial_fuel = self.runtime.remaining_fuel()?;
+        let fuel_profiler = self.runtime.fuel_profiler()?;
 
         metering::set_remaining_points(&mut self.store, &self.instance, self.initial_fuel);
 
+        let profiler_env = self.profiler_env.as_mut(&mut self.store);
+        profiler_env.initial_fuel = self.initial_fuel;
+        profiler_env.profiler.start(fuel_profiler, 0);
+
         Ok(())
     }
 
@@ -100,8 +174,15 @@ where
 
         assert!(self.initial_fuel >= remaining_fuel);
 
+        let consumed_fuel = self.initial_fuel - remaining_fuel;
+        let profiling_overhead = self
+            .profiler_env
+            .as_mut(&mut self.store)
+            .profiler
+            .finish(consumed_fuel);
+
         self.runtime
-            .consume_fuel(self.initial_fuel - remaining_fuel)
+            .consume_fuel(consumed_fuel.saturating_sub(profiling_overhead))
     }
 }
 
@@ -116,14 +197,21 @@ pub struct WasmerServiceInstance {
 
 impl WasmContractModule {
     /// Creates a new [`WasmContractModule`] using Wasmer with the provided bytecodes.
-    pub async fn from_wasmer(contract_bytecode: Bytecode) -> Result<Self, WasmExecutionError> {
+    pub async fn from_wasmer(
+        contract_bytecode: Bytecode,
+        functions: Option<Arc<ProfiledFunctions>>,
+    ) -> Result<Self, WasmExecutionError> {
         let mut contract_cache = CONTRACT_CACHE.lock().await;
         let (engine, module) = contract_cache
             .get_or_insert_with(contract_bytecode, CachedContractModule::new)
             .map_err(WasmExecutionError::LoadContractModule)?
             .create_execution_instance()
             .map_err(WasmExecutionError::LoadContractModule)?;
-        Ok(WasmContractModule::Wasmer { engine, module })
+        Ok(WasmContractModule::Wasmer {
+            engine,
+            module,
+            functions,
+        })
     }
 }
 
@@ -135,6 +223,7 @@ where
     pub fn prepare(
         contract_engine: &Engine,
         contract_module: &Module,
+        functions: Option<Arc<ProfiledFunctions>>,
         runtime: Runtime,
     ) -> Result<Self, WasmExecutionError> {
         let mut store = Store::new(contract_engine);
@@ -143,9 +232,22 @@ where
             contract_system_api::add_to_imports(&mut store, &mut imports, runtime.clone());
         let views_api_setup =
             view_system_api::add_to_imports(&mut store, &mut imports, runtime.clone());
+        let is_instrumented = functions.is_some();
+        let profiler_env = FunctionEnv::new(
+            &mut store,
+            ProfilerEnv {
+                instance: None,
+                initial_fuel: 0,
+                profiler: InstanceProfiler::new(functions),
+            },
+        );
+        if is_instrumented {
+            ProfilerEnv::add_hooks(&mut store, &profiler_env, &mut imports);
+        }
         let (application, instance) =
             contract::Contract::instantiate(&mut store, contract_module, &mut imports)
                 .map_err(WasmExecutionError::LoadContractModule)?;
+        profiler_env.as_mut(&mut store).instance = Some(instance.clone());
 
         system_api_setup(&instance, &store).map_err(WasmExecutionError::LoadContractModule)?;
         views_api_setup(&instance, &store).map_err(WasmExecutionError::LoadContractModule)?;
@@ -156,8 +258,50 @@ where
             runtime,
             instance,
             initial_fuel: 0,
+            profiler_env,
+            module: contract_module.clone(),
+            is_instrumented,
         })
     }
+
+    /// Instantiates the `migrate` entrypoint of the contract in the same store, if the contract
+    /// exports it.
+    ///
+    /// The returned [`Instance`] is separate from the one used by the other entrypoints, which
+    /// is fine because the application state only lives in storage.
+    fn prepare_migration(
+        &mut self,
+    ) -> Result<Option<(contract_migration::ContractMigration, Instance)>, ExecutionError> {
+        if !self
+            .module
+            .exports()
+            .any(|export| export.name() == "migrate")
+        {
+            return Ok(None);
+        }
+        let mut imports = imports! {};
+        let system_api_setup = contract_system_api::add_to_imports(
+            &mut self.store,
+            &mut imports,
+            self.runtime.clone(),
+        );
+        let views_api_setup =
+            view_system_api::add_to_imports(&mut self.store, &mut imports, self.runtime.clone());
+        if self.is_instrumented {
+            ProfilerEnv::add_hooks(&mut self.store, &self.profiler_env, &mut imports);
+        }
+        let (migration, instance) = contract_migration::ContractMigration::instantiate(
+            &mut self.store,
+            &self.module,
+            &mut imports,
+        )
+        .map_err(WasmExecutionError::LoadContractModule)?;
+
+        system_api_setup(&instance, &self.store).map_err(WasmExecutionError::LoadContractModule)?;
+        views_api_setup(&instance, &self.store).map_err(WasmExecutionError::LoadContractModule)?;
+
+        Ok(Some((migration, instance)))
+    }
 }
 
 impl WasmContractModule {
@@ -185,8 +329,16 @@ impl WasmServiceModule {
         let mut service_cache = SERVICE_CACHE.lock().await;
         let module = service_cache
             .get_or_insert_with(service_bytecode, |bytecode| {
-                Module::new(&*SERVICE_ENGINE, bytecode)
-                    .map_err(wit_bindgen_host_wasmer_rust::anyhow::Error::from)
+                let key = ArtifactKey::new(RUNTIME_NAME, wasmer::VERSION, "service", &bytecode);
+                persistent_cache::load_or_compile(
+                    &key,
+                    // SAFETY: Wasmer cannot check that an artifact is well-formed, and the
+                    // checksum of the persistent cache only detects corrupted files, not
+                    // tampered ones. The cache directory must be trusted.
+                    |artifact| Ok(unsafe { Module::deserialize(&*SERVICE_ENGINE, artifact) }?),
+                    || Ok(Module::new(&*SERVICE_ENGINE, bytecode)?),
+                    |module| Ok(module.serialize()?.to_vec()),
+                )
             })
             .map_err(WasmExecutionError::LoadServiceModule)?;
         Ok(WasmServiceModule::Wasmer { module })
@@ -220,7 +372,7 @@ impl WasmerServiceInstance {
 
 impl<Runtime> crate::UserContract for WasmerContractInstance<Runtime>
 where
-    Runtime: ContractRuntime + Send + Unpin,
+    Runtime: ContractRuntime + Clone + Send + Sync + Unpin + 'static,
 {
     fn initialize(
         &mut self,
@@ -239,6 +391,33 @@ where
         result?.map_err(ExecutionError::UserError)
     }
 
+    fn migrate(
+        &mut self,
+        context: OperationContext,
+        argument: Vec<u8>,
+    ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError> {
+        let Some((migration, instance)) = self.prepare_migration()? else {
+            return Ok(RawExecutionOutcome::default());
+        };
+        // Meter the fuel of the migration instance while it runs.
+        let previous_instance = std::mem::replace(&mut self.instance, instance.clone());
+        self.profiler_env.as_mut(&mut self.store).instance = Some(instance);
+
+        self.configure_initial_fuel()?;
+        let result = contract_migration::ContractMigration::migrate(
+            &migration,
+            &mut self.store,
+            context.into(),
+            &argument,
+        )
+        .map(|inner| inner.map(RawExecutionOutcome::from));
+        self.persist_remaining_fuel()?;
+
+        self.profiler_env.as_mut(&mut self.store).instance = Some(previous_instance.clone());
+        self.instance = previous_instance;
+        result?.map_err(ExecutionError::UserError)
+    }
+
     fn execute_operation(
         &mut self,
         context: OperationContext,
@@ -370,11 +549,33 @@ pub struct CachedContractModule {
 }
 
 impl CachedContractModule {
-    /// Creates a new [`CachedContractModule`] by compiling a `contract_bytecode`.
+    /// Creates a new [`CachedContractModule`] by compiling a `contract_bytecode`, unless it was
+    /// already compiled in the persistent cache.
     pub fn new(contract_bytecode: Bytecode) -> Result<Self, anyhow::Error> {
-        let module = Module::new(&Self::create_compilation_engine(), contract_bytecode)?;
-        let compiled_bytecode = module.serialize()?;
-        Ok(CachedContractModule { compiled_bytecode })
+        let key = ArtifactKey::new(
+            RUNTIME_NAME,
+            wasmer::VERSION,
+            "contract",
+            &contract_bytecode,
+        );
+        persistent_cache::load_or_compile(
+            &key,
+            |artifact| {
+                let module = CachedContractModule {
+                    compiled_bytecode: artifact.into(),
+                };
+                // Restore the module once, so that artifacts Wasmer can't load are compiled
+                // again instead of failing every execution.
+                module.create_execution_instance()?;
+                Ok(module)
+            },
+            || {
+                let module = Module::new(&Self::create_compilation_engine(), contract_bytecode)?;
+                let compiled_bytecode = module.serialize()?;
+                Ok(CachedContractModule { compiled_bytecode })
+            },
+            |module| Ok(module.compiled_bytecode.to_vec()),
+        )
     }
 
     /// Creates a new [`Engine`] to compile a contract bytecode.
@@ -391,6 +592,9 @@ impl CachedContractModule {
     pub fn create_execution_instance(&self) -> Result<(Engine, Module), anyhow::Error> {
         let engine = Engine::headless();
         let store = Store::new(&engine);
+        // SAFETY: The module was either compiled by this process or restored from the persistent
+        // cache, whose directory must be trusted because the checksum of its files only detects
+        // corrupted files, not tampered ones.
         let module = unsafe { Module::deserialize(&store, &*self.compiled_bytecode) }?;
         Ok((engine, module))
     }
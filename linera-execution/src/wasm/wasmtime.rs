@@ -24,6 +24,9 @@ wit_bindgen_host_wasmtime_rust::export!({
 // Import the interface implemented by a user contract.
 wit_bindgen_host_wasmtime_rust::import!("contract.wit");
 
+// Import the optional migration entrypoint of a user contract.
+wit_bindgen_host_wasmtime_rust::import!("contract_migration.wit");
+
 // Import the interface implemented by a user service.
 wit_bindgen_host_wasmtime_rust::import!("service.wit");
 
@@ -32,20 +35,46 @@ mod conversions_from_wit;
 #[path = "conversions_to_wit.rs"]
 mod conversions_to_wit;
 
-use self::{contract::ContractData, service::ServiceData, view_system_api::ViewSystemApiTables};
-use super::{module_cache::ModuleCache, WasmExecutionError};
+use self::{
+    contract::ContractData, contract_migration::ContractMigrationData, service::ServiceData,
+    view_system_api::ViewSystemApiTables,
+};
+use super::{
+    module_cache::ModuleCache,
+    persistent_cache::{self, ArtifactKey},
+    profiler::{InstanceProfiler, ProfiledFunctions, ENTER_HOOK, EXIT_HOOK, PROFILER_MODULE},
+    WasmExecutionError,
+};
 use crate::{
     wasm::{WasmContractModule, WasmServiceModule},
     ApplicationCallOutcome, BaseRuntime, Bytecode, CalleeContext, ContractRuntime, ExecutionError,
     MessageContext, OperationContext, QueryContext, RawExecutionOutcome, ServiceRuntime,
     SessionCallOutcome, SessionId,
 };
+use linera_base::crypto::CryptoHash;
 use once_cell::sync::Lazy;
-use std::error::Error;
+use std::{error::Error, sync::Arc};
 use tokio::sync::Mutex;
-use wasmtime::{Config, Engine, Linker, Module, Store};
+use wasmtime::{Caller, Config, Engine, Linker, Module, Store};
 use wit_bindgen_host_wasmtime_rust::Le;
 
+/// The name of this runtime in the persistent cache of compiled modules.
+const RUNTIME_NAME: &str = "wasmtime";
+
+/// The version of Wasmtime and of its configuration, used to name the compiled modules in the
+/// persistent cache.
+///
+/// Wasmtime doesn't expose its version, but records it with the configuration of the engine in
+/// every compiled module, so this is the hash of an empty module compiled by the contract
+/// engine. Wasmtime also refuses to deserialize modules compiled by a different version or
+/// configuration.
+static ENGINE_VERSION: Lazy<String> = Lazy::new(|| {
+    let empty_module = Module::new(&CONTRACT_ENGINE, b"\0asm\x01\0\0\0")
+        .and_then(|module| module.serialize())
+        .expect("Compiling an empty module should not fail");
+    CryptoHash::new(&Bytecode::new(empty_module)).to_string()
+});
+
 /// An [`Engine`] instance configured to run application contracts.
 static CONTRACT_ENGINE: Lazy<Engine> = Lazy::new(|| {
     let mut config = Config::default();
@@ -78,6 +107,12 @@ where
 
     /// The application's memory state.
     store: Store<ContractState<Runtime>>,
+
+    /// The compiled contract, to instantiate its optional migration entrypoint.
+    module: Module,
+
+    /// Whether the contract is instrumented for fuel profiling.
+    is_instrumented: bool,
 }
 
 impl<Runtime> WasmtimeContractInstance<Runtime>
@@ -87,11 +122,21 @@ where
     fn configure_initial_fuel(&mut self) -> Result<(), ExecutionError> {
         let runtime = &mut self.store.data_mut().runtime;
         let fuel = runtime.remaining_fuel()?;
+        let fuel_profiler = runtime.fuel_profiler()?;
 
         self.store
             .add_fuel(fuel)
             .expect("Fuel consumption wasn't properly enabled");
 
+        let consumed_fuel = self
+            .store
+            .fuel_consumed()
+            .expect("Failed to read consumed fuel");
+        self.store
+            .data_mut()
+            .profiler
+            .start(fuel_profiler, consumed_fuel);
+
         Ok(())
     }
 
@@ -100,9 +145,12 @@ where
             .store
             .fuel_consumed()
             .expect("Failed to read consumed fuel");
-        let runtime = &mut self.store.data_mut().runtime;
+        let state = self.store.data_mut();
+        let profiling_overhead = state.profiler.finish(consumed_fuel);
 
-        runtime.consume_fuel(consumed_fuel)
+        state
+            .runtime
+            .consume_fuel(consumed_fuel.saturating_sub(profiling_overhead))
     }
 }
 
@@ -120,14 +168,17 @@ where
 
 impl WasmContractModule {
     /// Creates a new [`WasmContractModule`] using Wasmtime with the provided bytecodes.
-    pub async fn from_wasmtime(contract_bytecode: Bytecode) -> Result<Self, WasmExecutionError> {
+    pub async fn from_wasmtime(
+        contract_bytecode: Bytecode,
+        functions: Option<Arc<ProfiledFunctions>>,
+    ) -> Result<Self, WasmExecutionError> {
         let mut contract_cache = CONTRACT_CACHE.lock().await;
         let module = contract_cache
             .get_or_insert_with(contract_bytecode, |bytecode| {
-                Module::new(&CONTRACT_ENGINE, bytecode)
+                load_or_compile(&CONTRACT_ENGINE, "contract", bytecode)
             })
             .map_err(WasmExecutionError::LoadContractModule)?;
-        Ok(WasmContractModule::Wasmtime { module })
+        Ok(WasmContractModule::Wasmtime { module, functions })
     }
 }
 
@@ -136,25 +187,99 @@ where
     Runtime: ContractRuntime + Send + Sync + 'static,
 {
     /// Prepares a runtime instance to call into the Wasm contract.
-    pub fn prepare(contract_module: &Module, runtime: Runtime) -> Result<Self, WasmExecutionError> {
+    pub fn prepare(
+        contract_module: &Module,
+        functions: Option<Arc<ProfiledFunctions>>,
+        runtime: Runtime,
+    ) -> Result<Self, WasmExecutionError> {
+        let is_instrumented = functions.is_some();
+        let mut linker = Self::create_linker(is_instrumented)?;
+
+        let state = ContractState::new(runtime, InstanceProfiler::new(functions));
+        let mut store = Store::new(&CONTRACT_ENGINE, state);
+        let (application, _instance) = contract::Contract::instantiate(
+            &mut store,
+            contract_module,
+            &mut linker,
+            ContractState::data,
+        )
+        .map_err(WasmExecutionError::LoadContractModule)?;
+
+        Ok(Self {
+            application,
+            store,
+            module: contract_module.clone(),
+            is_instrumented,
+        })
+    }
+
+    /// Creates a [`Linker`] with the system APIs exported to a contract.
+    fn create_linker(
+        is_instrumented: bool,
+    ) -> Result<Linker<ContractState<Runtime>>, WasmExecutionError> {
         let mut linker = Linker::new(&CONTRACT_ENGINE);
 
         contract_system_api::add_to_linker(&mut linker, ContractState::system_api)
             .map_err(WasmExecutionError::LoadContractModule)?;
         view_system_api::add_to_linker(&mut linker, ContractState::views_api)
             .map_err(WasmExecutionError::LoadContractModule)?;
+        if is_instrumented {
+            Self::add_profiler_hooks(&mut linker)
+                .map_err(WasmExecutionError::LoadContractModule)?;
+        }
 
-        let state = ContractState::new(runtime);
-        let mut store = Store::new(&CONTRACT_ENGINE, state);
-        let (application, _instance) = contract::Contract::instantiate(
-            &mut store,
-            contract_module,
+        Ok(linker)
+    }
+
+    /// Instantiates the `migrate` entrypoint of the contract in the same store, if the contract
+    /// exports it.
+    ///
+    /// The new instance is separate from the one used by the other entrypoints, which is fine
+    /// because the application state only lives in storage.
+    fn prepare_migration(
+        &mut self,
+    ) -> Result<Option<contract_migration::ContractMigration<ContractState<Runtime>>>, ExecutionError>
+    {
+        if !self
+            .module
+            .exports()
+            .any(|export| export.name() == "migrate")
+        {
+            return Ok(None);
+        }
+        let mut linker = Self::create_linker(self.is_instrumented)?;
+        let (migration, _instance) = contract_migration::ContractMigration::instantiate(
+            &mut self.store,
+            &self.module,
             &mut linker,
-            ContractState::data,
+            ContractState::migration_data,
         )
         .map_err(WasmExecutionError::LoadContractModule)?;
 
-        Ok(Self { application, store })
+        Ok(Some(migration))
+    }
+
+    /// Adds the hooks called by a contract instrumented for fuel profiling to the `linker`.
+    fn add_profiler_hooks(
+        linker: &mut Linker<ContractState<Runtime>>,
+    ) -> Result<(), anyhow::Error> {
+        linker.func_wrap(
+            PROFILER_MODULE,
+            ENTER_HOOK,
+            |mut caller: Caller<'_, ContractState<Runtime>>, index: u32| {
+                let consumed_fuel = caller.fuel_consumed().unwrap_or_default();
+                caller.data_mut().profiler.enter(index, consumed_fuel);
+            },
+        )?;
+        linker.func_wrap(
+            PROFILER_MODULE,
+            EXIT_HOOK,
+            |mut caller: Caller<'_, ContractState<Runtime>>, index: u32| {
+                let consumed_fuel = caller.fuel_consumed().unwrap_or_default();
+                caller.data_mut().profiler.exit(index, consumed_fuel);
+            },
+        )?;
+        Ok(())
     }
 }
 
@@ -164,13 +289,32 @@ impl WasmServiceModule {
         let mut service_cache = SERVICE_CACHE.lock().await;
         let module = service_cache
             .get_or_insert_with(service_bytecode, |bytecode| {
-                Module::new(&SERVICE_ENGINE, bytecode)
+                load_or_compile(&SERVICE_ENGINE, "service", bytecode)
             })
             .map_err(WasmExecutionError::LoadServiceModule)?;
         Ok(WasmServiceModule::Wasmtime { module })
     }
 }
 
+/// Compiles the `bytecode` of a `kind` of module with the `engine`, unless it was already
+/// compiled in the persistent cache.
+fn load_or_compile(
+    engine: &Engine,
+    kind: &'static str,
+    bytecode: Bytecode,
+) -> Result<Module, anyhow::Error> {
+    let key = ArtifactKey::new(RUNTIME_NAME, &ENGINE_VERSION, kind, &bytecode);
+    persistent_cache::load_or_compile(
+        &key,
+        // SAFETY: Wasmtime checks that the artifact is compatible with the `engine`, but not
+        // that it is well-formed, and the checksum of the persistent cache only detects corrupted
+        // files, not tampered ones. The cache directory must be trusted.
+        |artifact| unsafe { Module::deserialize(engine, artifact) },
+        || Module::new(engine, bytecode),
+        Module::serialize,
+    )
+}
+
 impl<Runtime> WasmtimeServiceInstance<Runtime>
 where
     Runtime: ServiceRuntime + Send + Sync + 'static,
@@ -204,8 +348,10 @@ where
     Runtime: ContractRuntime + Send + Sync + 'static,
 {
     data: ContractData,
+    migration_data: ContractMigrationData,
     runtime: Runtime,
     views_tables: ViewSystemApiTables<Runtime>,
+    profiler: InstanceProfiler,
 }
 
 /// Data stored by the runtime that's necessary for handling queries to and from the Wasm module.
@@ -224,12 +370,14 @@ where
 {
     /// Creates a new instance of [`ContractState`].
     ///
-    /// Uses `runtime` to export the system API.
-    pub fn new(runtime: Runtime) -> Self {
+    /// Uses `runtime` to export the system API, and `profiler` to attribute the consumed fuel.
+    pub fn new(runtime: Runtime, profiler: InstanceProfiler) -> Self {
         Self {
             data: ContractData::default(),
+            migration_data: ContractMigrationData::default(),
             runtime,
             views_tables: ViewSystemApiTables::default(),
+            profiler,
         }
     }
 
@@ -238,6 +386,11 @@ where
         &mut self.data
     }
 
+    /// Obtains the runtime instance specific [`ContractMigrationData`].
+    pub fn migration_data(&mut self) -> &mut ContractMigrationData {
+        &mut self.migration_data
+    }
+
     /// Obtains the data required by the runtime to export the system API.
     pub fn system_api(&mut self) -> &mut Runtime {
         &mut self.runtime
@@ -301,6 +454,26 @@ where
         result?.map_err(ExecutionError::UserError)
     }
 
+    fn migrate(
+        &mut self,
+        context: OperationContext,
+        argument: Vec<u8>,
+    ) -> Result<RawExecutionOutcome<Vec<u8>>, ExecutionError> {
+        let Some(migration) = self.prepare_migration()? else {
+            return Ok(RawExecutionOutcome::default());
+        };
+        self.configure_initial_fuel()?;
+        let result = contract_migration::ContractMigration::migrate(
+            &migration,
+            &mut self.store,
+            context.into(),
+            &argument,
+        )
+        .map(|inner| inner.map(RawExecutionOutcome::from));
+        self.persist_remaining_fuel()?;
+        result?.map_err(ExecutionError::UserError)
+    }
+
     fn execute_operation(
         &mut self,
         context: OperationContext,
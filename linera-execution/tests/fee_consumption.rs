@@ -7,14 +7,15 @@
 
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
-    data_types::{Amount, BlockHeight},
+    data_types::{Amount, BlockHeight, FeeSponsorship},
     identifiers::{Account, ChainDescription, ChainId, MessageId, Owner},
 };
 use linera_execution::{
     test_utils::{register_mock_applications, ExpectedCall, SystemExecutionState},
-    ContractRuntime, ExecutionError, ExecutionOutcome, Message, MessageContext,
-    RawExecutionOutcome, ResourceControlPolicy, ResourceController,
+    BaseRuntime, ContractRuntime, ExecutionError, ExecutionOutcome, Message, MessageContext,
+    Operation, OperationContext, RawExecutionOutcome, ResourceControlPolicy, ResourceController,
 };
+use linera_views::batch::Batch;
 use std::{sync::Arc, vec};
 use test_case::test_case;
 
@@ -147,6 +148,7 @@ async fn test_fee_consumption(
         message_byte: Amount::from_tokens(31),
         maximum_bytes_read_per_block: 37,
         maximum_bytes_written_per_block: 41,
+        ..ResourceControlPolicy::default()
     };
 
     let consumed_fees = spends
@@ -188,6 +190,7 @@ async fn test_fee_consumption(
         height: BlockHeight(0),
         certificate_hash: CryptoHash::default(),
         message_id: MessageId::default(),
+        response_to: None,
         next_message_index: 0,
     };
     let mut grant = initial_grant.unwrap_or_default();
@@ -271,6 +274,195 @@ async fn test_fee_consumption(
     }
 }
 
+/// Tests that an application sponsoring its operations pays their fees up to its limits.
+#[tokio::test]
+async fn test_sponsored_fee_consumption() {
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        balance: Amount::from_tokens(10),
+        ..SystemExecutionState::default()
+    };
+    let mut view = state.into_view().await;
+
+    let mut applications = register_mock_applications(&mut view, 1).await.unwrap();
+    let (application_id, application) = applications
+        .next()
+        .expect("Mock application should be registered");
+    view.system
+        .application_balances
+        .insert(&application_id, Amount::from_tokens(4))
+        .unwrap();
+    view.system
+        .set_fee_sponsorship(
+            application_id,
+            Some(FeeSponsorship {
+                max_fees_per_operation: Amount::from_tokens(3),
+                max_fees_per_block: Amount::from_tokens(5),
+            }),
+        )
+        .unwrap();
+
+    let mut controller = ResourceController {
+        policy: Arc::new(ResourceControlPolicy {
+            fuel_unit: Amount::from_tokens(2),
+            ..ResourceControlPolicy::default()
+        }),
+        ..ResourceController::default()
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            assert_eq!(
+                runtime.read_application_balance().unwrap(),
+                Amount::from_tokens(1)
+            );
+            runtime.fund_application(None, Amount::from_tokens(1))?;
+            runtime.consume_fuel(1)?;
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+
+    let mut sponsor = view
+        .system
+        .take_sponsored_fees(application_id, Amount::ZERO)
+        .await
+        .unwrap();
+    assert_eq!(sponsor, Some(Amount::from_tokens(3)));
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: None,
+        next_message_index: 0,
+    };
+    view.execute_operation_with_sponsor(
+        context,
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        sponsor.as_mut(),
+        &mut controller,
+    )
+    .await
+    .unwrap();
+
+    // The fuel was paid by the application, and the funding came from the chain.
+    assert_eq!(sponsor, Some(Amount::from_tokens(1)));
+    assert_eq!(*view.system.balance.get(), Amount::from_tokens(9));
+    view.system
+        .return_sponsored_fees(application_id, Amount::from_tokens(1))
+        .await
+        .unwrap();
+    assert_eq!(
+        view.system
+            .application_balances
+            .get(&application_id)
+            .await
+            .unwrap(),
+        Some(Amount::from_tokens(3))
+    );
+
+    // Only what is left of the block limit can be used by the next operation.
+    let sponsor = view
+        .system
+        .take_sponsored_fees(application_id, Amount::from_tokens(4))
+        .await
+        .unwrap();
+    assert_eq!(sponsor, Some(Amount::from_tokens(1)));
+}
+
+/// Tests that growing the storage of an application takes a deposit, and that freeing it
+/// refunds at most that deposit.
+#[tokio::test]
+async fn test_storage_fees() {
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        balance: Amount::from_tokens(10),
+        ..SystemExecutionState::default()
+    };
+    let mut view = state.into_view().await;
+
+    let mut applications = register_mock_applications(&mut view, 1).await.unwrap();
+    let (application_id, application) = applications
+        .next()
+        .expect("Mock application should be registered");
+
+    let mut controller = ResourceController {
+        policy: Arc::new(ResourceControlPolicy {
+            byte_stored: Amount::from_tokens(1),
+            ..ResourceControlPolicy::default()
+        }),
+        ..ResourceController::default()
+    };
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: None,
+        next_message_index: 0,
+    };
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], vec![1, 2, 3]);
+            runtime.write_batch(batch)?;
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+    view.execute_operation(context, operation.clone(), &mut controller)
+        .await
+        .unwrap();
+    assert_eq!(*view.system.balance.get(), Amount::from_tokens(6));
+    assert_eq!(controller.tracker.bytes_stored, 4);
+    assert_eq!(controller.tracker.fees, Amount::ZERO);
+    let deposits = view
+        .system
+        .storage_deposits
+        .try_load_entry(&application_id)
+        .await
+        .unwrap()
+        .expect("a deposit should have been recorded");
+    assert_eq!(
+        deposits.get(&None).await.unwrap(),
+        Some(Amount::from_tokens(4))
+    );
+    drop(deposits);
+
+    // A higher price doesn't refund more than what was paid.
+    controller.policy = Arc::new(ResourceControlPolicy {
+        byte_stored: Amount::from_tokens(2),
+        ..ResourceControlPolicy::default()
+    });
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.delete_key(vec![0]);
+            runtime.write_batch(batch)?;
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+    view.execute_operation(context, operation, &mut controller)
+        .await
+        .unwrap();
+    assert_eq!(*view.system.balance.get(), Amount::from_tokens(10));
+    assert_eq!(controller.tracker.bytes_stored, 0);
+    let deposits = view
+        .system
+        .storage_deposits
+        .try_load_entry(&application_id)
+        .await
+        .unwrap()
+        .expect("the deposits of the application should still be loadable");
+    assert_eq!(deposits.get(&None).await.unwrap(), None);
+}
+
 /// A runtime operation that costs some amount of fees.
 pub enum FeeSpend {
     /// Consume some execution fuel.
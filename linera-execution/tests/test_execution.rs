@@ -5,9 +5,9 @@
 
 use assert_matches::assert_matches;
 use linera_base::{
-    crypto::PublicKey,
+    crypto::{CryptoHash, PublicKey},
     data_types::{Amount, BlockHeight, Resources, Timestamp},
-    identifiers::{Account, ChainDescription, ChainId, Destination, MessageId, Owner},
+    identifiers::{Account, BytecodeId, ChainDescription, ChainId, Destination, MessageId, Owner},
     ownership::ChainOwnership,
 };
 use linera_execution::{
@@ -15,14 +15,15 @@ use linera_execution::{
     system::{ApplicationPermissions, SystemMessage},
     test_utils::{
         create_dummy_user_application_registrations, register_mock_applications, ExpectedCall,
-        SystemExecutionState,
+        MockApplication, SystemExecutionState,
     },
     ApplicationCallOutcome, BaseRuntime, ContractRuntime, ExecutionError, ExecutionOutcome,
-    MessageKind, Operation, OperationContext, Query, QueryContext, RawExecutionOutcome,
-    RawOutgoingMessage, ResourceController, Response, SessionCallOutcome, SystemOperation,
+    ExecutionRuntimeContext, Message, MessageContext, MessageKind, Operation, OperationContext,
+    Query, QueryContext, RawExecutionOutcome, RawOutgoingMessage, ResourceController, Response,
+    SessionCallOutcome, SystemOperation,
 };
-use linera_views::batch::Batch;
-use std::{collections::BTreeMap, vec};
+use linera_views::{batch::Batch, common::Context, views::View};
+use std::{collections::BTreeMap, sync::Arc, vec};
 
 fn make_operation_context() -> OperationContext {
     OperationContext {
@@ -415,6 +416,8 @@ async fn test_simple_message() -> anyhow::Result<()> {
         authenticated: false,
         grant: Resources::default(),
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: b"msg".to_vec(),
     };
 
@@ -448,6 +451,8 @@ async fn test_simple_message() -> anyhow::Result<()> {
         authenticated: false,
         grant: Amount::ZERO,
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: SystemMessage::RegisterApplications {
             applications: vec![application_description],
         },
@@ -510,6 +515,8 @@ async fn test_message_from_cross_application_call() -> anyhow::Result<()> {
         authenticated: false,
         grant: Resources::default(),
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: b"msg".to_vec(),
     };
 
@@ -543,6 +550,8 @@ async fn test_message_from_cross_application_call() -> anyhow::Result<()> {
         authenticated: false,
         grant: Amount::ZERO,
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: SystemMessage::RegisterApplications {
             applications: vec![target_description],
         },
@@ -632,6 +641,8 @@ async fn test_message_from_session_call() -> anyhow::Result<()> {
         authenticated: false,
         grant: Resources::default(),
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: b"msg".to_vec(),
     };
 
@@ -674,6 +685,8 @@ async fn test_message_from_session_call() -> anyhow::Result<()> {
         authenticated: false,
         grant: Amount::ZERO,
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: SystemMessage::RegisterApplications {
             applications: vec![target_description],
         },
@@ -749,6 +762,8 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
         authenticated: false,
         grant: Resources::default(),
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: b"first".to_vec(),
     };
 
@@ -784,6 +799,8 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
         authenticated: false,
         grant: Resources::default(),
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: b"second".to_vec(),
     };
 
@@ -805,7 +822,7 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
     // Execute the operation, starting the test scenario
     let context = make_operation_context();
     let mut controller = ResourceController::default();
-    let mut outcomes = view
+    let outcomes = view
         .execute_operation(
             context,
             Operation::User {
@@ -831,6 +848,8 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
         authenticated: false,
         grant: Amount::ZERO,
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: SystemMessage::RegisterApplications {
             applications: vec![sending_target_description.clone(), caller_description],
         },
@@ -841,30 +860,13 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
         authenticated: false,
         grant: Amount::ZERO,
         kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
         message: SystemMessage::RegisterApplications {
             applications: vec![sending_target_description],
         },
     };
 
-    // Need to deconstruct the outcome and verify each field individually because the messages are
-    // built from a `HashMap`, which may produce a different order every run
-    let ExecutionOutcome::System(system_outcome) = outcomes.remove(0) else {
-        panic!(
-            "First execution outcome is not the system outcome with messages to \
-            register applications"
-        );
-    };
-    assert!(system_outcome.authenticated_signer.is_none());
-    assert!(system_outcome.subscribe.is_empty());
-    assert!(system_outcome.unsubscribe.is_empty());
-    // Check for the two registration messages
-    assert_eq!(system_outcome.messages.len(), 2);
-    assert!(system_outcome
-        .messages
-        .contains(&first_registration_message));
-    assert!(system_outcome
-        .messages
-        .contains(&second_registration_message));
     let account = Account {
         chain_id: ChainId::root(0),
         owner: None,
@@ -872,7 +874,6 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
 
     let first_message = first_message.into_priced(&Default::default())?;
     let second_message = second_message.into_priced(&Default::default())?;
-    // Return to checking the user application outcomes
     assert_eq!(
         outcomes,
         &[
@@ -880,6 +881,13 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
                 silent_target_id,
                 RawExecutionOutcome::default().with_refund_grant_to(Some(account)),
             ),
+            // The registration messages precede the first outcome with messages to each
+            // destination.
+            ExecutionOutcome::System(
+                RawExecutionOutcome::default()
+                    .with_message(first_registration_message)
+                    .with_message(second_registration_message)
+            ),
             ExecutionOutcome::User(
                 sending_target_id,
                 RawExecutionOutcome::default()
@@ -899,6 +907,399 @@ async fn test_multiple_messages_from_different_applications() -> anyhow::Result<
     Ok(())
 }
 
+/// Tests if a request is sent with a predictable ID, if the response refers to that ID, and if
+/// only a single response to a pending request is accepted.
+#[tokio::test]
+async fn test_request_and_response() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let mut applications = register_mock_applications(&mut view, 1).await?;
+    let (application_id, application) = applications
+        .next()
+        .expect("Mock application should be registered");
+
+    let context = OperationContext {
+        next_message_index: 3,
+        ..make_operation_context()
+    };
+    // The request is preceded by the message registering the application.
+    let request_id = MessageId {
+        chain_id: context.chain_id,
+        height: context.height,
+        index: context.next_message_index + 1,
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            // Operations are not requests.
+            assert_matches!(
+                runtime.send_response(b"response".to_vec()),
+                Err(ExecutionError::NoRequestToAnswer)
+            );
+            let id = runtime.send_request(ChainId::root(1), false, b"request".to_vec())?;
+            assert_eq!(id, request_id);
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+
+    let mut controller = ResourceController::default();
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let outcomes = view
+        .execute_operation(context, operation, &mut controller)
+        .await?;
+
+    let registration_message = RawOutgoingMessage {
+        destination: Destination::from(ChainId::root(1)),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Simple,
+        deadline: None,
+        response_to: None,
+        message: SystemMessage::RegisterApplications {
+            applications: vec![
+                view.system
+                    .registry
+                    .describe_application(application_id)
+                    .await?,
+            ],
+        },
+    };
+    let request = RawOutgoingMessage {
+        destination: Destination::from(ChainId::root(1)),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Tracked,
+        deadline: None,
+        response_to: None,
+        message: b"request".to_vec(),
+    };
+    let account = Account {
+        chain_id: ChainId::root(0),
+        owner: None,
+    };
+    assert_eq!(
+        outcomes,
+        &[
+            ExecutionOutcome::System(
+                RawExecutionOutcome::default().with_message(registration_message)
+            ),
+            ExecutionOutcome::User(
+                application_id,
+                RawExecutionOutcome::default().with_message(request)
+            ),
+            ExecutionOutcome::User(
+                application_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+        ]
+    );
+
+    assert_eq!(
+        view.system.pending_requests.get(&request_id).await?,
+        Some(application_id)
+    );
+
+    // The receiving application answers the request, only once.
+    application.expect_call(ExpectedCall::execute_message(
+        move |runtime, context, _message| {
+            assert_eq!(context.message_id, request_id);
+            runtime.send_response(b"response".to_vec())?;
+            assert_matches!(
+                runtime.send_response(b"response".to_vec()),
+                Err(ExecutionError::NoRequestToAnswer)
+            );
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+
+    let context = MessageContext {
+        chain_id: ChainId::root(0),
+        is_bouncing: false,
+        authenticated_signer: None,
+        refund_grant_to: None,
+        height: BlockHeight(0),
+        certificate_hash: CryptoHash::default(),
+        message_id: request_id,
+        response_to: None,
+        next_message_index: 0,
+    };
+    let message = Message::User {
+        application_id,
+        bytes: b"request".to_vec(),
+    };
+    let outcomes = view
+        .execute_message(context, message, None, &mut controller)
+        .await?;
+
+    let response = outcomes
+        .iter()
+        .find_map(|outcome| match outcome {
+            ExecutionOutcome::User(_, outcome) => outcome.messages.first(),
+            ExecutionOutcome::System(_) => None,
+        })
+        .expect("Missing response");
+    assert_eq!(response.destination, Destination::from(request_id.chain_id));
+    assert_eq!(response.kind, MessageKind::Simple);
+    assert_eq!(response.response_to, Some(request_id));
+    assert_eq!(response.message, b"response".to_vec());
+
+    // The response is delivered to the application that sent the request.
+    application.expect_call(ExpectedCall::execute_message(
+        move |_runtime, context, _message| {
+            assert_eq!(context.response_to, Some(request_id));
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+    let context = MessageContext {
+        message_id: MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight(0),
+            index: 0,
+        },
+        response_to: Some(request_id),
+        ..context
+    };
+    let message = Message::User {
+        application_id,
+        bytes: b"response".to_vec(),
+    };
+    view.execute_message(context, message.clone(), None, &mut controller)
+        .await?;
+    assert_eq!(view.system.pending_requests.get(&request_id).await?, None);
+
+    // A second response to the same request is rejected.
+    assert_matches!(
+        view.execute_message(context, message, None, &mut controller)
+            .await,
+        Err(ExecutionError::UnexpectedResponse(id)) if id == request_id
+    );
+
+    Ok(())
+}
+
+/// Tests that upgrading an application runs its migration and then uses the new bytecode, and
+/// that the other chains with a state for the application migrate it when they receive the new
+/// description, running the migrations of every version they missed in order.
+#[tokio::test]
+async fn test_upgrade_application() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    // The mock applications are created on this chain.
+    state.description = Some(ChainDescription::Root(1));
+    let mut view = state.into_view().await;
+
+    let mut applications = register_mock_applications(&mut view, 1).await?;
+    let (application_id, _old_application) = applications
+        .next()
+        .expect("Mock application should be registered");
+
+    let owner = Owner::from(PublicKey::test_key(0));
+    let mut description = view
+        .system
+        .registry
+        .describe_application(application_id)
+        .await?;
+    description.governance = Some(owner);
+    view.system
+        .registry
+        .known_applications
+        .insert(&application_id, description.clone())?;
+    let old_description = description;
+
+    let new_bytecode_id = BytecodeId::new(MessageId {
+        chain_id: ChainId::root(1),
+        height: BlockHeight(10),
+        index: 0,
+    });
+    view.system
+        .registry
+        .register_published_bytecode(new_bytecode_id, old_description.bytecode_location)?;
+    let new_application = MockApplication::default();
+    view.context()
+        .extra()
+        .user_contracts()
+        .insert(new_bytecode_id, Arc::new(new_application.clone()));
+
+    let context = OperationContext {
+        chain_id: ChainId::root(1),
+        authenticated_signer: Some(owner),
+        ..make_operation_context()
+    };
+    let mut controller = ResourceController::default();
+
+    // The old bytecode has no expected calls, so only the new one may run.
+    new_application.expect_call(ExpectedCall::migrate(|_runtime, _context, argument| {
+        assert_eq!(argument, b"migrate".to_vec());
+        Ok(RawExecutionOutcome::default())
+    }));
+    let operation = SystemOperation::UpgradeApplication {
+        application_id,
+        bytecode_id: new_bytecode_id,
+        migration_argument: b"migrate".to_vec(),
+    };
+    view.execute_operation(context, Operation::System(operation), &mut controller)
+        .await?;
+
+    let description = view
+        .system
+        .registry
+        .describe_application(application_id)
+        .await?;
+    assert_eq!(description.version(), 1);
+    assert_eq!(description.current_bytecode_id(), new_bytecode_id);
+
+    new_application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _operation| Ok(RawExecutionOutcome::default()),
+    ));
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    view.execute_operation(context, operation, &mut controller)
+        .await?;
+
+    // A second upgrade, which the other chain will receive without having seen the first one.
+    let newest_bytecode_id = BytecodeId::new(MessageId {
+        chain_id: ChainId::root(1),
+        height: BlockHeight(11),
+        index: 0,
+    });
+    view.system
+        .registry
+        .register_published_bytecode(newest_bytecode_id, old_description.bytecode_location)?;
+    let newest_application = MockApplication::default();
+    view.context()
+        .extra()
+        .user_contracts()
+        .insert(newest_bytecode_id, Arc::new(newest_application.clone()));
+    newest_application.expect_call(ExpectedCall::migrate(|_runtime, _context, argument| {
+        assert_eq!(argument, b"migrate again".to_vec());
+        Ok(RawExecutionOutcome::default())
+    }));
+    let operation = SystemOperation::UpgradeApplication {
+        application_id,
+        bytecode_id: newest_bytecode_id,
+        migration_argument: b"migrate again".to_vec(),
+    };
+    view.execute_operation(context, Operation::System(operation), &mut controller)
+        .await?;
+    let description = view
+        .system
+        .registry
+        .describe_application(application_id)
+        .await?;
+    assert_eq!(description.version(), 2);
+    assert_eq!(description.current_bytecode_id(), newest_bytecode_id);
+
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(2));
+    let mut other_view = state.into_view().await;
+    other_view
+        .system
+        .registry
+        .known_applications
+        .insert(&application_id, old_description)?;
+    let forward_destination = Destination::from(ChainId::root(3));
+    other_view
+        .system
+        .registry
+        .record_registration(application_id, &forward_destination)
+        .await?;
+    other_view.users.try_load_entry_mut(&application_id).await?;
+    let other_application = MockApplication::default();
+    other_view
+        .context()
+        .extra()
+        .user_contracts()
+        .insert(new_bytecode_id, Arc::new(other_application.clone()));
+    let other_newest_application = MockApplication::default();
+    other_view.context().extra().user_contracts().insert(
+        newest_bytecode_id,
+        Arc::new(other_newest_application.clone()),
+    );
+
+    // Each migration runs with the bytecode of its own version, in order.
+    let migrated_versions = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let migrated = migrated_versions.clone();
+    other_application.expect_call(ExpectedCall::migrate(move |_runtime, context, argument| {
+        assert_eq!(context.authenticated_signer, None);
+        assert_eq!(argument, b"migrate".to_vec());
+        migrated.lock().unwrap().push(1);
+        Ok(RawExecutionOutcome::default())
+    }));
+    let migrated = migrated_versions.clone();
+    other_newest_application.expect_call(ExpectedCall::migrate(
+        move |_runtime, context, argument| {
+            assert_eq!(context.authenticated_signer, None);
+            assert_eq!(argument, b"migrate again".to_vec());
+            migrated.lock().unwrap().push(2);
+            Ok(RawExecutionOutcome::default())
+        },
+    ));
+    let message_context = MessageContext {
+        chain_id: ChainId::root(2),
+        is_bouncing: false,
+        authenticated_signer: Some(owner),
+        refund_grant_to: None,
+        height: BlockHeight(0),
+        certificate_hash: CryptoHash::default(),
+        message_id: MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight(0),
+            index: 0,
+        },
+        response_to: None,
+        next_message_index: 0,
+    };
+    let message = Message::System(SystemMessage::RegisterApplications {
+        applications: vec![description.clone()],
+    });
+    let outcomes = other_view
+        .execute_message(message_context, message.clone(), None, &mut controller)
+        .await?;
+    assert_eq!(*migrated_versions.lock().unwrap(), vec![1, 2]);
+    assert_eq!(
+        other_view
+            .system
+            .registry
+            .describe_application(application_id)
+            .await?,
+        description
+    );
+    let Some(ExecutionOutcome::System(forwarded)) = outcomes.last() else {
+        panic!("Missing forwarded description: {outcomes:?}");
+    };
+    assert_eq!(
+        forwarded.messages,
+        vec![RawOutgoingMessage {
+            destination: forward_destination,
+            authenticated: false,
+            grant: Amount::ZERO,
+            kind: MessageKind::Tracked,
+            deadline: None,
+            response_to: None,
+            message: SystemMessage::RegisterApplications {
+                applications: vec![description],
+            },
+        }]
+    );
+
+    // Receiving the same description again neither migrates nor forwards anything.
+    let outcomes = other_view
+        .execute_message(message_context, message, None, &mut controller)
+        .await?;
+    assert!(outcomes.iter().all(|outcome| match outcome {
+        ExecutionOutcome::System(outcome) => outcome.messages.is_empty(),
+        ExecutionOutcome::User(..) => false,
+    }));
+
+    Ok(())
+}
+
 /// Tests the system API calls `open_chain` and `chain_ownership`.
 #[tokio::test]
 async fn test_open_chain() {
@@ -4,12 +4,15 @@
 #![allow(clippy::field_reassign_with_default)]
 
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{CryptoHash, KeyPair},
     data_types::{Amount, BlockHeight},
-    identifiers::{Account, ChainDescription, ChainId, MessageId},
+    identifiers::{
+        Account, ApplicationId, BytecodeId, ChainDescription, ChainId, MessageId, Owner,
+    },
+    ownership::ChainOwnership,
 };
 use linera_execution::{
-    system::{Recipient, UserData},
+    system::{Recipient, SystemExecutionError, UserData},
     test_utils::SystemExecutionState,
     ExecutionOutcome, Message, MessageContext, Operation, OperationContext, Query, QueryContext,
     RawExecutionOutcome, ResourceController, Response, SystemMessage, SystemOperation, SystemQuery,
@@ -54,6 +57,36 @@ async fn test_simple_system_operation() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_open_proposer_cannot_spend_chain_balance() -> anyhow::Result<()> {
+    let owner_key = KeyPair::generate().public();
+    let stranger = Owner::from(KeyPair::generate().public());
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state.ownership = ChainOwnership::single(owner_key).with_open_multi_leader_rounds();
+    state.balance = Amount::from_tokens(4);
+    let mut view = state.into_view().await;
+
+    // Applications can't spend the chain's balance on behalf of a non-owner.
+    let result = view
+        .system
+        .transfer(Some(stranger), None, Recipient::Burn, Amount::ONE)
+        .await;
+    assert!(matches!(
+        result,
+        Err(SystemExecutionError::UnauthorizedOpenProposer)
+    ));
+    assert_eq!(view.system.balance.get(), &Amount::from_tokens(4));
+
+    // But they can for the owner.
+    let owner = Some(Owner::from(owner_key));
+    view.system
+        .transfer(owner, None, Recipient::Burn, Amount::ONE)
+        .await?;
+    assert_eq!(view.system.balance.get(), &Amount::from_tokens(3));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_simple_system_message() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
@@ -74,6 +107,7 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
             height: BlockHeight(0),
             index: 0,
         },
+        response_to: None,
         authenticated_signer: None,
         refund_grant_to: None,
         next_message_index: 0,
@@ -91,6 +125,74 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_bounced_application_credit_returns_to_application() -> anyhow::Result<()> {
+    let message_id = MessageId {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+    };
+    let application_id = ApplicationId {
+        bytecode_id: BytecodeId::new(message_id),
+        creation: message_id,
+    };
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state
+        .application_balances
+        .insert(application_id, Amount::from_tokens(4));
+    let mut view = state.into_view().await;
+
+    let destination = Account {
+        chain_id: ChainId::root(1),
+        owner: Some(Owner::from(KeyPair::generate().public())),
+    };
+    let outgoing = view
+        .system
+        .withdraw_from_application(application_id, destination, Amount::ONE)
+        .await?;
+    assert_eq!(
+        view.system
+            .application_balances
+            .get(&application_id)
+            .await?,
+        Some(Amount::from_tokens(3))
+    );
+
+    let context = MessageContext {
+        chain_id: ChainId::root(0),
+        is_bouncing: true,
+        height: BlockHeight(1),
+        certificate_hash: CryptoHash::test_hash("certificate"),
+        message_id: MessageId {
+            chain_id: ChainId::root(0),
+            height: BlockHeight(0),
+            index: 1,
+        },
+        response_to: None,
+        authenticated_signer: None,
+        refund_grant_to: None,
+        next_message_index: 0,
+    };
+    let mut controller = ResourceController::default();
+    view.execute_message(
+        context,
+        Message::System(outgoing.message),
+        None,
+        &mut controller,
+    )
+    .await?;
+    assert_eq!(
+        view.system
+            .application_balances
+            .get(&application_id)
+            .await?,
+        Some(Amount::from_tokens(4))
+    );
+    assert_eq!(view.system.balance.get(), &Amount::ZERO);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_simple_system_query() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
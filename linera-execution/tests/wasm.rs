@@ -10,9 +10,10 @@ use linera_base::{
 };
 use linera_execution::{
     test_utils::{create_dummy_user_application_description, SystemExecutionState},
-    ExecutionOutcome, ExecutionRuntimeConfig, ExecutionRuntimeContext, Operation, OperationContext,
-    Query, QueryContext, RawExecutionOutcome, ResourceControlPolicy, ResourceController,
-    ResourceTracker, Response, WasmContractModule, WasmRuntime, WasmServiceModule,
+    ExecutionOutcome, ExecutionRuntimeConfig, ExecutionRuntimeContext, HostCallFuel,
+    HostCallFuelCosts, Operation, OperationContext, Query, QueryContext, RawExecutionOutcome,
+    ResourceControlPolicy, ResourceController, ResourceTracker, Response, WasmContractModule,
+    WasmRuntime, WasmServiceModule,
 };
 use linera_views::views::View;
 use serde_json::json;
@@ -131,3 +132,150 @@ async fn test_fuel_for_counter_wasm_application(
     );
     Ok(())
 }
+
+/// Test that the fuel costs of the system API calls made by the "counter" example application
+/// are charged in addition to the fuel consumed by its instructions.
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::Wasmer, 40_754; "wasmer"))]
+#[cfg_attr(with_wasmtime, test_case(WasmRuntime::Wasmtime, 41_182; "wasmtime"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_host_call_fuel_for_counter_wasm_application(
+    wasm_runtime: WasmRuntime,
+    instruction_fuel: u64,
+) -> anyhow::Result<()> {
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(ChainId::root(0), ExecutionRuntimeConfig::Synchronous)
+        .await;
+    let app_desc = create_dummy_user_application_description(1);
+    let app_id = view.system.registry.register_application(app_desc).await?;
+
+    let contract =
+        WasmContractModule::from_file("tests/fixtures/counter_contract.wasm", wasm_runtime).await?;
+    view.context()
+        .extra
+        .user_contracts()
+        .insert(app_id, Arc::new(contract));
+
+    let app_id = app_id.with_abi::<CounterAbi>();
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: None,
+        next_message_index: 0,
+    };
+    let policy = ResourceControlPolicy {
+        fuel_unit: Amount::from_attos(1),
+        host_call_fuel: HostCallFuelCosts {
+            read_value_bytes: HostCallFuel {
+                base: 1_000,
+                per_byte: 100,
+            },
+            write_batch: HostCallFuel {
+                base: 10_000,
+                per_byte: 10,
+            },
+            ..HostCallFuelCosts::default()
+        },
+        ..ResourceControlPolicy::default()
+    };
+    *view.system.balance.get_mut() = Amount::from_tokens(1);
+    let mut controller = ResourceController {
+        policy: Arc::new(policy),
+        tracker: ResourceTracker::default(),
+        account: None,
+    };
+    for increment in [2_u64, 9, 7, 1000] {
+        view.execute_operation(
+            context,
+            Operation::user(app_id, &increment).unwrap(),
+            &mut controller,
+        )
+        .await?;
+    }
+
+    // Each operation reads the 8-byte state (absent the first time) with an empty key, then
+    // writes it back.
+    let read_fuel = 4 * 1_000 + 3 * 8 * 100;
+    let write_fuel = 4 * (10_000 + 8 * 10);
+    assert_eq!(
+        controller.tracker.fuel,
+        instruction_fuel + read_fuel + write_fuel
+    );
+    Ok(())
+}
+
+/// Test that an operation fails once the fuel costs of its system API calls alone exceed the
+/// fuel remaining in the block.
+#[cfg_attr(feature = "wasmer", test_case(WasmRuntime::Wasmer; "wasmer"))]
+#[cfg_attr(with_wasmtime, test_case(WasmRuntime::Wasmtime; "wasmtime"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_host_calls_exhausting_fuel_for_counter_wasm_application(
+    wasm_runtime: WasmRuntime,
+) -> anyhow::Result<()> {
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(ChainId::root(0), ExecutionRuntimeConfig::Synchronous)
+        .await;
+    let app_desc = create_dummy_user_application_description(1);
+    let app_id = view.system.registry.register_application(app_desc).await?;
+
+    let contract =
+        WasmContractModule::from_file("tests/fixtures/counter_contract.wasm", wasm_runtime).await?;
+    view.context()
+        .extra
+        .user_contracts()
+        .insert(app_id, Arc::new(contract));
+
+    let app_id = app_id.with_abi::<CounterAbi>();
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        height: BlockHeight(0),
+        index: 0,
+        authenticated_signer: None,
+        next_message_index: 0,
+    };
+    // Reading the state and writing it back each fit in the block, but not both.
+    let maximum_fuel_per_block = 1_000_000;
+    let policy = ResourceControlPolicy {
+        fuel_unit: Amount::from_attos(1),
+        maximum_fuel_per_block,
+        host_call_fuel: HostCallFuelCosts {
+            read_value_bytes: HostCallFuel {
+                base: 600_000,
+                per_byte: 0,
+            },
+            write_batch: HostCallFuel {
+                base: 600_000,
+                per_byte: 0,
+            },
+            ..HostCallFuelCosts::default()
+        },
+        ..ResourceControlPolicy::default()
+    };
+    *view.system.balance.get_mut() = Amount::from_tokens(1);
+    let mut controller = ResourceController {
+        policy: Arc::new(policy),
+        tracker: ResourceTracker::default(),
+        account: None,
+    };
+    let result = view
+        .execute_operation(
+            context,
+            Operation::user(app_id, &1_u64).unwrap(),
+            &mut controller,
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert!(controller.tracker.fuel <= maximum_fuel_per_block);
+    Ok(())
+}
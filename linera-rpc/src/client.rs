@@ -7,8 +7,10 @@ use crate::{grpc_network::GrpcClient, simple_network::SimpleClient};
 use linera_base::identifiers::ChainId;
 use linera_chain::data_types::{BlockProposal, Certificate, HashedValue, LiteCertificate};
 use linera_core::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
-    node::{CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    data_types::{BlockHeightRange, ChainInfoQuery, ChainInfoResponse},
+    node::{
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+    },
 };
 
 #[derive(Clone)]
@@ -103,4 +105,17 @@ impl ValidatorNode for Client {
             Client::Simple(simple_client) => simple_client.get_version_info().await?,
         })
     }
+
+    async fn download_certificates(
+        &mut self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError> {
+        match self {
+            Client::Grpc(grpc_client) => grpc_client.download_certificates(chain_id, range).await,
+            Client::Simple(simple_client) => {
+                simple_client.download_certificates(chain_id, range).await
+            }
+        }
+    }
 }
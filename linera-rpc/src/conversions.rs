@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    grpc_network::{grpc, grpc::ChainInfoResult},
-    HandleCertificateRequest, HandleLiteCertificateRequest,
+    grpc_network::{
+        grpc,
+        grpc::{CertificateResult, ChainInfoResult},
+    },
+    DownloadCertificatesRequest, HandleCertificateRequest, HandleLiteCertificateRequest,
 };
 use linera_base::{
     crypto::{CryptoError, CryptoHash, PublicKey, Signature},
@@ -164,6 +167,55 @@ impl TryFrom<NodeError> for ChainInfoResult {
     }
 }
 
+impl TryFrom<Result<Certificate, NodeError>> for CertificateResult {
+    type Error = ProtoConversionError;
+
+    fn try_from(result: Result<Certificate, NodeError>) -> Result<Self, Self::Error> {
+        let inner = match result {
+            Ok(certificate) => {
+                grpc::certificate_result::Inner::Certificate(bincode::serialize(&certificate)?)
+            }
+            Err(error) => grpc::certificate_result::Inner::Error(bincode::serialize(&error)?),
+        };
+        Ok(CertificateResult { inner: Some(inner) })
+    }
+}
+
+impl TryFrom<CertificateResult> for Result<Certificate, NodeError> {
+    type Error = ProtoConversionError;
+
+    fn try_from(result: CertificateResult) -> Result<Self, Self::Error> {
+        match result.inner.ok_or(ProtoConversionError::MissingField)? {
+            grpc::certificate_result::Inner::Certificate(certificate) => {
+                Ok(Ok(bincode::deserialize(&certificate)?))
+            }
+            grpc::certificate_result::Inner::Error(error) => Ok(Err(bincode::deserialize(&error)?)),
+        }
+    }
+}
+
+impl TryFrom<DownloadCertificatesRequest> for grpc::DownloadCertificatesRequest {
+    type Error = ProtoConversionError;
+
+    fn try_from(request: DownloadCertificatesRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            chain_id: Some(request.chain_id.into()),
+            range: bincode::serialize(&request.range)?,
+        })
+    }
+}
+
+impl TryFrom<grpc::DownloadCertificatesRequest> for DownloadCertificatesRequest {
+    type Error = ProtoConversionError;
+
+    fn try_from(request: grpc::DownloadCertificatesRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            chain_id: try_proto_convert(request.chain_id)?,
+            range: bincode::deserialize(&request.range)?,
+        })
+    }
+}
+
 impl TryFrom<BlockProposal> for grpc::BlockProposal {
     type Error = ProtoConversionError;
 
@@ -172,12 +224,14 @@ impl TryFrom<BlockProposal> for grpc::BlockProposal {
             chain_id: Some(block_proposal.content.block.chain_id.into()),
             content: bincode::serialize(&block_proposal.content)?,
             owner: Some(block_proposal.owner.into()),
+            public_key: Some(block_proposal.public_key.into()),
             signature: Some(block_proposal.signature.into()),
             blobs: bincode::serialize(&block_proposal.blobs)?,
             validated: block_proposal
                 .validated
                 .map(|cert| bincode::serialize(&cert))
                 .transpose()?,
+            approvals: bincode::serialize(&block_proposal.approvals)?,
         })
     }
 }
@@ -194,12 +248,14 @@ impl TryFrom<grpc::BlockProposal> for BlockProposal {
         Ok(Self {
             content,
             owner: try_proto_convert(block_proposal.owner)?,
+            public_key: try_proto_convert(block_proposal.public_key)?,
             signature: try_proto_convert(block_proposal.signature)?,
             blobs: bincode::deserialize(&block_proposal.blobs)?,
             validated: block_proposal
                 .validated
                 .map(|bytes| bincode::deserialize(&bytes))
                 .transpose()?,
+            approvals: bincode::deserialize(&block_proposal.approvals)?,
         })
     }
 }
@@ -524,7 +580,7 @@ pub mod tests {
         data_types::{Amount, Round, Timestamp},
     };
     use linera_chain::{
-        data_types::{Block, BlockAndRound, ExecutedBlock, HashedValue},
+        data_types::{Block, BlockAndRound, BlockApproval, ExecutedBlock, HashedValue},
         test::make_first_block,
     };
     use linera_core::data_types::ChainInfo;
@@ -649,6 +705,18 @@ pub mod tests {
         round_trip_check::<_, grpc::ChainInfoQuery>(chain_info_query_some);
     }
 
+    #[test]
+    pub fn test_download_certificates_request() {
+        let request = DownloadCertificatesRequest {
+            chain_id: ChainId::root(0),
+            range: linera_core::data_types::BlockHeightRange {
+                start: BlockHeight::from(3),
+                limit: None,
+            },
+        };
+        round_trip_check::<_, grpc::DownloadCertificatesRequest>(request);
+    }
+
     #[test]
     pub fn test_lite_certificate() {
         let key_pair = KeyPair::generate();
@@ -734,6 +802,7 @@ pub mod tests {
                 round: Round::SingleLeader(4),
             },
             owner: Owner::from(KeyPair::generate().public()),
+            public_key: KeyPair::generate().public(),
             signature: Signature::new(&TestString::new("test"), &KeyPair::generate()),
             blobs: vec![HashedValue::new_confirmed(ExecutedBlock {
                 block: get_block(),
@@ -754,6 +823,7 @@ pub mod tests {
                     Signature::new(&TestString::new("signed"), &key_pair),
                 )],
             )),
+            approvals: vec![BlockApproval::new(&get_block(), &key_pair)],
         };
 
         round_trip_check::<_, grpc::BlockProposal>(block_proposal);
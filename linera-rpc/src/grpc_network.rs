@@ -20,7 +20,7 @@ use crate::{
     grpc_pool::ConnectionPool,
     mass::{MassClient, MassClientError},
     node_provider::NodeOptions,
-    rpc::{HandleCertificateRequest, HandleLiteCertificateRequest},
+    rpc::{DownloadCertificatesRequest, HandleCertificateRequest, HandleLiteCertificateRequest},
     RpcMessage,
 };
 use async_trait::async_trait;
@@ -28,7 +28,8 @@ use futures::{
     channel::{mpsc, mpsc::Receiver, oneshot::Sender},
     future,
     future::BoxFuture,
-    stream, FutureExt, StreamExt,
+    stream::{self, BoxStream},
+    FutureExt, StreamExt,
 };
 use grpc::{
     chain_info_result::Inner,
@@ -36,13 +37,16 @@ use grpc::{
     validator_node_client::ValidatorNodeClient,
     validator_worker_client::ValidatorWorkerClient,
     validator_worker_server::{ValidatorWorker as ValidatorWorkerRpc, ValidatorWorkerServer},
-    BlockProposal, Certificate, ChainInfoQuery, ChainInfoResult, CrossChainRequest,
-    LiteCertificate, SubscriptionRequest,
+    BlockProposal, Certificate, CertificateResult, ChainInfoQuery, ChainInfoResult,
+    CrossChainRequest, LiteCertificate, SubscriptionRequest,
 };
 use linera_base::identifiers::ChainId;
 use linera_chain::data_types;
 use linera_core::{
-    node::{CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    data_types::BlockHeightRange,
+    node::{
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+    },
     worker::{NetworkActions, Notification, ValidatorWorker, WorkerError, WorkerState},
 };
 use linera_storage::Storage;
@@ -475,6 +479,8 @@ where
     S: Storage + Clone + Send + Sync + 'static,
     ViewError: From<S::ContextError>,
 {
+    type DownloadCertificatesStream = BoxStream<'static, Result<CertificateResult, Status>>;
+
     #[instrument(target = "grpc_server", skip_all, err, fields(nickname = self.state.nickname(), chain_id = ?request.get_ref().chain_id()))]
     async fn handle_block_proposal(
         &self,
@@ -618,6 +624,40 @@ where
         }
     }
 
+    #[instrument(target = "grpc_server", skip_all, err, fields(nickname = self.state.nickname(), chain_id = ?request.get_ref().chain_id()))]
+    async fn download_certificates(
+        &self,
+        request: Request<grpc::DownloadCertificatesRequest>,
+    ) -> Result<Response<Self::DownloadCertificatesStream>, Status> {
+        let start = Instant::now();
+        let DownloadCertificatesRequest { chain_id, range } = request.into_inner().try_into()?;
+        debug!(?chain_id, ?range, "Handling certificate download");
+        match self.state.certificates_in_range(chain_id, range).await {
+            Ok(certificates) => {
+                Self::log_request_success_and_latency(start, "download_certificates");
+                let stream = certificates.map(|result| -> Result<_, Status> {
+                    Ok(CertificateResult::try_from(
+                        result.map_err(NodeError::from),
+                    )?)
+                });
+                Ok(Response::new(stream.boxed()))
+            }
+            Err(error) => {
+                #[cfg(with_metrics)]
+                {
+                    SERVER_REQUEST_ERROR
+                        .with_label_values(&["download_certificates"])
+                        .inc();
+                }
+                error!(nickname = self.state.nickname(), %error, "Failed to download certificates");
+                let result = CertificateResult::try_from(Err::<data_types::Certificate, _>(
+                    NodeError::from(error),
+                ))?;
+                Ok(Response::new(stream::iter([Ok(result)]).boxed()))
+            }
+        }
+    }
+
     #[instrument(target = "grpc_server", skip_all, err, fields(nickname = self.state.nickname(), chain_id= ?request.get_ref().chain_id()))]
     async fn handle_cross_chain_request(
         &self,
@@ -865,6 +905,42 @@ impl ValidatorNode for GrpcClient {
     async fn get_version_info(&mut self) -> Result<VersionInfo, NodeError> {
         Ok(self.client.get_version_info(()).await?.into_inner().into())
     }
+
+    #[instrument(target = "grpc_client", skip_all, err, fields(address = self.address))]
+    async fn download_certificates(
+        &mut self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError> {
+        let request = DownloadCertificatesRequest { chain_id, range };
+        debug!(?request, "sending gRPC request");
+        let request: grpc::DownloadCertificatesRequest =
+            request.try_into().map_err(|_| NodeError::GrpcError {
+                error: "could not convert request to proto".to_string(),
+            })?;
+        let stream = self
+            .client
+            .download_certificates(Request::new(request))
+            .await
+            .map_err(|status| NodeError::GrpcError {
+                error: format!(
+                    "remote request [download_certificates] failed with status: {:?}",
+                    status
+                ),
+            })?
+            .into_inner();
+        let certificates = stream.map(|result| {
+            let result = result.map_err(|status| NodeError::GrpcError {
+                error: format!("certificate download interrupted: {:?}", status),
+            })?;
+            Result::<data_types::Certificate, NodeError>::try_from(result).map_err(|err| {
+                NodeError::GrpcError {
+                    error: format!("failed to marshal certificate: {}", err),
+                }
+            })?
+        });
+        Ok(Box::pin(certificates))
+    }
 }
 
 #[async_trait]
@@ -952,6 +1028,12 @@ impl Proxyable for ChainInfoQuery {
     }
 }
 
+impl Proxyable for grpc::DownloadCertificatesRequest {
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id.clone()?.try_into().ok()
+    }
+}
+
 impl Proxyable for CrossChainRequest {
     fn chain_id(&self) -> Option<ChainId> {
         use grpc::cross_chain_request::Inner;
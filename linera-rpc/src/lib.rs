@@ -17,4 +17,6 @@ mod codec;
 mod conversions;
 mod rpc;
 
-pub use rpc::{HandleCertificateRequest, HandleLiteCertificateRequest, RpcMessage};
+pub use rpc::{
+    DownloadCertificatesRequest, HandleCertificateRequest, HandleLiteCertificateRequest, RpcMessage,
+};
@@ -7,7 +7,7 @@ use linera_chain::data_types::{
     BlockProposal, Certificate, HashedValue, LiteCertificate, LiteVote,
 };
 use linera_core::{
-    data_types::{ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
+    data_types::{BlockHeightRange, ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
     node::NodeError,
 };
 use linera_version::VersionInfo;
@@ -32,6 +32,10 @@ pub enum RpcMessage {
 
     // Internal to a validator
     CrossChainRequest(Box<CrossChainRequest>),
+
+    // Certificate downloads, one page per request
+    DownloadCertificates(Box<DownloadCertificatesRequest>),
+    DownloadCertificatesResponse(Vec<Certificate>),
 }
 
 impl RpcMessage {
@@ -46,12 +50,14 @@ impl RpcMessage {
             LiteCertificate(request) => request.certificate.value.chain_id,
             Certificate(request) => request.certificate.value().chain_id(),
             ChainInfoQuery(query) => query.chain_id,
+            DownloadCertificates(request) => request.chain_id,
             CrossChainRequest(request) => request.target_chain_id(),
             Vote(_)
             | Error(_)
             | ChainInfoResponse(_)
             | VersionInfoQuery
-            | VersionInfoResponse(_) => {
+            | VersionInfoResponse(_)
+            | DownloadCertificatesResponse(_) => {
                 return None;
             }
         };
@@ -84,6 +90,18 @@ impl TryFrom<RpcMessage> for VersionInfo {
     }
 }
 
+impl TryFrom<RpcMessage> for Vec<Certificate> {
+    type Error = NodeError;
+    fn try_from(message: RpcMessage) -> Result<Self, Self::Error> {
+        use RpcMessage::*;
+        match message {
+            DownloadCertificatesResponse(certificates) => Ok(certificates),
+            Error(error) => Err(*error),
+            _ => Err(NodeError::UnexpectedMessage),
+        }
+    }
+}
+
 impl From<BlockProposal> for RpcMessage {
     fn from(block_proposal: BlockProposal) -> Self {
         RpcMessage::BlockProposal(Box::new(block_proposal))
@@ -108,6 +126,12 @@ impl From<ChainInfoQuery> for RpcMessage {
     }
 }
 
+impl From<DownloadCertificatesRequest> for RpcMessage {
+    fn from(request: DownloadCertificatesRequest) -> Self {
+        RpcMessage::DownloadCertificates(Box::new(request))
+    }
+}
+
 impl From<LiteVote> for RpcMessage {
     fn from(vote: LiteVote) -> Self {
         RpcMessage::Vote(Box::new(vote))
@@ -138,6 +162,12 @@ impl From<VersionInfo> for RpcMessage {
     }
 }
 
+impl From<Vec<Certificate>> for RpcMessage {
+    fn from(certificates: Vec<Certificate>) -> Self {
+        RpcMessage::DownloadCertificatesResponse(certificates)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[cfg_attr(any(test, feature = "test"), derive(Eq, PartialEq))]
 pub struct HandleLiteCertificateRequest<'a> {
@@ -152,3 +182,10 @@ pub struct HandleCertificateRequest {
     pub wait_for_outgoing_messages: bool,
     pub blobs: Vec<HashedValue>,
 }
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(any(test, feature = "test"), derive(Eq, PartialEq))]
+pub struct DownloadCertificatesRequest {
+    pub chain_id: ChainId,
+    pub range: BlockHeightRange,
+}
@@ -10,15 +10,22 @@ use crate::{
     },
     mass::{MassClient, MassClientError},
     transport::{MessageHandler, ServerHandle, TransportProtocol},
-    HandleCertificateRequest, HandleLiteCertificateRequest, RpcMessage,
+    DownloadCertificatesRequest, HandleCertificateRequest, HandleLiteCertificateRequest,
+    RpcMessage,
 };
 use async_trait::async_trait;
-use futures::{channel::mpsc, sink::SinkExt, stream::StreamExt};
+use futures::{
+    channel::mpsc,
+    sink::SinkExt,
+    stream::{self, StreamExt, TryStreamExt},
+};
 use linera_base::identifiers::ChainId;
 use linera_chain::data_types::{BlockProposal, Certificate, HashedValue, LiteCertificate};
 use linera_core::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
-    node::{CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    data_types::{BlockHeightRange, ChainInfoQuery, ChainInfoResponse},
+    node::{
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+    },
     worker::{NetworkActions, ValidatorWorker, WorkerError, WorkerState},
 };
 use linera_storage::Storage;
@@ -29,6 +36,10 @@ use std::{io, time::Duration};
 use tokio::{sync::oneshot, time};
 use tracing::{debug, error, info, instrument, warn};
 
+/// The maximum number of certificates sent in response to a single `DownloadCertificates`
+/// request. Longer ranges are downloaded one page at a time.
+const CERTIFICATES_PER_PAGE: u64 = 16;
+
 #[derive(Clone)]
 pub struct Server<S> {
     network: ValidatorInternalNetworkPreConfig<TransportProtocol>,
@@ -276,6 +287,32 @@ where
                     }
                 }
             }
+            RpcMessage::DownloadCertificates(request) => {
+                let DownloadCertificatesRequest {
+                    chain_id,
+                    mut range,
+                } = *request;
+                // Responses cannot be streamed, so at most one page is sent back.
+                range.limit = Some(range.limit.map_or(CERTIFICATES_PER_PAGE, |limit| {
+                    limit.min(CERTIFICATES_PER_PAGE)
+                }));
+                let result = match self
+                    .server
+                    .state
+                    .certificates_in_range(chain_id, range)
+                    .await
+                {
+                    Ok(certificates) => certificates.try_collect::<Vec<_>>().await,
+                    Err(error) => Err(error),
+                };
+                match result {
+                    Ok(certificates) => Ok(Some(certificates.into())),
+                    Err(error) => {
+                        error!(nickname = self.server.state.nickname(), %error, "Failed to download certificates");
+                        Err(error.into())
+                    }
+                }
+            }
             RpcMessage::CrossChainRequest(request) => {
                 match self.server.state.handle_cross_chain_request(*request).await {
                     Ok(actions) => {
@@ -294,7 +331,8 @@ where
             RpcMessage::Vote(_)
             | RpcMessage::Error(_)
             | RpcMessage::ChainInfoResponse(_)
-            | RpcMessage::VersionInfoResponse(_) => Err(NodeError::UnexpectedMessage),
+            | RpcMessage::VersionInfoResponse(_)
+            | RpcMessage::DownloadCertificatesResponse(_) => Err(NodeError::UnexpectedMessage),
         };
 
         self.server.packets_processed += 1;
@@ -450,6 +488,47 @@ impl ValidatorNode for SimpleClient {
     async fn get_version_info(&mut self) -> Result<VersionInfo, NodeError> {
         self.query(RpcMessage::VersionInfoQuery).await
     }
+
+    async fn download_certificates(
+        &mut self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError> {
+        let client = self.clone();
+        // Each page is only requested once the previous one has been consumed.
+        let pages = stream::try_unfold(Some(range), move |range| {
+            let mut client = client.clone();
+            async move {
+                let Some(range) = range else {
+                    return Ok(None);
+                };
+                let page_size = range.limit.map_or(CERTIFICATES_PER_PAGE, |limit| {
+                    limit.min(CERTIFICATES_PER_PAGE)
+                });
+                let request = DownloadCertificatesRequest {
+                    chain_id,
+                    range: BlockHeightRange {
+                        start: range.start,
+                        limit: Some(page_size),
+                    },
+                };
+                let certificates: Vec<Certificate> = client.query(request.into()).await?;
+                let count = certificates.len() as u64;
+                let limit = range.limit.map(|limit| limit.saturating_sub(count));
+                let next_range = if count < page_size || limit == Some(0) {
+                    None
+                } else {
+                    Some(BlockHeightRange {
+                        start: range.start.try_add(count.into())?,
+                        limit,
+                    })
+                };
+                let certificates = stream::iter(certificates.into_iter().map(Ok));
+                Ok::<_, NodeError>(Some((certificates, next_range)))
+            }
+        });
+        Ok(Box::pin(pages.try_flatten()))
+    }
 }
 
 #[derive(Clone)]
@@ -3,4 +3,4 @@
 
 //! Types reexported from [`linera_base`].
 
-pub use linera_base::{abi::*, data_types::*, identifiers::*, BcsHexParseError};
+pub use linera_base::{abi::*, data_types::*, http::*, identifiers::*, BcsHexParseError};
@@ -321,6 +321,36 @@ pub fn add_to_linker(linker: &mut Linker<Resources>) -> Result<()> {
             })
         },
     )?;
+    linker.func_wrap1_async(
+        "contract_system_api",
+        "read-block-randomness: \
+            func() -> option<record { part1: u64, part2: u64, part3: u64, part4: u64 }>",
+        move |mut caller: Caller<'_, Resources>, return_offset: i32| {
+            Box::new(async move {
+                let function = get_function(
+                    &mut caller,
+                    "mocked-read-block-randomness: \
+                        func() -> option<record { part1: u64, part2: u64, part3: u64, part4: u64 }>",
+                )
+                .expect(
+                    "Missing `mocked-read-block-randomness` function in the module. \
+                    Please ensure `linera_sdk::test::mock_block_randomness` was called",
+                );
+
+                let (result_offset,) = function
+                    .typed::<(), (i32,), _>(&mut caller)
+                    .expect("Incorrect `mocked-read-block-randomness` function signature")
+                    .call_async(&mut caller, ())
+                    .await
+                    .expect(
+                        "Failed to call `mocked-read-block-randomness` function. \
+                        Please ensure `linera_sdk::test::mock_block_randomness` was called",
+                    );
+
+                copy_memory_slices(&mut caller, result_offset, return_offset, 40);
+            })
+        },
+    )?;
     linker.func_wrap3_async(
         "contract_system_api",
         "log: func(message: string, level: enum { trace, debug, info, warn, error }) -> unit",
@@ -694,6 +724,73 @@ pub fn add_to_linker(linker: &mut Linker<Resources>) -> Result<()> {
             })
         },
     )?;
+    linker.func_wrap8_async(
+        "service_system_api",
+        "http-request: func(\
+            method: enum { get, post }, \
+            url: string, \
+            headers: list<tuple<string, string>>, \
+            body: list<u8>\
+        ) -> result<\
+            record { status: u16, headers: list<tuple<string, string>>, body: list<u8> }, \
+            string\
+        >",
+        move |mut caller: Caller<'_, Resources>,
+              method: i32,
+              url_address: i32,
+              url_length: i32,
+              headers_address: i32,
+              headers_length: i32,
+              body_address: i32,
+              body_length: i32,
+              return_offset: i32| {
+            Box::new(async move {
+                let function = get_function(
+                    &mut caller,
+                    "mocked-http-request: func(\
+                        method: enum { get, post }, \
+                        url: string, \
+                        headers: list<tuple<string, string>>, \
+                        body: list<u8>\
+                    ) -> result<\
+                        record { \
+                            status: u16, \
+                            headers: list<tuple<string, string>>, \
+                            body: list<u8> \
+                        }, \
+                        string\
+                    >",
+                )
+                .expect(
+                    "Missing `mocked-http-request` function in the module. \
+                    Please ensure `linera_sdk::test::mock_http_request` was called",
+                );
+
+                let (result_offset,) = function
+                    .typed::<(i32, i32, i32, i32, i32, i32, i32), (i32,), _>(&mut caller)
+                    .expect("Incorrect `mocked-http-request` function signature")
+                    .call_async(
+                        &mut caller,
+                        (
+                            method,
+                            url_address,
+                            url_length,
+                            headers_address,
+                            headers_length,
+                            body_address,
+                            body_length,
+                        ),
+                    )
+                    .await
+                    .expect(
+                        "Failed to call `mocked-http-request` function. \
+                        Please ensure `linera_sdk::test::mock_http_request` was called",
+                    );
+
+                copy_memory_slices(&mut caller, result_offset, return_offset, 24);
+            })
+        },
+    )?;
 
     linker.func_wrap2_async(
         "view_system_api",
@@ -27,6 +27,17 @@ impl From<wit_types::OperationContext> for OperationContext {
     }
 }
 
+impl From<wit_types::migration_types::OperationContext> for OperationContext {
+    fn from(context: wit_types::migration_types::OperationContext) -> Self {
+        OperationContext {
+            chain_id: ChainId(context.chain_id.into()),
+            authenticated_signer: context.authenticated_signer.map(Owner::from),
+            height: BlockHeight(context.height),
+            index: context.index,
+        }
+    }
+}
+
 impl From<wit_types::MessageContext> for MessageContext {
     fn from(context: wit_types::MessageContext) -> Self {
         MessageContext {
@@ -35,6 +46,7 @@ impl From<wit_types::MessageContext> for MessageContext {
             authenticated_signer: context.authenticated_signer.map(Owner::from),
             height: BlockHeight(context.height),
             message_id: context.message_id.into(),
+            response_to: context.response_to.map(Into::into),
         }
     }
 }
@@ -94,6 +106,23 @@ impl From<wit_types::CryptoHash> for CryptoHash {
     }
 }
 
+impl From<wit_types::migration_types::CryptoHash> for Owner {
+    fn from(crypto_hash: wit_types::migration_types::CryptoHash) -> Self {
+        Owner(crypto_hash.into())
+    }
+}
+
+impl From<wit_types::migration_types::CryptoHash> for CryptoHash {
+    fn from(crypto_hash: wit_types::migration_types::CryptoHash) -> Self {
+        CryptoHash::from([
+            crypto_hash.part1,
+            crypto_hash.part2,
+            crypto_hash.part3,
+            crypto_hash.part4,
+        ])
+    }
+}
+
 impl From<wit_system_api::MessageId> for MessageId {
     fn from(message_id: wit_system_api::MessageId) -> Self {
         MessageId {
@@ -188,6 +217,8 @@ impl From<wit_system_api::ChainOwnershipResult> for ChainOwnership {
             owners,
             multi_leader_rounds,
             timeout_config,
+            approval_threshold,
+            open_multi_leader_rounds,
         } = guest;
         ChainOwnership {
             super_owners: super_owners
@@ -206,6 +237,8 @@ impl From<wit_system_api::ChainOwnershipResult> for ChainOwnership {
                 .collect(),
             multi_leader_rounds,
             timeout_config: timeout_config.into(),
+            approval_threshold,
+            open_multi_leader_rounds,
         }
     }
 }
@@ -4,11 +4,14 @@
 //! Conversions from types declared in [`linera-sdk`] to types generated by
 //! [`wit-bindgen-guest-rust`].
 
-use super::{contract_system_api as wit_system_api, wit_types};
+use super::{
+    contract_system_api as wit_system_api,
+    wit_types::{self, migration_types},
+};
 use crate::{ApplicationCallOutcome, ExecutionOutcome, OutgoingMessage, SessionCallOutcome};
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{Amount, Resources},
+    data_types::{Amount, FeeSponsorship, Resources},
     identifiers::{Account, ApplicationId, ChannelName, Destination, MessageId, Owner, SessionId},
 };
 
@@ -40,6 +43,15 @@ impl From<Amount> for wit_system_api::Amount {
     }
 }
 
+impl From<FeeSponsorship> for wit_system_api::FeeSponsorship {
+    fn from(sponsorship: FeeSponsorship) -> Self {
+        wit_system_api::FeeSponsorship {
+            max_fees_per_operation: sponsorship.max_fees_per_operation.into(),
+            max_fees_per_block: sponsorship.max_fees_per_block.into(),
+        }
+    }
+}
+
 impl From<CryptoHash> for wit_types::CryptoHash {
     fn from(crypto_hash: CryptoHash) -> Self {
         let parts = <[u64; 4]>::from(crypto_hash);
@@ -53,6 +65,19 @@ impl From<CryptoHash> for wit_types::CryptoHash {
     }
 }
 
+impl From<CryptoHash> for migration_types::CryptoHash {
+    fn from(crypto_hash: CryptoHash) -> Self {
+        let parts = <[u64; 4]>::from(crypto_hash);
+
+        migration_types::CryptoHash {
+            part1: parts[0],
+            part2: parts[1],
+            part3: parts[2],
+            part4: parts[3],
+        }
+    }
+}
+
 impl From<Account> for wit_system_api::Account {
     fn from(account: Account) -> Self {
         wit_system_api::Account {
@@ -128,6 +153,7 @@ impl From<OutgoingMessage<Vec<u8>>> for wit_types::OutgoingMessage {
             authenticated: message.authenticated,
             is_tracked: message.is_tracked,
             resources: message.resources.into(),
+            deadline: message.deadline.map(|deadline| deadline.micros()),
             message: message.message,
         }
     }
@@ -196,3 +222,80 @@ impl From<ChannelName> for wit_types::ChannelName {
         }
     }
 }
+
+impl From<OutgoingMessage<Vec<u8>>> for migration_types::OutgoingMessage {
+    fn from(message: OutgoingMessage<Vec<u8>>) -> Self {
+        Self {
+            destination: message.destination.into(),
+            authenticated: message.authenticated,
+            is_tracked: message.is_tracked,
+            resources: message.resources.into(),
+            deadline: message.deadline.map(|deadline| deadline.micros()),
+            message: message.message,
+        }
+    }
+}
+
+impl From<Resources> for migration_types::Resources {
+    fn from(resources: Resources) -> Self {
+        migration_types::Resources {
+            fuel: resources.fuel,
+            read_operations: resources.read_operations,
+            write_operations: resources.write_operations,
+            bytes_to_read: resources.bytes_to_read,
+            bytes_to_write: resources.bytes_to_write,
+            messages: resources.messages,
+            message_size: resources.message_size,
+            storage_size_delta: resources.storage_size_delta,
+        }
+    }
+}
+
+impl From<ExecutionOutcome<Vec<u8>>> for migration_types::ExecutionOutcome {
+    fn from(outcome: ExecutionOutcome<Vec<u8>>) -> Self {
+        let messages = outcome
+            .messages
+            .into_iter()
+            .map(migration_types::OutgoingMessage::from)
+            .collect();
+
+        let subscribe = outcome
+            .subscribe
+            .into_iter()
+            .map(|(subscription, chain_id)| (subscription.into(), chain_id.0.into()))
+            .collect();
+
+        let unsubscribe = outcome
+            .unsubscribe
+            .into_iter()
+            .map(|(subscription, chain_id)| (subscription.into(), chain_id.0.into()))
+            .collect();
+
+        migration_types::ExecutionOutcome {
+            messages,
+            subscribe,
+            unsubscribe,
+        }
+    }
+}
+
+impl From<Destination> for migration_types::Destination {
+    fn from(destination: Destination) -> Self {
+        match destination {
+            Destination::Recipient(chain_id) => {
+                migration_types::Destination::Recipient(chain_id.0.into())
+            }
+            Destination::Subscribers(subscription) => {
+                migration_types::Destination::Subscribers(subscription.into())
+            }
+        }
+    }
+}
+
+impl From<ChannelName> for migration_types::ChannelName {
+    fn from(name: ChannelName) -> Self {
+        migration_types::ChannelName {
+            name: name.into_bytes(),
+        }
+    }
+}
@@ -46,6 +46,22 @@ macro_rules! contract {
             )
         }
 
+        #[doc(hidden)]
+        #[no_mangle]
+        fn __contract_migrate(
+            context: $crate::OperationContext,
+            argument: Vec<u8>,
+        ) -> Result<$crate::ExecutionOutcome<Vec<u8>>, String> {
+            $crate::contract::run_async_entrypoint::<$application, _, _, _, _>(
+                move |mut application| async move {
+                    application
+                        .migrate(&context.into(), argument)
+                        .await
+                        .map(|outcome| (application, outcome.into_raw()))
+                },
+            )
+        }
+
         #[doc(hidden)]
         #[no_mangle]
         fn __contract_execute_operation(
@@ -182,6 +198,11 @@ extern "Rust" {
         argument: Vec<u8>,
     ) -> Result<ExecutionOutcome<Vec<u8>>, String>;
 
+    fn __contract_migrate(
+        context: OperationContext,
+        argument: Vec<u8>,
+    ) -> Result<ExecutionOutcome<Vec<u8>>, String>;
+
     fn __contract_execute_operation(
         context: OperationContext,
         argument: Vec<u8>,
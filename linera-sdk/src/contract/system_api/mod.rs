@@ -9,11 +9,13 @@ mod private;
 pub mod private;
 
 pub(crate) use self::private::{
-    call_application, call_session, current_application_parameters, load_view, store_view,
+    call_application, call_session, current_application_parameters, load_view, send_request,
+    send_response, store_view,
 };
 use super::contract_system_api as wit;
 use linera_base::{
-    data_types::{Amount, Timestamp},
+    crypto::CryptoHash,
+    data_types::{Amount, FeeSponsorship, Timestamp},
     identifiers::{Account, ApplicationId, ChainId, Owner},
     ownership::ChainOwnership,
 };
@@ -53,6 +55,28 @@ pub fn claim(source: Account, destination: Account, amount: Amount) {
     wit::claim(source.into(), destination.into(), amount.into())
 }
 
+/// Retrieves the balance of the current application's account on the current chain.
+pub fn current_application_balance() -> Amount {
+    wit::read_application_balance().into()
+}
+
+/// Moves amount from the chain balance, or from the account of `source`, to the current
+/// application's account.
+pub fn fund_application(source: Option<Owner>, amount: Amount) {
+    wit::fund_application(source.map(|source| source.into()), amount.into())
+}
+
+/// Sends amount from the current application's account to destination.
+pub fn withdraw_from_application(destination: Account, amount: Amount) {
+    wit::withdraw_from_application(destination.into(), amount.into())
+}
+
+/// Makes the current application pay the fees of the operations that target it, up to the
+/// given limits, using the funds in its account. Passing `None` stops the sponsorship.
+pub fn set_fee_sponsorship(sponsorship: Option<FeeSponsorship>) {
+    wit::set_fee_sponsorship(sponsorship.map(|sponsorship| sponsorship.into()))
+}
+
 /// Retrieves the owner configuration for the current chain.
 pub fn chain_ownership() -> ChainOwnership {
     wit::chain_ownership().into()
@@ -63,6 +87,16 @@ pub fn current_system_time() -> Timestamp {
     wit::read_system_timestamp().into()
 }
 
+/// Retrieves a random value derived from the validators' signatures on the previous block
+/// of this chain.
+///
+/// Each call returns a different value. The proposer of the block knows these values in
+/// advance, so they should only be used to resolve commitments made in earlier blocks.
+/// Returns `None` in the first block of a chain and after a change of epoch.
+pub fn block_randomness() -> Option<CryptoHash> {
+    wit::read_block_randomness().map(CryptoHash::from)
+}
+
 /// Requests the host to log a message.
 ///
 /// Useful for debugging locally, but may be ignored by validators.
@@ -6,7 +6,7 @@
 
 use super::super::contract_system_api as wit;
 use crate::views::ViewStorageContext;
-use linera_base::identifiers::{ApplicationId, SessionId};
+use linera_base::identifiers::{ApplicationId, ChainId, MessageId, SessionId};
 use linera_views::views::{RootView, View};
 
 /// Retrieves the current application parameters.
@@ -61,3 +61,13 @@ pub fn call_session(
 
     wit::try_call_session(authenticated, session.into(), argument, &forwarded_sessions).into()
 }
+
+/// Sends a request to the current application on another chain.
+pub fn send_request(destination: ChainId, authenticated: bool, message: &[u8]) -> MessageId {
+    wit::send_request(destination.0.into(), authenticated, message).into()
+}
+
+/// Sends a response to the request that the current application is executing.
+pub fn send_response(message: &[u8]) {
+    wit::send_response(message)
+}
@@ -8,15 +8,26 @@
 // Export the contract interface.
 wit_bindgen_guest_rust::export!("contract.wit");
 
+// Export the optional migration entrypoint.
+wit_bindgen_guest_rust::export!("contract_migration.wit");
+
 pub use self::contract::{
     ApplicationCallOutcome, ApplicationId, BlockHeight, BytecodeId, CalleeContext, ChainId,
     ChannelName, CryptoHash, Destination, ExecutionOutcome, MessageContext, MessageId,
     OperationContext, OutgoingMessage, Owner, Resources, SessionCallOutcome, SessionId,
     SessionState,
 };
+/// Types of the migration entrypoint, which are distinct from the contract interface's ones.
+pub mod migration_types {
+    pub use super::contract_migration::{
+        ChannelName, CryptoHash, Destination, ExecutionOutcome, OperationContext, OutgoingMessage,
+        Resources,
+    };
+}
+
 use super::{
     __contract_execute_message, __contract_execute_operation, __contract_handle_application_call,
-    __contract_handle_session_call, __contract_initialize,
+    __contract_handle_session_call, __contract_initialize, __contract_migrate,
 };
 
 /// Implementation of the contract WIT entrypoints.
@@ -78,3 +89,16 @@ impl contract::Contract for Contract {
         .map(|outcome| outcome.into())
     }
 }
+
+/// Implementation of the optional migration WIT entrypoint.
+pub struct ContractMigration;
+
+impl contract_migration::ContractMigration for ContractMigration {
+    fn migrate(
+        context: migration_types::OperationContext,
+        argument: Vec<u8>,
+    ) -> Result<migration_types::ExecutionOutcome, String> {
+        let context = context.into();
+        unsafe { __contract_migrate(context, argument) }.map(|outcome| outcome.into())
+    }
+}
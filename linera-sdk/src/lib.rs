@@ -47,6 +47,7 @@ pub mod service;
 #[cfg_attr(not(target_arch = "wasm32"), path = "./test/integration/mod.rs")]
 #[cfg_attr(target_arch = "wasm32", path = "./test/unit/mod.rs")]
 pub mod test;
+pub mod two_phase_commit;
 pub mod util;
 pub mod views;
 
@@ -54,7 +55,7 @@ use self::contract::ContractStateStorage;
 use async_trait::async_trait;
 use linera_base::{
     abi::{ContractAbi, ServiceAbi, WithContractAbi, WithServiceAbi},
-    data_types::BlockHeight,
+    data_types::{BlockHeight, Timestamp},
     identifiers::{ApplicationId, ChainId, ChannelName, Destination, MessageId, Owner},
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -116,6 +117,23 @@ pub trait Contract: WithContractAbi + ContractAbi + Send + Sized {
         argument: Self::InitializationArgument,
     ) -> Result<ExecutionOutcome<Self::Message>, Self::Error>;
 
+    /// Migrates the application state after an upgrade.
+    ///
+    /// This is called once per upgrade, using the new bytecode, on the microchain that created
+    /// the application and then on every other microchain with a state for the application,
+    /// when it receives the new description. The state is loaded from what the previous bytecode
+    /// stored, so it must remain readable by the new state type.
+    ///
+    /// The `argument` is the one given in the upgrade operation. By default, nothing is done.
+    /// Applications compiled without this entrypoint are upgraded without a migration.
+    async fn migrate(
+        &mut self,
+        _context: &OperationContext,
+        _argument: Vec<u8>,
+    ) -> Result<ExecutionOutcome<Self::Message>, Self::Error> {
+        Ok(ExecutionOutcome::default())
+    }
+
     /// Applies an operation from the current block.
     ///
     /// Operations are created by users and added to blocks, serving as the starting point for an
@@ -256,6 +274,36 @@ pub trait Contract: WithContractAbi + ContractAbi + Send + Sized {
         Ok((response, ids))
     }
 
+    /// Sends a request to this application on the `destination` chain.
+    ///
+    /// Returns the ID of the request. The response is received as a message whose
+    /// [`MessageContext::response_to`] is that ID. If the request is rejected, it bounces back
+    /// with the same [`MessageContext::response_to`] instead.
+    fn send_request(
+        &mut self,
+        destination: ChainId,
+        authenticated: bool,
+        request: &Self::Message,
+    ) -> Result<MessageId, Self::Error> {
+        let request_bytes = bcs::to_bytes(request)?;
+        Ok(crate::contract::system_api::send_request(
+            destination,
+            authenticated,
+            &request_bytes,
+        ))
+    }
+
+    /// Sends a `response` to the request that is currently being executed, back to this
+    /// application on the chain that sent it.
+    ///
+    /// Each request can only be answered once, from [`Contract::execute_message`]. The
+    /// execution fails if there is no request to answer.
+    fn send_response(&mut self, response: &Self::Message) -> Result<(), Self::Error> {
+        let response_bytes = bcs::to_bytes(response)?;
+        crate::contract::system_api::send_response(&response_bytes);
+        Ok(())
+    }
+
     /// Retrieves the parameters of the application.
     fn parameters() -> Result<Self::Parameters, Self::Error> {
         let bytes = crate::contract::system_api::current_application_parameters();
@@ -345,6 +393,9 @@ pub struct MessageContext {
     /// The id of the message (based on the operation height and index in the remote
     /// chain that created the message).
     pub message_id: MessageId,
+    /// The ID of the request that this message answers if it is a response, or the ID of the
+    /// original message if it is bouncing.
+    pub response_to: Option<MessageId>,
 }
 
 /// The context of the execution of an application's cross-application call or session call handler.
@@ -380,6 +431,9 @@ pub struct OutgoingMessage<Message> {
     pub is_tracked: bool,
     /// Resources to be forwarded with the message.
     pub resources: Resources,
+    /// The time after which the receiver can no longer accept the message, if any. Tracked
+    /// messages then bounce back to the sender.
+    pub deadline: Option<Timestamp>,
     /// The message itself.
     pub message: Message,
 }
@@ -397,9 +451,16 @@ where
             authenticated: self.authenticated,
             is_tracked: self.is_tracked,
             resources: self.resources,
+            deadline: self.deadline,
             message,
         }
     }
+
+    /// Sets the time after which the receiver can no longer accept the message.
+    pub fn with_deadline(mut self, deadline: Timestamp) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
 }
 
 /// Externally visible results of an execution. These results are meant in the context of
@@ -435,6 +496,7 @@ impl<Message: Serialize + Debug + DeserializeOwned> ExecutionOutcome<Message> {
             authenticated: false,
             is_tracked: false,
             resources: Resources::default(),
+            deadline: None,
             message,
         });
         self
@@ -453,6 +515,7 @@ impl<Message: Serialize + Debug + DeserializeOwned> ExecutionOutcome<Message> {
             authenticated: true,
             is_tracked: false,
             resources: Resources::default(),
+            deadline: None,
             message,
         });
         self
@@ -472,6 +535,7 @@ impl<Message: Serialize + Debug + DeserializeOwned> ExecutionOutcome<Message> {
             authenticated: false,
             is_tracked: true,
             resources: Resources::default(),
+            deadline: None,
             message,
         });
         self
@@ -492,6 +556,7 @@ impl<Message: Serialize + Debug + DeserializeOwned> ExecutionOutcome<Message> {
             authenticated: true,
             is_tracked: true,
             resources: Resources::default(),
+            deadline: None,
             message,
         });
         self
@@ -11,6 +11,7 @@ use crate::QueryContext;
 use linera_base::{
     crypto::CryptoHash,
     data_types::{Amount, BlockHeight},
+    http::HttpResponse,
     identifiers::{ApplicationId, BytecodeId, ChainId, MessageId, Owner},
 };
 
@@ -77,3 +78,13 @@ impl From<wit_system_api::Amount> for Amount {
         Amount::from_attos(value)
     }
 }
+
+impl From<wit_system_api::HttpResponse> for HttpResponse {
+    fn from(response: wit_system_api::HttpResponse) -> Self {
+        HttpResponse {
+            status: response.status,
+            headers: response.headers,
+            body: response.body,
+        }
+    }
+}
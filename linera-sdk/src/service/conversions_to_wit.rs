@@ -6,6 +6,7 @@
 use super::{service_system_api as wit_system_api, wit_types};
 use linera_base::{
     crypto::CryptoHash,
+    http::HttpMethod,
     identifiers::{ApplicationId, MessageId, Owner},
 };
 
@@ -21,6 +22,15 @@ impl From<log::Level> for wit_system_api::LogLevel {
     }
 }
 
+impl From<HttpMethod> for wit_system_api::HttpMethod {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => wit_system_api::HttpMethod::Get,
+            HttpMethod::Post => wit_system_api::HttpMethod::Post,
+        }
+    }
+}
+
 impl From<CryptoHash> for wit_system_api::CryptoHash {
     fn from(hash_value: CryptoHash) -> Self {
         let parts = <[u64; 4]>::from(hash_value);
@@ -51,6 +51,15 @@ macro_rules! service {
             unreachable!("Contract entrypoint should not be called in service");
         }
 
+        #[doc(hidden)]
+        #[no_mangle]
+        fn __contract_migrate(
+            _: $crate::OperationContext,
+            _: Vec<u8>,
+        ) -> Result<$crate::ExecutionOutcome<Vec<u8>>, String> {
+            unreachable!("Contract entrypoint should not be called in service");
+        }
+
         #[doc(hidden)]
         #[no_mangle]
         fn __contract_execute_operation(
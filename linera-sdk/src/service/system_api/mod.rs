@@ -12,6 +12,7 @@ pub(crate) use self::private::{current_application_parameters, load_view, query_
 use super::service_system_api as wit;
 use linera_base::{
     data_types::{Amount, Timestamp},
+    http::{HttpRequest, HttpResponse},
     identifiers::{ApplicationId, ChainId, Owner},
 };
 use std::fmt;
@@ -47,3 +48,18 @@ pub fn current_system_time() -> Timestamp {
 pub fn log(message: &fmt::Arguments<'_>, level: log::Level) {
     wit::log(&message.to_string(), level.into());
 }
+
+/// Requests the host to send an HTTP request, and returns its response.
+///
+/// Only the hosts allowed by the node executing the service can be contacted, the sizes of
+/// the bodies are limited, and each query may only make a limited number of requests within
+/// a limited time. The error describes why no response was obtained.
+pub fn http_request(request: HttpRequest) -> Result<HttpResponse, String> {
+    let headers = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect::<Vec<_>>();
+    wit::http_request(request.method.into(), &request.url, &headers, &request.body)
+        .map(HttpResponse::from)
+}
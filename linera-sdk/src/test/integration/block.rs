@@ -12,7 +12,8 @@ use linera_base::{
     identifiers::{ApplicationId, ChainId, MessageId, Owner},
 };
 use linera_chain::data_types::{
-    Block, Certificate, HashedValue, IncomingMessage, LiteVote, SignatureAggregator,
+    Block, Certificate, HashedValue, IncomingMessage, LiteVote, MessageAction,
+    PreviousBlockSignatures, SignatureAggregator,
 };
 use linera_execution::{system::SystemOperation, Operation};
 use std::mem;
@@ -21,7 +22,7 @@ use std::mem;
 /// [`Certificate`]s using a [`TestValidator`].
 pub struct BlockBuilder {
     block: Block,
-    incoming_messages: Vec<MessageId>,
+    incoming_messages: Vec<(MessageId, MessageAction)>,
     validator: TestValidator,
 }
 
@@ -44,6 +45,8 @@ impl BlockBuilder {
         validator: TestValidator,
     ) -> Self {
         let previous_block_hash = previous_block.map(|certificate| certificate.hash());
+        let previous_block_signatures = previous_block
+            .map(|certificate| PreviousBlockSignatures::new(certificate, validator.committee()));
         let height = previous_block
             .map(|certificate| {
                 certificate
@@ -61,6 +64,7 @@ impl BlockBuilder {
                 incoming_messages: vec![],
                 operations: vec![],
                 previous_block_hash,
+                previous_block_signatures,
                 height,
                 authenticated_signer: Some(owner),
                 timestamp: Timestamp::from(0),
@@ -116,7 +120,19 @@ impl BlockBuilder {
     /// The block that produces the message must have already been executed by the test validator,
     /// so that the message is already in the inbox of the microchain this block belongs to.
     pub fn with_incoming_message(&mut self, message_id: MessageId) -> &mut Self {
-        self.incoming_messages.push(message_id);
+        self.incoming_messages
+            .push((message_id, MessageAction::Accept));
+        self
+    }
+
+    /// Rejects an incoming message referenced by the [`MessageId`].
+    ///
+    /// The message is removed from the inbox without being executed. If it is tracked, it bounces
+    /// back to its sender. The block that produces the message must have already been executed
+    /// by the test validator.
+    pub fn with_rejected_incoming_message(&mut self, message_id: MessageId) -> &mut Self {
+        self.incoming_messages
+            .push((message_id, MessageAction::Reject));
         self
     }
 
@@ -128,7 +144,11 @@ impl BlockBuilder {
         &mut self,
         message_ids: impl IntoIterator<Item = MessageId>,
     ) -> &mut Self {
-        self.incoming_messages.extend(message_ids);
+        self.incoming_messages.extend(
+            message_ids
+                .into_iter()
+                .map(|message_id| (message_id, MessageAction::Accept)),
+        );
         self
     }
 
@@ -183,8 +203,8 @@ impl BlockBuilder {
     async fn collect_incoming_messages(&mut self) {
         let chain_id = self.block.chain_id;
 
-        for message_id in mem::take(&mut self.incoming_messages) {
-            let message = self
+        for (message_id, action) in mem::take(&mut self.incoming_messages) {
+            let mut message = self
                 .validator
                 .worker()
                 .await
@@ -192,6 +212,7 @@ impl BlockBuilder {
                 .await
                 .expect("Failed to find message to receive in block")
                 .expect("Message that block should consume has not been emitted");
+            message.action = action;
 
             self.block.incoming_messages.push(message);
         }
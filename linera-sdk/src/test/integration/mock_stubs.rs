@@ -9,7 +9,9 @@
 //! called.
 
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Timestamp},
+    http::{HttpRequest, HttpResponse},
     identifiers::{ApplicationId, ChainId},
 };
 use linera_views::memory::MemoryContext;
@@ -49,6 +51,11 @@ pub fn mock_system_timestamp(_system_timestamp: impl Into<Option<Timestamp>>) {
     unreachable!("{ERROR_MESSAGE}");
 }
 
+/// Sets the mocked values returned by the block randomness, in order.
+pub fn mock_block_randomness(_values: impl IntoIterator<Item = CryptoHash>) {
+    unreachable!("{ERROR_MESSAGE}");
+}
+
 /// Returns all messages logged so far.
 pub fn log_messages() -> Vec<(log::Level, String)> {
     unreachable!("{ERROR_MESSAGE}");
@@ -72,3 +79,10 @@ pub fn mock_try_query_application<E>(
 {
     unreachable!("{ERROR_MESSAGE}");
 }
+
+/// Mocks the `http_request` system API.
+pub fn mock_http_request(
+    _handler: impl FnMut(HttpRequest) -> Result<HttpResponse, String> + 'static,
+) {
+    unreachable!("{ERROR_MESSAGE}");
+}
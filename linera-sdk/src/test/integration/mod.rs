@@ -22,3 +22,5 @@ mod validator;
 pub use self::mock_stubs::*;
 #[cfg(any(feature = "wasmer", feature = "wasmtime"))]
 pub use self::{block::BlockBuilder, chain::ActiveChain, validator::TestValidator};
+#[cfg(any(feature = "wasmer", feature = "wasmtime"))]
+pub use linera_execution::FuelProfile;
@@ -18,7 +18,7 @@ use linera_base::{
 use linera_core::worker::WorkerState;
 use linera_execution::{
     committee::{Committee, ValidatorName},
-    WasmRuntime,
+    FuelProfile, FuelProfiler, WasmRuntime,
 };
 use linera_storage::{MemoryStorage, Storage, WallClock};
 use linera_views::memory::{MemoryStoreConfig, TEST_MEMORY_MAX_STREAM_QUERIES};
@@ -44,6 +44,7 @@ pub struct TestValidator {
     worker: Arc<Mutex<WorkerState<MemoryStorage<WallClock>>>>,
     root_chain_counter: Arc<AtomicU32>,
     chains: Arc<DashMap<ChainId, ActiveChain>>,
+    fuel_profiler: FuelProfiler,
 }
 
 impl Default for TestValidator {
@@ -53,10 +54,12 @@ impl Default for TestValidator {
         let store_config = MemoryStoreConfig::new(TEST_MEMORY_MAX_STREAM_QUERIES);
         let namespace = "validator";
         let wasm_runtime = Some(WasmRuntime::default());
-        let storage = MemoryStorage::new(store_config, namespace, wasm_runtime)
+        let mut storage = MemoryStorage::new(store_config, namespace, wasm_runtime)
             .now_or_never()
             .expect("execution of MemoryStorage::new should not await anything")
             .expect("storage");
+        let fuel_profiler = FuelProfiler::default();
+        storage.fuel_profiler = Some(fuel_profiler.clone());
 
         let worker = WorkerState::new(
             "Single validator node".to_string(),
@@ -70,6 +73,7 @@ impl Default for TestValidator {
             worker: Arc::new(Mutex::new(worker)),
             root_chain_counter: Arc::default(),
             chains: Arc::default(),
+            fuel_profiler,
         }
     }
 }
@@ -82,6 +86,7 @@ impl Clone for TestValidator {
             worker: self.worker.clone(),
             root_chain_counter: self.root_chain_counter.clone(),
             chains: self.chains.clone(),
+            fuel_profiler: self.fuel_profiler.clone(),
         }
     }
 }
@@ -174,4 +179,22 @@ impl TestValidator {
     pub fn get_chain(&self, chain_id: &ChainId) -> ActiveChain {
         self.chains.get(chain_id).expect("Chain not found").clone()
     }
+
+    /// Enables attributing the fuel consumed by contracts to their WebAssembly functions in the
+    /// [`FuelProfile`]s, instead of only to the applications and system API calls.
+    ///
+    /// Applies to all the validators of the test process, and must be called before any
+    /// application is loaded.
+    pub fn enable_fuel_profiling() {
+        linera_execution::enable_fuel_profiling();
+    }
+
+    /// Returns the fuel consumed by the contracts executed by this validator since the last call,
+    /// attributed to the stacks of applications, functions and system API calls that consumed it.
+    ///
+    /// The profile can be saved with [`FuelProfile::save_folded`] and rendered by flamegraph
+    /// tools.
+    pub fn take_fuel_profile(&self) -> FuelProfile {
+        self.fuel_profiler.take()
+    }
 }
@@ -6,6 +6,7 @@
 use super::wit;
 use linera_base::{
     crypto::CryptoHash,
+    http::HttpMethod,
     identifiers::{ApplicationId, BytecodeId, ChainId, MessageId},
 };
 use linera_views::batch::WriteOperation;
@@ -22,6 +23,15 @@ impl From<wit::WriteOperation> for WriteOperation {
     }
 }
 
+impl From<wit::HttpMethod> for HttpMethod {
+    fn from(method: wit::HttpMethod) -> Self {
+        match method {
+            wit::HttpMethod::Get => HttpMethod::Get,
+            wit::HttpMethod::Post => HttpMethod::Post,
+        }
+    }
+}
+
 impl From<wit::ApplicationId> for ApplicationId {
     fn from(application_id: wit::ApplicationId) -> Self {
         ApplicationId {
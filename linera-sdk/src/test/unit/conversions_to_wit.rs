@@ -7,6 +7,7 @@ use super::wit;
 use linera_base::{
     crypto::CryptoHash,
     data_types::Amount,
+    http::HttpResponse,
     identifiers::{ApplicationId, ChainId, MessageId},
 };
 
@@ -57,6 +58,16 @@ impl From<Amount> for wit::Amount {
     }
 }
 
+impl From<HttpResponse> for wit::HttpResponse {
+    fn from(response: HttpResponse) -> Self {
+        wit::HttpResponse {
+            status: response.status,
+            headers: response.headers,
+            body: response.body,
+        }
+    }
+}
+
 impl From<wit::LogLevel> for log::Level {
     fn from(level: wit::LogLevel) -> Self {
         match level {
@@ -19,7 +19,9 @@ mod conversions_to_wit;
 use self::mock_system_api as wit;
 use futures::FutureExt;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Timestamp},
+    http::{HttpRequest, HttpResponse},
     identifiers::{ApplicationId, ChainId},
 };
 use linera_views::{
@@ -28,6 +30,7 @@ use linera_views::{
     memory::MemoryContext,
 };
 use serde::Serialize;
+use std::collections::VecDeque;
 
 static mut MOCK_CHAIN_ID: Option<ChainId> = None;
 static mut MOCK_APPLICATION_ID: Option<ApplicationId> = None;
@@ -35,10 +38,13 @@ static mut MOCK_APPLICATION_PARAMETERS: Option<Vec<u8>> = None;
 static mut MOCK_SYSTEM_BALANCE: Option<Amount> = None;
 static mut MOCK_OWNER_BALANCE: Option<Amount> = None;
 static mut MOCK_SYSTEM_TIMESTAMP: Option<Timestamp> = None;
+static mut MOCK_BLOCK_RANDOMNESS: Option<VecDeque<CryptoHash>> = None;
 static mut MOCK_LOG_COLLECTOR: Vec<(log::Level, String)> = Vec::new();
 static mut MOCK_KEY_VALUE_STORE: Option<MemoryContext<()>> = None;
 static mut MOCK_TRY_QUERY_APPLICATION: Option<Box<dyn FnMut(ApplicationId, Vec<u8>) -> Vec<u8>>> =
     None;
+static mut MOCK_HTTP_REQUEST: Option<Box<dyn FnMut(HttpRequest) -> Result<HttpResponse, String>>> =
+    None;
 
 /// Sets the mocked chain ID.
 pub fn mock_chain_id(chain_id: impl Into<Option<ChainId>>) {
@@ -73,6 +79,13 @@ pub fn mock_system_timestamp(system_timestamp: impl Into<Option<Timestamp>>) {
     unsafe { MOCK_SYSTEM_TIMESTAMP = system_timestamp.into() };
 }
 
+/// Sets the mocked values returned by the block randomness, in order.
+///
+/// Once all values have been returned, the block randomness is reported as unavailable.
+pub fn mock_block_randomness(values: impl IntoIterator<Item = CryptoHash>) {
+    unsafe { MOCK_BLOCK_RANDOMNESS = Some(values.into_iter().collect()) };
+}
+
 /// Returns all messages logged so far.
 pub fn log_messages() -> Vec<(log::Level, String)> {
     unsafe { MOCK_LOG_COLLECTOR.clone() }
@@ -92,6 +105,13 @@ pub fn mock_try_query_application(
     unsafe { MOCK_TRY_QUERY_APPLICATION = Some(Box::new(handler)) }
 }
 
+/// Mocks the `http_request` system API.
+pub fn mock_http_request(
+    handler: impl FnMut(HttpRequest) -> Result<HttpResponse, String> + 'static,
+) {
+    unsafe { MOCK_HTTP_REQUEST = Some(Box::new(handler)) }
+}
+
 /// Implementation of type that exports an interface for using the mock system API.
 pub struct MockSystemApi;
 
@@ -149,6 +169,16 @@ impl wit::MockSystemApi for MockSystemApi {
             .micros()
     }
 
+    fn mocked_read_block_randomness() -> Option<wit::CryptoHash> {
+        unsafe { MOCK_BLOCK_RANDOMNESS.as_mut() }
+            .expect(
+                "Unexpected call to the `read_block_randomness` system API. \
+                Please call `mock_block_randomness` first",
+            )
+            .pop_front()
+            .map(wit::CryptoHash::from)
+    }
+
     fn mocked_log(message: String, level: wit::LogLevel) {
         unsafe { MOCK_LOG_COLLECTOR.push((level.into(), message)) }
     }
@@ -223,4 +253,24 @@ impl wit::MockSystemApi for MockSystemApi {
 
         handler(application.into(), query).into()
     }
+
+    fn mocked_http_request(
+        method: wit::HttpMethod,
+        url: String,
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    ) -> Result<wit::HttpResponse, String> {
+        let handler = unsafe { MOCK_HTTP_REQUEST.as_mut() }.expect(
+            "Unexpected call to `http_request` system API. \
+            Please call `mock_http_request` first",
+        );
+
+        let request = HttpRequest {
+            method: method.into(),
+            url,
+            headers,
+            body,
+        };
+        handler(request).map(wit::HttpResponse::from)
+    }
 }
@@ -0,0 +1,839 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers to run atomic transactions across several microchains using a two-phase commit.
+//!
+//! One chain acts as the [`Coordinator`] of a transaction. It sends a tracked
+//! [`TransactionMessage::Prepare`] message to every participant chain, with a deadline. Each
+//! participant validates its part of the transaction, locks the resources it needs using a
+//! [`Participant`] view, and answers with [`TransactionMessage::Prepared`]. Once all
+//! participants are prepared, the coordinator sends [`TransactionMessage::Commit`] to all of them.
+//!
+//! A participant refuses a transaction by rejecting the `Prepare` message, which then bounces
+//! back to the coordinator. The same happens if the message is not received before its deadline.
+//! The coordinator then sends [`TransactionMessage::Abort`] to the other participants and returns
+//! the payloads of the transaction, so that the application can compensate for any effect the
+//! transaction had on the coordinator chain.
+//!
+//! Since messages from one chain are received in the order they were sent, a participant always
+//! receives the `Commit` or `Abort` message of a transaction after its `Prepare` message.
+//!
+//! Only the coordinator of a transaction, i.e. the chain in its [`TransactionId`], may prepare,
+//! commit or abort it, and a coordinator only handles the transactions it started. Messages
+//! from other chains fail with a [`TransactionError`].
+
+use crate::{
+    base::{ChainId, Timestamp},
+    views::{View, ViewError, ViewStorageContext},
+    ExecutionOutcome, OutgoingMessage, Resources,
+};
+use linera_views::{common::Context, map_view::MapView, register_view::RegisterView};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// The unique identifier of a transaction.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct TransactionId {
+    /// The chain coordinating the transaction.
+    pub coordinator: ChainId,
+    /// The index of the transaction among the ones started by the coordinator.
+    pub index: u64,
+}
+
+/// The messages exchanged by the coordinator and the participants of a transaction.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TransactionMessage<Payload> {
+    /// Asks a participant to lock the resources needed by its part of the transaction.
+    Prepare {
+        /// The transaction to prepare.
+        transaction_id: TransactionId,
+        /// The participant's part of the transaction.
+        payload: Payload,
+    },
+    /// Informs the coordinator that a participant is ready to commit.
+    Prepared {
+        /// The prepared transaction.
+        transaction_id: TransactionId,
+    },
+    /// Tells a participant to apply its part of the transaction.
+    Commit {
+        /// The transaction to commit.
+        transaction_id: TransactionId,
+    },
+    /// Tells a participant to release its locks without applying the transaction.
+    Abort {
+        /// The transaction to abort.
+        transaction_id: TransactionId,
+    },
+}
+
+/// The final result of a transaction, as seen by its coordinator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Decision<Payload> {
+    /// All participants were prepared and have been told to commit.
+    Committed {
+        /// The transaction that was committed.
+        transaction_id: TransactionId,
+        /// The part of the transaction of each participant.
+        payloads: BTreeMap<ChainId, Payload>,
+    },
+    /// The transaction was refused or timed out, and the participants have been told to abort.
+    Aborted {
+        /// The transaction that was aborted.
+        transaction_id: TransactionId,
+        /// The part of the transaction of each participant.
+        payloads: BTreeMap<ChainId, Payload>,
+    },
+}
+
+/// An error when handling a message of a transaction.
+#[derive(Debug)]
+pub enum TransactionError {
+    /// The message was sent by another chain than the coordinator of the transaction.
+    UnauthorizedSender {
+        /// The transaction of the message.
+        transaction_id: TransactionId,
+        /// The chain that sent the message.
+        sender: ChainId,
+    },
+    /// The transaction is coordinated by another chain than the current one.
+    WrongCoordinator {
+        /// The transaction of the message.
+        transaction_id: TransactionId,
+        /// The current chain.
+        chain_id: ChainId,
+    },
+    /// An error in the views storing the transactions.
+    View(ViewError),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::UnauthorizedSender {
+                transaction_id,
+                sender,
+            } => write!(
+                formatter,
+                "Chain {sender} is not the coordinator of transaction {transaction_id:?}"
+            ),
+            TransactionError::WrongCoordinator {
+                transaction_id,
+                chain_id,
+            } => write!(
+                formatter,
+                "Transaction {transaction_id:?} is not coordinated by chain {chain_id}"
+            ),
+            TransactionError::View(error) => error.fmt(formatter),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransactionError::View(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ViewError> for TransactionError {
+    fn from(error: ViewError) -> Self {
+        TransactionError::View(error)
+    }
+}
+
+impl TransactionId {
+    /// Checks that a message about this transaction was sent by its coordinator.
+    fn check_sender(&self, sender: ChainId) -> Result<(), TransactionError> {
+        if sender != self.coordinator {
+            return Err(TransactionError::UnauthorizedSender {
+                transaction_id: *self,
+                sender,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that this transaction is coordinated by the chain `chain_id`.
+    fn check_coordinator(&self, chain_id: ChainId) -> Result<(), TransactionError> {
+        if chain_id != self.coordinator {
+            return Err(TransactionError::WrongCoordinator {
+                transaction_id: *self,
+                chain_id,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A transaction waiting for its participants to be prepared.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PendingTransaction<Payload> {
+    /// The part of the transaction of each participant.
+    pub payloads: BTreeMap<ChainId, Payload>,
+    /// The participants that are ready to commit.
+    pub prepared: BTreeSet<ChainId>,
+    /// The time after which the transaction can no longer be prepared.
+    pub deadline: Timestamp,
+}
+
+/// The state of the chain coordinating transactions.
+#[derive(View)]
+pub struct CoordinatorView<C, Payload>
+where
+    Payload: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// The index of the next transaction to start.
+    pub next_index: RegisterView<C, u64>,
+    /// The transactions that are neither committed nor aborted yet.
+    pub transactions: MapView<C, u64, PendingTransaction<Payload>>,
+}
+
+/// An alias to [`CoordinatorView`] that uses the WebAssembly specific [`ViewStorageContext`].
+pub type Coordinator<Payload> = CoordinatorView<ViewStorageContext, Payload>;
+
+impl<C, Payload> CoordinatorView<C, Payload>
+where
+    C: Context + Send + Sync + Clone + 'static,
+    ViewError: From<C::Error>,
+    Payload: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Starts a new transaction coordinated by the chain `coordinator`, by adding a `Prepare`
+    /// message for each participant to the `outcome`.
+    ///
+    /// Participants that do not receive their `Prepare` message before the `deadline` can no
+    /// longer accept it, so the message bounces back and the transaction is aborted.
+    pub fn begin<Message>(
+        &mut self,
+        coordinator: ChainId,
+        payloads: BTreeMap<ChainId, Payload>,
+        deadline: Timestamp,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) -> Result<TransactionId, ViewError>
+    where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        let index = *self.next_index.get();
+        self.next_index.set(index + 1);
+        let transaction_id = TransactionId { coordinator, index };
+        for (participant, payload) in &payloads {
+            let message = TransactionMessage::Prepare {
+                transaction_id,
+                payload: payload.clone(),
+            };
+            outcome.messages.push(OutgoingMessage {
+                destination: (*participant).into(),
+                authenticated: false,
+                is_tracked: true,
+                resources: Resources::default(),
+                deadline: Some(deadline),
+                message: message.into(),
+            });
+        }
+        let transaction = PendingTransaction {
+            payloads,
+            prepared: BTreeSet::new(),
+            deadline,
+        };
+        self.transactions.insert(&index, transaction)?;
+        Ok(transaction_id)
+    }
+
+    /// Records that the `participant` is ready to commit a transaction started by the chain
+    /// `coordinator`. Once all participants are ready, adds the `Commit` messages to the
+    /// `outcome` and returns the [`Decision`].
+    ///
+    /// Answers to transactions that were already aborted are ignored: the participant receives
+    /// an `Abort` message after its `Prepare` message anyway.
+    pub async fn handle_prepared<Message>(
+        &mut self,
+        coordinator: ChainId,
+        transaction_id: TransactionId,
+        participant: ChainId,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) -> Result<Option<Decision<Payload>>, TransactionError>
+    where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        transaction_id.check_coordinator(coordinator)?;
+        let index = transaction_id.index;
+        let Some(transaction) = self.transactions.get_mut(&index).await? else {
+            return Ok(None);
+        };
+        if !transaction.payloads.contains_key(&participant) {
+            return Ok(None);
+        }
+        transaction.prepared.insert(participant);
+        if transaction.prepared.len() < transaction.payloads.len() {
+            return Ok(None);
+        }
+        let payloads = transaction.payloads.clone();
+        self.transactions.remove(&index)?;
+        for participant in payloads.keys() {
+            let message = TransactionMessage::Commit { transaction_id };
+            Self::send(*participant, message, outcome);
+        }
+        Ok(Some(Decision::Committed {
+            transaction_id,
+            payloads,
+        }))
+    }
+
+    /// Aborts a transaction started by the chain `coordinator` because the `Prepare` message
+    /// sent to `participant` bounced back, adding the `Abort` messages for the other
+    /// participants to the `outcome`.
+    pub async fn handle_bounced_prepare<Message>(
+        &mut self,
+        coordinator: ChainId,
+        transaction_id: TransactionId,
+        participant: ChainId,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) -> Result<Option<Decision<Payload>>, TransactionError>
+    where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        transaction_id.check_coordinator(coordinator)?;
+        Ok(self
+            .abort(transaction_id, Some(participant), outcome)
+            .await?)
+    }
+
+    /// Aborts all the transactions whose deadline is before `now`, adding the `Abort` messages
+    /// to the `outcome`.
+    pub async fn abort_expired<Message>(
+        &mut self,
+        coordinator: ChainId,
+        now: Timestamp,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) -> Result<Vec<Decision<Payload>>, ViewError>
+    where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        let mut expired = Vec::new();
+        self.transactions
+            .for_each_index_value(|index, transaction| {
+                if transaction.deadline < now {
+                    expired.push(index);
+                }
+                Ok(())
+            })
+            .await?;
+        let mut decisions = Vec::new();
+        for index in expired {
+            let transaction_id = TransactionId { coordinator, index };
+            if let Some(decision) = self.abort(transaction_id, None, outcome).await? {
+                decisions.push(decision);
+            }
+        }
+        Ok(decisions)
+    }
+
+    /// Removes a pending transaction and tells its participants, except the one that refused
+    /// it, to abort.
+    async fn abort<Message>(
+        &mut self,
+        transaction_id: TransactionId,
+        refused_by: Option<ChainId>,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) -> Result<Option<Decision<Payload>>, ViewError>
+    where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        let index = transaction_id.index;
+        let Some(transaction) = self.transactions.get(&index).await? else {
+            return Ok(None);
+        };
+        self.transactions.remove(&index)?;
+        for participant in transaction.payloads.keys() {
+            if Some(*participant) != refused_by {
+                let message = TransactionMessage::Abort { transaction_id };
+                Self::send(*participant, message, outcome);
+            }
+        }
+        Ok(Some(Decision::Aborted {
+            transaction_id,
+            payloads: transaction.payloads,
+        }))
+    }
+
+    fn send<Message>(
+        participant: ChainId,
+        message: TransactionMessage<Payload>,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        outcome.messages.push(OutgoingMessage {
+            destination: participant.into(),
+            authenticated: false,
+            is_tracked: false,
+            resources: Resources::default(),
+            deadline: None,
+            message: message.into(),
+        });
+    }
+}
+
+/// The state of a chain taking part in transactions.
+#[derive(View)]
+pub struct ParticipantView<C, Payload>
+where
+    Payload: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// The prepared transactions, with the part of each transaction that is locked on this
+    /// chain.
+    pub locks: MapView<C, TransactionId, Payload>,
+}
+
+/// An alias to [`ParticipantView`] that uses the WebAssembly specific [`ViewStorageContext`].
+pub type Participant<Payload> = ParticipantView<ViewStorageContext, Payload>;
+
+impl<C, Payload> ParticipantView<C, Payload>
+where
+    C: Context + Send + Sync + Clone + 'static,
+    ViewError: From<C::Error>,
+    Payload: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Locks the `payload` of a transaction whose `Prepare` message was sent by the chain
+    /// `sender`, and adds the `Prepared` answer for the coordinator to the `outcome`.
+    ///
+    /// The application should check that its part of the transaction can be applied before
+    /// calling this, and fail otherwise so that the `Prepare` message bounces back.
+    pub fn prepare<Message>(
+        &mut self,
+        transaction_id: TransactionId,
+        sender: ChainId,
+        payload: Payload,
+        outcome: &mut ExecutionOutcome<Message>,
+    ) -> Result<(), TransactionError>
+    where
+        Message: From<TransactionMessage<Payload>>,
+    {
+        transaction_id.check_sender(sender)?;
+        self.locks.insert(&transaction_id, payload)?;
+        let message = TransactionMessage::Prepared { transaction_id };
+        outcome.messages.push(OutgoingMessage {
+            destination: transaction_id.coordinator.into(),
+            authenticated: false,
+            is_tracked: false,
+            resources: Resources::default(),
+            deadline: None,
+            message: message.into(),
+        });
+        Ok(())
+    }
+
+    /// Releases the lock of a transaction whose `Commit` message was sent by the chain
+    /// `sender`, returning the payload to apply.
+    pub async fn commit(
+        &mut self,
+        transaction_id: TransactionId,
+        sender: ChainId,
+    ) -> Result<Option<Payload>, TransactionError> {
+        transaction_id.check_sender(sender)?;
+        Ok(self.release(transaction_id).await?)
+    }
+
+    /// Releases the lock of a transaction whose `Abort` message was sent by the chain
+    /// `sender`, returning the payload that must no longer be applied.
+    pub async fn abort(
+        &mut self,
+        transaction_id: TransactionId,
+        sender: ChainId,
+    ) -> Result<Option<Payload>, TransactionError> {
+        transaction_id.check_sender(sender)?;
+        Ok(self.release(transaction_id).await?)
+    }
+
+    /// Returns whether the transaction is prepared but not yet committed or aborted.
+    pub async fn is_locked(&self, transaction_id: TransactionId) -> Result<bool, ViewError> {
+        self.locks.contains_key(&transaction_id).await
+    }
+
+    async fn release(
+        &mut self,
+        transaction_id: TransactionId,
+    ) -> Result<Option<Payload>, ViewError> {
+        let payload = self.locks.get(&transaction_id).await?;
+        if payload.is_some() {
+            self.locks.remove(&transaction_id)?;
+        }
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CoordinatorView, Decision, ParticipantView, TransactionError, TransactionId,
+        TransactionMessage,
+    };
+    use crate::{
+        base::{ChainId, Destination, Timestamp},
+        views::View,
+        ExecutionOutcome,
+    };
+    use linera_views::memory::{create_memory_context, MemoryContext};
+    use std::collections::BTreeMap;
+
+    type Outcome = ExecutionOutcome<TransactionMessage<i64>>;
+
+    /// Tests that a transaction is committed once all participants are prepared, and that the
+    /// participants then release their locks.
+    #[tokio::test]
+    async fn transaction_is_committed_when_all_participants_are_prepared() {
+        let (coordinator_id, participants) = chains();
+        let mut coordinator = coordinator().await;
+        let mut outcome = Outcome::default();
+        let transaction_id = coordinator
+            .begin(
+                coordinator_id,
+                payloads(&participants),
+                deadline(),
+                &mut outcome,
+            )
+            .unwrap();
+        assert_eq!(
+            sent_messages(&outcome),
+            participants
+                .iter()
+                .zip([1, -1])
+                .map(|(participant, payload)| (
+                    *participant,
+                    TransactionMessage::Prepare {
+                        transaction_id,
+                        payload
+                    }
+                ))
+                .collect::<Vec<_>>()
+        );
+
+        let mut participant = participant().await;
+        let mut outcome = Outcome::default();
+        participant
+            .prepare(transaction_id, coordinator_id, 1, &mut outcome)
+            .unwrap();
+        assert!(participant.is_locked(transaction_id).await.unwrap());
+        assert_eq!(
+            sent_messages(&outcome),
+            vec![(
+                coordinator_id,
+                TransactionMessage::Prepared { transaction_id }
+            )]
+        );
+
+        let mut outcome = Outcome::default();
+        let decision = coordinator
+            .handle_prepared(
+                coordinator_id,
+                transaction_id,
+                participants[0],
+                &mut outcome,
+            )
+            .await
+            .unwrap();
+        assert_eq!(decision, None);
+        assert!(outcome.messages.is_empty());
+        let decision = coordinator
+            .handle_prepared(
+                coordinator_id,
+                transaction_id,
+                participants[1],
+                &mut outcome,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            decision,
+            Some(Decision::Committed {
+                transaction_id,
+                payloads: payloads(&participants),
+            })
+        );
+        assert_eq!(
+            sent_messages(&outcome),
+            participants
+                .iter()
+                .map(|participant| (*participant, TransactionMessage::Commit { transaction_id }))
+                .collect::<Vec<_>>()
+        );
+
+        let payload = participant
+            .commit(transaction_id, coordinator_id)
+            .await
+            .unwrap();
+        assert_eq!(payload, Some(1));
+        assert!(!participant.is_locked(transaction_id).await.unwrap());
+    }
+
+    /// Tests that participants refuse to prepare, commit or abort a transaction when the
+    /// message was not sent by the transaction's coordinator.
+    #[tokio::test]
+    async fn participant_only_accepts_messages_from_the_coordinator() {
+        let (coordinator_id, participants) = chains();
+        let attacker = participants[1];
+        let transaction_id = TransactionId {
+            coordinator: coordinator_id,
+            index: 0,
+        };
+        let mut participant = participant().await;
+        let mut outcome = Outcome::default();
+
+        let result = participant.prepare(transaction_id, attacker, 1, &mut outcome);
+        assert!(matches!(
+            result,
+            Err(TransactionError::UnauthorizedSender { sender, .. }) if sender == attacker
+        ));
+        assert!(outcome.messages.is_empty());
+
+        participant
+            .prepare(transaction_id, coordinator_id, 1, &mut outcome)
+            .unwrap();
+        let result = participant.commit(transaction_id, attacker).await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::UnauthorizedSender { .. })
+        ));
+        let result = participant.abort(transaction_id, attacker).await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::UnauthorizedSender { .. })
+        ));
+        assert!(participant.is_locked(transaction_id).await.unwrap());
+
+        let payload = participant
+            .abort(transaction_id, coordinator_id)
+            .await
+            .unwrap();
+        assert_eq!(payload, Some(1));
+        assert!(!participant.is_locked(transaction_id).await.unwrap());
+    }
+
+    /// Tests that a coordinator ignores answers about transactions coordinated by other
+    /// chains.
+    #[tokio::test]
+    async fn coordinator_only_handles_its_own_transactions() {
+        let (coordinator_id, participants) = chains();
+        let other_coordinator = ChainId::root(3);
+        let mut coordinator = coordinator().await;
+        let mut outcome = Outcome::default();
+        let transaction_id = coordinator
+            .begin(
+                coordinator_id,
+                payloads(&participants),
+                deadline(),
+                &mut outcome,
+            )
+            .unwrap();
+        let foreign_transaction_id = TransactionId {
+            coordinator: other_coordinator,
+            ..transaction_id
+        };
+
+        let mut outcome = Outcome::default();
+        for participant in &participants {
+            let result = coordinator
+                .handle_prepared(
+                    coordinator_id,
+                    foreign_transaction_id,
+                    *participant,
+                    &mut outcome,
+                )
+                .await;
+            assert!(matches!(
+                result,
+                Err(TransactionError::WrongCoordinator { chain_id, .. }) if chain_id == coordinator_id
+            ));
+        }
+        let result = coordinator
+            .handle_bounced_prepare(
+                coordinator_id,
+                foreign_transaction_id,
+                participants[0],
+                &mut outcome,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::WrongCoordinator { .. })
+        ));
+        assert!(outcome.messages.is_empty());
+        assert!(coordinator
+            .transactions
+            .contains_key(&transaction_id.index)
+            .await
+            .unwrap());
+    }
+
+    /// Tests that a bounced `Prepare` message aborts the transaction on the other participants
+    /// only.
+    #[tokio::test]
+    async fn bounced_prepare_aborts_the_other_participants() {
+        let (coordinator_id, participants) = chains();
+        let mut coordinator = coordinator().await;
+        let mut outcome = Outcome::default();
+        let transaction_id = coordinator
+            .begin(
+                coordinator_id,
+                payloads(&participants),
+                deadline(),
+                &mut outcome,
+            )
+            .unwrap();
+
+        let mut outcome = Outcome::default();
+        let decision = coordinator
+            .handle_bounced_prepare(
+                coordinator_id,
+                transaction_id,
+                participants[1],
+                &mut outcome,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            decision,
+            Some(Decision::Aborted {
+                transaction_id,
+                payloads: payloads(&participants),
+            })
+        );
+        assert_eq!(
+            sent_messages(&outcome),
+            vec![(
+                participants[0],
+                TransactionMessage::Abort { transaction_id }
+            )]
+        );
+
+        // A late answer from the other participant is ignored.
+        let mut outcome = Outcome::default();
+        let decision = coordinator
+            .handle_prepared(
+                coordinator_id,
+                transaction_id,
+                participants[0],
+                &mut outcome,
+            )
+            .await
+            .unwrap();
+        assert_eq!(decision, None);
+        assert!(outcome.messages.is_empty());
+    }
+
+    /// Tests that [`CoordinatorView::abort_expired`] only aborts the transactions whose
+    /// deadline has passed, and tells all their participants to abort.
+    #[tokio::test]
+    async fn only_expired_transactions_are_aborted() {
+        let (coordinator_id, participants) = chains();
+        let mut coordinator = coordinator().await;
+        let mut outcome = Outcome::default();
+        let early_deadline = Timestamp::from(1_000);
+        let late_deadline = Timestamp::from(2_000);
+        let expiring_id = coordinator
+            .begin(
+                coordinator_id,
+                payloads(&participants),
+                early_deadline,
+                &mut outcome,
+            )
+            .unwrap();
+        let pending_id = coordinator
+            .begin(
+                coordinator_id,
+                payloads(&participants),
+                late_deadline,
+                &mut outcome,
+            )
+            .unwrap();
+
+        let mut outcome = Outcome::default();
+        let decisions = coordinator
+            .abort_expired(coordinator_id, early_deadline, &mut outcome)
+            .await
+            .unwrap();
+        assert!(decisions.is_empty());
+        assert!(outcome.messages.is_empty());
+
+        let decisions = coordinator
+            .abort_expired(coordinator_id, Timestamp::from(1_500), &mut outcome)
+            .await
+            .unwrap();
+        assert_eq!(
+            decisions,
+            vec![Decision::Aborted {
+                transaction_id: expiring_id,
+                payloads: payloads(&participants),
+            }]
+        );
+        assert_eq!(
+            sent_messages(&outcome),
+            participants
+                .iter()
+                .map(|participant| (
+                    *participant,
+                    TransactionMessage::Abort {
+                        transaction_id: expiring_id
+                    }
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert!(!coordinator
+            .transactions
+            .contains_key(&expiring_id.index)
+            .await
+            .unwrap());
+        assert!(coordinator
+            .transactions
+            .contains_key(&pending_id.index)
+            .await
+            .unwrap());
+    }
+
+    /// Returns the coordinator chain and two participant chains.
+    fn chains() -> (ChainId, [ChainId; 2]) {
+        (ChainId::root(0), [ChainId::root(1), ChainId::root(2)])
+    }
+
+    /// Returns the payloads of a transaction moving one unit from the second participant to
+    /// the first one.
+    fn payloads(participants: &[ChainId; 2]) -> BTreeMap<ChainId, i64> {
+        BTreeMap::from([(participants[0], 1), (participants[1], -1)])
+    }
+
+    /// Returns a deadline for transactions that do not expire during the test.
+    fn deadline() -> Timestamp {
+        Timestamp::from(u64::MAX)
+    }
+
+    /// Returns the destination chains and messages added to an `outcome`.
+    fn sent_messages(outcome: &Outcome) -> Vec<(ChainId, TransactionMessage<i64>)> {
+        outcome
+            .messages
+            .iter()
+            .map(|message| {
+                let Destination::Recipient(chain_id) = message.destination else {
+                    panic!("Unexpected destination {:?}", message.destination);
+                };
+                (chain_id, message.message.clone())
+            })
+            .collect()
+    }
+
+    async fn coordinator() -> CoordinatorView<MemoryContext<()>, i64> {
+        CoordinatorView::load(create_memory_context())
+            .await
+            .expect("Failed to load the coordinator view")
+    }
+
+    async fn participant() -> ParticipantView<MemoryContext<()>, i64> {
+        ParticipantView::load(create_memory_context())
+            .await
+            .expect("Failed to load the participant view")
+    }
+}
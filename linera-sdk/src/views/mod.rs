@@ -10,6 +10,7 @@ pub use self::system_api::ViewStorageContext;
 pub use linera_views::{
     self,
     common::CustomSerialize,
+    proof::{ProofError, ProofLeaf, ProofPosition, ViewProof},
     views::{RootView, View, ViewError},
 };
 
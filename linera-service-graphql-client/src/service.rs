@@ -5,7 +5,7 @@ use graphql_client::GraphQLQuery;
 use linera_base::{
     crypto::CryptoHash,
     data_types::{Amount, BlockHeight, Timestamp},
-    identifiers::{Account, ChainDescription, ChainId, ChannelName, Destination, Owner},
+    identifiers::{Account, ChainDescription, ChainId, ChannelName, Destination, MessageId, Owner},
 };
 
 pub type JSONObject = serde_json::Value;
@@ -27,6 +27,7 @@ mod types {
     pub type MessageAction = Value;
     pub type Operation = Value;
     pub type Origin = Value;
+    pub type PreviousBlockSignatures = Value;
     pub type Target = Value;
     pub type UserApplicationDescription = Value;
 
@@ -58,7 +59,9 @@ mod types {
 mod types {
     pub use linera_base::ownership::ChainOwnership;
     pub use linera_chain::{
-        data_types::{ChannelFullName, Event, MessageAction, Origin, Target},
+        data_types::{
+            ChannelFullName, Event, MessageAction, Origin, PreviousBlockSignatures, Target,
+        },
         ChainManager,
     };
     pub use linera_core::worker::{Notification, Reason};
@@ -157,6 +160,7 @@ mod from {
                 timestamp,
                 authenticated_signer,
                 previous_block_hash,
+                previous_block_signatures,
             } = val;
             let incoming_messages = incoming_messages
                 .into_iter()
@@ -171,6 +175,7 @@ mod from {
                 timestamp,
                 authenticated_signer,
                 previous_block_hash,
+                previous_block_signatures,
             }
         }
     }
@@ -183,6 +188,8 @@ mod from {
                 grant,
                 refund_grant_to,
                 kind,
+                deadline,
+                response_to,
                 message,
             } = val;
             OutgoingMessage {
@@ -191,6 +198,8 @@ mod from {
                 grant,
                 refund_grant_to,
                 kind,
+                deadline,
+                response_to,
                 message,
             }
         }
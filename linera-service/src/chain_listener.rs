@@ -13,12 +13,13 @@ use linera_chain::data_types::OutgoingMessage;
 use linera_core::{
     client::ChainClient,
     node::ValidatorNodeProvider,
+    sync::ChainSynchronizer,
     worker::{Notification, Reason},
 };
 use linera_execution::{Message, SystemMessage};
 use linera_storage::Storage;
 use linera_views::views::ViewError;
-use std::{collections::btree_map, sync::Arc, time::Duration};
+use std::{collections::btree_map, pin::pin, sync::Arc, time::Duration};
 use tracing::{error, info, warn};
 
 #[derive(Debug, Clone, clap::Args)]
@@ -30,6 +31,10 @@ pub struct ChainListenerConfig {
     /// Wait after processing any notification (useful for rate limiting).
     #[arg(long = "listener-delay-after-ms", default_value = "0")]
     pub delay_after_ms: u64,
+
+    /// The maximum number of chains to synchronize at the same time on startup.
+    #[arg(long = "listener-max-concurrent-syncs", default_value = "16")]
+    pub max_concurrent_syncs: usize,
 }
 
 #[async_trait]
@@ -74,20 +79,48 @@ where
     where
         C: ClientContext<P> + Send + 'static,
     {
-        let chain_ids = context.lock().await.wallet_state().chain_ids();
-        for chain_id in chain_ids {
-            Self::run_with_chain_id(
-                chain_id,
-                self.clients.clone(),
-                context.clone(),
-                storage.clone(),
-                self.config.clone(),
-            );
-        }
+        let chain_clients = {
+            let context_guard = context.lock().await;
+            context_guard
+                .wallet_state()
+                .chain_ids()
+                .into_iter()
+                .map(|chain_id| context_guard.make_chain_client(storage.clone(), chain_id))
+                .collect::<Vec<_>>()
+        };
+        let synchronizer = ChainSynchronizer::new(chain_clients)
+            .with_max_concurrent_chains(self.config.max_concurrent_syncs);
+        // Synchronize the chains in the background, and start listening to each of them as
+        // soon as it is up to date.
+        let _handle = tokio::task::spawn(async move {
+            let mut outcomes = pin!(synchronizer.run());
+            while let Some(mut outcome) = outcomes.next().await {
+                let chain_id = outcome.client.chain_id();
+                match &outcome.result {
+                    Ok(_) => info!("Synchronized chain {chain_id} ({})", outcome.progress),
+                    Err(error) => warn!("Failed to synchronize chain {chain_id}: {error}"),
+                }
+                context
+                    .lock()
+                    .await
+                    .update_wallet(&mut outcome.client)
+                    .await;
+                Self::run_with_chain_id(
+                    chain_id,
+                    Some(outcome.client),
+                    self.clients.clone(),
+                    context.clone(),
+                    storage.clone(),
+                    self.config.clone(),
+                );
+            }
+        });
     }
 
+    /// Starts listening to the given chain, using `client` if it was already created.
     fn run_with_chain_id<C>(
         chain_id: ChainId,
+        client: Option<ChainClient<P, S>>,
         clients: ChainClients<P, S>,
         context: Arc<Mutex<C>>,
         storage: S,
@@ -97,7 +130,7 @@ where
     {
         let _handle = tokio::task::spawn(async move {
             if let Err(err) =
-                Self::run_client_stream(chain_id, clients, context, storage, config).await
+                Self::run_client_stream(chain_id, client, clients, context, storage, config).await
             {
                 error!("Stream for chain {} failed: {}", chain_id, err);
             }
@@ -106,6 +139,7 @@ where
 
     async fn run_client_stream<C>(
         chain_id: ChainId,
+        client: Option<ChainClient<P, S>>,
         clients: ChainClients<P, S>,
         context: Arc<Mutex<C>>,
         storage: S,
@@ -123,7 +157,8 @@ where
                 // chain, and then process the OpenChain message in the parent.
                 return Ok(());
             };
-            let client = context_guard.make_chain_client(storage.clone(), chain_id);
+            let client = client
+                .unwrap_or_else(|| context_guard.make_chain_client(storage.clone(), chain_id));
             let client = Arc::new(Mutex::new(client));
             entry.insert(client.clone());
             client
@@ -180,6 +215,7 @@ where
                         }
                         Self::run_with_chain_id(
                             *new_id,
+                            None,
                             clients.clone(),
                             context.clone(),
                             storage.clone(),
@@ -166,8 +166,20 @@ impl ClientWrapper {
             operation_byte,
             message,
             message_byte,
+            validator_fee_percent,
             maximum_bytes_read_per_block,
             maximum_bytes_written_per_block,
+            maximum_operations_per_block,
+            maximum_incoming_messages_per_block,
+            maximum_outgoing_messages_per_block,
+            maximum_operation_bytes_per_block,
+            maximum_block_size,
+            maximum_fuel_per_block,
+            maximum_bytecode_size,
+            maximum_wasm_memory_pages,
+            host_call_fuel,
+            open_proposer_block,
+            bytecode_rules_version,
         } = policy;
         let mut command = self.command().await?;
         command
@@ -189,6 +201,10 @@ impl ClientWrapper {
             .args(["--operation-price", &operation.to_string()])
             .args(["--operation-byte-price", &operation_byte.to_string()])
             .args(["--message-price", &message.to_string()])
+            .args([
+                "--validator-fee-percent",
+                &validator_fee_percent.to_string(),
+            ])
             .args([
                 "--maximum-bytes-read-per-block",
                 &maximum_bytes_read_per_block.to_string(),
@@ -196,6 +212,44 @@ impl ClientWrapper {
             .args([
                 "--maximum-bytes-written-per-block",
                 &maximum_bytes_written_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-operations-per-block",
+                &maximum_operations_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-incoming-messages-per-block",
+                &maximum_incoming_messages_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-outgoing-messages-per-block",
+                &maximum_outgoing_messages_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-operation-bytes-per-block",
+                &maximum_operation_bytes_per_block.to_string(),
+            ])
+            .args(["--maximum-block-size", &maximum_block_size.to_string()])
+            .args([
+                "--maximum-fuel-per-block",
+                &maximum_fuel_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-bytecode-size",
+                &maximum_bytecode_size.to_string(),
+            ])
+            .args([
+                "--maximum-wasm-memory-pages",
+                &maximum_wasm_memory_pages.to_string(),
+            ])
+            .args(["--host-call-fuel", &serde_json::to_string(&host_call_fuel)?])
+            .args([
+                "--open-proposer-block-price",
+                &open_proposer_block.to_string(),
+            ])
+            .args([
+                "--bytecode-rules-version",
+                &bytecode_rules_version.to_string(),
             ]);
         if let Some(seed) = self.testing_prng_seed {
             command.arg("--testing-prng-seed").arg(seed.to_string());
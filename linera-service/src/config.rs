@@ -12,9 +12,9 @@ use fs_err::{self, File, OpenOptions};
 use linera_base::{
     crypto::{BcsSignable, CryptoHash, CryptoRng, KeyPair, PublicKey},
     data_types::{Amount, BlockHeight, Timestamp},
-    identifiers::{ChainDescription, ChainId, Owner},
+    identifiers::{Account, ChainDescription, ChainId, Owner},
 };
-use linera_chain::data_types::Block;
+use linera_chain::data_types::{Block, BlockApproval};
 use linera_core::{client::ChainClient, node::ValidatorNodeProvider};
 use linera_execution::{
     committee::{Committee, ValidatorName, ValidatorState},
@@ -56,6 +56,9 @@ pub struct ValidatorConfig {
     pub name: ValidatorName,
     /// The network configuration for the validator.
     pub network: ValidatorPublicNetworkConfig,
+    /// The account receiving the validator's share of the fees, if any.
+    #[serde(default)]
+    pub fee_recipient: Option<Account>,
 }
 
 /// The private configuration of a validator service.
@@ -89,6 +92,7 @@ impl CommitteeConfig {
                     ValidatorState {
                         network_address: v.network.to_string(),
                         votes: 1,
+                        fee_recipient: v.fee_recipient,
                     },
                 )
             })
@@ -105,6 +109,9 @@ pub struct UserChain {
     pub timestamp: Timestamp,
     pub next_block_height: BlockHeight,
     pub pending_block: Option<Block>,
+    /// Approvals of the pending block by other owners, for chains that require several.
+    #[serde(default)]
+    pub block_approvals: Vec<BlockApproval>,
 }
 
 impl UserChain {
@@ -122,6 +129,7 @@ impl UserChain {
             timestamp,
             next_block_height: BlockHeight::ZERO,
             pending_block: None,
+            block_approvals: Vec::new(),
         }
     }
 
@@ -135,6 +143,7 @@ impl UserChain {
             timestamp,
             next_block_height: BlockHeight::ZERO,
             pending_block: None,
+            block_approvals: Vec::new(),
         }
     }
 }
@@ -238,6 +247,7 @@ impl WalletState {
             timestamp,
             next_block_height: BlockHeight(0),
             pending_block: None,
+            block_approvals: Vec::new(),
         };
         self.insert(user_chain);
         Ok(())
@@ -269,6 +279,7 @@ impl WalletState {
                 next_block_height: state.next_block_height(),
                 timestamp: state.timestamp(),
                 pending_block: state.pending_block().clone(),
+                block_approvals: state.block_approvals().to_vec(),
             },
         );
     }
@@ -20,8 +20,9 @@ use linera_rpc::{
             notifier_service_server::{NotifierService, NotifierServiceServer},
             validator_node_server::{ValidatorNode, ValidatorNodeServer},
             validator_worker_client::ValidatorWorkerClient,
-            BlockProposal, Certificate, ChainInfoQuery, ChainInfoResult, LiteCertificate,
-            Notification, SubscriptionRequest, VersionInfo,
+            BlockProposal, Certificate, CertificateResult, ChainInfoQuery, ChainInfoResult,
+            DownloadCertificatesRequest, LiteCertificate, Notification, SubscriptionRequest,
+            VersionInfo,
         },
         Proxyable, MAX_MESSAGE_SIZE,
     },
@@ -40,7 +41,7 @@ use tokio::select;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tonic::{
     transport::{Body, Channel, Identity, Server, ServerTlsConfig},
-    Request, Response, Status,
+    Request, Response, Status, Streaming,
 };
 use tower::{builder::ServiceBuilder, Layer, Service};
 use tracing::{debug, info, instrument};
@@ -265,16 +266,16 @@ impl GrpcProxy {
         Ok((client, inner))
     }
 
-    fn log_and_return_proxy_request_outcome(
-        result: Result<Response<ChainInfoResult>, Status>,
+    fn log_and_return_proxy_request_outcome<T>(
+        result: Result<Response<T>, Status>,
         method_name: &str,
-    ) -> Result<Response<ChainInfoResult>, Status> {
+    ) -> Result<Response<T>, Status> {
         match result {
-            Ok(chain_info_result) => {
+            Ok(response) => {
                 PROXY_REQUEST_SUCCESS
                     .with_label_values(&[method_name])
                     .inc();
-                Ok(chain_info_result)
+                Ok(response)
             }
             Err(status) => {
                 PROXY_REQUEST_ERROR.with_label_values(&[method_name]).inc();
@@ -287,6 +288,7 @@ impl GrpcProxy {
 #[async_trait]
 impl ValidatorNode for GrpcProxy {
     type SubscribeStream = UnboundedReceiverStream<Result<Notification, Status>>;
+    type DownloadCertificatesStream = Streaming<CertificateResult>;
 
     #[instrument(skip_all, err(Display))]
     async fn handle_block_proposal(
@@ -336,6 +338,18 @@ impl ValidatorNode for GrpcProxy {
         )
     }
 
+    #[instrument(skip_all, err(Display))]
+    async fn download_certificates(
+        &self,
+        request: Request<DownloadCertificatesRequest>,
+    ) -> Result<Response<Self::DownloadCertificatesStream>, Status> {
+        let (mut client, inner) = self.client_for_proxy_worker(request).await?;
+        Self::log_and_return_proxy_request_outcome(
+            client.download_certificates(inner).await,
+            "download_certificates",
+        )
+    }
+
     #[instrument(skip_all, err(Display))]
     async fn subscribe(
         &self,
@@ -0,0 +1,142 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sending the HTTP requests of application services.
+
+use crate::util;
+use async_trait::async_trait;
+use linera_execution::http::{
+    HttpClient, HttpError, HttpMethod, HttpOracle, HttpOracleConfig, HttpRequest, HttpResponse,
+};
+use std::time::Duration;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct HttpOracleOptions {
+    /// Allow application services to send HTTP requests to this host. May be repeated.
+    /// Services can't make HTTP requests if no host is allowed.
+    #[arg(long = "http-allowed-host")]
+    pub allowed_hosts: Vec<String>,
+
+    /// The maximum size of the body of an HTTP request made by a service, in bytes.
+    #[arg(long = "http-max-request-bytes", default_value = "65536")]
+    pub max_request_bytes: usize,
+
+    /// The maximum size of the body of an HTTP response received by a service, in bytes.
+    #[arg(long = "http-max-response-bytes", default_value = "1048576")]
+    pub max_response_bytes: usize,
+
+    /// The maximum duration of an HTTP request made by a service (milliseconds).
+    #[arg(long = "http-timeout-ms", default_value = "10000", value_parser = util::parse_millis)]
+    pub timeout: Duration,
+
+    /// The maximum number of HTTP requests made by services to answer a single query.
+    #[arg(long = "http-max-requests-per-query", default_value = "16")]
+    pub max_requests_per_query: usize,
+
+    /// The maximum total duration of the HTTP requests made by services to answer a single
+    /// query (milliseconds).
+    #[arg(
+        long = "http-max-query-duration-ms",
+        default_value = "30000",
+        value_parser = util::parse_millis
+    )]
+    pub max_query_duration: Duration,
+}
+
+impl HttpOracleOptions {
+    /// Returns the oracle to use for the HTTP requests of services, if any host is allowed.
+    pub fn oracle(&self) -> Option<HttpOracle> {
+        if self.allowed_hosts.is_empty() {
+            return None;
+        }
+        let config = HttpOracleConfig {
+            allowed_hosts: self
+                .allowed_hosts
+                .iter()
+                .map(|host| host.to_ascii_lowercase())
+                .collect(),
+            max_request_bytes: self.max_request_bytes,
+            max_response_bytes: self.max_response_bytes,
+            timeout: self.timeout,
+            max_requests_per_query: self.max_requests_per_query,
+            max_query_duration: self.max_query_duration,
+        };
+        Some(HttpOracle::new(config, ReqwestHttpClient::new()))
+    }
+}
+
+/// An [`HttpClient`] sending requests with [`reqwest`].
+#[derive(Clone)]
+pub struct ReqwestHttpClient {
+    client: reqwest::Client,
+}
+
+impl ReqwestHttpClient {
+    /// Creates a client that doesn't follow redirections, since their targets were not
+    /// checked against the allowed hosts.
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to create an HTTP client");
+        ReqwestHttpClient { client }
+    }
+}
+
+impl Default for ReqwestHttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HttpClient for ReqwestHttpClient {
+    async fn send(
+        &self,
+        request: HttpRequest,
+        timeout: Duration,
+        max_response_bytes: usize,
+    ) -> Result<HttpResponse, HttpError> {
+        let method = match request.method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+        };
+        let mut builder = self
+            .client
+            .request(method, &request.url)
+            .timeout(timeout)
+            .body(request.body);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let mut response = builder.send().await.map_err(reqwest_error)?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect();
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(reqwest_error)? {
+            if body.len() + chunk.len() > max_response_bytes {
+                return Err(HttpError::ResponseTooLarge {
+                    maximum: max_response_bytes,
+                });
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+fn reqwest_error(error: reqwest::Error) -> HttpError {
+    if error.is_timeout() {
+        HttpError::Timeout
+    } else {
+        HttpError::Failed(error.to_string())
+    }
+}
@@ -9,6 +9,7 @@ pub mod cli_wrappers;
 pub mod config;
 pub mod faucet;
 pub mod grpc_proxy;
+pub mod http_oracle;
 pub mod node_service;
 pub mod project;
 pub mod prometheus_server;
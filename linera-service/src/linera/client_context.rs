@@ -14,7 +14,7 @@ use linera_chain::data_types::Certificate;
 use linera_core::{
     client::{ChainClient, ChainClientBuilder},
     data_types::ClientOutcome,
-    node::{CrossChainMessageDelivery, ValidatorNodeProvider},
+    node::{CachingNodeProvider, CrossChainMessageDelivery, ValidatorNodeProvider},
 };
 use linera_execution::Bytecode;
 use linera_rpc::node_provider::{NodeOptions, NodeProvider};
@@ -40,7 +40,9 @@ use {
         identifiers::{AccountOwner, ApplicationId, Owner},
         ownership::ChainOwnership,
     },
-    linera_chain::data_types::{Block, BlockAndRound, BlockProposal, SignatureAggregator, Vote},
+    linera_chain::data_types::{
+        Block, BlockAndRound, BlockProposal, PreviousBlockSignatures, SignatureAggregator, Vote,
+    },
     linera_core::{
         data_types::ChainInfoQuery, local_node::LocalNodeClient, notifier::Notifier,
         worker::WorkerState,
@@ -65,7 +67,7 @@ use {
 
 pub struct ClientContext {
     wallet_state: WalletState,
-    chain_client_builder: ChainClientBuilder<NodeProvider>,
+    chain_client_builder: ChainClientBuilder<CachingNodeProvider<NodeProvider>>,
     send_timeout: Duration,
     recv_timeout: Duration,
     notification_retry_delay: Duration,
@@ -74,12 +76,16 @@ pub struct ClientContext {
 }
 
 #[async_trait]
-impl chain_listener::ClientContext<NodeProvider> for ClientContext {
+impl chain_listener::ClientContext<CachingNodeProvider<NodeProvider>> for ClientContext {
     fn wallet_state(&self) -> &WalletState {
         &self.wallet_state
     }
 
-    fn make_chain_client<S>(&self, storage: S, chain_id: ChainId) -> ChainClient<NodeProvider, S> {
+    fn make_chain_client<S>(
+        &self,
+        storage: S,
+        chain_id: ChainId,
+    ) -> ChainClient<CachingNodeProvider<NodeProvider>, S> {
         self.make_chain_client(storage, chain_id)
     }
 
@@ -92,8 +98,10 @@ impl chain_listener::ClientContext<NodeProvider> for ClientContext {
         self.update_wallet_for_new_chain(chain_id, key_pair, timestamp);
     }
 
-    async fn update_wallet<'a, S>(&'a mut self, client: &'a mut ChainClient<NodeProvider, S>)
-    where
+    async fn update_wallet<'a, S>(
+        &'a mut self,
+        client: &'a mut ChainClient<CachingNodeProvider<NodeProvider>, S>,
+    ) where
         S: Storage + Clone + Send + Sync + 'static,
         ViewError: From<S::ContextError>,
     {
@@ -148,7 +156,7 @@ impl ClientContext {
             notification_retry_delay: options.notification_retry_delay,
             notification_retries: options.notification_retries,
         };
-        let node_provider = NodeProvider::new(node_options);
+        let node_provider = CachingNodeProvider::new(NodeProvider::new(node_options));
         let delivery = CrossChainMessageDelivery::new(options.wait_for_outgoing_messages);
         let chain_client_builder =
             ChainClientBuilder::new(node_provider, options.max_pending_messages, delivery);
@@ -205,7 +213,11 @@ impl ClientContext {
             .expect("No chain specified in wallet with no default chain")
     }
 
-    fn make_chain_client<S>(&self, storage: S, chain_id: ChainId) -> ChainClient<NodeProvider, S> {
+    fn make_chain_client<S>(
+        &self,
+        storage: S,
+        chain_id: ChainId,
+    ) -> ChainClient<CachingNodeProvider<NodeProvider>, S> {
         let chain = self
             .wallet_state
             .get(chain_id)
@@ -225,6 +237,7 @@ impl ClientContext {
             chain.timestamp,
             chain.next_block_height,
             chain.pending_block.clone(),
+            chain.block_approvals.clone(),
         )
     }
 
@@ -283,6 +296,7 @@ impl ClientContext {
                 timestamp,
                 next_block_height: BlockHeight::ZERO,
                 pending_block: None,
+                block_approvals: Vec::new(),
             });
         }
     }
@@ -332,6 +346,7 @@ impl ClientContext {
         chain_client: &mut ChainClient<impl ValidatorNodeProvider + Sync + 'static, S>,
         contract: PathBuf,
         service: PathBuf,
+        compress: bool,
     ) -> anyhow::Result<BytecodeId>
     where
         S: Storage + Clone + Send + Sync + 'static,
@@ -350,11 +365,19 @@ impl ClientContext {
         info!("Publishing bytecode");
         let bytecode_id = loop {
             let stream = chain_client.subscribe().await?;
-            match chain_client
-                .publish_bytecode(contract_bytecode.clone(), service_bytecode.clone())
-                .await
-                .context("failed to publish bytecode")?
-            {
+            let outcome = if compress {
+                chain_client
+                    .publish_compressed_bytecode(
+                        contract_bytecode.compress(),
+                        service_bytecode.compress(),
+                    )
+                    .await
+            } else {
+                chain_client
+                    .publish_bytecode(contract_bytecode.clone(), service_bytecode.clone())
+                    .await
+            };
+            match outcome.context("failed to publish bytecode")? {
                 ClientOutcome::Committed((bytecode_id, _)) => break bytecode_id,
                 ClientOutcome::WaitForTimeout(timeout) => {
                     wait_for_next_round(stream, timeout).await;
@@ -379,17 +402,17 @@ impl ClientContext {
     /// timeout, it will wait and retry.
     pub async fn apply_client_command<S, F, Fut, T>(
         &mut self,
-        mut client: ChainClient<NodeProvider, S>,
+        mut client: ChainClient<CachingNodeProvider<NodeProvider>, S>,
         mut f: F,
-    ) -> anyhow::Result<(T, ChainClient<NodeProvider, S>)>
+    ) -> anyhow::Result<(T, ChainClient<CachingNodeProvider<NodeProvider>, S>)>
     where
         S: Storage + Clone + Send + Sync + 'static,
         ViewError: From<S::ContextError>,
-        F: FnMut(ChainClient<NodeProvider, S>) -> Fut,
+        F: FnMut(ChainClient<CachingNodeProvider<NodeProvider>, S>) -> Fut,
         Fut: Future<
             Output = (
                 anyhow::Result<ClientOutcome<T>>,
-                ChainClient<NodeProvider, S>,
+                ChainClient<CachingNodeProvider<NodeProvider>, S>,
             ),
         >,
     {
@@ -591,12 +614,18 @@ impl ClientContext {
     }
 
     /// Makes one block proposal per chain, up to `num_chains` blocks.
-    pub fn make_benchmark_block_proposals(
+    pub async fn make_benchmark_block_proposals<S>(
         &mut self,
         key_pairs: &HashMap<ChainId, KeyPair>,
         transactions_per_block: usize,
         fungible_application_id: Option<ApplicationId>,
-    ) -> Vec<RpcMessage> {
+        storage: &S,
+    ) -> anyhow::Result<Vec<RpcMessage>>
+    where
+        S: Storage + Clone + Send + Sync + 'static,
+        ViewError: From<S::ContextError>,
+    {
+        let committee = self.wallet_state.genesis_config().create_committee();
         let mut proposals = Vec::new();
         let mut next_recipient = self.wallet_state.last_chain().unwrap().chain_id;
         let amount = Amount::from(1);
@@ -621,12 +650,22 @@ impl ClientContext {
                 .take(transactions_per_block)
                 .collect();
             let chain = self.wallet_state.get(chain_id).expect("should have chain");
+            // Blocks must include the signatures on the previous block of the same epoch.
+            let previous_block_signatures = match chain.block_hash {
+                Some(hash) => {
+                    let certificate = storage.read_certificate(hash).await?;
+                    (certificate.value().epoch() == Epoch::ZERO)
+                        .then(|| PreviousBlockSignatures::new(&certificate, &committee))
+                }
+                None => None,
+            };
             let block = Block {
                 epoch: Epoch::ZERO,
                 chain_id,
                 incoming_messages: Vec::new(),
                 operations,
                 previous_block_hash: chain.block_hash,
+                previous_block_signatures,
                 height: chain.next_block_height,
                 authenticated_signer: Some(Owner::from(public_key)),
                 timestamp: chain.timestamp.max(Timestamp::now()),
@@ -644,7 +683,7 @@ impl ClientContext {
             proposals.push(proposal.into());
             next_recipient = chain.chain_id;
         }
-        proposals
+        Ok(proposals)
     }
 
     /// Tries to aggregate votes into certificates.
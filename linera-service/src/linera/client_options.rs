@@ -5,16 +5,17 @@ use crate::ClientContext;
 use anyhow::Error;
 use chrono::{DateTime, Utc};
 use linera_base::{
-    crypto::PublicKey,
+    crypto::{CryptoHash, PublicKey},
     data_types::Amount,
     identifiers::{Account, ApplicationId, BytecodeId, ChainId, MessageId},
 };
 use linera_execution::{
-    committee::ValidatorName, system::SystemChannel, UserApplicationId, WasmRuntime,
-    WithWasmDefault,
+    committee::ValidatorName, system::SystemChannel, HostCallFuelCosts, UserApplicationId,
+    WasmRuntime, WithWasmDefault,
 };
 use linera_service::{
     chain_listener::{ChainListenerConfig, ClientContext as _},
+    http_oracle::HttpOracleOptions,
     storage::{full_initialize_storage, run_with_storage},
     util,
 };
@@ -71,6 +72,16 @@ pub struct ClientOptions {
     #[arg(long, default_value = "1000")]
     pub cache_size: usize,
 
+    /// A directory, only writable by the current user, in which to keep compiled WebAssembly
+    /// modules across restarts.
+    #[arg(long)]
+    pub module_cache_dir: Option<PathBuf>,
+
+    /// The maximal size in bytes of the compiled WebAssembly modules kept in
+    /// `module_cache_dir`.
+    #[arg(long, default_value = "4294967296")]
+    pub module_cache_max_size: u64,
+
     /// Subcommand.
     #[command(subcommand)]
     pub command: ClientCommand,
@@ -119,6 +130,12 @@ impl ClientOptions {
         let context = ClientContext::from_options(&self)?;
         let genesis_config = context.wallet_state().genesis_config().clone();
         let wasm_runtime = self.wasm_runtime.with_wasm_default();
+        if let Some(module_cache_dir) = &self.module_cache_dir {
+            linera_execution::enable_persistent_module_cache(
+                module_cache_dir,
+                self.module_cache_max_size,
+            )?;
+        }
         let max_concurrent_queries = self.max_concurrent_queries;
         let max_stream_queries = self.max_stream_queries;
         let cache_size = self.cache_size;
@@ -267,6 +284,16 @@ pub enum ClientCommand {
             value_parser = util::parse_millis
         )]
         timeout_increment: Duration,
+
+        /// The total weight of the owners that must approve each block. By default, the
+        /// proposer's signature is enough.
+        #[arg(long = "approval-threshold")]
+        approval_threshold: Option<u64>,
+
+        /// Make the chain public: anyone can propose blocks in the multi-leader rounds,
+        /// paying the fees from their own account on the chain.
+        #[arg(long = "open-multi-leader-rounds")]
+        open_multi_leader_rounds: bool,
     },
 
     /// Changes the application permissions configuration.
@@ -336,6 +363,14 @@ pub enum ClientCommand {
         /// The chain to synchronize with validators. If omitted, synchronizes the
         /// default chain of the wallet.
         chain_id: Option<ChainId>,
+
+        /// Synchronize all the chains of the wallet instead.
+        #[arg(long, conflicts_with = "chain_id")]
+        all: bool,
+
+        /// The maximum number of chains to synchronize at the same time, with `--all`.
+        #[arg(long, default_value = "16")]
+        max_concurrent_chains: usize,
     },
 
     /// Process all pending incoming messages from the inbox of the given chain by creating as many
@@ -347,6 +382,9 @@ pub enum ClientCommand {
     },
 
     /// Show the current set of validators for a chain.
+    ///
+    /// Also show the health of each validator, as measured by querying it. These measurements
+    /// are not saved, so they only cover the queries made by this command.
     QueryValidators {
         /// The chain to query. If omitted, query the default chain of the wallet.
         chain_id: Option<ChainId>,
@@ -365,6 +403,11 @@ pub enum ClientCommand {
         /// Voting power
         #[arg(long, default_value = "1")]
         votes: u64,
+
+        /// The account receiving the validator's share of the fees. If omitted, the current
+        /// recipient of an existing validator is kept.
+        #[arg(long)]
+        fee_recipient: Option<Account>,
     },
 
     /// Remove a validator (admin only)
@@ -420,6 +463,11 @@ pub enum ClientCommand {
         #[arg(long)]
         message_byte: Option<Amount>,
 
+        /// Set the percentage of the fees paid out to the validators at the end of each
+        /// epoch, at most 100.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(..=100))]
+        validator_fee_percent: Option<u8>,
+
         /// Set the maximum read data per block.
         #[arg(long)]
         maximum_bytes_read_per_block: Option<u64>,
@@ -427,6 +475,54 @@ pub enum ClientCommand {
         /// Set the maximum write data per block.
         #[arg(long)]
         maximum_bytes_written_per_block: Option<u64>,
+
+        /// Set the maximum number of operations per block.
+        #[arg(long)]
+        maximum_operations_per_block: Option<u64>,
+
+        /// Set the maximum number of incoming messages per block.
+        #[arg(long)]
+        maximum_incoming_messages_per_block: Option<u64>,
+
+        /// Set the maximum number of outgoing messages per block.
+        #[arg(long)]
+        maximum_outgoing_messages_per_block: Option<u64>,
+
+        /// Set the maximum total size of the arguments of the user operations in a block.
+        #[arg(long)]
+        maximum_operation_bytes_per_block: Option<u64>,
+
+        /// Set the maximum size of a block, in bytes.
+        #[arg(long)]
+        maximum_block_size: Option<u64>,
+
+        /// Set the maximum fuel consumed per block.
+        #[arg(long)]
+        maximum_fuel_per_block: Option<u64>,
+
+        /// Set the maximum size of a published contract or service bytecode, in bytes.
+        #[arg(long)]
+        maximum_bytecode_size: Option<u64>,
+
+        /// Set the maximum number of 64 KiB pages of linear memory declared by a published
+        /// bytecode.
+        #[arg(long)]
+        maximum_wasm_memory_pages: Option<u64>,
+
+        /// Set the fuel charged for calling each function of the system API, as a JSON object
+        /// such as `{"log": {"base": 100, "per_byte": 1}}`. Omitted functions cost no fuel.
+        #[arg(long, value_parser = util::parse_json::<HostCallFuelCosts>)]
+        host_call_fuel: Option<HostCallFuelCosts>,
+
+        /// Set the additional price of a block proposed on a public chain by a signer who
+        /// doesn't own it.
+        #[arg(long)]
+        open_proposer_block: Option<Amount>,
+
+        /// Set the version of the rules that published bytecodes must follow, or zero to only
+        /// check their size.
+        #[arg(long)]
+        bytecode_rules_version: Option<u32>,
     },
 
     /// Send one transfer per chain in bulk mode
@@ -526,6 +622,11 @@ pub enum ClientCommand {
         #[arg(long, default_value = "0")]
         message_byte_price: Amount,
 
+        /// Set the percentage of the fees paid out to the validators at the end of each
+        /// epoch, at most 100.
+        #[arg(long, default_value = "0", value_parser = clap::value_parser!(u8).range(..=100))]
+        validator_fee_percent: u8,
+
         /// Set the maximum read data per block.
         #[arg(long)]
         maximum_bytes_read_per_block: Option<u64>,
@@ -534,6 +635,54 @@ pub enum ClientCommand {
         #[arg(long)]
         maximum_bytes_written_per_block: Option<u64>,
 
+        /// Set the maximum number of operations per block.
+        #[arg(long)]
+        maximum_operations_per_block: Option<u64>,
+
+        /// Set the maximum number of incoming messages per block.
+        #[arg(long)]
+        maximum_incoming_messages_per_block: Option<u64>,
+
+        /// Set the maximum number of outgoing messages per block.
+        #[arg(long)]
+        maximum_outgoing_messages_per_block: Option<u64>,
+
+        /// Set the maximum total size of the arguments of the user operations in a block.
+        #[arg(long)]
+        maximum_operation_bytes_per_block: Option<u64>,
+
+        /// Set the maximum size of a block, in bytes.
+        #[arg(long)]
+        maximum_block_size: Option<u64>,
+
+        /// Set the maximum fuel consumed per block.
+        #[arg(long)]
+        maximum_fuel_per_block: Option<u64>,
+
+        /// Set the maximum size of a published contract or service bytecode, in bytes.
+        #[arg(long)]
+        maximum_bytecode_size: Option<u64>,
+
+        /// Set the maximum number of 64 KiB pages of linear memory declared by a published
+        /// bytecode.
+        #[arg(long)]
+        maximum_wasm_memory_pages: Option<u64>,
+
+        /// Set the fuel charged for calling each function of the system API, as a JSON object
+        /// such as `{"log": {"base": 100, "per_byte": 1}}`. Omitted functions cost no fuel.
+        #[arg(long, value_parser = util::parse_json::<HostCallFuelCosts>)]
+        host_call_fuel: Option<HostCallFuelCosts>,
+
+        /// Set the additional price of a block proposed on a public chain by a signer who
+        /// doesn't own it.
+        #[arg(long, default_value = "0")]
+        open_proposer_block_price: Amount,
+
+        /// Set the version of the rules that published bytecodes must follow, or zero to only
+        /// check their size.
+        #[arg(long, default_value_t = linera_execution::LATEST_BYTECODE_RULES)]
+        bytecode_rules_version: u32,
+
         /// Force this wallet to generate keys using a PRNG and a given seed. USE FOR
         /// TESTING ONLY.
         #[arg(long)]
@@ -559,6 +708,15 @@ pub enum ClientCommand {
         #[command(flatten)]
         config: ChainListenerConfig,
 
+        #[command(flatten)]
+        http_oracle: HttpOracleOptions,
+
+        /// Keep the messages logged by contracts in the latest blocks of each chain, so that
+        /// they can be queried. By default, logs are not kept. Validators never capture logs, so
+        /// only the blocks executed by this node are covered.
+        #[arg(long = "application-log-retention", default_value = "0")]
+        application_log_retention: u64,
+
         /// The port on which to run the server
         #[arg(long = "port", default_value = "8080")]
         port: NonZeroU16,
@@ -595,6 +753,11 @@ pub enum ClientCommand {
         /// An optional chain ID to publish the bytecode. The default chain of the wallet
         /// is used otherwise.
         publisher: Option<ChainId>,
+
+        /// Whether to compress the bytecode before publishing it, which reduces the size of
+        /// the block and the fees.
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        compress: bool,
     },
 
     /// Create an application.
@@ -675,6 +838,23 @@ pub enum ClientCommand {
         requester_chain_id: Option<ChainId>,
     },
 
+    /// Upgrade an application to a new bytecode, on the chain that created it.
+    UpgradeApplication {
+        /// The ID of the application to upgrade.
+        application_id: UserApplicationId,
+
+        /// The ID of the bytecode to use from now on.
+        bytecode_id: BytecodeId,
+
+        /// The migration argument as a JSON string.
+        #[arg(long)]
+        json_argument: Option<String>,
+
+        /// Path to a JSON file containing the migration argument.
+        #[arg(long)]
+        json_argument_path: Option<PathBuf>,
+    },
+
     /// Create an unassigned key-pair.
     Keygen,
 
@@ -699,6 +879,72 @@ pub enum ClientCommand {
         chain_id: Option<ChainId>,
     },
 
+    /// Print the block we unsuccessfully tried to propose earlier, as JSON.
+    ///
+    /// On chains that require block proposals to be approved by several owners, the other
+    /// owners can approve the printed block with `approve-block`.
+    ShowPendingBlock {
+        /// The chain with the pending block. If not specified, the wallet's default chain is used.
+        chain_id: Option<ChainId>,
+    },
+
+    /// Approve a block proposed by another owner of a chain, and print the approval as JSON.
+    ///
+    /// This is needed on chains that require block proposals to be approved by several
+    /// owners. The chain of the block must be in the wallet, with one of its owners' keys.
+    ApproveBlock {
+        /// The JSON file with the block, as printed by `show-pending-block`.
+        block: PathBuf,
+    },
+
+    /// Add approvals of our pending block by other owners, and retry proposing it.
+    ///
+    /// The approvals are kept in the wallet until the block is committed, so that they can
+    /// be submitted as they arrive.
+    SubmitBlockApprovals {
+        /// The JSON files with the approvals, as printed by `approve-block`.
+        #[arg(required = true)]
+        approvals: Vec<PathBuf>,
+
+        /// The chain with the pending block. If not specified, the wallet's default chain is used.
+        #[arg(long)]
+        chain_id: Option<ChainId>,
+    },
+
+    /// Re-execute a confirmed block from the local storage, recording the execution of its
+    /// applications.
+    ///
+    /// The earlier blocks of the chain are replayed in a temporary in-memory storage, and the
+    /// block is then executed again, printing every action of its applications, their calls to
+    /// the runtime and the fuel they consumed. The replay fails if the outcome differs from the
+    /// one that was certified.
+    ///
+    /// With `--pending-block-of`, the pending block of a chain in the wallet is replayed instead,
+    /// e.g. to investigate a proposal that failed before being confirmed.
+    ReplayBlock {
+        /// The hash of the certificate of the block.
+        #[arg(required_unless_present = "pending_block_of")]
+        certificate_hash: Option<CryptoHash>,
+
+        /// Replay the pending block of this chain in the wallet instead of a confirmed block.
+        #[arg(long, conflicts_with = "certificate_hash")]
+        pending_block_of: Option<ChainId>,
+
+        /// Save the execution trace of the block in this JSON file.
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Compare the execution trace of the block with the one saved in this JSON file, and
+        /// report the first difference.
+        #[arg(long)]
+        expected_trace: Option<PathBuf>,
+
+        /// Profile the fuel consumed by the applications in the block, and save it in this file
+        /// using the folded stacks format of flamegraph tools.
+        #[arg(long)]
+        fuel_profile: Option<PathBuf>,
+    },
+
     /// Show the contents of the wallet.
     #[command(subcommand)]
     Wallet(WalletCommand),
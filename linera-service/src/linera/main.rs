@@ -15,19 +15,22 @@ use linera_base::{
     identifiers::{ChainDescription, ChainId, MessageId, Owner},
     ownership::{ChainOwnership, TimeoutConfig},
 };
-use linera_chain::data_types::{CertificateValue, ExecutedBlock};
+use linera_chain::data_types::{
+    Block, BlockApproval, Certificate, CertificateValue, ExecutedBlock,
+};
 use linera_core::{
     client::{ChainClient, ChainClientError},
     data_types::{ChainInfoQuery, ClientOutcome},
     local_node::LocalNodeClient,
     node::ValidatorNodeProvider,
     notifier::Notifier,
-    worker::WorkerState,
+    sync::{ChainSynchronizer, SyncProgress},
+    worker::{WorkerError, WorkerState},
 };
 use linera_execution::{
     committee::{Committee, ValidatorName, ValidatorState},
     system::{ApplicationPermissions, SystemChannel, UserData},
-    Message, ResourceControlPolicy, SystemMessage,
+    ExecutionTrace, ExecutionTracer, FuelProfiler, Message, ResourceControlPolicy, SystemMessage,
 };
 use linera_service::{
     chain_listener::ClientContext as _,
@@ -42,14 +45,15 @@ use linera_service::{
     project::{self, Project},
     storage::Runnable,
 };
-use linera_storage::Storage;
-use linera_views::views::ViewError;
-use rand::Rng as _;
+use linera_storage::{MemoryStorage, Storage};
+use linera_views::{memory::MemoryStoreConfig, views::ViewError};
+use rand::{seq::SliceRandom as _, Rng as _};
 use serde_json::Value;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     env, iter,
     path::PathBuf,
+    pin::pin,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -87,6 +91,18 @@ fn deserialize_response(response: RpcMessage) -> Option<ChainInfoResponse> {
 
 struct Job(ClientContext, ClientCommand);
 
+/// A block to re-execute with `linera replay-block`.
+enum ReplayedBlock {
+    /// A confirmed block, whose outcome must match the certified one.
+    Confirmed(Certificate),
+    /// A block that was proposed but not confirmed, e.g. because its execution failed.
+    Pending(Block),
+}
+
+/// The maximum number of concurrent stream queries of the temporary storage used to replay
+/// blocks.
+const REPLAY_MAX_STREAM_QUERIES: usize = 10;
+
 fn read_json(string: Option<String>, path: Option<PathBuf>) -> Result<Vec<u8>, anyhow::Error> {
     let value = match (string, path) {
         (Some(_), Some(_)) => bail!("cannot have both a json string and file"),
@@ -197,6 +213,8 @@ impl Runnable for Job {
                 fast_round_duration,
                 base_timeout,
                 timeout_increment,
+                approval_threshold,
+                open_multi_leader_rounds,
             } => {
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(storage, chain_id);
@@ -227,11 +245,13 @@ impl Runnable for Job {
                 };
                 let ((message_id, certificate), _) = context
                     .apply_client_command(chain_client, |mut chain_client| {
-                        let ownership = ChainOwnership::multiple(
+                        let mut ownership = ChainOwnership::multiple(
                             owners.clone(),
                             multi_leader_rounds,
                             timeout_config.clone(),
                         );
+                        ownership.approval_threshold = approval_threshold;
+                        ownership.open_multi_leader_rounds = open_multi_leader_rounds;
                         async move {
                             let result = chain_client
                                 .open_chain(ownership, balance)
@@ -399,7 +419,11 @@ impl Runnable for Job {
                 println!("{}", balance);
             }
 
-            Sync { chain_id } => {
+            Sync {
+                chain_id,
+                all: false,
+                ..
+            } => {
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let mut chain_client = context.make_chain_client(storage, chain_id);
                 info!("Synchronizing chain information");
@@ -413,6 +437,49 @@ impl Runnable for Job {
                 );
             }
 
+            Sync {
+                all: true,
+                max_concurrent_chains,
+                ..
+            } => {
+                let clients = context
+                    .wallet_state()
+                    .chain_ids()
+                    .into_iter()
+                    .map(|chain_id| context.make_chain_client(storage.clone(), chain_id))
+                    .collect::<Vec<_>>();
+                info!("Synchronizing {} chains", clients.len());
+                let time_start = Instant::now();
+                let mut outcomes = pin!(ChainSynchronizer::new(clients)
+                    .with_max_concurrent_chains(max_concurrent_chains)
+                    .run());
+                let mut progress = SyncProgress::default();
+                while let Some(mut outcome) = outcomes.next().await {
+                    // Save every chain as soon as it is done, so that an interrupted
+                    // synchronization doesn't start over.
+                    context.update_and_save_wallet(&mut outcome.client).await;
+                    progress = outcome.progress;
+                    let chain_id = outcome.client.chain_id();
+                    match outcome.result {
+                        Ok(_) => info!("Synchronized chain {chain_id} ({progress})"),
+                        Err(error) => {
+                            warn!("Failed to synchronize chain {chain_id}: {error} ({progress})")
+                        }
+                    }
+                }
+                let time_total = time_start.elapsed();
+                info!(
+                    "Synchronized chain information in {} ms",
+                    time_total.as_millis()
+                );
+                ensure!(
+                    progress.failed == 0,
+                    "Failed to synchronize {} of {} chains",
+                    progress.failed,
+                    progress.total
+                );
+            }
+
             ProcessInbox { chain_id } => {
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let mut chain_client = context.make_chain_client(storage, chain_id);
@@ -445,12 +512,10 @@ impl Runnable for Job {
                 info!("Validators obtained after {} ms", time_total.as_millis());
                 info!("{:?}", committee.validators());
                 let node_provider = context.make_node_provider();
-                for (name, state) in committee.validators() {
-                    match node_provider
-                        .make_node(&state.network_address)?
-                        .get_version_info()
-                        .await
-                    {
+                let health = chain_client.validator_health();
+                let nodes = health.track(node_provider.make_nodes::<Vec<_>>(&committee)?);
+                for (name, mut node) in nodes {
+                    match node.get_version_info().await {
                         Ok(version_info) => {
                             info!(
                                 "Version information for validator {name:?}:{}",
@@ -461,6 +526,14 @@ impl Runnable for Job {
                             warn!("Failed to get version information for validator {name:?}:\n{e}")
                         }
                     }
+                    // Measure the latency of a regular query as well.
+                    let query = ChainInfoQuery::new(chain_id);
+                    if let Err(e) = node.handle_chain_info_query(query).await {
+                        warn!("Failed to query chain {chain_id} from validator {name:?}:\n{e}");
+                    }
+                }
+                for (name, stats) in health.all_stats() {
+                    println!("{name}: {stats}");
                 }
             }
 
@@ -498,12 +571,18 @@ impl Runnable for Job {
                                     name,
                                     address,
                                     votes,
+                                    fee_recipient,
                                 } => {
+                                    // Keep the current fee recipient unless a new one is given.
+                                    let fee_recipient = fee_recipient.or_else(|| {
+                                        validators.get(&name).and_then(|state| state.fee_recipient)
+                                    });
                                     validators.insert(
                                         name,
                                         ValidatorState {
                                             network_address: address,
                                             votes,
+                                            fee_recipient,
                                         },
                                     );
                                 }
@@ -525,8 +604,20 @@ impl Runnable for Job {
                                     operation_byte,
                                     message,
                                     message_byte,
+                                    validator_fee_percent,
                                     maximum_bytes_read_per_block,
                                     maximum_bytes_written_per_block,
+                                    maximum_operations_per_block,
+                                    maximum_incoming_messages_per_block,
+                                    maximum_outgoing_messages_per_block,
+                                    maximum_operation_bytes_per_block,
+                                    maximum_block_size,
+                                    maximum_fuel_per_block,
+                                    maximum_bytecode_size,
+                                    maximum_wasm_memory_pages,
+                                    host_call_fuel,
+                                    open_proposer_block,
+                                    bytecode_rules_version,
                                 } => {
                                     if let Some(block) = block {
                                         policy.block = block;
@@ -561,6 +652,9 @@ impl Runnable for Job {
                                     if let Some(message_byte) = message_byte {
                                         policy.message_byte = message_byte;
                                     }
+                                    if let Some(validator_fee_percent) = validator_fee_percent {
+                                        policy.validator_fee_percent = validator_fee_percent;
+                                    }
                                     if let Some(maximum_bytes_read_per_block) =
                                         maximum_bytes_read_per_block
                                     {
@@ -573,6 +667,54 @@ impl Runnable for Job {
                                         policy.maximum_bytes_written_per_block =
                                             maximum_bytes_written_per_block;
                                     }
+                                    if let Some(maximum_operations_per_block) =
+                                        maximum_operations_per_block
+                                    {
+                                        policy.maximum_operations_per_block =
+                                            maximum_operations_per_block;
+                                    }
+                                    if let Some(maximum_incoming_messages_per_block) =
+                                        maximum_incoming_messages_per_block
+                                    {
+                                        policy.maximum_incoming_messages_per_block =
+                                            maximum_incoming_messages_per_block;
+                                    }
+                                    if let Some(maximum_outgoing_messages_per_block) =
+                                        maximum_outgoing_messages_per_block
+                                    {
+                                        policy.maximum_outgoing_messages_per_block =
+                                            maximum_outgoing_messages_per_block;
+                                    }
+                                    if let Some(maximum_operation_bytes_per_block) =
+                                        maximum_operation_bytes_per_block
+                                    {
+                                        policy.maximum_operation_bytes_per_block =
+                                            maximum_operation_bytes_per_block;
+                                    }
+                                    if let Some(maximum_block_size) = maximum_block_size {
+                                        policy.maximum_block_size = maximum_block_size;
+                                    }
+                                    if let Some(maximum_fuel_per_block) = maximum_fuel_per_block {
+                                        policy.maximum_fuel_per_block = maximum_fuel_per_block;
+                                    }
+                                    if let Some(maximum_bytecode_size) = maximum_bytecode_size {
+                                        policy.maximum_bytecode_size = maximum_bytecode_size;
+                                    }
+                                    if let Some(maximum_wasm_memory_pages) =
+                                        maximum_wasm_memory_pages
+                                    {
+                                        policy.maximum_wasm_memory_pages =
+                                            maximum_wasm_memory_pages;
+                                    }
+                                    if let Some(host_call_fuel) = &host_call_fuel {
+                                        policy.host_call_fuel = host_call_fuel.clone();
+                                    }
+                                    if let Some(open_proposer_block) = open_proposer_block {
+                                        policy.open_proposer_block = open_proposer_block;
+                                    }
+                                    if let Some(bytecode_rules_version) = bytecode_rules_version {
+                                        policy.bytecode_rules_version = bytecode_rules_version;
+                                    }
                                     info!(
                                         "ResourceControlPolicy:\n\
                             {:.2} base cost per block\n\
@@ -586,8 +728,19 @@ impl Runnable for Job {
                             {:.2} per byte in the argument of an operation\n\
                             {:.2} per outgoing messages\n\
                             {:.2} per byte in the argument of an outgoing messages\n\
+                            {}% of the fees paid out to the validators\n\
                             {:.2} maximum number bytes read per block\n\
-                            {:.2} maximum number bytes written per block",
+                            {:.2} maximum number bytes written per block\n\
+                            {:.2} maximum number of operations per block\n\
+                            {:.2} maximum number of incoming messages per block\n\
+                            {:.2} maximum number of outgoing messages per block\n\
+                            {:.2} maximum number of bytes of operation arguments per block\n\
+                            {:.2} maximum number of bytes per block\n\
+                            {:.2} maximum fuel per block\n\
+                            {:.2} maximum number of bytes per bytecode\n\
+                            {:.2} maximum number of Wasm memory pages per bytecode\n\
+                            {:.2} additional cost per block proposed by a non-owner\n\
+                            {} version of the bytecode validation rules",
                                         policy.block,
                                         policy.fuel_unit,
                                         policy.read_operation,
@@ -599,8 +752,19 @@ impl Runnable for Job {
                                         policy.operation_byte,
                                         policy.message,
                                         policy.message_byte,
+                                        policy.validator_fee_percent,
                                         policy.maximum_bytes_read_per_block,
-                                        policy.maximum_bytes_written_per_block
+                                        policy.maximum_bytes_written_per_block,
+                                        policy.maximum_operations_per_block,
+                                        policy.maximum_incoming_messages_per_block,
+                                        policy.maximum_outgoing_messages_per_block,
+                                        policy.maximum_operation_bytes_per_block,
+                                        policy.maximum_block_size,
+                                        policy.maximum_fuel_per_block,
+                                        policy.maximum_bytecode_size,
+                                        policy.maximum_wasm_memory_pages,
+                                        policy.open_proposer_block,
+                                        policy.bytecode_rules_version
                                     );
                                     if block.is_none()
                                         && fuel_unit.is_none()
@@ -613,8 +777,20 @@ impl Runnable for Job {
                                         && operation_byte.is_none()
                                         && message.is_none()
                                         && message_byte.is_none()
+                                        && validator_fee_percent.is_none()
                                         && maximum_bytes_read_per_block.is_none()
                                         && maximum_bytes_written_per_block.is_none()
+                                        && maximum_operations_per_block.is_none()
+                                        && maximum_incoming_messages_per_block.is_none()
+                                        && maximum_outgoing_messages_per_block.is_none()
+                                        && maximum_operation_bytes_per_block.is_none()
+                                        && maximum_block_size.is_none()
+                                        && maximum_fuel_per_block.is_none()
+                                        && maximum_bytecode_size.is_none()
+                                        && maximum_wasm_memory_pages.is_none()
+                                        && host_call_fuel.is_none()
+                                        && open_proposer_block.is_none()
+                                        && bytecode_rules_version.is_none()
                                     {
                                         return (Ok(ClientOutcome::Committed(None)), chain_client);
                                     }
@@ -683,11 +859,14 @@ impl Runnable for Job {
                 // For this command, we create proposals and gather certificates without using
                 // the client library. We update the wallet storage at the end using a local node.
                 info!("Starting benchmark phase 1 (block proposals)");
-                let proposals = context.make_benchmark_block_proposals(
-                    &key_pairs,
-                    transactions_per_block,
-                    fungible_application_id,
-                );
+                let proposals = context
+                    .make_benchmark_block_proposals(
+                        &key_pairs,
+                        transactions_per_block,
+                        fungible_application_id,
+                        &storage,
+                    )
+                    .await?;
                 let num_proposal = proposals.len();
                 let mut values = HashMap::new();
 
@@ -777,7 +956,21 @@ impl Runnable for Job {
                 // Not saving the wallet because `listen()` does not create blocks.
             }
 
-            Service { config, port } => {
+            Service {
+                config,
+                http_oracle,
+                application_log_retention,
+                port,
+            } => {
+                let mut storage = storage;
+                storage.set_application_log_retention(application_log_retention);
+                if let Some(oracle) = http_oracle.oracle() {
+                    info!(
+                        "Allowing services to send HTTP requests to {:?}",
+                        oracle.config().allowed_hosts
+                    );
+                    storage.set_http_oracle(oracle);
+                }
                 let default_chain = context.wallet_state().default_chain();
                 let service = NodeService::new(config, port, default_chain, storage, context);
                 service.run().await?;
@@ -816,13 +1009,14 @@ impl Runnable for Job {
                 contract,
                 service,
                 publisher,
+                compress,
             } => {
                 let start_time = Instant::now();
                 let publisher = publisher.unwrap_or_else(|| context.default_chain());
                 info!("Publishing bytecode on chain {}", publisher);
                 let mut chain_client = context.make_chain_client(storage, publisher);
                 let result = context
-                    .publish_bytecode(&mut chain_client, contract, service)
+                    .publish_bytecode(&mut chain_client, contract, service, compress)
                     .await;
                 context.update_and_save_wallet(&mut chain_client).await;
                 let bytecode_id = result.context("failed to publish bytecode")?;
@@ -894,7 +1088,7 @@ impl Runnable for Job {
                 let argument = read_json(json_argument, json_argument_path)?;
 
                 let result = context
-                    .publish_bytecode(&mut chain_client, contract, service)
+                    .publish_bytecode(&mut chain_client, contract, service, true)
                     .await;
                 context.update_and_save_wallet(&mut chain_client).await;
                 let bytecode_id = result.context("failed to publish bytecode")?;
@@ -940,6 +1134,39 @@ impl Runnable for Job {
                 debug!("{:?}", certificate);
             }
 
+            UpgradeApplication {
+                application_id,
+                bytecode_id,
+                json_argument,
+                json_argument_path,
+            } => {
+                let start_time = Instant::now();
+                let chain_id = application_id.creation.chain_id;
+                info!("Upgrading application on chain {}", chain_id);
+                let mut chain_client = context.make_chain_client(storage, chain_id);
+                let argument = read_json(json_argument, json_argument_path)?;
+
+                info!("Synchronizing");
+                chain_client.synchronize_from_validators().await?;
+                context.process_inbox(&mut chain_client).await?;
+
+                let (certificate, _) = context
+                    .apply_client_command(chain_client, move |mut chain_client| {
+                        let argument = argument.clone();
+                        async move {
+                            let result = chain_client
+                                .upgrade_application(application_id, bytecode_id, argument)
+                                .await
+                                .context("failed to upgrade application");
+                            (result, chain_client)
+                        }
+                    })
+                    .await?;
+                debug!("{:?}", certificate);
+                info!("{}", "Application upgraded successfully!".green().bold());
+                info!("Time elapsed: {} ms", start_time.elapsed().as_millis());
+            }
+
             Assign { key, message_id } => {
                 let chain_id = ChainId::child(message_id);
                 info!(
@@ -984,7 +1211,7 @@ impl Runnable for Job {
                     let (contract_path, service_path) = project.build(name)?;
 
                     let result = context
-                        .publish_bytecode(&mut chain_client, contract_path, service_path)
+                        .publish_bytecode(&mut chain_client, contract_path, service_path, true)
                         .await;
                     context.update_and_save_wallet(&mut chain_client).await;
                     let bytecode_id = result.context("failed to publish bytecode")?;
@@ -1032,6 +1259,147 @@ impl Runnable for Job {
                 context.update_and_save_wallet(&mut chain_client).await;
             }
 
+            ShowPendingBlock { chain_id } => {
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let block = context
+                    .wallet_state()
+                    .get(chain_id)
+                    .with_context(|| format!("chain {chain_id} is not in the wallet"))?
+                    .pending_block
+                    .clone()
+                    .with_context(|| format!("chain {chain_id} has no pending block"))?;
+                println!("{}", serde_json::to_string_pretty(&block)?);
+            }
+
+            ApproveBlock { block } => {
+                let block: Block = serde_json::from_str(&fs_err::read_to_string(block)?)?;
+                let chain_id = block.chain_id;
+                ensure!(
+                    context.wallet_state().get(chain_id).is_some(),
+                    "chain {chain_id} is not in the wallet"
+                );
+                info!("Approving block {} of chain {}", block.height, chain_id);
+                let mut chain_client = context.make_chain_client(storage, chain_id);
+                chain_client.synchronize_from_validators().await?;
+                let approval = chain_client.approve_block(&block).await?;
+                println!("{}", serde_json::to_string_pretty(&approval)?);
+            }
+
+            SubmitBlockApprovals {
+                approvals,
+                chain_id,
+            } => {
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let approvals = approvals
+                    .into_iter()
+                    .map(|path| -> anyhow::Result<BlockApproval> {
+                        Ok(serde_json::from_str(&fs_err::read_to_string(path)?)?)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                info!(
+                    "Submitting {} approvals of the pending block of chain {}",
+                    approvals.len(),
+                    chain_id
+                );
+                let mut chain_client = context.make_chain_client(storage, chain_id);
+                chain_client.add_block_approvals(approvals);
+                // Save the approvals before proposing, in case there are still not enough.
+                context.update_and_save_wallet(&mut chain_client).await;
+                let outcome = chain_client.process_pending_block().await;
+                context.update_and_save_wallet(&mut chain_client).await;
+                match outcome? {
+                    ClientOutcome::Committed(Some(certificate)) => {
+                        info!("Pending block committed successfully.");
+                        println!("{}", certificate.hash());
+                    }
+                    ClientOutcome::Committed(None) => info!("No block is currently pending."),
+                    ClientOutcome::WaitForTimeout(timeout) => {
+                        info!("Please try again at {}", timeout.timestamp)
+                    }
+                }
+            }
+
+            ReplayBlock {
+                certificate_hash,
+                pending_block_of,
+                output,
+                expected_trace,
+                fuel_profile,
+            } => {
+                let replayed_block = match (certificate_hash, pending_block_of) {
+                    (Some(certificate_hash), _) => {
+                        info!("Replaying block {}", certificate_hash);
+                        ReplayedBlock::Confirmed(storage.read_certificate(certificate_hash).await?)
+                    }
+                    (None, Some(chain_id)) => {
+                        info!("Replaying the pending block of chain {}", chain_id);
+                        let block = context
+                            .wallet_state()
+                            .get(chain_id)
+                            .with_context(|| format!("chain {chain_id} is not in the wallet"))?
+                            .pending_block
+                            .clone()
+                            .with_context(|| format!("chain {chain_id} has no pending block"))?;
+                        ReplayedBlock::Pending(block)
+                    }
+                    (None, None) => bail!("a certificate hash or a chain is required"),
+                };
+                let is_pending = matches!(replayed_block, ReplayedBlock::Pending(_));
+                let genesis_config = context.wallet_state().genesis_config().clone();
+                let fuel_profiler = fuel_profile.is_some().then(|| {
+                    linera_execution::enable_fuel_profiling();
+                    FuelProfiler::default()
+                });
+                let (trace, result) = Self::replay_block(
+                    storage,
+                    replayed_block,
+                    &genesis_config,
+                    fuel_profiler.clone(),
+                )
+                .await?;
+                for entry in &trace.entries {
+                    println!("{}", entry);
+                }
+                if let Some(path) = output {
+                    fs_err::write(path, serde_json::to_string_pretty(&trace)?)?;
+                }
+                if let (Some(path), Some(fuel_profiler)) = (fuel_profile, fuel_profiler) {
+                    let profile = fuel_profiler.take();
+                    info!(
+                        "Saving the profile of {} units of fuel to {}",
+                        profile.total_fuel(),
+                        path.display()
+                    );
+                    profile.save_folded(&path).with_context(|| {
+                        format!("failed to save the fuel profile to {}", path.display())
+                    })?;
+                }
+                let matches_expected_trace = match expected_trace {
+                    Some(path) => {
+                        let expected_trace: ExecutionTrace =
+                            serde_json::from_str(&fs_err::read_to_string(path)?)?;
+                        let divergence = trace.first_divergence(&expected_trace);
+                        if let Some(divergence) = &divergence {
+                            warn!("{}", divergence);
+                        }
+                        divergence.is_none()
+                    }
+                    None => true,
+                };
+                if is_pending {
+                    result.context("the execution of the pending block failed")?;
+                } else {
+                    result.context(
+                        "the replayed block has a different outcome than the certified one",
+                    )?;
+                }
+                ensure!(
+                    matches_expected_trace,
+                    "the execution trace differs from the expected one"
+                );
+                info!("Block replayed successfully");
+            }
+
             Wallet(WalletCommand::Init {
                 faucet: Some(faucet_url),
                 with_new_chain: true,
@@ -1101,7 +1469,7 @@ impl Job {
         // Take the latest committee we know of.
         let admin_chain_id = context.wallet_state().genesis_admin_chain();
         let query = ChainInfoQuery::new(admin_chain_id).with_committees();
-        let nodes = if let Some(validators) = validators {
+        let mut nodes: Vec<_> = if let Some(validators) = validators {
             context
                 .make_node_provider()
                 .make_nodes_from_list(validators)?
@@ -1112,6 +1480,7 @@ impl Job {
                 .context("Invalid chain info response; missing latest committee")?;
             context.make_node_provider().make_nodes(committee)?
         };
+        nodes.shuffle(&mut rand::thread_rng());
 
         // Download the parent chain.
         let target_height = message_id.height.try_add_one()?;
@@ -1152,6 +1521,97 @@ impl Job {
         Ok(())
     }
 
+    /// Re-executes the block certified by `certificate_hash` in a temporary storage, after
+    /// replaying the earlier blocks of its chain, and returns the execution trace of the block
+    /// together with the outcome of its re-execution.
+    async fn replay_block<S>(
+        storage: S,
+        replayed_block: ReplayedBlock,
+        genesis_config: &GenesisConfig,
+        fuel_profiler: Option<FuelProfiler>,
+    ) -> anyhow::Result<(ExecutionTrace, Result<(), WorkerError>)>
+    where
+        S: Storage + Clone + Send + Sync + 'static,
+        ViewError: From<S::ContextError>,
+    {
+        let block = match &replayed_block {
+            ReplayedBlock::Confirmed(certificate) => {
+                &certificate
+                    .value()
+                    .executed_block()
+                    .context("the certificate is not for a confirmed block")?
+                    .block
+            }
+            ReplayedBlock::Pending(block) => block,
+        };
+        let chain_id = block.chain_id;
+        let height = usize::try_from(block.height.0)?;
+        let earlier_hashes = storage
+            .load_chain(chain_id)
+            .await?
+            .confirmed_log
+            .read(..height)
+            .await?;
+        ensure!(
+            earlier_hashes.len() == height,
+            "the earlier blocks of chain {chain_id} are missing from the local storage"
+        );
+        let earlier_certificates = storage.read_certificates(earlier_hashes).await?;
+
+        // The bytecode used by the blocks was published by other chains, which are not replayed.
+        let mut bytecode_hashes = BTreeSet::new();
+        let earlier_blocks = earlier_certificates
+            .iter()
+            .filter_map(|certificate| certificate.value().executed_block())
+            .map(|executed_block| &executed_block.block);
+        for block in earlier_blocks.chain([block]) {
+            bytecode_hashes.extend(
+                block
+                    .bytecode_locations()
+                    .into_keys()
+                    .map(|location| location.certificate_hash),
+            );
+        }
+
+        let tracer = ExecutionTracer::default();
+        let store_config = MemoryStoreConfig::new(REPLAY_MAX_STREAM_QUERIES);
+        let mut replay_storage =
+            MemoryStorage::new(store_config, "replay", storage.wasm_runtime()).await?;
+        replay_storage.execution_tracer = Some(tracer.clone());
+        replay_storage.fuel_profiler = fuel_profiler.clone();
+        genesis_config
+            .initialize_storage(&mut replay_storage)
+            .await?;
+        for hash in bytecode_hashes {
+            replay_storage
+                .write_value(&storage.read_value(hash).await?)
+                .await?;
+        }
+
+        let mut worker = WorkerState::new("Replay".to_string(), None, replay_storage)
+            .with_allow_inactive_chains(true)
+            .with_allow_messages_from_deprecated_epochs(true);
+        for earlier_certificate in earlier_certificates {
+            worker
+                .fully_handle_certificate(earlier_certificate, vec![])
+                .await
+                .context("failed to replay an earlier block")?;
+        }
+        // Only keep the trace and the profile of the replayed block.
+        tracer.take();
+        if let Some(fuel_profiler) = &fuel_profiler {
+            fuel_profiler.take();
+        }
+        let result = match replayed_block {
+            ReplayedBlock::Confirmed(certificate) => worker
+                .fully_handle_certificate(certificate, vec![])
+                .await
+                .map(|_| ()),
+            ReplayedBlock::Pending(block) => worker.stage_block_execution(block).await.map(|_| ()),
+        };
+        Ok((tracer.take(), result))
+    }
+
     /// Prints a warning message to explain that the wallet has been initialized using data from
     /// untrusted nodes, and gives instructions to verify that we are connected to the right
     /// network.
@@ -1386,8 +1846,20 @@ async fn run(options: ClientOptions) -> Result<(), anyhow::Error> {
             operation_byte_price,
             message_price,
             message_byte_price,
+            validator_fee_percent,
             maximum_bytes_read_per_block,
             maximum_bytes_written_per_block,
+            maximum_operations_per_block,
+            maximum_incoming_messages_per_block,
+            maximum_outgoing_messages_per_block,
+            maximum_operation_bytes_per_block,
+            maximum_block_size,
+            maximum_fuel_per_block,
+            maximum_bytecode_size,
+            maximum_wasm_memory_pages,
+            host_call_fuel,
+            open_proposer_block_price,
+            bytecode_rules_version,
             testing_prng_seed,
             network_name,
         } => {
@@ -1413,8 +1885,23 @@ async fn run(options: ClientOptions) -> Result<(), anyhow::Error> {
                 operation: *operation_price,
                 message_byte: *message_byte_price,
                 message: *message_price,
+                validator_fee_percent: *validator_fee_percent,
                 maximum_bytes_read_per_block,
                 maximum_bytes_written_per_block,
+                maximum_operations_per_block: maximum_operations_per_block.unwrap_or(u64::MAX),
+                maximum_incoming_messages_per_block: maximum_incoming_messages_per_block
+                    .unwrap_or(u64::MAX),
+                maximum_outgoing_messages_per_block: maximum_outgoing_messages_per_block
+                    .unwrap_or(u64::MAX),
+                maximum_operation_bytes_per_block: maximum_operation_bytes_per_block
+                    .unwrap_or(u64::MAX),
+                maximum_block_size: maximum_block_size.unwrap_or(u64::MAX),
+                maximum_fuel_per_block: maximum_fuel_per_block.unwrap_or(u64::MAX),
+                maximum_bytecode_size: maximum_bytecode_size.unwrap_or(u64::MAX),
+                maximum_wasm_memory_pages: maximum_wasm_memory_pages.unwrap_or(u64::MAX),
+                host_call_fuel: host_call_fuel.clone().unwrap_or_default(),
+                open_proposer_block: *open_proposer_block_price,
+                bytecode_rules_version: *bytecode_rules_version,
             };
             let timestamp = start_timestamp
                 .map(|st| {
@@ -22,12 +22,15 @@ use futures::{
 };
 use linera_base::{
     crypto::{CryptoError, CryptoHash, PublicKey},
-    data_types::{Amount, Timestamp},
+    data_types::{Amount, BlockHeight, Timestamp},
     identifiers::{ApplicationId, BytecodeId, ChainId, Owner},
     ownership::{ChainOwnership, TimeoutConfig},
     BcsHexParseError,
 };
-use linera_chain::{data_types::HashedValue, ChainStateView};
+use linera_chain::{
+    data_types::{Block, BlockApproval, HashedValue},
+    ChainStateView,
+};
 use linera_core::{
     client::{ChainClient, ChainClientError},
     data_types::{ClientOutcome, RoundTimeout},
@@ -37,8 +40,8 @@ use linera_core::{
 use linera_execution::{
     committee::{Committee, Epoch},
     system::{AdminOperation, ApplicationPermissions, Recipient, SystemChannel, UserData},
-    Bytecode, Operation, Query, Response, SystemOperation, UserApplicationDescription,
-    UserApplicationId,
+    ApplicationLog, Bytecode, Operation, Query, Response, SystemOperation,
+    UserApplicationDescription, UserApplicationId,
 };
 use linera_storage::Storage;
 use linera_views::views::ViewError;
@@ -314,6 +317,38 @@ where
         }
     }
 
+    /// Approves the given block with the key of the chain's owner in the wallet, so that it can
+    /// be submitted to the proposer of the block.
+    async fn approve_block(
+        &self,
+        chain_id: ChainId,
+        block: async_graphql::Json<Block>,
+    ) -> Result<BlockApproval, Error> {
+        let mut client = self.clients.try_client_lock(&chain_id).await?;
+        client.synchronize_from_validators().await?;
+        Ok(client.approve_block(&block.0).await?)
+    }
+
+    /// Adds the given approvals to the pending block and retries proposing it.
+    async fn submit_block_approvals(
+        &self,
+        chain_id: ChainId,
+        approvals: Vec<BlockApproval>,
+    ) -> Result<Option<CryptoHash>, Error> {
+        let mut client = self.clients.try_client_lock(&chain_id).await?;
+        client.add_block_approvals(approvals);
+        let outcome = client.process_pending_block().await;
+        self.context.lock().await.update_wallet(&mut *client).await;
+        match outcome? {
+            ClientOutcome::Committed(Some(certificate)) => Ok(Some(certificate.hash())),
+            ClientOutcome::Committed(None) => Ok(None),
+            ClientOutcome::WaitForTimeout(timeout) => Err(Error::from(format!(
+                "Please try again at {}",
+                timeout.timestamp
+            ))),
+        }
+    }
+
     /// Transfers `amount` units of value from the given owner's account to the recipient.
     /// If no owner is given, try to take the units out of the unattributed account.
     async fn transfer(
@@ -420,6 +455,15 @@ where
             default = 1_000
         )]
         timeout_increment_ms: u64,
+        #[graphql(desc = "The total weight of the owners that must approve each block; \
+                    default: only the proposer")]
+        approval_threshold: Option<u64>,
+        #[graphql(
+            desc = "Whether anyone can propose blocks in the multi-leader rounds, paying their \
+                    own fees",
+            default = false
+        )]
+        open_multi_leader_rounds: bool,
     ) -> Result<ChainId, Error> {
         let owners = if let Some(weights) = weights {
             if weights.len() != public_keys.len() {
@@ -442,7 +486,9 @@ where
             base_timeout: Duration::from_millis(base_timeout_ms),
             timeout_increment: Duration::from_millis(timeout_increment_ms),
         };
-        let ownership = ChainOwnership::multiple(owners, multi_leader_rounds, timeout_config);
+        let mut ownership = ChainOwnership::multiple(owners, multi_leader_rounds, timeout_config);
+        ownership.approval_threshold = approval_threshold;
+        ownership.open_multi_leader_rounds = open_multi_leader_rounds;
         let balance = balance.unwrap_or(Amount::ZERO);
         let message_id = self
             .apply_client_command(&chain_id, move |mut client| {
@@ -482,6 +528,8 @@ where
             owners: Vec::new(),
             multi_leader_rounds: 2,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
+            open_multi_leader_rounds: false,
         };
         self.execute_system_operation(operation, chain_id).await
     }
@@ -507,6 +555,15 @@ where
             default = 1_000
         )]
         timeout_increment_ms: u64,
+        #[graphql(desc = "The total weight of the owners that must approve each block; \
+                    default: only the proposer")]
+        approval_threshold: Option<u64>,
+        #[graphql(
+            desc = "Whether anyone can propose blocks in the multi-leader rounds, paying their \
+                    own fees",
+            default = false
+        )]
+        open_multi_leader_rounds: bool,
     ) -> Result<CryptoHash, Error> {
         let operation = SystemOperation::ChangeOwnership {
             super_owners: Vec::new(),
@@ -517,6 +574,8 @@ where
                 base_timeout: Duration::from_millis(base_timeout_ms),
                 timeout_increment: Duration::from_millis(timeout_increment_ms),
             },
+            approval_threshold,
+            open_multi_leader_rounds,
         };
         self.execute_system_operation(operation, chain_id).await
     }
@@ -745,6 +804,28 @@ where
         }
     }
 
+    /// Returns the messages logged by contracts in the block at the given height, optionally
+    /// only those of one application. Logs are only available for the latest blocks, if the
+    /// service was started with `--application-log-retention`.
+    async fn logs(
+        &self,
+        chain_id: ChainId,
+        application_id: Option<UserApplicationId>,
+        height: BlockHeight,
+    ) -> Result<Vec<ApplicationLog>, Error> {
+        let client = self.clients.try_client_lock(&chain_id).await?;
+        let view = client.chain_state_view().await?;
+        let logs = view
+            .application_logs
+            .get(&height)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|log| application_id.map_or(true, |id| log.application_id == id))
+            .collect();
+        Ok(logs)
+    }
+
     /// Returns the version information on this node service.
     async fn version(&self) -> linera_version::VersionInfo {
         linera_version::VersionInfo::default()
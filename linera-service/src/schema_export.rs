@@ -6,9 +6,9 @@ use linera_base::{crypto::KeyPair, data_types::Timestamp, identifiers::ChainId};
 use linera_chain::data_types::{BlockProposal, Certificate, HashedValue, LiteCertificate};
 use linera_core::{
     client::ChainClient,
-    data_types::{ChainInfoQuery, ChainInfoResponse},
+    data_types::{BlockHeightRange, ChainInfoQuery, ChainInfoResponse},
     node::{
-        CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
         ValidatorNodeProvider,
     },
 };
@@ -68,6 +68,14 @@ impl ValidatorNode for DummyValidatorNode {
     async fn get_version_info(&mut self) -> Result<VersionInfo, NodeError> {
         Err(NodeError::UnexpectedMessage)
     }
+
+    async fn download_certificates(
+        &mut self,
+        _: ChainId,
+        _: BlockHeightRange,
+    ) -> Result<CertificateStream, NodeError> {
+        Err(NodeError::UnexpectedMessage)
+    }
 }
 
 struct DummyValidatorNodeProvider;
@@ -128,6 +136,7 @@ async fn main() -> std::io::Result<()> {
     let config = ChainListenerConfig {
         delay_before_ms: 0,
         delay_after_ms: 0,
+        max_concurrent_syncs: 1,
     };
     let context = DummyContext;
     let service = NodeService::<DummyValidatorNodeProvider, _, _>::new(
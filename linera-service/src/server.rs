@@ -5,7 +5,10 @@
 use anyhow::bail;
 use async_trait::async_trait;
 use futures::future::join_all;
-use linera_base::crypto::{CryptoRng, KeyPair};
+use linera_base::{
+    crypto::{CryptoRng, KeyPair},
+    identifiers::Account,
+};
 use linera_core::worker::WorkerState;
 use linera_execution::{committee::ValidatorName, WasmRuntime, WithWasmDefault};
 use linera_rpc::{
@@ -256,6 +259,10 @@ struct ValidatorOptions {
 
     /// The public name and the port of each of the shards
     shards: Vec<ShardConfig>,
+
+    /// The account receiving the validator's share of the fees, if any.
+    #[serde(default)]
+    fee_recipient: Option<Account>,
 }
 
 fn make_server_config<R: CryptoRng>(
@@ -277,7 +284,11 @@ fn make_server_config<R: CryptoRng>(
     };
     let key = KeyPair::generate_from(rng);
     let name = ValidatorName(key.public());
-    let validator = ValidatorConfig { network, name };
+    let validator = ValidatorConfig {
+        network,
+        name,
+        fee_recipient: options.fee_recipient,
+    };
     ValidatorServerConfig {
         validator,
         key,
@@ -334,6 +345,16 @@ enum ServerCommand {
         /// The maximal number of entries in the storage cache.
         #[arg(long, default_value = "1000")]
         cache_size: usize,
+
+        /// A directory, only writable by the current user, in which to keep compiled WebAssembly
+        /// modules across restarts.
+        #[arg(long)]
+        module_cache_dir: Option<PathBuf>,
+
+        /// The maximal size in bytes of the compiled WebAssembly modules kept in
+        /// `module_cache_dir`.
+        #[arg(long, default_value = "4294967296")]
+        module_cache_max_size: u64,
     },
 
     /// Act as a trusted third-party and generate all server configurations
@@ -424,6 +445,8 @@ async fn run(options: ServerOptions) {
             max_concurrent_queries,
             max_stream_queries,
             cache_size,
+            module_cache_dir,
+            module_cache_max_size,
         } => {
             let genesis_config = GenesisConfig::read(&genesis_config_path)
                 .expect("Fail to read initial chain config");
@@ -445,6 +468,13 @@ async fn run(options: ServerOptions) {
                 grace_period,
             };
             let wasm_runtime = wasm_runtime.with_wasm_default();
+            if let Some(module_cache_dir) = module_cache_dir {
+                linera_execution::enable_persistent_module_cache(
+                    module_cache_dir,
+                    module_cache_max_size,
+                )
+                .expect("Failed to create the module cache directory");
+            }
             let common_config = CommonStoreConfig {
                 max_concurrent_queries,
                 max_stream_queries,
@@ -574,6 +604,7 @@ mod test {
                         metrics_port: Some(5002),
                     },
                 ],
+                fee_recipient: None,
             }
         );
     }
@@ -6,6 +6,7 @@ use async_graphql::http::GraphiQLSource;
 use async_trait::async_trait;
 use axum::response::{self, IntoResponse};
 use http::Uri;
+use serde::de::DeserializeOwned;
 use std::{
     num::ParseIntError,
     path::{Path, PathBuf},
@@ -310,6 +311,10 @@ pub fn parse_millis(s: &str) -> Result<Duration, ParseIntError> {
     Ok(Duration::from_millis(s.parse()?))
 }
 
+pub fn parse_json<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error> {
+    serde_json::from_str(s)
+}
+
 #[test]
 fn test_parse_version_message() {
     let s = "something\n . . . version12\nother things";
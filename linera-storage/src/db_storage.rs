@@ -4,13 +4,18 @@
 use crate::{chain_guards::ChainGuards, ChainRuntimeContext, Storage};
 use async_trait::async_trait;
 use dashmap::DashMap;
-use linera_base::{crypto::CryptoHash, data_types::Timestamp, identifiers::ChainId};
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::Timestamp,
+    identifiers::{BytecodeId, ChainId},
+};
 use linera_chain::{
     data_types::{Certificate, CertificateValue, HashedValue, LiteCertificate},
     ChainStateView,
 };
 use linera_execution::{
-    ExecutionRuntimeConfig, UserApplicationId, UserContractCode, UserServiceCode, WasmRuntime,
+    http::HttpOracle, ApplicationLogger, ExecutionRuntimeConfig, ExecutionTracer, FuelProfiler,
+    UserContractCode, UserServiceCode, WasmRuntime,
 };
 use linera_views::{
     batch::Batch,
@@ -118,8 +123,8 @@ pub static LOAD_CHAIN_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
 pub struct DbStorageInner<Client> {
     client: Client,
     pub(crate) guards: ChainGuards,
-    user_contracts: Arc<DashMap<UserApplicationId, UserContractCode>>,
-    user_services: Arc<DashMap<UserApplicationId, UserServiceCode>>,
+    user_contracts: Arc<DashMap<BytecodeId, UserContractCode>>,
+    user_services: Arc<DashMap<BytecodeId, UserServiceCode>>,
     wasm_runtime: Option<WasmRuntime>,
 }
 
@@ -183,6 +188,14 @@ pub struct DbStorage<Client, Clock> {
     pub(crate) client: Arc<DbStorageInner<Client>>,
     pub clock: Clock,
     pub execution_runtime_config: ExecutionRuntimeConfig,
+    /// Records the execution of user applications in all the chains, if set.
+    pub execution_tracer: Option<ExecutionTracer>,
+    /// Attributes the fuel consumed by user applications in all the chains, if set.
+    pub fuel_profiler: Option<FuelProfiler>,
+    /// Performs the HTTP requests of services, if they are allowed.
+    pub http_oracle: Option<HttpOracle>,
+    /// The number of blocks for which each chain keeps the messages logged by contracts.
+    pub application_log_retention: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -268,6 +281,11 @@ where
             storage: self.clone(),
             chain_id,
             execution_runtime_config: self.execution_runtime_config,
+            execution_tracer: self.execution_tracer.clone(),
+            fuel_profiler: self.fuel_profiler.clone(),
+            http_oracle: self.http_oracle.clone(),
+            application_logger: (self.application_log_retention > 0)
+                .then(|| ApplicationLogger::new(self.application_log_retention)),
             user_contracts: self.client.user_contracts.clone(),
             user_services: self.client.user_services.clone(),
             _chain_guard: Arc::new(guard),
@@ -385,6 +403,14 @@ where
     fn wasm_runtime(&self) -> Option<WasmRuntime> {
         self.client.wasm_runtime
     }
+
+    fn set_http_oracle(&mut self, http_oracle: HttpOracle) {
+        self.http_oracle = Some(http_oracle);
+    }
+
+    fn set_application_log_retention(&mut self, retained_blocks: u64) {
+        self.application_log_retention = retained_blocks;
+    }
 }
 
 impl<Client, C> DbStorage<Client, C>
@@ -427,6 +453,10 @@ where
             client: Arc::new(storage),
             clock,
             execution_runtime_config: ExecutionRuntimeConfig::default(),
+            execution_tracer: None,
+            fuel_profiler: None,
+            http_oracle: None,
+            application_log_retention: 0,
         }
     }
 }
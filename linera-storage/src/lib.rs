@@ -38,7 +38,7 @@ use futures::future;
 use linera_base::{
     crypto::{CryptoHash, PublicKey},
     data_types::{Amount, BlockHeight, Timestamp},
-    identifiers::{ChainDescription, ChainId},
+    identifiers::{BytecodeId, ChainDescription, ChainId},
     ownership::ChainOwnership,
 };
 use linera_chain::{
@@ -47,10 +47,11 @@ use linera_chain::{
 };
 use linera_execution::{
     committee::{Committee, Epoch},
+    http::HttpOracle,
     system::SystemChannel,
-    ChannelSubscription, ExecutionError, ExecutionRuntimeConfig, ExecutionRuntimeContext,
-    GenericApplicationId, UserApplicationDescription, UserApplicationId, UserContractCode,
-    UserServiceCode, WasmRuntime,
+    ApplicationLogger, ChannelSubscription, ExecutionError, ExecutionRuntimeConfig,
+    ExecutionRuntimeContext, ExecutionTracer, FuelProfiler, GenericApplicationId,
+    UserApplicationDescription, UserContractCode, UserServiceCode, WasmRuntime,
 };
 use linera_views::{
     common::Context,
@@ -61,7 +62,9 @@ use std::{fmt::Debug, sync::Arc};
 #[cfg(with_wasm_runtime)]
 use {
     linera_chain::data_types::CertificateValue,
-    linera_execution::{Operation, SystemOperation, WasmContractModule, WasmServiceModule},
+    linera_execution::{
+        CompressedBytecode, Operation, SystemOperation, WasmContractModule, WasmServiceModule,
+    },
 };
 
 /// Communicate with a persistent storage using the "views" abstraction.
@@ -217,6 +220,14 @@ pub trait Storage: Sized {
     /// Selects the WebAssembly runtime to use for applications (if any).
     fn wasm_runtime(&self) -> Option<WasmRuntime>;
 
+    /// Allows the services to make the HTTP requests accepted by the `http_oracle`.
+    fn set_http_oracle(&mut self, http_oracle: HttpOracle);
+
+    /// Keeps the messages logged by contracts in the latest `retained_blocks` blocks of each
+    /// chain. Logs are not kept if `retained_blocks` is zero, which is always the case for
+    /// validators.
+    fn set_application_log_retention(&mut self, retained_blocks: u64);
+
     /// Creates a [`UserContractCode`] instance using the bytecode in storage referenced
     /// by the `application_description`.
     #[cfg(with_wasm_runtime)]
@@ -227,10 +238,12 @@ pub trait Storage: Sized {
         let Some(wasm_runtime) = self.wasm_runtime() else {
             panic!("A Wasm runtime is required to load user applications.");
         };
-        let SystemOperation::PublishBytecode { contract, .. } =
-            read_publish_bytecode_operation(self, application_description).await?
-        else {
-            unreachable!("unexpected bytecode operation");
+        let contract = match read_publish_bytecode_operation(self, application_description).await? {
+            SystemOperation::PublishBytecode { contract, .. } => contract,
+            SystemOperation::PublishCompressedBytecode { contract, .. } => {
+                contract.decompress(CompressedBytecode::MAXIMUM_DECOMPRESSED_SIZE)?
+            }
+            _ => unreachable!("unexpected bytecode operation"),
         };
         Ok(Arc::new(
             WasmContractModule::new(contract, wasm_runtime).await?,
@@ -260,10 +273,12 @@ pub trait Storage: Sized {
         let Some(wasm_runtime) = self.wasm_runtime() else {
             panic!("A Wasm runtime is required to load user applications.");
         };
-        let SystemOperation::PublishBytecode { service, .. } =
-            read_publish_bytecode_operation(self, application_description).await?
-        else {
-            unreachable!("unexpected bytecode operation");
+        let service = match read_publish_bytecode_operation(self, application_description).await? {
+            SystemOperation::PublishBytecode { service, .. } => service,
+            SystemOperation::PublishCompressedBytecode { service, .. } => {
+                service.decompress(CompressedBytecode::MAXIMUM_DECOMPRESSED_SIZE)?
+            }
+            _ => unreachable!("unexpected bytecode operation"),
         };
         Ok(Arc::new(
             WasmServiceModule::new(service, wasm_runtime).await?,
@@ -289,11 +304,8 @@ async fn read_publish_bytecode_operation(
     storage: &impl Storage,
     application_description: &UserApplicationDescription,
 ) -> Result<SystemOperation, ExecutionError> {
-    let UserApplicationDescription {
-        bytecode_id,
-        bytecode_location,
-        ..
-    } = application_description;
+    let bytecode_id = application_description.current_bytecode_id();
+    let bytecode_location = application_description.current_bytecode_location();
     let value = storage
         .read_value(bytecode_location.certificate_hash)
         .await
@@ -306,15 +318,16 @@ async fn read_publish_bytecode_operation(
         .into_inner();
     let operations = match value {
         CertificateValue::ConfirmedBlock { executed_block, .. } => executed_block.block.operations,
-        _ => return Err(ExecutionError::InvalidBytecodeId(*bytecode_id)),
+        _ => return Err(ExecutionError::InvalidBytecodeId(bytecode_id)),
     };
     let index = usize::try_from(bytecode_location.operation_index)
         .map_err(|_| linera_base::data_types::ArithmeticError::Overflow)?;
     match operations.into_iter().nth(index) {
-        Some(Operation::System(operation @ SystemOperation::PublishBytecode { .. })) => {
-            Ok(operation)
-        }
-        _ => Err(ExecutionError::InvalidBytecodeId(*bytecode_id)),
+        Some(Operation::System(
+            operation @ (SystemOperation::PublishBytecode { .. }
+            | SystemOperation::PublishCompressedBytecode { .. }),
+        )) => Ok(operation),
+        _ => Err(ExecutionError::InvalidBytecodeId(bytecode_id)),
     }
 }
 
@@ -323,8 +336,12 @@ pub struct ChainRuntimeContext<S> {
     storage: S,
     chain_id: ChainId,
     execution_runtime_config: ExecutionRuntimeConfig,
-    user_contracts: Arc<DashMap<UserApplicationId, UserContractCode>>,
-    user_services: Arc<DashMap<UserApplicationId, UserServiceCode>>,
+    execution_tracer: Option<ExecutionTracer>,
+    fuel_profiler: Option<FuelProfiler>,
+    http_oracle: Option<HttpOracle>,
+    application_logger: Option<ApplicationLogger>,
+    user_contracts: Arc<DashMap<BytecodeId, UserContractCode>>,
+    user_services: Arc<DashMap<BytecodeId, UserServiceCode>>,
     _chain_guard: Arc<ChainGuard>,
 }
 
@@ -341,11 +358,27 @@ where
         self.execution_runtime_config
     }
 
-    fn user_contracts(&self) -> &Arc<DashMap<UserApplicationId, UserContractCode>> {
+    fn execution_tracer(&self) -> Option<&ExecutionTracer> {
+        self.execution_tracer.as_ref()
+    }
+
+    fn fuel_profiler(&self) -> Option<&FuelProfiler> {
+        self.fuel_profiler.as_ref()
+    }
+
+    fn http_oracle(&self) -> Option<&HttpOracle> {
+        self.http_oracle.as_ref()
+    }
+
+    fn application_logger(&self) -> Option<&ApplicationLogger> {
+        self.application_logger.as_ref()
+    }
+
+    fn user_contracts(&self) -> &Arc<DashMap<BytecodeId, UserContractCode>> {
         &self.user_contracts
     }
 
-    fn user_services(&self) -> &Arc<DashMap<UserApplicationId, UserServiceCode>> {
+    fn user_services(&self) -> &Arc<DashMap<BytecodeId, UserServiceCode>> {
         &self.user_services
     }
 
@@ -353,7 +386,7 @@ where
         &self,
         description: &UserApplicationDescription,
     ) -> Result<UserContractCode, ExecutionError> {
-        match self.user_contracts.entry(description.into()) {
+        match self.user_contracts.entry(description.current_bytecode_id()) {
             Entry::Occupied(entry) => Ok(entry.get().clone()),
             Entry::Vacant(entry) => {
                 let contract = self.storage.load_contract(description).await?;
@@ -367,7 +400,7 @@ where
         &self,
         description: &UserApplicationDescription,
     ) -> Result<UserServiceCode, ExecutionError> {
-        match self.user_services.entry(description.into()) {
+        match self.user_services.entry(description.current_bytecode_id()) {
             Entry::Occupied(entry) => Ok(entry.get().clone()),
             Entry::Vacant(entry) => {
                 let service = self.storage.load_service(description).await?;
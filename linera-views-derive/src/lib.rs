@@ -288,6 +288,41 @@ fn generate_hash_view_code(input: ItemStruct) -> TokenStream2 {
     }
 }
 
+fn generate_field_hashes_code(input: ItemStruct) -> TokenStream2 {
+    let struct_name = input.ident;
+    let (impl_generics, type_generics, maybe_where_clause) = input.generics.split_for_impl();
+    let template_vect = get_seq_parameter(input.generics.clone());
+
+    let (_context, context_constraints) = context_and_constraints(&input.attrs, &template_vect);
+
+    let mut where_clause = maybe_where_clause
+        .cloned()
+        .unwrap_or_else(empty_where_clause);
+    where_clause
+        .predicates
+        .extend(context_constraints.predicates);
+
+    let mut field_hashes = Vec::new();
+    for e in input.fields {
+        let name = e.clone().ident.unwrap();
+        field_hashes.push(quote! { hashes.push(self.#name.hash().await?); });
+    }
+
+    quote! {
+        impl #impl_generics #struct_name #type_generics
+        #where_clause
+        {
+            /// Returns the hashes of the fields, in order, as combined by `HashableView::hash`.
+            pub async fn field_hashes(&self) -> Result<Vec<linera_views::common::HasherOutput>, linera_views::views::ViewError> {
+                use linera_views::views::HashableView;
+                let mut hashes = Vec::new();
+                #(#field_hashes)*
+                Ok(hashes)
+            }
+        }
+    }
+}
+
 fn generate_crypto_hash_code(input: ItemStruct) -> TokenStream2 {
     let struct_name = input.ident;
     let (impl_generics, type_generics, maybe_where_clause) = input.generics.split_for_impl();
@@ -311,18 +346,14 @@ fn generate_crypto_hash_code(input: ItemStruct) -> TokenStream2 {
         {
             async fn crypto_hash(&self) -> Result<linera_base::crypto::CryptoHash, linera_views::views::ViewError> {
                 use linera_base::crypto::{BcsHashable, CryptoHash};
-                use linera_views::{
-                    batch::Batch,
-                    generic_array::GenericArray,
-                    sha3::{digest::OutputSizeUser, Sha3_256},
-                    views::HashableView,
-                };
+                use linera_views::common::HasherOutput;
+                use linera_views::views::{HashableView, VIEW_HASH_VERSION};
                 use serde::{Serialize, Deserialize};
                 #[derive(Serialize, Deserialize)]
-                struct #hash_type(GenericArray<u8, <Sha3_256 as OutputSizeUser>::OutputSize>);
+                struct #hash_type(u8, HasherOutput);
                 impl BcsHashable for #hash_type {}
                 let hash = self.hash().await?;
-                Ok(CryptoHash::new(&#hash_type(hash)))
+                Ok(CryptoHash::new(&#hash_type(VIEW_HASH_VERSION, hash)))
             }
         }
     }
@@ -363,7 +394,8 @@ pub fn derive_view(input: TokenStream) -> TokenStream {
 pub fn derive_hash_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let mut stream = generate_view_code(input.clone(), false);
-    stream.extend(generate_hash_view_code(input));
+    stream.extend(generate_hash_view_code(input.clone()));
+    stream.extend(generate_field_hashes_code(input));
     stream.into()
 }
 
@@ -380,6 +412,7 @@ pub fn derive_crypto_hash_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let mut stream = generate_view_code(input.clone(), false);
     stream.extend(generate_hash_view_code(input.clone()));
+    stream.extend(generate_field_hashes_code(input.clone()));
     stream.extend(generate_crypto_hash_code(input));
     stream.into()
 }
@@ -390,6 +423,7 @@ pub fn derive_crypto_hash_root_view(input: TokenStream) -> TokenStream {
     let mut stream = generate_view_code(input.clone(), true);
     stream.extend(generate_save_delete_view_code(input.clone()));
     stream.extend(generate_hash_view_code(input.clone()));
+    stream.extend(generate_field_hashes_code(input.clone()));
     stream.extend(generate_crypto_hash_code(input));
     stream.into()
 }
@@ -400,7 +434,8 @@ pub fn derive_hashable_root_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let mut stream = generate_view_code(input.clone(), true);
     stream.extend(generate_save_delete_view_code(input.clone()));
-    stream.extend(generate_hash_view_code(input));
+    stream.extend(generate_hash_view_code(input.clone()));
+    stream.extend(generate_field_hashes_code(input));
     stream.into()
 }
 
@@ -453,6 +488,17 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_field_hashes_code() {
+        for context in SpecificContextInfo::test_cases() {
+            let input = context.test_view_input();
+            insta::assert_display_snapshot!(
+                format!("test_generate_field_hashes_code_{}", context.name),
+                pretty(generate_field_hashes_code(input))
+            );
+        }
+    }
+
     #[test]
     fn test_generate_save_delete_view_code() {
         for context in SpecificContextInfo::test_cases() {
@@ -4,6 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, CustomSerialize, HasherOutput, KeyIterable, Update, MIN_VIEW_TAG},
+    proof::ViewProof,
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
@@ -575,11 +576,21 @@ where
         #[cfg(with_metrics)]
         let _hash_latency = COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
         let mut hasher = sha3::Sha3_256::default();
+        let entries = self.entry_hashes().await?;
+        hasher.update_with_bcs_bytes(&entries.len())?;
+        for (key, hash) in entries {
+            hasher.update_with_prefixed_bytes(&key)?;
+            hasher.write_all(hash.as_ref())?;
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Returns the keys of the collection with the hashes of the corresponding subviews.
+    async fn entry_hashes(&self) -> Result<Vec<(Vec<u8>, HasherOutput)>, ViewError> {
         let keys = self.keys().await?;
-        hasher.update_with_bcs_bytes(&keys.len())?;
+        let mut entries = Vec::with_capacity(keys.len());
         let updates = self.updates.read().await;
         for key in keys {
-            hasher.update_with_bytes(&key)?;
             let hash = match updates.get(&key) {
                 Some(entry) => {
                     let Update::Set(view) = entry else {
@@ -594,9 +605,24 @@ where
                     view.hash().await?
                 }
             };
-            hasher.write_all(hash.as_ref())?;
+            entries.push((key, hash));
         }
-        Ok(hasher.finalize())
+        Ok(entries)
+    }
+
+    /// Extends a proof about the subview at the given key into a proof about the
+    /// collection.
+    pub async fn prove_subview(
+        &self,
+        short_key: &[u8],
+        proof: ViewProof,
+    ) -> Result<ViewProof, ViewError> {
+        let entries = self.entry_hashes().await?;
+        let index = entries
+            .iter()
+            .position(|(key, _)| key == short_key)
+            .ok_or_else(|| ViewError::not_found("no subview for key", short_key))?;
+        Ok(proof.through_collection(entries, index))
     }
 }
 
@@ -971,6 +997,35 @@ where
     }
 }
 
+impl<C, I, W> CollectionView<C, I, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Serialize,
+    W: HashableView<C> + Send + Sync + 'static,
+{
+    /// Extends a proof about the subview at the given index into a proof about the
+    /// collection.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::memory::create_memory_context;
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use crate::linera_views::views::{HashableView, View};
+    /// # let context = create_memory_context();
+    ///   let mut view : CollectionView<_, u64, RegisterView<_,String>> = CollectionView::load(context).await.unwrap();
+    ///   view.load_entry_mut(&23).await.unwrap().set(String::from("Hello"));
+    ///   let proof = view.try_load_entry(&23).await.unwrap().unwrap().prove().unwrap();
+    ///   let proof = view.prove_subview(&23, proof).await.unwrap();
+    ///   proof.verify(&view.hash().await.unwrap()).unwrap();
+    /// # })
+    /// ```
+    pub async fn prove_subview(&self, index: &I, proof: ViewProof) -> Result<ViewProof, ViewError> {
+        let short_key = C::derive_short_key(index)?;
+        self.collection.prove_subview(&short_key, proof).await
+    }
+}
+
 /// A MapView that serializes the indices.
 #[derive(Debug)]
 pub struct CustomCollectionView<C, I, W> {
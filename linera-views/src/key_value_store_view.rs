@@ -8,6 +8,7 @@ use crate::{
         KeyIterable, KeyValueIterable, SuffixClosedSetIterator, Update, MIN_VIEW_TAG,
     },
     map_view::ByteMapView,
+    proof::{ProofLeaf, ViewProof},
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
@@ -961,14 +962,26 @@ where
         let mut count = 0;
         self.for_each_index_value(|index, value| -> Result<(), ViewError> {
             count += 1;
-            hasher.update_with_bytes(index)?;
-            hasher.update_with_bytes(value)?;
+            hasher.update_with_prefixed_bytes(index)?;
+            hasher.update_with_prefixed_bytes(value)?;
             Ok(())
         })
         .await?;
         hasher.update_with_bcs_bytes(&count)?;
         Ok(hasher.finalize())
     }
+
+    /// Creates a proof of the current content of the key-value store. Since the hash of
+    /// the store depends on all its entries, the proof contains all of them.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        let mut entries = Vec::new();
+        self.for_each_index_value(|index, value| -> Result<(), ViewError> {
+            entries.push((index.to_vec(), value.to_vec()));
+            Ok(())
+        })
+        .await?;
+        Ok(ViewProof::new(ProofLeaf::Map { entries }))
+    }
 }
 
 #[async_trait]
@@ -115,6 +115,9 @@ pub mod key_value_store_view;
 /// Wrapping a view to compute a hash.
 pub mod hashable_wrapper;
 
+/// Inclusion proofs for the content of hashable views.
+pub mod proof;
+
 /// A storage backend for views based on ScyllaDB
 #[cfg(with_scylladb)]
 pub mod scylla_db;
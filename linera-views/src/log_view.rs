@@ -4,6 +4,7 @@
 use crate::{
     batch::Batch,
     common::{from_bytes_opt, Context, HasherOutput, MIN_VIEW_TAG},
+    proof::{ProofLeaf, ViewProof},
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
@@ -345,9 +346,23 @@ where
         let _hash_latency = LOG_VIEW_HASH_RUNTIME.measure_latency();
         let elements = self.read(..).await?;
         let mut hasher = sha3::Sha3_256::default();
-        hasher.update_with_bcs_bytes(&elements)?;
+        hasher.update_with_bcs_bytes(&elements.len())?;
+        for element in &elements {
+            hasher.update_with_prefixed_bytes(&bcs::to_bytes(element)?)?;
+        }
         Ok(hasher.finalize())
     }
+
+    /// Creates a proof of the current content of the log.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        let elements = self
+            .read(..)
+            .await?
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, _>>()?;
+        Ok(ViewProof::new(ProofLeaf::Log { elements }))
+    }
 }
 
 #[async_trait]
@@ -43,6 +43,7 @@ use crate::{
         contains_key, get_interval, insert_key_prefix, Context, CustomSerialize, HasherOutput,
         KeyIterable, KeyValueIterable, SuffixClosedSetIterator, Update, MIN_VIEW_TAG,
     },
+    proof::{ProofLeaf, ViewProof},
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
@@ -676,8 +677,8 @@ where
         self.for_each_key_value(
             |index, value| {
                 count += 1;
-                hasher.update_with_bytes(index)?;
-                hasher.update_with_bytes(value)?;
+                hasher.update_with_prefixed_bytes(index)?;
+                hasher.update_with_prefixed_bytes(value)?;
                 Ok(())
             },
             prefix,
@@ -686,6 +687,21 @@ where
         hasher.update_with_bcs_bytes(&count)?;
         Ok(hasher.finalize())
     }
+
+    /// Creates a proof of the current content of the map. Since the hash of the map
+    /// depends on all its entries, the proof contains all of them.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        let mut entries = Vec::new();
+        self.for_each_key_value(
+            |index, value| {
+                entries.push((index.to_vec(), value.to_vec()));
+                Ok(())
+            },
+            Vec::new(),
+        )
+        .await?;
+        Ok(ViewProof::new(ProofLeaf::Map { entries }))
+    }
 }
 
 impl<C, V> ByteMapView<C, V>
@@ -1228,6 +1244,18 @@ where
     }
 }
 
+impl<C, I, V> MapView<C, I, V>
+where
+    C: Context + Sync,
+    ViewError: From<C::Error>,
+    V: Sync + Send + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a proof of the current content of the map.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        self.map.prove().await
+    }
+}
+
 #[async_trait]
 impl<C, I, V> HashableView<C> for MapView<C, I, V>
 where
@@ -1642,6 +1670,18 @@ where
     }
 }
 
+impl<C, I, V> CustomMapView<C, I, V>
+where
+    C: Context + Sync,
+    ViewError: From<C::Error>,
+    V: Sync + Send + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a proof of the current content of the map.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        self.map.prove().await
+    }
+}
+
 #[async_trait]
 impl<C, I, V> HashableView<C> for CustomMapView<C, I, V>
 where
@@ -0,0 +1,342 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [`ViewProof`] contains the data needed to recompute the hash of a view, as computed by
+//! [`HashableView::hash`](crate::views::HashableView::hash), from a leaf view (e.g. a
+//! `MapView`) up to a root view (e.g. the execution state of a chain). Verifying a proof
+//! does not require access to storage.
+//!
+//! Since the hashes of leaf views are computed over all their entries, a proof about a leaf
+//! view contains the whole content of that view. This also allows proving that a key is
+//! absent. Proofs about nested views only contain the hashes of the sibling subviews.
+//!
+//! The hashes of views that proofs rely on include the length of every key and value, so
+//! that a prover cannot split the same bytes differently to make claims about other keys.
+//! This changed the hashes of views: see [`VIEW_HASH_VERSION`].
+//!
+//! A verifier must also check where the leaf view is located inside the root view, since a
+//! valid proof about one subview says nothing about another one. This is why
+//! [`ViewProof::verify_crypto_hash`] requires the expected [`ProofPosition`]s.
+
+use crate::{
+    common::HasherOutput,
+    views::{Hasher, ViewError, VIEW_HASH_VERSION},
+};
+use linera_base::crypto::CryptoHash;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use thiserror::Error;
+
+/// The content of a leaf view, sufficient to recompute its hash.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ProofLeaf {
+    /// A `RegisterView`, with its serialized value.
+    Register {
+        /// The BCS serialization of the value.
+        value: Vec<u8>,
+    },
+    /// A `LogView`, with its serialized elements.
+    Log {
+        /// The BCS serialization of each element, in order.
+        elements: Vec<Vec<u8>>,
+    },
+    /// A map-like view (`MapView`, `KeyValueStoreView`), with all its entries.
+    Map {
+        /// The serialized keys and values, in the order of the keys.
+        entries: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+    /// A `SetView`, with all its keys.
+    Set {
+        /// The serialized keys, in order.
+        keys: Vec<Vec<u8>>,
+    },
+}
+
+/// A step from the hash of a view to the hash of the view containing it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ProofStep {
+    /// The parent is a struct of views, hashed from the hashes of its fields.
+    Fields {
+        /// The hashes of all the fields, in order.
+        hashes: Vec<HasherOutput>,
+        /// The position of the child view among the fields.
+        index: usize,
+    },
+    /// The parent is a `CollectionView` or a `ReentrantCollectionView`.
+    Collection {
+        /// The serialized keys and the hashes of all the subviews, in the order of the keys.
+        entries: Vec<(Vec<u8>, HasherOutput)>,
+        /// The position of the child view among the entries.
+        index: usize,
+    },
+}
+
+/// The position of a view inside its parent, as expected by the verifier of a proof.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProofPosition {
+    /// The field number `index` of a struct of `len` views.
+    Field {
+        /// The number of fields of the struct.
+        len: usize,
+        /// The position of the view among the fields.
+        index: usize,
+    },
+    /// The subview stored under the given key in a `CollectionView` or a
+    /// `ReentrantCollectionView`.
+    CollectionEntry {
+        /// The serialized key of the subview.
+        key: Vec<u8>,
+    },
+}
+
+/// A proof that a leaf view has a given content inside a root view with a given hash.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ViewProof {
+    /// The content of the leaf view.
+    pub leaf: ProofLeaf,
+    /// The steps from the leaf view up to the root view.
+    pub path: Vec<ProofStep>,
+}
+
+/// An error when verifying a [`ViewProof`].
+#[derive(Debug, Error)]
+pub enum ProofError {
+    /// The proof refers to a position that does not exist.
+    #[error("The proof refers to an index {index} out of {len} entries")]
+    InvalidIndex {
+        /// The invalid position.
+        index: usize,
+        /// The number of available entries.
+        len: usize,
+    },
+
+    /// The hash of the proof does not match the expected one.
+    #[error("The proof does not match the expected hash")]
+    HashMismatch,
+
+    /// The proof is about another view than the expected one.
+    #[error("The proof is not about the expected view")]
+    UnexpectedPath,
+
+    /// The hash computation failed.
+    #[error(transparent)]
+    ViewError(#[from] ViewError),
+}
+
+impl ProofLeaf {
+    /// Computes the hash of the leaf view, as in `HashableView::hash`.
+    pub fn hash(&self) -> Result<HasherOutput, ViewError> {
+        let mut hasher = sha3::Sha3_256::default();
+        match self {
+            ProofLeaf::Register { value } => hasher.update_with_bytes(value)?,
+            ProofLeaf::Log { elements } => {
+                hasher.update_with_bcs_bytes(&elements.len())?;
+                for element in elements {
+                    hasher.update_with_prefixed_bytes(element)?;
+                }
+            }
+            ProofLeaf::Map { entries } => {
+                let mut count = 0;
+                for (key, value) in entries {
+                    count += 1;
+                    hasher.update_with_prefixed_bytes(key)?;
+                    hasher.update_with_prefixed_bytes(value)?;
+                }
+                hasher.update_with_bcs_bytes(&count)?;
+            }
+            ProofLeaf::Set { keys } => {
+                let mut count = 0;
+                for key in keys {
+                    count += 1;
+                    hasher.update_with_prefixed_bytes(key)?;
+                }
+                hasher.update_with_bcs_bytes(&count)?;
+            }
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Returns the serialized value of a register.
+    pub fn register_value(&self) -> Option<&[u8]> {
+        match self {
+            ProofLeaf::Register { value } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the serialized value of the given serialized key of a map. Since the proof
+    /// contains all the entries of the map, `None` means that the key is absent.
+    pub fn map_value(&self, key: &[u8]) -> Option<&[u8]> {
+        match self {
+            ProofLeaf::Map { entries } => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the given serialized key is in a set.
+    pub fn set_contains(&self, key: &[u8]) -> bool {
+        match self {
+            ProofLeaf::Set { keys } => keys.iter().any(|entry| entry == key),
+            _ => false,
+        }
+    }
+
+    /// Returns the serialized element at the given position of a log.
+    pub fn log_element(&self, index: usize) -> Option<&[u8]> {
+        match self {
+            ProofLeaf::Log { elements } => elements.get(index).map(Vec::as_slice),
+            _ => None,
+        }
+    }
+}
+
+impl ProofStep {
+    /// Computes the hash of the parent view from the hash of the child view.
+    pub fn hash(&self, child: &HasherOutput) -> Result<HasherOutput, ProofError> {
+        let mut hasher = sha3::Sha3_256::default();
+        match self {
+            ProofStep::Fields { hashes, index } => {
+                check_index(*index, hashes.len())?;
+                for (i, hash) in hashes.iter().enumerate() {
+                    let hash = if i == *index { child } else { hash };
+                    hasher.write_all(hash.as_ref()).map_err(ViewError::from)?;
+                }
+            }
+            ProofStep::Collection { entries, index } => {
+                check_index(*index, entries.len())?;
+                hasher.update_with_bcs_bytes(&entries.len())?;
+                for (i, (key, hash)) in entries.iter().enumerate() {
+                    let hash = if i == *index { child } else { hash };
+                    hasher.update_with_prefixed_bytes(key)?;
+                    hasher.write_all(hash.as_ref()).map_err(ViewError::from)?;
+                }
+            }
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Returns whether the child view is at the given position in the parent view.
+    pub fn is_at(&self, position: &ProofPosition) -> bool {
+        match (self, position) {
+            (
+                ProofStep::Fields { hashes, index },
+                ProofPosition::Field {
+                    len,
+                    index: expected,
+                },
+            ) => hashes.len() == *len && index == expected,
+            (ProofStep::Collection { .. }, ProofPosition::CollectionEntry { key }) => {
+                self.collection_key() == Some(key.as_slice())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the serialized key of the child view in a collection.
+    pub fn collection_key(&self) -> Option<&[u8]> {
+        match self {
+            ProofStep::Collection { entries, index } => {
+                entries.get(*index).map(|(key, _)| key.as_slice())
+            }
+            ProofStep::Fields { .. } => None,
+        }
+    }
+}
+
+impl ViewProof {
+    /// Creates a proof about a leaf view.
+    pub fn new(leaf: ProofLeaf) -> Self {
+        ViewProof {
+            leaf,
+            path: Vec::new(),
+        }
+    }
+
+    /// Extends the proof to the struct of views containing the current root view as its
+    /// field number `index`.
+    pub fn through_fields(mut self, hashes: Vec<HasherOutput>, index: usize) -> Self {
+        self.path.push(ProofStep::Fields { hashes, index });
+        self
+    }
+
+    /// Extends the proof to the collection containing the current root view at position
+    /// `index`.
+    pub fn through_collection(
+        mut self,
+        entries: Vec<(Vec<u8>, HasherOutput)>,
+        index: usize,
+    ) -> Self {
+        self.path.push(ProofStep::Collection { entries, index });
+        self
+    }
+
+    /// Computes the hash of the root view.
+    pub fn root_hash(&self) -> Result<HasherOutput, ProofError> {
+        let mut hash = self.leaf.hash()?;
+        for step in &self.path {
+            hash = step.hash(&hash)?;
+        }
+        Ok(hash)
+    }
+
+    /// Verifies the proof against the expected hash of the root view.
+    pub fn verify(&self, expected: &HasherOutput) -> Result<(), ProofError> {
+        if self.root_hash()? == *expected {
+            Ok(())
+        } else {
+            Err(ProofError::HashMismatch)
+        }
+    }
+
+    /// Checks that the steps of the proof go through the given positions, from the leaf
+    /// view up to the root view.
+    pub fn check_path(&self, positions: &[ProofPosition]) -> Result<(), ProofError> {
+        if self.path.len() == positions.len()
+            && self
+                .path
+                .iter()
+                .zip(positions)
+                .all(|(step, position)| step.is_at(position))
+        {
+            Ok(())
+        } else {
+            Err(ProofError::UnexpectedPath)
+        }
+    }
+
+    /// Verifies the proof against the expected `CryptoHash` of a root view deriving
+    /// `CryptoHashView`, such as the `execution_state_hash` of a chain. `view_name` is the
+    /// name of the root view type, e.g. `ExecutionStateView`, and `positions` the expected
+    /// location of the leaf view inside it, as in [`ViewProof::check_path`].
+    pub fn verify_crypto_hash(
+        &self,
+        view_name: &str,
+        positions: &[ProofPosition],
+        expected: &CryptoHash,
+    ) -> Result<(), ProofError> {
+        self.check_path(positions)?;
+        let root_hash = self.root_hash()?;
+        // This mirrors the `BcsHashable` implementation used by the derive macro.
+        let mut hasher = sha3::Sha3_256::default();
+        write!(hasher, "{}Hash::", view_name).map_err(ViewError::from)?;
+        hasher.update_with_bcs_bytes(&VIEW_HASH_VERSION)?;
+        hasher.update_with_bytes(&root_hash)?;
+        let hash = hasher.finalize();
+        if hash.as_slice() == expected.as_bytes().as_slice() {
+            Ok(())
+        } else {
+            Err(ProofError::HashMismatch)
+        }
+    }
+}
+
+fn check_index(index: usize, len: usize) -> Result<(), ProofError> {
+    if index < len {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidIndex { index, len })
+    }
+}
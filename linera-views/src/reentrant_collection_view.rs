@@ -4,6 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, CustomSerialize, HasherOutput, KeyIterable, Update, MIN_VIEW_TAG},
+    proof::ViewProof,
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::{Mutex, RwLock, RwLockReadGuardArc, RwLockWriteGuardArc};
@@ -778,11 +779,21 @@ where
         #[cfg(with_metrics)]
         let _hash_latency = REENTRANT_COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
         let mut hasher = sha3::Sha3_256::default();
+        let entries = self.entry_hashes().await?;
+        hasher.update_with_bcs_bytes(&entries.len())?;
+        for (key, hash) in entries {
+            hasher.update_with_prefixed_bytes(&key)?;
+            hasher.write_all(hash.as_ref())?;
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Returns the keys of the collection with the hashes of the corresponding subviews.
+    async fn entry_hashes(&self) -> Result<Vec<(Vec<u8>, HasherOutput)>, ViewError> {
         let keys = self.keys().await?;
-        hasher.update_with_bcs_bytes(&keys.len())?;
+        let mut entries = Vec::with_capacity(keys.len());
         let updates = self.updates.lock().await;
         for key in keys {
-            hasher.update_with_bytes(&key)?;
             let hash = match updates.get(&key) {
                 Some(entry) => {
                     let Update::Set(view) = entry else {
@@ -790,7 +801,7 @@ where
                     };
                     let view = view
                         .try_read_arc()
-                        .ok_or_else(|| ViewError::TryLockError(key))?;
+                        .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
                     view.hash().await?
                 }
                 None => {
@@ -800,9 +811,24 @@ where
                     view.hash().await?
                 }
             };
-            hasher.write_all(hash.as_ref())?;
+            entries.push((key, hash));
         }
-        Ok(hasher.finalize())
+        Ok(entries)
+    }
+
+    /// Extends a proof about the subview at the given key into a proof about the
+    /// collection.
+    pub async fn prove_subview(
+        &self,
+        short_key: &[u8],
+        proof: ViewProof,
+    ) -> Result<ViewProof, ViewError> {
+        let entries = self.entry_hashes().await?;
+        let index = entries
+            .iter()
+            .position(|(key, _)| key == short_key)
+            .ok_or_else(|| ViewError::not_found("no subview for key", short_key))?;
+        Ok(proof.through_collection(entries, index))
     }
 }
 
@@ -1267,6 +1293,21 @@ where
     }
 }
 
+impl<C, I, W> ReentrantCollectionView<C, I, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Serialize,
+    W: HashableView<C> + Send + Sync + 'static,
+{
+    /// Extends a proof about the subview at the given index into a proof about the
+    /// collection.
+    pub async fn prove_subview(&self, index: &I, proof: ViewProof) -> Result<ViewProof, ViewError> {
+        let short_key = C::derive_short_key(index)?;
+        self.collection.prove_subview(&short_key, proof).await
+    }
+}
+
 /// A view that supports accessing a collection of views of the same kind, indexed by an ordered key,
 /// possibly several subviews at a time.
 #[derive(Debug)]
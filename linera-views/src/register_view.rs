@@ -4,6 +4,7 @@
 use crate::{
     batch::Batch,
     common::{from_bytes_opt, Context, HasherOutput, MIN_VIEW_TAG},
+    proof::{ProofLeaf, ViewProof},
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
@@ -218,6 +219,24 @@ where
         hasher.update_with_bcs_bytes(self.get())?;
         Ok(hasher.finalize())
     }
+
+    /// Creates a proof of the current value of the register.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::memory::create_memory_context;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use crate::linera_views::views::{HashableView, View};
+    /// # let context = create_memory_context();
+    ///   let mut register : RegisterView<_,u32> = RegisterView::load(context).await.unwrap();
+    ///   register.set(5);
+    ///   let proof = register.prove().unwrap();
+    ///   assert!(proof.verify(&register.hash().await.unwrap()).is_ok());
+    /// # })
+    /// ```
+    pub fn prove(&self) -> Result<ViewProof, ViewError> {
+        let value = bcs::to_bytes(self.get())?;
+        Ok(ViewProof::new(ProofLeaf::Register { value }))
+    }
 }
 
 #[async_trait]
@@ -4,6 +4,7 @@
 use crate::{
     batch::Batch,
     common::{Context, CustomSerialize, HasherOutput, KeyIterable, Update, MIN_VIEW_TAG},
+    proof::{ProofLeaf, ViewProof},
     views::{ClonableView, HashableView, Hasher, View, ViewError},
 };
 use async_lock::Mutex;
@@ -343,13 +344,25 @@ where
         let mut count = 0;
         self.for_each_key(|key| {
             count += 1;
-            hasher.update_with_bytes(key)?;
+            hasher.update_with_prefixed_bytes(key)?;
             Ok(())
         })
         .await?;
         hasher.update_with_bcs_bytes(&count)?;
         Ok(hasher.finalize())
     }
+
+    /// Creates a proof of the current content of the set. Since the hash of the set
+    /// depends on all its keys, the proof contains all of them.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        let mut keys = Vec::new();
+        self.for_each_key(|key| {
+            keys.push(key.to_vec());
+            Ok(())
+        })
+        .await?;
+        Ok(ViewProof::new(ProofLeaf::Set { keys }))
+    }
 }
 
 #[async_trait]
@@ -617,6 +630,17 @@ where
     }
 }
 
+impl<C, I> SetView<C, I>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    /// Creates a proof of the current content of the set.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        self.set.prove().await
+    }
+}
+
 #[async_trait]
 impl<C, I> HashableView<C> for SetView<C, I>
 where
@@ -871,6 +895,17 @@ where
     }
 }
 
+impl<C, I> CustomSetView<C, I>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    /// Creates a proof of the current content of the set.
+    pub async fn prove(&self) -> Result<ViewProof, ViewError> {
+        self.set.prove().await
+    }
+}
+
 #[async_trait]
 impl<C, I> HashableView<C> for CustomSetView<C, I>
 where
@@ -128,6 +128,15 @@ pub trait HashableView<C>: View<C> {
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError>;
 }
 
+/// The version of the hashing of views, included in the `CryptoHash` of the views deriving
+/// `CryptoHashView`. It changes whenever the same content is hashed differently, so that
+/// hashes computed by different versions are never mistaken for each other.
+///
+/// * Version 1: the keys and values of maps and sets, the keys of collections and the
+///   elements of logs are prefixed with their lengths, so that the content of views can be
+///   proven (see [`crate::proof`]). Before that, no version was included.
+pub const VIEW_HASH_VERSION: u8 = 1;
+
 /// The requirement for the hasher type in [`HashableView`].
 pub trait Hasher: Default + Write + Send + Sync + 'static {
     /// The output type.
@@ -147,6 +156,12 @@ pub trait Hasher: Default + Write + Send + Sync + 'static {
         self.write_all(value)?;
         Ok(())
     }
+
+    /// Includes bytes in the hash, preceded by their length, so that consecutive byte
+    /// strings cannot be split differently to obtain the same hash.
+    fn update_with_prefixed_bytes(&mut self, value: &[u8]) -> Result<(), ViewError> {
+        self.update_with_bcs_bytes(&value)
+    }
 }
 
 impl Hasher for sha3::Sha3_256 {
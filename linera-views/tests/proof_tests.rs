@@ -0,0 +1,161 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use linera_views::{
+    key_value_store_view::KeyValueStoreView,
+    log_view::LogView,
+    map_view::MapView,
+    memory::create_memory_context,
+    proof::{ProofError, ProofLeaf, ProofPosition, ProofStep},
+    reentrant_collection_view::ReentrantCollectionView,
+    register_view::RegisterView,
+    set_view::SetView,
+    views::{CryptoHashView, HashableView, View},
+};
+use linera_views_derive::CryptoHashRootView;
+
+#[derive(CryptoHashRootView)]
+struct ProofTestView<C> {
+    pub register: RegisterView<C, String>,
+    pub log: LogView<C, u32>,
+    pub map: MapView<C, String, u64>,
+    pub set: SetView<C, u16>,
+    pub stores: ReentrantCollectionView<C, u8, KeyValueStoreView<C>>,
+}
+
+#[tokio::test]
+async fn test_leaf_proofs() {
+    let context = create_memory_context();
+    let mut view = ProofTestView::load(context).await.unwrap();
+    view.register.set("Hello".to_string());
+    view.log.push(4);
+    view.log.push(2);
+    view.map.insert("one", 1).unwrap();
+    view.map.insert("two", 2).unwrap();
+    view.set.insert(&7).unwrap();
+
+    let proof = view.register.prove().unwrap();
+    proof.verify(&view.register.hash().await.unwrap()).unwrap();
+    let proof = view.log.prove().await.unwrap();
+    proof.verify(&view.log.hash().await.unwrap()).unwrap();
+    assert_eq!(proof.leaf.log_element(1), Some(&[2, 0, 0, 0][..]));
+    let proof = view.map.prove().await.unwrap();
+    proof.verify(&view.map.hash().await.unwrap()).unwrap();
+    let key = bcs::to_bytes("two").unwrap();
+    assert_eq!(
+        proof.leaf.map_value(&key),
+        Some(&[2, 0, 0, 0, 0, 0, 0, 0][..])
+    );
+    // All the entries are included, so the proof also shows that a key is absent.
+    let key = bcs::to_bytes("three").unwrap();
+    assert_eq!(proof.leaf.map_value(&key), None);
+    let proof = view.set.prove().await.unwrap();
+    proof.verify(&view.set.hash().await.unwrap()).unwrap();
+}
+
+#[tokio::test]
+async fn test_entries_cannot_be_split_differently() {
+    let context = create_memory_context();
+    let mut store = KeyValueStoreView::load(context).await.unwrap();
+    store.insert(b"ab".to_vec(), b"c".to_vec()).await.unwrap();
+    let hash = store.hash().await.unwrap();
+    let proof = store.prove().await.unwrap();
+    proof.verify(&hash).unwrap();
+
+    let mut forged = proof.clone();
+    forged.leaf = ProofLeaf::Map {
+        entries: vec![(b"a".to_vec(), b"bc".to_vec())],
+    };
+    assert!(matches!(
+        forged.verify(&hash),
+        Err(ProofError::HashMismatch)
+    ));
+
+    let mut forged = proof;
+    forged.leaf = ProofLeaf::Set {
+        keys: vec![b"a".to_vec(), b"bc".to_vec()],
+    };
+    assert!(matches!(
+        forged.verify(&hash),
+        Err(ProofError::HashMismatch)
+    ));
+}
+
+#[tokio::test]
+async fn test_nested_proof() {
+    let context = create_memory_context();
+    let mut view = ProofTestView::load(context).await.unwrap();
+    view.register.set("Hello".to_string());
+    for i in 0..3u8 {
+        let mut store = view.stores.try_load_entry_mut(&i).await.unwrap();
+        store.insert(vec![i], vec![i, i]).await.unwrap();
+    }
+
+    let proof = {
+        let store = view.stores.try_load_entry(&1).await.unwrap().unwrap();
+        store.prove().await.unwrap()
+    };
+    let proof = view.stores.prove_subview(&1, proof).await.unwrap();
+    assert_eq!(proof.path[0].collection_key(), Some(&[1][..]));
+    let field_hashes = view.field_hashes().await.unwrap();
+    assert_eq!(field_hashes[4], view.stores.hash().await.unwrap());
+    let proof = proof.through_fields(field_hashes, 4);
+    let hash = view.hash().await.unwrap();
+    proof.verify(&hash).unwrap();
+    let crypto_hash = view.crypto_hash().await.unwrap();
+    let positions = |key: u8| {
+        vec![
+            ProofPosition::CollectionEntry { key: vec![key] },
+            ProofPosition::Field { len: 5, index: 4 },
+        ]
+    };
+    proof
+        .verify_crypto_hash("ProofTestView", &positions(1), &crypto_hash)
+        .unwrap();
+    assert_eq!(proof.leaf.map_value(&[1]), Some(&[1, 1][..]));
+
+    // Tampering with the value is detected.
+    let mut forged = proof.clone();
+    forged.leaf = ProofLeaf::Map {
+        entries: vec![(vec![1], vec![9, 9])],
+    };
+    assert!(matches!(
+        forged.verify_crypto_hash("ProofTestView", &positions(1), &crypto_hash),
+        Err(ProofError::HashMismatch)
+    ));
+
+    // So is pretending that the subview is stored under another key.
+    let mut forged = proof.clone();
+    if let ProofStep::Collection { index, .. } = &mut forged.path[0] {
+        *index = 2;
+    }
+    assert!(forged
+        .verify_crypto_hash("ProofTestView", &positions(1), &crypto_hash)
+        .is_err());
+
+    // A valid proof about a subview is not a proof about another one.
+    assert!(matches!(
+        proof.verify_crypto_hash("ProofTestView", &positions(2), &crypto_hash),
+        Err(ProofError::UnexpectedPath)
+    ));
+    let mut positions_in_other_field = positions(1);
+    positions_in_other_field[1] = ProofPosition::Field { len: 5, index: 3 };
+    assert!(matches!(
+        proof.verify_crypto_hash("ProofTestView", &positions_in_other_field, &crypto_hash),
+        Err(ProofError::UnexpectedPath)
+    ));
+
+    // Invalid positions are rejected.
+    let mut forged = proof;
+    if let ProofStep::Fields { index, .. } = &mut forged.path[1] {
+        *index = 5;
+    }
+    assert!(matches!(
+        forged.verify(&hash),
+        Err(ProofError::InvalidIndex { index: 5, len: 5 })
+    ));
+    assert!(matches!(
+        forged.verify_crypto_hash("ProofTestView", &positions(1), &crypto_hash),
+        Err(ProofError::UnexpectedPath)
+    ));
+}
@@ -14,7 +14,9 @@ mod wit_load;
 mod wit_store;
 mod wit_type;
 
-use self::util::{apply_specialization_attribute, extract_namespace, Specializations};
+use self::util::{
+    apply_specialization_attribute, extract_namespace, has_flags_attribute, Specializations,
+};
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use proc_macro_error::{abort, proc_macro_error};
@@ -25,16 +27,27 @@ use syn::{parse_macro_input, Data, DeriveInput, Ident, ItemTrait};
 
 /// Derives `WitType` for a Rust type.
 ///
-/// All fields in the type must also implement `WitType`.
+/// All fields in the type must also implement `WitType`. A `struct` with only `bool` fields can
+/// be marked with `#[witty(flags)]` to be represented as a WIT `flags` type.
 #[proc_macro_error]
 #[proc_macro_derive(WitType, attributes(witty, witty_specialize_with))]
 pub fn derive_wit_type(input: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(input as DeriveInput);
 
     let specializations = apply_specialization_attribute(&mut input);
+    let is_flags = has_flags_attribute(&input.attrs);
 
     let body = match &input.data {
+        Data::Struct(struct_item) if is_flags => {
+            wit_type::derive_for_flags(&input.ident, &struct_item.fields)
+        }
         Data::Struct(struct_item) => wit_type::derive_for_struct(&struct_item.fields),
+        Data::Enum(_enum_item) if is_flags => {
+            abort!(
+                input.ident,
+                "Only `struct`s can be represented as WIT `flags`"
+            )
+        }
         Data::Enum(enum_item) => wit_type::derive_for_enum(&input.ident, enum_item.variants.iter()),
         Data::Union(_union_item) => {
             abort!(input.ident, "Can't derive `WitType` for `union`s")
@@ -51,16 +64,27 @@ pub fn derive_wit_type(input: TokenStream) -> TokenStream {
 
 /// Derives `WitLoad` for the Rust type.
 ///
-/// All fields in the type must also implement `WitLoad`.
+/// All fields in the type must also implement `WitLoad`. A `struct` with only `bool` fields can
+/// be marked with `#[witty(flags)]` to be represented as a WIT `flags` type.
 #[proc_macro_error]
 #[proc_macro_derive(WitLoad, attributes(witty, witty_specialize_with))]
 pub fn derive_wit_load(input: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(input as DeriveInput);
 
     let specializations = apply_specialization_attribute(&mut input);
+    let is_flags = has_flags_attribute(&input.attrs);
 
     let body = match &input.data {
+        Data::Struct(struct_item) if is_flags => {
+            wit_load::derive_for_flags(&input.ident, &struct_item.fields)
+        }
         Data::Struct(struct_item) => wit_load::derive_for_struct(&struct_item.fields),
+        Data::Enum(_enum_item) if is_flags => {
+            abort!(
+                input.ident,
+                "Only `struct`s can be represented as WIT `flags`"
+            )
+        }
         Data::Enum(enum_item) => wit_load::derive_for_enum(&input.ident, enum_item.variants.iter()),
         Data::Union(_union_item) => {
             abort!(input.ident, "Can't derive `WitLoad` for `union`s")
@@ -77,16 +101,27 @@ pub fn derive_wit_load(input: TokenStream) -> TokenStream {
 
 /// Derives `WitStore` for the Rust type.
 ///
-/// All fields in the type must also implement `WitStore`.
+/// All fields in the type must also implement `WitStore`. A `struct` with only `bool` fields can
+/// be marked with `#[witty(flags)]` to be represented as a WIT `flags` type.
 #[proc_macro_error]
 #[proc_macro_derive(WitStore, attributes(witty, witty_specialize_with))]
 pub fn derive_wit_store(input: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(input as DeriveInput);
 
     let specializations = apply_specialization_attribute(&mut input);
+    let is_flags = has_flags_attribute(&input.attrs);
 
     let body = match &input.data {
+        Data::Struct(struct_item) if is_flags => {
+            wit_store::derive_for_flags(&input.ident, &struct_item.fields)
+        }
         Data::Struct(struct_item) => wit_store::derive_for_struct(&struct_item.fields),
+        Data::Enum(_enum_item) if is_flags => {
+            abort!(
+                input.ident,
+                "Only `struct`s can be represented as WIT `flags`"
+            )
+        }
         Data::Enum(enum_item) => {
             wit_store::derive_for_enum(&input.ident, enum_item.variants.iter())
         }
@@ -5,7 +5,7 @@
 
 #![cfg(test)]
 
-use super::{derive_for_enum, derive_for_struct};
+use super::{derive_for_enum, derive_for_flags, derive_for_struct};
 use quote::quote;
 use syn::{parse_quote, Fields, ItemEnum, ItemStruct};
 
@@ -554,3 +554,59 @@ fn enum_type_with_skipped_fields() {
 
     assert_eq!(output.to_string(), expected.to_string());
 }
+
+/// Check the generated code for the body of the implementation of `WitLoad` for a struct
+/// represented as WIT `flags`.
+#[test]
+fn flags_struct() {
+    let input: ItemStruct = parse_quote! {
+        struct Type {
+            first: bool,
+            second: bool,
+            #[witty(skip)]
+            ignored: bool,
+            third: bool,
+        }
+    };
+    let output = derive_for_flags(&input.ident, &input.fields);
+
+    let expected = quote! {
+        fn load<Instance>(
+            memory: &linera_witty::Memory<'_, Instance>,
+            location: linera_witty::GuestPointer,
+        ) -> Result<Self, linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            let flags = <u8 as linera_witty::WitLoad>::load(memory, location)?;
+
+            let (first, second, third,) = (flags & 1 != 0, flags & 2 != 0, flags & 4 != 0,);
+
+            let ignored = Default::default();
+
+            Ok(Self { first, second, ignored, third })
+        }
+
+        fn lift_from<Instance>(
+            flat_layout: <Self::Layout as linera_witty::Layout>::Flat,
+            memory: &linera_witty::Memory<'_, Instance>,
+        ) -> Result<Self, linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            let flags = <u8 as linera_witty::WitLoad>::lift_from(flat_layout, memory)?;
+
+            let (first, second, third,) = (flags & 1 != 0, flags & 2 != 0, flags & 4 != 0,);
+
+            let ignored = Default::default();
+
+            Ok(Self { first, second, ignored, third })
+        }
+    };
+
+    assert_eq!(output.to_string(), expected.to_string());
+}
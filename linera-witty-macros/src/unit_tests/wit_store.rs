@@ -5,7 +5,7 @@
 
 #![cfg(test)]
 
-use super::{derive_for_enum, derive_for_struct};
+use super::{derive_for_enum, derive_for_flags, derive_for_struct};
 use quote::quote;
 use syn::{parse_quote, Fields, ItemEnum, ItemStruct};
 
@@ -491,3 +491,48 @@ fn enum_type_with_skipped_fields() {
 
     assert_eq!(output.to_string(), expected.to_string());
 }
+
+/// Check the generated code for the body of the implementation of `WitStore` for a struct
+/// represented as WIT `flags`.
+#[test]
+fn flags_struct() {
+    let input: ItemStruct = parse_quote! {
+        struct Type(bool, bool);
+    };
+    let output = derive_for_flags(&input.ident, &input.fields);
+
+    let expected = quote! {
+        fn store<Instance>(
+            &self,
+            memory: &mut linera_witty::Memory<'_, Instance>,
+            location: linera_witty::GuestPointer,
+        ) -> Result<(), linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            let Self(field0, field1) = self;
+            let flags: u8 = (if *field0 { 1 } else { 0 }) | (if *field1 { 2 } else { 0 });
+
+            linera_witty::WitStore::store(&flags, memory, location)
+        }
+
+        fn lower<Instance>(
+            &self,
+            memory: &mut linera_witty::Memory<'_, Instance>,
+        ) -> Result<<Self::Layout as linera_witty::Layout>::Flat, linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            let Self(field0, field1) = self;
+            let flags: u8 = (if *field0 { 1 } else { 0 }) | (if *field1 { 2 } else { 0 });
+
+            linera_witty::WitStore::lower(&flags, memory)
+        }
+    };
+
+    assert_eq!(output.to_string(), expected.to_string());
+}
@@ -5,7 +5,7 @@
 
 #![cfg(test)]
 
-use super::{derive_for_enum, derive_for_struct};
+use super::{derive_for_enum, derive_for_flags, derive_for_struct};
 use quote::quote;
 use syn::{parse_quote, Fields, ItemEnum, ItemStruct};
 
@@ -264,3 +264,25 @@ fn enum_type_with_skipped_fields() {
 
     assert_eq!(output.to_string(), expected.to_string());
 }
+
+/// Check the generated code for the body of the implementation of `WitType` for a struct
+/// represented as WIT `flags`.
+#[test]
+fn flags_struct() {
+    let input: ItemStruct = parse_quote! {
+        struct Type {
+            first: bool,
+            second: bool,
+            third: bool,
+        }
+    };
+    let output = derive_for_flags(&input.ident, &input.fields);
+
+    let expected = quote! {
+        const SIZE: u32 = <u8 as linera_witty::WitType>::SIZE;
+
+        type Layout = <u8 as linera_witty::WitType>::Layout;
+    };
+
+    assert_eq!(output.to_string(), expected.to_string());
+}
@@ -0,0 +1,66 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of the `#[witty(...)]` attributes placed on types and functions.
+
+use heck::ToKebabCase;
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use syn::{Attribute, Expr, ExprLit, Ident, Lit, Meta, MetaList, MetaNameValue};
+
+/// Checks if the `attributes` contain a `#[witty(flags)]` attribute, which requests a `struct`
+/// with `bool` fields to be represented as a WIT `flags` type.
+pub fn has_flags_attribute(attributes: &[Attribute]) -> bool {
+    witty_attribute_arguments(attributes).any(|tokens| tokens.to_string() == "flags")
+}
+
+/// Returns the name of a function in the WIT interface.
+///
+/// The name is either the one specified in a `#[witty(name = "...")]` attribute, or the
+/// `function_name` converted to kebab case. Specifying a name is necessary for the functions
+/// related to resources, like `[resource-drop]handle` or `[method]handle.get`.
+pub fn function_wit_name(attributes: &[Attribute], function_name: &Ident) -> String {
+    let mut wit_name = None;
+
+    for tokens in witty_attribute_arguments(attributes) {
+        let Ok(MetaNameValue { path, value, .. }) = syn::parse2::<MetaNameValue>(tokens.clone())
+        else {
+            abort!(tokens, r#"Expected a `name = "..."` parameter"#);
+        };
+
+        if !path.is_ident("name") {
+            abort!(path, "Unknown `witty` attribute parameter");
+        }
+
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(name),
+            ..
+        }) = value
+        else {
+            abort!(value, "Expected a string literal");
+        };
+
+        if wit_name.replace(name.value()).is_some() {
+            abort!(name, "Function has more than one WIT name");
+        }
+    }
+
+    wit_name.unwrap_or_else(|| function_name.to_string().to_kebab_case())
+}
+
+/// Removes the `#[witty(...)]` attributes from a list of `attributes`, so that the item they
+/// were placed on can be emitted again.
+#[cfg(with_wit_export)]
+pub fn remove_witty_attributes(attributes: &mut Vec<Attribute>) {
+    attributes.retain(|attribute| !attribute.path().is_ident("witty"));
+}
+
+/// Returns an iterator over the arguments of the `#[witty(...)]` attributes in `attributes`.
+fn witty_attribute_arguments(attributes: &[Attribute]) -> impl Iterator<Item = &TokenStream> + '_ {
+    attributes
+        .iter()
+        .filter_map(|attribute| match &attribute.meta {
+            Meta::List(MetaList { path, tokens, .. }) if path.is_ident("witty") => Some(tokens),
+            _ => None,
+        })
+}
@@ -3,10 +3,11 @@
 
 //! Helper types to process [`Fields`] from `struct`s and `enum` variants.
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::abort;
 use quote::{format_ident, quote};
 use std::{borrow::Cow, ops::Deref};
-use syn::{Field, Fields, Ident, Meta, MetaList};
+use syn::{Field, Fields, Ident, LitInt, Meta, MetaList};
 
 /// A helper type with information about a list of [`Fields`].
 pub struct FieldsInformation<'input> {
@@ -77,6 +78,26 @@ impl<'input> FieldsInformation<'input> {
         }
     }
 
+    /// Returns the integer type used to store the non-skipped fields as the bits of a WIT `flags`
+    /// type.
+    pub fn flags_type(&self, name: &Ident) -> TokenStream {
+        match self.non_skipped_fields().count() {
+            0 => abort!(name, "`flags` types must have at least one field"),
+            1..=8 => quote! { u8 },
+            9..=16 => quote! { u16 },
+            17..=32 => quote! { u32 },
+            _ => abort!(name, "`flags` types can't have more than 32 fields"),
+        }
+    }
+
+    /// Returns the masks of the bits that represent each of the non-skipped fields in a WIT
+    /// `flags` type.
+    pub fn flag_masks(&self) -> impl Iterator<Item = LitInt> + '_ {
+        self.non_skipped_fields()
+            .enumerate()
+            .map(|(index, _)| LitInt::new(&(1_u32 << index).to_string(), Span::call_site()))
+    }
+
     /// Returns the code with the body pattern to destructure the container of the fields.
     ///
     /// Does not include bindings for skipped fields.
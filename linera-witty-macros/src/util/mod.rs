@@ -3,12 +3,17 @@
 
 //! Helper types and functions shared between different macro implementations.
 
+mod attributes;
 mod fields;
 mod specialization;
 
 #[cfg(with_wit_export)]
-pub use self::specialization::Specialization;
-pub use self::{fields::FieldsInformation, specialization::Specializations};
+pub use self::{attributes::remove_witty_attributes, specialization::Specialization};
+pub use self::{
+    attributes::{function_wit_name, has_flags_attribute},
+    fields::{FieldInformation, FieldsInformation},
+    specialization::Specializations,
+};
 use heck::ToKebabCase;
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error::abort;
@@ -3,7 +3,7 @@
 
 //! Extraction of information and generation of code related to a single exported host function.
 
-use heck::ToKebabCase;
+use crate::util::function_wit_name;
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error::abort;
 use quote::{quote, quote_spanned, ToTokens};
@@ -48,7 +48,7 @@ impl<'input> FunctionInformation<'input> {
     /// Parses a function definition and collects pieces of information into a
     /// [`FunctionInformation`] instance.
     pub fn new(function: &'input ImplItemFn, caller_type: Option<&'input Ident>) -> Self {
-        let wit_name = function.sig.ident.to_string().to_kebab_case();
+        let wit_name = function_wit_name(&function.attrs, &function.sig.ident);
         let is_reentrant = Self::is_reentrant(&function.sig)
             || Self::uses_caller_parameter(&function.sig, caller_type);
         let (parameter_bindings, parameter_types) =
@@ -10,12 +10,13 @@ mod caller_type_parameter;
 mod function_information;
 
 use self::{caller_type_parameter::CallerTypeParameter, function_information::FunctionInformation};
+use crate::util::remove_witty_attributes;
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::{quote, ToTokens};
 use syn::{
-    parse_quote, punctuated::Punctuated, token::Paren, Generics, Ident, ItemImpl, LitStr, Type,
-    TypePath, TypeTuple,
+    parse_quote, punctuated::Punctuated, token::Paren, Generics, Ident, ImplItem, ItemImpl, LitStr,
+    Type, TypePath, TypeTuple,
 };
 
 /// Returns the code generated for exporting host functions to guest Wasm instances.
@@ -66,7 +67,7 @@ impl<'input> WitExportGenerator<'input> {
 
     /// Consumes the collected pieces to generate the final code.
     pub fn generate(mut self) -> TokenStream {
-        let implementation = self.implementation;
+        let implementation = self.implementation_without_witty_attributes();
         let wasmer = self.generate_for_wasmer();
         let wasmtime = self.generate_for_wasmtime();
         let mock_instance = self.generate_for_mock_instance();
@@ -79,6 +80,20 @@ impl<'input> WitExportGenerator<'input> {
         }
     }
 
+    /// Returns the `impl` block with the `#[witty(...)]` attributes removed from its functions,
+    /// since they are only used by this macro.
+    fn implementation_without_witty_attributes(&self) -> ItemImpl {
+        let mut implementation = self.implementation.clone();
+
+        for item in &mut implementation.items {
+            if let ImplItem::Fn(function) = item {
+                remove_witty_attributes(&mut function.attrs);
+            }
+        }
+
+        implementation
+    }
+
     /// Generates the code to export functions using the Wasmer runtime.
     fn generate_for_wasmer(&mut self) -> Option<TokenStream> {
         #[cfg(with_wasmer)]
@@ -3,8 +3,7 @@
 
 //! Generation of code to import functions from a Wasm guest module.
 
-use crate::util::TokensSetItem;
-use heck::ToKebabCase;
+use crate::util::{function_wit_name, TokensSetItem};
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error::abort;
 use quote::{format_ident, quote, quote_spanned, ToTokens};
@@ -129,7 +128,7 @@ impl<'input> WitImportGenerator<'input> {
             let namespace = self.namespace;
 
             let function_name = function.name();
-            let function_wit_name = function_name.to_string().to_kebab_case();
+            let function_wit_name = function_wit_name(&function.function.attrs, function_name);
 
             let instance = &function.instance_constraint;
             let parameters = &function.parameter_definitions;
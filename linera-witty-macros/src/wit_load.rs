@@ -6,7 +6,7 @@
 #[path = "unit_tests/wit_load.rs"]
 mod tests;
 
-use crate::util::FieldsInformation;
+use crate::util::{FieldInformation, FieldsInformation};
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::quote;
@@ -58,6 +58,62 @@ pub fn derive_for_struct<'input>(fields: impl Into<FieldsInformation<'input>>) -
     }
 }
 
+/// Returns the body of the `WitLoad` implementation for the Rust `struct` with the specified
+/// `fields`, represented as a WIT `flags` type.
+pub fn derive_for_flags<'input>(
+    name: &Ident,
+    fields: impl Into<FieldsInformation<'input>>,
+) -> TokenStream {
+    let fields = fields.into();
+    let flags_type = fields.flags_type(name);
+    let field_names = fields.non_skipped_fields().map(FieldInformation::name);
+    let field_values = fields
+        .flag_masks()
+        .map(|mask| quote! { flags & #mask != 0 });
+    let construction = fields.construction();
+    let fallback_bindings = fields.bindings_for_skipped_fields();
+
+    let bindings = quote! {
+        let ( #( #field_names, )* ) = ( #( #field_values, )* );
+
+        #fallback_bindings
+    };
+
+    quote! {
+        fn load<Instance>(
+            memory: &linera_witty::Memory<'_, Instance>,
+            location: linera_witty::GuestPointer,
+        ) -> Result<Self, linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            let flags = <#flags_type as linera_witty::WitLoad>::load(memory, location)?;
+
+            #bindings
+
+            Ok(Self #construction)
+        }
+
+        fn lift_from<Instance>(
+            flat_layout: <Self::Layout as linera_witty::Layout>::Flat,
+            memory: &linera_witty::Memory<'_, Instance>,
+        ) -> Result<Self, linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            let flags = <#flags_type as linera_witty::WitLoad>::lift_from(flat_layout, memory)?;
+
+            #bindings
+
+            Ok(Self #construction)
+        }
+    }
+}
+
 /// Returns the body of the `WitLoad` implementation for the Rust `enum` with the specified
 /// `variants`.
 pub fn derive_for_enum<'variants>(
@@ -3,7 +3,7 @@
 
 //! Derivation of the `WitStore` trait.
 
-use crate::util::FieldsInformation;
+use crate::util::{FieldInformation, FieldsInformation};
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::quote;
@@ -51,6 +51,58 @@ pub fn derive_for_struct<'input>(fields: impl Into<FieldsInformation<'input>>) -
     }
 }
 
+/// Returns the body of the `WitStore` implementation for the Rust `struct` with the specified
+/// `fields`, represented as a WIT `flags` type.
+pub fn derive_for_flags<'input>(
+    name: &Ident,
+    fields: impl Into<FieldsInformation<'input>>,
+) -> TokenStream {
+    let fields = fields.into();
+    let flags_type = fields.flags_type(name);
+    let pattern = fields.destructuring();
+    let flag_bits = fields
+        .non_skipped_fields()
+        .map(FieldInformation::name)
+        .zip(fields.flag_masks())
+        .map(|(field_name, mask)| quote! { (if *#field_name { #mask } else { 0 }) });
+
+    let flags = quote! {
+        let Self #pattern = self;
+        let flags: #flags_type = #( #flag_bits )|*;
+    };
+
+    quote! {
+        fn store<Instance>(
+            &self,
+            memory: &mut linera_witty::Memory<'_, Instance>,
+            location: linera_witty::GuestPointer,
+        ) -> Result<(), linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            #flags
+
+            linera_witty::WitStore::store(&flags, memory, location)
+        }
+
+        fn lower<Instance>(
+            &self,
+            memory: &mut linera_witty::Memory<'_, Instance>,
+        ) -> Result<<Self::Layout as linera_witty::Layout>::Flat, linera_witty::RuntimeError>
+        where
+            Instance: linera_witty::InstanceWithMemory,
+            <Instance::Runtime as linera_witty::Runtime>::Memory:
+                linera_witty::RuntimeMemory<Instance>,
+        {
+            #flags
+
+            linera_witty::WitStore::lower(&flags, memory)
+        }
+    }
+}
+
 /// Returns the body of the `WitStore` implementation for the Rust `enum` with the specified
 /// `variants`.
 pub fn derive_for_enum<'variants>(
@@ -24,6 +24,21 @@ pub fn derive_for_struct<'input>(fields: impl Into<FieldsInformation<'input>>) -
     }
 }
 
+/// Returns the body of the `WitType` implementation for the Rust `struct` with the specified
+/// `fields`, represented as a WIT `flags` type.
+pub fn derive_for_flags<'input>(
+    name: &Ident,
+    fields: impl Into<FieldsInformation<'input>>,
+) -> TokenStream {
+    let flags_type = fields.into().flags_type(name);
+
+    quote! {
+        const SIZE: u32 = <#flags_type as linera_witty::WitType>::SIZE;
+
+        type Layout = <#flags_type as linera_witty::WitType>::Layout;
+    }
+}
+
 /// Returns the body of the `WitType` implementation for the Rust `enum` with the specified
 /// `variants`.
 pub fn derive_for_enum<'variants>(
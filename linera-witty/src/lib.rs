@@ -18,6 +18,7 @@ mod exported_function_interface;
 mod imported_function_interface;
 mod memory_layout;
 mod primitive_types;
+mod resources;
 mod runtime;
 mod type_traits;
 mod util;
@@ -32,6 +33,7 @@ pub use self::{
     exported_function_interface::{ExportFunction, ExportTo, ExportedFunctionInterface},
     imported_function_interface::ImportedFunctionInterface,
     memory_layout::{JoinFlatLayouts, Layout},
+    resources::{Borrow, Own, ResourceTable},
     runtime::{
         GuestPointer, Instance, InstanceWithFunction, InstanceWithMemory, Memory, Runtime,
         RuntimeError, RuntimeMemory,
@@ -0,0 +1,264 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Handles to WIT resources shared between the host and a guest Wasm instance.
+//!
+//! A resource is represented in the Wasm module by an integer handle, which is an index into a
+//! table kept by the side that implements the resource. An [`Own`] handle transfers the ownership
+//! of the resource, while a [`Borrow`] handle only allows using the resource during a function
+//! call.
+//!
+//! Resources implemented by the host are stored in a [`ResourceTable`], which is usually kept in
+//! the instance's custom user data so that exported host functions can reach it.
+
+use crate::RuntimeError;
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+#[cfg(test)]
+#[path = "unit_tests/resources.rs"]
+mod tests;
+
+/// A handle that owns a resource of type `T`.
+pub struct Own<T> {
+    handle: u32,
+    _resource: PhantomData<fn() -> T>,
+}
+
+impl<T> Own<T> {
+    /// Creates an [`Own`] handle from its raw `handle` representation.
+    pub fn from_handle(handle: u32) -> Self {
+        Own {
+            handle,
+            _resource: PhantomData,
+        }
+    }
+
+    /// Returns the raw representation of this handle.
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+
+    /// Returns a [`Borrow`] handle to the same resource.
+    pub fn borrow(&self) -> Borrow<T> {
+        Borrow::from_handle(self.handle)
+    }
+}
+
+/// A handle that borrows a resource of type `T` for the duration of a function call.
+pub struct Borrow<T> {
+    handle: u32,
+    _resource: PhantomData<fn() -> T>,
+}
+
+impl<T> Borrow<T> {
+    /// Creates a [`Borrow`] handle from its raw `handle` representation.
+    pub fn from_handle(handle: u32) -> Self {
+        Borrow {
+            handle,
+            _resource: PhantomData,
+        }
+    }
+
+    /// Returns the raw representation of this handle.
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+}
+
+impl<T> From<&Own<T>> for Borrow<T> {
+    fn from(own: &Own<T>) -> Self {
+        own.borrow()
+    }
+}
+
+// The trait implementations are written manually so that they don't require `T` to implement
+// the traits.
+macro_rules! impl_handle_traits {
+    ($handle_type:ident) => {
+        impl<T> Debug for $handle_type<T> {
+            fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter
+                    .debug_tuple(stringify!($handle_type))
+                    .field(&self.handle)
+                    .finish()
+            }
+        }
+
+        impl<T> PartialEq for $handle_type<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.handle == other.handle
+            }
+        }
+
+        impl<T> Eq for $handle_type<T> {}
+
+        impl<T> Hash for $handle_type<T> {
+            fn hash<H>(&self, state: &mut H)
+            where
+                H: Hasher,
+            {
+                self.handle.hash(state)
+            }
+        }
+    };
+}
+
+impl_handle_traits!(Own);
+impl_handle_traits!(Borrow);
+
+impl<T> Clone for Borrow<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Borrow<T> {}
+
+/// The number of bits of a handle used for the index of its slot in a [`ResourceTable`]. The
+/// remaining bits store the generation of the slot.
+const INDEX_BITS: u32 = 20;
+
+/// The mask to extract the index of a slot from a handle.
+const INDEX_MASK: u32 = (1 << INDEX_BITS) - 1;
+
+/// The last generation of a slot, after which it is no longer reused.
+const MAX_GENERATION: u32 = u32::MAX >> INDEX_BITS;
+
+/// A table with the resources of type `T` that were handed out to a guest Wasm instance.
+///
+/// Handles start at one, so that a zeroed handle is never valid. Each handle also contains the
+/// generation of its slot, which changes every time the slot is reused, so that a handle to a
+/// removed resource never refers to a resource inserted later. Slots are retired once their
+/// generation is exhausted.
+pub struct ResourceTable<T> {
+    slots: Vec<Slot<T>>,
+    free_slots: Vec<u32>,
+    len: usize,
+}
+
+/// An entry of a [`ResourceTable`].
+struct Slot<T> {
+    generation: u32,
+    resource: Option<T>,
+}
+
+impl<T> Default for ResourceTable<T> {
+    fn default() -> Self {
+        ResourceTable {
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> ResourceTable<T> {
+    /// Creates an empty [`ResourceTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of resources in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no resources in the table.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Stores a `resource` in the table, returning the handle that owns it.
+    pub fn insert(&mut self, resource: T) -> Own<T> {
+        let index = match self.free_slots.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.slots.len())
+                    .ok()
+                    .filter(|index| *index < INDEX_MASK)
+                    .expect("Too many resources in the table");
+                self.slots.push(Slot {
+                    generation: 0,
+                    resource: None,
+                });
+                index
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.resource = Some(resource);
+        self.len += 1;
+
+        Own::from_handle((slot.generation << INDEX_BITS) | (index + 1))
+    }
+
+    /// Returns a reference to the resource referred to by the `handle`.
+    pub fn get(&self, handle: Borrow<T>) -> Result<&T, RuntimeError> {
+        self.slot(handle.handle)
+            .and_then(|slot| slot.resource.as_ref())
+            .ok_or(RuntimeError::InvalidResourceHandle(handle.handle))
+    }
+
+    /// Returns a mutable reference to the resource referred to by the `handle`.
+    pub fn get_mut(&mut self, handle: Borrow<T>) -> Result<&mut T, RuntimeError> {
+        self.slot_mut(handle.handle)
+            .and_then(|slot| slot.resource.as_mut())
+            .ok_or(RuntimeError::InvalidResourceHandle(handle.handle))
+    }
+
+    /// Removes the resource owned by the `handle` from the table, returning it.
+    pub fn remove(&mut self, handle: Own<T>) -> Result<T, RuntimeError> {
+        let slot = self
+            .slot_mut(handle.handle)
+            .ok_or(RuntimeError::InvalidResourceHandle(handle.handle))?;
+        let resource = slot
+            .resource
+            .take()
+            .ok_or(RuntimeError::InvalidResourceHandle(handle.handle))?;
+
+        if slot.generation < MAX_GENERATION {
+            slot.generation += 1;
+            self.free_slots.push((handle.handle & INDEX_MASK) - 1);
+        }
+        self.len -= 1;
+
+        Ok(resource)
+    }
+
+    /// Returns the slot referred to by the raw `handle`, if the handle is for its current
+    /// generation.
+    fn slot(&self, handle: u32) -> Option<&Slot<T>> {
+        let (index, generation) = Self::decode(handle)?;
+        self.slots
+            .get(index)
+            .filter(|slot| slot.generation == generation)
+    }
+
+    /// Returns the slot referred to by the raw `handle` for mutation, if the handle is for its
+    /// current generation.
+    fn slot_mut(&mut self, handle: u32) -> Option<&mut Slot<T>> {
+        let (index, generation) = Self::decode(handle)?;
+        self.slots
+            .get_mut(index)
+            .filter(|slot| slot.generation == generation)
+    }
+
+    /// Returns the index in the table and the generation of the slot referred to by the raw
+    /// `handle`.
+    fn decode(handle: u32) -> Option<(usize, u32)> {
+        let index = (handle & INDEX_MASK).checked_sub(1)?;
+        Some((index as usize, handle >> INDEX_BITS))
+    }
+}
+
+impl<T> Debug for ResourceTable<T> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ResourceTable")
+            .field("len", &self.len())
+            .finish()
+    }
+}
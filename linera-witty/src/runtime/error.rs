@@ -49,6 +49,10 @@ pub enum RuntimeError {
     #[error("Unexpected variant discriminant")]
     InvalidVariant,
 
+    /// Attempt to use a resource handle that doesn't refer to a resource in the table.
+    #[error("Invalid resource handle {_0}")]
+    InvalidResourceHandle(u32),
+
     /// Wasmer runtime error.
     #[cfg(with_wasmer)]
     #[error(transparent)]
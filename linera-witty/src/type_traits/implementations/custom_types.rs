@@ -4,8 +4,8 @@
 //! Implementations of the custom traits for types declared in this crate.
 
 use crate::{
-    GuestPointer, InstanceWithMemory, Layout, Memory, Runtime, RuntimeError, RuntimeMemory,
-    WitLoad, WitStore, WitType,
+    Borrow, GuestPointer, InstanceWithMemory, Layout, Memory, Own, Runtime, RuntimeError,
+    RuntimeMemory, WitLoad, WitStore, WitType,
 };
 use frunk::{hlist, hlist_pat, HList};
 
@@ -63,3 +63,65 @@ impl WitStore for GuestPointer {
         Ok(hlist![self.0 as i32])
     }
 }
+
+macro_rules! impl_wit_traits_for_handle {
+    ($handle_type:ident) => {
+        impl<T> WitType for $handle_type<T> {
+            const SIZE: u32 = u32::SIZE;
+
+            type Layout = HList![i32];
+        }
+
+        impl<T> WitLoad for $handle_type<T> {
+            fn load<Instance>(
+                memory: &Memory<'_, Instance>,
+                location: GuestPointer,
+            ) -> Result<Self, RuntimeError>
+            where
+                Instance: InstanceWithMemory,
+                <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
+            {
+                Ok($handle_type::from_handle(u32::load(memory, location)?))
+            }
+
+            fn lift_from<Instance>(
+                hlist_pat![handle]: <Self::Layout as Layout>::Flat,
+                _memory: &Memory<'_, Instance>,
+            ) -> Result<Self, RuntimeError>
+            where
+                Instance: InstanceWithMemory,
+                <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
+            {
+                Ok($handle_type::from_handle(handle as u32))
+            }
+        }
+
+        impl<T> WitStore for $handle_type<T> {
+            fn store<Instance>(
+                &self,
+                memory: &mut Memory<'_, Instance>,
+                location: GuestPointer,
+            ) -> Result<(), RuntimeError>
+            where
+                Instance: InstanceWithMemory,
+                <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
+            {
+                self.handle().store(memory, location)
+            }
+
+            fn lower<Instance>(
+                &self,
+                _memory: &mut Memory<'_, Instance>,
+            ) -> Result<Self::Layout, RuntimeError>
+            where
+                Instance: InstanceWithMemory,
+                <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
+            {
+                Ok(hlist![self.handle() as i32])
+            }
+        }
+    };
+}
+
+impl_wit_traits_for_handle!(Own);
+impl_wit_traits_for_handle!(Borrow);
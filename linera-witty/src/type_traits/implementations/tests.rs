@@ -3,7 +3,7 @@
 
 //! Unit tests for implementations of the custom traits for existing types.
 
-use crate::{InstanceWithMemory, Layout, MockInstance, WitLoad, WitStore};
+use crate::{Borrow, InstanceWithMemory, Layout, MockInstance, Own, WitLoad, WitStore};
 use frunk::hlist;
 use std::fmt::Debug;
 
@@ -143,6 +143,19 @@ fn large_err() {
     );
 }
 
+/// Test roundtrip of handles to resources.
+#[test]
+fn resource_handles() {
+    test_memory_roundtrip(
+        Own::<String>::from_handle(0x0102_0304),
+        &[0x04, 0x03, 0x02, 0x01],
+    );
+    test_flattening_roundtrip(
+        Borrow::<String>::from_handle(0x8000_0001),
+        hlist![0x8000_0001_u32 as i32],
+    );
+}
+
 /// Test storing an instance of `T` to memory, checking that the `memory_data` bytes are correctly
 /// written, and check that the instance can be loaded from those bytes.
 fn test_memory_roundtrip<T>(input: T, memory_data: &[u8])
@@ -0,0 +1,99 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Unit tests for the table of resources handed out to guest Wasm instances.
+
+use super::{Borrow, Own, ResourceTable, MAX_GENERATION};
+use crate::RuntimeError;
+use std::collections::HashSet;
+
+/// Test inserting, accessing and removing resources from a [`ResourceTable`].
+#[test]
+fn resource_table_lifecycle() {
+    let mut table = ResourceTable::new();
+
+    let first = table.insert("first".to_owned());
+    let second = table.insert("second".to_owned());
+
+    assert_eq!(first.handle(), 1);
+    assert_eq!(second.handle(), 2);
+    assert_eq!(table.len(), 2);
+
+    assert_eq!(table.get(first.borrow()).unwrap(), "first");
+    table.get_mut(second.borrow()).unwrap().push_str("-changed");
+    assert_eq!(table.get(Borrow::from(&second)).unwrap(), "second-changed");
+
+    let first_handle = first.handle();
+    assert_eq!(table.remove(first).unwrap(), "first");
+    assert_eq!(table.len(), 1);
+    assert!(matches!(
+        table.get(Borrow::from_handle(first_handle)),
+        Err(RuntimeError::InvalidResourceHandle(handle)) if handle == first_handle
+    ));
+
+    let third = table.insert("third".to_owned());
+
+    assert_ne!(third.handle(), first_handle);
+    assert_eq!(table.get(third.borrow()).unwrap(), "third");
+    assert_eq!(table.get(second.borrow()).unwrap(), "second-changed");
+}
+
+/// Test that a handle to a removed resource doesn't refer to a resource later stored in the
+/// same slot of a [`ResourceTable`].
+#[test]
+fn stale_handles_are_rejected_after_slots_are_reused() {
+    let mut table = ResourceTable::new();
+
+    let first = table.insert(1_u32);
+    let first_handle = first.handle();
+    assert_eq!(table.remove(first).unwrap(), 1);
+
+    let second = table.insert(2);
+    let second_handle = second.handle();
+    assert_ne!(second_handle, first_handle);
+    assert_eq!(table.len(), 1);
+
+    assert!(matches!(
+        table.get(Borrow::from_handle(first_handle)),
+        Err(RuntimeError::InvalidResourceHandle(handle)) if handle == first_handle
+    ));
+    assert!(table.get_mut(Borrow::from_handle(first_handle)).is_err());
+    assert!(table.remove(Own::from_handle(first_handle)).is_err());
+
+    assert_eq!(table.get(Borrow::from_handle(second_handle)).unwrap(), &2);
+    assert_eq!(table.remove(second).unwrap(), 2);
+    assert!(table.is_empty());
+}
+
+/// Test that a slot whose generations are exhausted is retired instead of being reused, so that
+/// handles never repeat.
+#[test]
+fn exhausted_slots_are_retired() {
+    let mut table = ResourceTable::new();
+    let mut handles = HashSet::new();
+
+    for value in 0..=MAX_GENERATION {
+        let resource = table.insert(value);
+        assert!(handles.insert(resource.handle()));
+        assert_eq!(table.remove(resource).unwrap(), value);
+    }
+
+    let resource = table.insert(0);
+    assert!(handles.insert(resource.handle()));
+    assert_eq!(resource.handle(), 2);
+}
+
+/// Test that handles which were never handed out by a [`ResourceTable`] are rejected.
+#[test]
+fn invalid_resource_handles() {
+    let mut table = ResourceTable::<u32>::new();
+    let owned = table.insert(10);
+
+    assert!(table.get(Borrow::from_handle(0)).is_err());
+    assert!(table.get_mut(Borrow::from_handle(2)).is_err());
+    assert!(table.remove(Own::from_handle(u32::MAX)).is_err());
+
+    assert_eq!(table.remove(owned).unwrap(), 10);
+    assert!(table.remove(Own::from_handle(1)).is_err());
+    assert!(table.is_empty());
+}
@@ -0,0 +1,82 @@
+;; Copyright (c) Zefchain Labs, Inc.
+;; SPDX-License-Identifier: Apache-2.0
+
+;; Helper Wasm module that creates, uses and drops a resource, and checks the `flags` and the
+;; typed errors returned by the host.
+;;
+;; It's written by hand because the `wit-bindgen` version used to build the other test modules
+;; doesn't support resources. It implements the following world:
+;;
+;; ```wit
+;; interface counters {
+;;     flags counter-status {
+;;         is-zero,
+;;         is-even,
+;;     }
+;;
+;;     variant counter-error {
+;;         overflow(u32),
+;;     }
+;;
+;;     resource counter {
+;;         constructor(start: u32)
+;;         status: func() -> counter-status
+;;         increment: func(amount: u32) -> result<u32, counter-error>
+;;     }
+;; }
+;;
+;; world import-resources {
+;;     import counters
+;;     export entrypoint
+;; }
+;; ```
+(module
+  (import "witty-macros:test-modules/counters" "[constructor]counter"
+    (func $new_counter (param i32) (result i32)))
+  (import "witty-macros:test-modules/counters" "[method]counter.status"
+    (func $status (param i32) (result i32)))
+  ;; The `result` doesn't fit in a single flat value, so it's written to the address in the
+  ;; last parameter.
+  (import "witty-macros:test-modules/counters" "[method]counter.increment"
+    (func $increment (param i32 i32 i32)))
+  (import "witty-macros:test-modules/counters" "[resource-drop]counter"
+    (func $drop_counter (param i32)))
+
+  (memory (export "memory") 1)
+
+  ;; The address where the results of `increment` are written.
+  (global $result_address i32 (i32.const 16))
+
+  ;; Traps if the `condition` is false.
+  (func $assert (param $condition i32)
+    (if (i32.eqz (local.get $condition))
+      (then unreachable)))
+
+  (func (export "witty-macros:test-modules/entrypoint#entrypoint")
+    (local $counter i32)
+
+    ;; Start the counter at `u32::MAX - 1`.
+    (local.set $counter (call $new_counter (i32.const -2)))
+
+    ;; Only the `is-even` flag is set.
+    (call $assert
+      (i32.eq (call $status (local.get $counter)) (i32.const 2)))
+
+    ;; Incrementing the counter to `u32::MAX` returns `ok(u32::MAX)`.
+    (call $increment (local.get $counter) (i32.const 1) (global.get $result_address))
+    (call $assert
+      (i32.eq (i32.load8_u (global.get $result_address)) (i32.const 0)))
+    (call $assert
+      (i32.eq (i32.load offset=4 (global.get $result_address)) (i32.const -1)))
+
+    ;; Incrementing it again returns `err(overflow(u32::MAX))`.
+    (call $increment (local.get $counter) (i32.const 1) (global.get $result_address))
+    (call $assert
+      (i32.eq (i32.load8_u (global.get $result_address)) (i32.const 1)))
+    (call $assert
+      (i32.eq (i32.load8_u offset=4 (global.get $result_address)) (i32.const 0)))
+    (call $assert
+      (i32.eq (i32.load offset=8 (global.get $result_address)) (i32.const -1)))
+
+    (call $drop_counter (local.get $counter)))
+)
@@ -9,14 +9,15 @@ use linera_witty::wasmer;
 #[cfg(with_wasmtime)]
 use linera_witty::wasmtime;
 use linera_witty::{
-    ExportTo, InstanceWithMemory, Layout, MockExportedFunction, MockInstance, RuntimeError,
-    WitLoad, WitStore,
+    ExportTo, InstanceWithMemory, Layout, MockExportedFunction, MockInstance, Own, RuntimeError,
+    WitLoad, WitStore, WitType,
 };
 use std::{
     any::Any,
     fmt::Debug,
     marker::PhantomData,
     ops::Add,
+    path::Path,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc,
@@ -45,6 +46,21 @@ pub trait TestInstanceFactory {
         ExportedFunctions: ExportTo<Self::Builder>;
 }
 
+/// Returns the path of the test module with the provided `module` name from the named `group`.
+///
+/// Modules that can't be built with the `wit-bindgen` version used by the test modules crate are
+/// handwritten in the WebAssembly text format.
+#[cfg(any(with_wasmer, with_wasmtime))]
+fn test_module_path(group: &str, module: &str) -> String {
+    let handwritten_module = format!("test-modules/wat/{group}-{module}.wat");
+
+    if Path::new(&handwritten_module).exists() {
+        handwritten_module
+    } else {
+        format!("../target/wasm32-unknown-unknown/debug/{group}-{module}.wasm")
+    }
+}
+
 /// A factory of [`wasmtime::Entrypoint`] instances.
 #[cfg(with_wasmtime)]
 #[derive(Default)]
@@ -64,11 +80,8 @@ where
         ExportedFunctions: ExportTo<Self::Builder>,
     {
         let engine = ::wasmtime::Engine::default();
-        let module = ::wasmtime::Module::from_file(
-            &engine,
-            format!("../target/wasm32-unknown-unknown/debug/{group}-{module}.wasm"),
-        )
-        .expect("Failed to load module");
+        let module = ::wasmtime::Module::from_file(&engine, test_module_path(group, module))
+            .expect("Failed to load module");
 
         let mut linker = wasmtime::Linker::new(&engine);
 
@@ -103,11 +116,8 @@ where
         ExportedFunctions: ExportTo<Self::Builder>,
     {
         let engine = ::wasmer::EngineBuilder::new(::wasmer::Singlepass::default()).engine();
-        let module = ::wasmer::Module::from_file(
-            &engine,
-            format!("../target/wasm32-unknown-unknown/debug/{group}-{module}.wasm"),
-        )
-        .expect("Failed to load module");
+        let module = ::wasmer::Module::from_file(&engine, test_module_path(group, module))
+            .expect("Failed to load module");
 
         let mut builder = wasmer::InstanceBuilder::new(engine, UserData::default());
 
@@ -150,6 +160,7 @@ where
             ("import", "getters") => self.import_getters(&mut instance),
             ("import", "setters") => self.import_setters(&mut instance),
             ("import", "operations") => self.import_operations(&mut instance),
+            ("import", "resources") => self.import_resources(&mut instance),
             ("reentrancy", "simple-function") => self.reentrancy_simple_function(&mut instance),
             ("reentrancy", "getters") => self.reentrancy_getters(&mut instance),
             ("reentrancy", "setters") => self.reentrancy_setters(&mut instance),
@@ -594,6 +605,91 @@ where
         );
     }
 
+    /// Mock calling the imported functions in the "import-resources" module.
+    fn import_resources(&mut self, instance: &mut MockInstance<UserData>) {
+        /// The guest's representation of the `counter-status` flags.
+        #[derive(Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
+        #[witty(flags)]
+        struct CounterStatus {
+            is_zero: bool,
+            is_even: bool,
+        }
+
+        /// The guest's representation of the `counter-error` variant.
+        #[derive(Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
+        enum CounterError {
+            Overflow { value: u32 },
+        }
+
+        fn call_counter_function<Parameters, Results, UserData>(
+            caller: &MockInstance<UserData>,
+            name: &str,
+            parameters: Parameters,
+        ) -> Results
+        where
+            Parameters: WitStore + 'static,
+            Results: WitLoad + 'static,
+        {
+            caller
+                .call_imported_function(
+                    &format!("witty-macros:test-modules/counters#{name}"),
+                    parameters,
+                )
+                .unwrap_or_else(|error| panic!("Failed to call counter function {name:?}: {error}"))
+        }
+
+        self.mock_exported_function(
+            instance,
+            "witty-macros:test-modules/entrypoint#entrypoint",
+            |mut caller, _: HList![]| {
+                let counter: Own<u32> =
+                    call_counter_function(&caller, "[constructor]counter", hlist![u32::MAX - 1]);
+
+                let status: CounterStatus = call_counter_function(
+                    &caller,
+                    "[method]counter.status",
+                    hlist![counter.borrow()],
+                );
+                assert_eq!(
+                    status,
+                    CounterStatus {
+                        is_zero: false,
+                        is_even: true,
+                    }
+                );
+
+                let result_address = caller
+                    .memory()?
+                    .allocate(<Result<u32, CounterError> as WitType>::SIZE)?;
+
+                call_counter_function::<_, (), _>(
+                    &caller,
+                    "[method]counter.increment",
+                    hlist![counter.borrow(), 1_u32, result_address],
+                );
+                let result = Result::<u32, CounterError>::load(&caller.memory()?, result_address)?;
+                assert_eq!(result, Ok(u32::MAX));
+
+                call_counter_function::<_, (), _>(
+                    &caller,
+                    "[method]counter.increment",
+                    hlist![counter.borrow(), 1_u32, result_address],
+                );
+                let result = Result::<u32, CounterError>::load(&caller.memory()?, result_address)?;
+                assert_eq!(result, Err(CounterError::Overflow { value: u32::MAX }));
+
+                call_counter_function::<_, (), _>(
+                    &caller,
+                    "[resource-drop]counter",
+                    hlist![counter],
+                );
+
+                Ok(hlist![])
+            },
+            1,
+        );
+    }
+
     /// Mock the behavior of the "reentrancy-simple-function" module.
     fn reentrancy_simple_function(&mut self, instance: &mut MockInstance<UserData>) {
         self.import_simple_function(instance);
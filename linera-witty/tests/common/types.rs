@@ -50,6 +50,31 @@ pub enum Enum {
     SmallerVariantWithStrictAlignment { inner: u64 },
 }
 
+/// A struct with `bool` fields represented as WIT `flags`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
+#[witty(flags)]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+/// A tuple struct represented as WIT `flags` with too many flags to fit in a single byte.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
+#[witty(flags)]
+pub struct ManyFlags(
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+    pub bool,
+);
+
 /// A generic struct with some specialized fields.
 #[derive(Clone, Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
 #[witty_specialize_with(A = u8, B = i16)]
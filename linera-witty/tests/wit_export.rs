@@ -11,7 +11,11 @@ use self::test_instance::WasmerInstanceFactory;
 #[cfg(with_wasmtime)]
 use self::test_instance::WasmtimeInstanceFactory;
 use self::test_instance::{MockInstanceFactory, TestInstanceFactory};
-use linera_witty::{wit_export, wit_import, ExportTo, Instance, Runtime, RuntimeMemory};
+use linera_witty::{
+    wit_export, wit_import, Borrow, ExportTo, Instance, Own, ResourceTable, Runtime, RuntimeError,
+    RuntimeMemory, WitLoad, WitStore, WitType,
+};
+use std::marker::PhantomData;
 use test_case::test_case;
 
 /// An interface to call into the test modules.
@@ -260,3 +264,94 @@ where
         .entrypoint()
         .expect("Failed to execute test of imported operations");
 }
+
+/// The status of a counter, represented as WIT `flags`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
+#[witty(flags)]
+pub struct CounterStatus {
+    pub is_zero: bool,
+    pub is_even: bool,
+}
+
+/// The error sent to the guest when a counter can't be incremented.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, WitType, WitLoad, WitStore)]
+pub enum CounterError {
+    Overflow { value: u32 },
+}
+
+/// Type to export functions that handle a `counter` resource, using WIT `flags` and typed errors.
+pub struct Counters<Caller>(PhantomData<Caller>);
+
+#[wit_export(package = "witty-macros:test-modules")]
+impl<Caller> Counters<Caller>
+where
+    Caller: Instance<UserData = ResourceTable<u32>>,
+{
+    #[witty(name = "[constructor]counter")]
+    fn new_counter(caller: &mut Caller, start: u32) -> Result<Own<u32>, RuntimeError> {
+        Ok(caller.user_data_mut().insert(start))
+    }
+
+    #[witty(name = "[method]counter.status")]
+    fn status(caller: &mut Caller, counter: Borrow<u32>) -> Result<CounterStatus, RuntimeError> {
+        let value = *caller.user_data().get(counter)?;
+
+        Ok(CounterStatus {
+            is_zero: value == 0,
+            is_even: value % 2 == 0,
+        })
+    }
+
+    #[witty(name = "[method]counter.increment")]
+    fn increment(
+        caller: &mut Caller,
+        counter: Borrow<u32>,
+        amount: u32,
+    ) -> Result<Result<u32, CounterError>, RuntimeError> {
+        let mut counters = caller.user_data_mut();
+        let value = counters.get_mut(counter)?;
+
+        Ok(match value.checked_add(amount) {
+            Some(new_value) => {
+                *value = new_value;
+                Ok(new_value)
+            }
+            None => Err(CounterError::Overflow { value: *value }),
+        })
+    }
+
+    #[witty(name = "[resource-drop]counter")]
+    fn drop_counter(caller: &mut Caller, counter: Own<u32>) -> Result<(), RuntimeError> {
+        caller.user_data_mut().remove(counter)?;
+        Ok(())
+    }
+}
+
+/// Test exporting functions that create, use and drop resources, that return WIT `flags` and that
+/// return typed errors to the guest.
+///
+/// The guest module is handwritten, because the `wit-bindgen` version used to build the other
+/// guest test modules doesn't support resources.
+#[test_case(MockInstanceFactory::default(); "with a mock instance")]
+#[cfg_attr(
+    with_wasmer,
+    test_case(WasmerInstanceFactory::<ResourceTable<u32>>::default(); "with Wasmer")
+)]
+#[cfg_attr(
+    with_wasmtime,
+    test_case(WasmtimeInstanceFactory::<ResourceTable<u32>>::default(); "with Wasmtime")
+)]
+fn test_resources<InstanceFactory>(mut factory: InstanceFactory)
+where
+    InstanceFactory: TestInstanceFactory,
+    InstanceFactory::Instance: Instance<UserData = ResourceTable<u32>> + InstanceForEntrypoint,
+    <<InstanceFactory::Instance as Instance>::Runtime as Runtime>::Memory:
+        RuntimeMemory<InstanceFactory::Instance>,
+    Counters<InstanceFactory::Caller<'static>>: ExportTo<InstanceFactory::Builder>,
+{
+    let instance = factory.load_test_module::<Counters<_>>("import", "resources");
+
+    Entrypoint::new(instance)
+        .entrypoint()
+        .expect("Failed to execute test of imported resources");
+}
@@ -7,8 +7,8 @@
 mod types;
 
 use self::types::{
-    Branch, Enum, Leaf, RecordWithDoublePadding, SimpleWrapper, SpecializedGenericEnum,
-    SpecializedGenericStruct, TupleWithPadding, TupleWithoutPadding,
+    Branch, Enum, Leaf, ManyFlags, Permissions, RecordWithDoublePadding, SimpleWrapper,
+    SpecializedGenericEnum, SpecializedGenericStruct, TupleWithPadding, TupleWithoutPadding,
 };
 use linera_witty::{hlist, InstanceWithMemory, Layout, MockInstance, WitLoad};
 use std::fmt::Debug;
@@ -240,6 +240,27 @@ fn test_specialized_generic_enum_type() {
     test_lift_from_flat_layout(hlist![2_i32, 1_i32, 0x0c0b_0a09_i32], &expected, &[]);
 }
 
+/// Check that types represented as WIT `flags` are properly loaded from memory and lifted from
+/// their flat layout.
+#[test]
+fn test_flags() {
+    let expected = Permissions {
+        read: true,
+        write: false,
+        execute: true,
+    };
+
+    test_load_from_memory(&[0b101], &expected);
+    test_lift_from_flat_layout(hlist![0b101], &expected, &[]);
+
+    let expected = ManyFlags(
+        true, false, false, false, false, false, false, false, false, true,
+    );
+
+    test_load_from_memory(&[0x01, 0x02], &expected);
+    test_lift_from_flat_layout(hlist![0x0201], &expected, &[]);
+}
+
 /// Tests that the type `T` can be loaded from an `input` sequence of bytes in memory and that it
 /// matches the `expected` value.
 fn test_load_from_memory<T>(input: &[u8], expected: &T)
@@ -7,8 +7,8 @@
 mod types;
 
 use self::types::{
-    Branch, Enum, Leaf, RecordWithDoublePadding, SimpleWrapper, SpecializedGenericEnum,
-    SpecializedGenericStruct, TupleWithPadding, TupleWithoutPadding,
+    Branch, Enum, Leaf, ManyFlags, Permissions, RecordWithDoublePadding, SimpleWrapper,
+    SpecializedGenericEnum, SpecializedGenericStruct, TupleWithPadding, TupleWithoutPadding,
 };
 use linera_witty::{hlist, InstanceWithMemory, Layout, MockInstance, WitStore};
 use std::fmt::Debug;
@@ -351,6 +351,27 @@ fn test_specialized_generic_enum_type() {
     );
 }
 
+/// Check that types represented as WIT `flags` are properly stored in memory and lowered into
+/// their flat layout.
+#[test]
+fn test_flags() {
+    let data = Permissions {
+        read: true,
+        write: false,
+        execute: true,
+    };
+
+    test_store_in_memory(&data, &[0b101], &[]);
+    test_lower_to_flat_layout(&data, hlist![0b101], &[]);
+
+    let data = ManyFlags(
+        true, false, false, false, false, false, false, false, false, true,
+    );
+
+    test_store_in_memory(&data, &[0x01, 0x02], &[]);
+    test_lower_to_flat_layout(&data, hlist![0x0201], &[]);
+}
+
 /// Tests that the `data` of type `T` can be stored as a sequence of bytes in memory and that it
 /// matches the `expected` bytes.
 fn test_store_in_memory<T>(
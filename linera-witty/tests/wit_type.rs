@@ -7,8 +7,8 @@
 mod types;
 
 use self::types::{
-    Branch, Enum, Leaf, RecordWithDoublePadding, SimpleWrapper, SpecializedGenericEnum,
-    SpecializedGenericStruct, TupleWithPadding, TupleWithoutPadding,
+    Branch, Enum, Leaf, ManyFlags, Permissions, RecordWithDoublePadding, SimpleWrapper,
+    SpecializedGenericEnum, SpecializedGenericStruct, TupleWithPadding, TupleWithoutPadding,
 };
 use linera_witty::{HList, Layout, WitType};
 
@@ -104,3 +104,15 @@ fn test_specialized_generic_enum_type() {
         3
     );
 }
+
+/// Check the memory size and layout derived for types represented as WIT `flags`.
+#[test]
+fn test_flags() {
+    assert_eq!(Permissions::SIZE, 1);
+    assert_eq!(<Permissions as WitType>::Layout::ALIGNMENT, 1);
+    assert_eq!(<<Permissions as WitType>::Layout as Layout>::Flat::LEN, 1);
+
+    assert_eq!(ManyFlags::SIZE, 2);
+    assert_eq!(<ManyFlags as WitType>::Layout::ALIGNMENT, 2);
+    assert_eq!(<<ManyFlags as WitType>::Layout as Layout>::Flat::LEN, 1);
+}